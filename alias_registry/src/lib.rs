@@ -0,0 +1,238 @@
+#![no_std]
+
+//! # Alias Registry
+//!
+//! Human-readable names such as `mama.nairobi` that resolve to Stellar
+//! addresses, so users pick recipients by name instead of pasting raw
+//! addresses.
+//!
+//! Names are 3 to 64 characters of lowercase letters, digits, `.`, `-` and
+//! `_`. Whoever registers a name owns it until it expires: only the owner can
+//! point it at a different address, renew it, transfer it or release it. An
+//! expired name stops resolving and can be registered again by anyone.
+//!
+//! Bill payees and family wallet recipient allowlists can refer to aliases;
+//! those contracts call `resolve` each time they need the address, so
+//! updating an alias takes effect everywhere it is used.
+//!
+//! ## Events
+//!
+//! `("alias", action)` with the name for `register`, `update`, `renew`,
+//! `transfer` and `release`.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, String, Symbol,
+};
+
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const ALIAS: Symbol = symbol_short!("alias");
+
+pub const MIN_NAME_LEN: u32 = 3;
+pub const MAX_NAME_LEN: u32 = 64;
+/// Longest single registration or renewal, about five years
+pub const MAX_DURATION: u64 = 5 * 365 * 24 * 60 * 60;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// Name has the wrong length or a character outside [a-z0-9._-]
+    InvalidName = 1,
+    /// Duration is zero or above MAX_DURATION
+    InvalidDuration = 2,
+    /// Name is registered and has not expired
+    AliasTaken = 3,
+    AliasNotFound = 4,
+    Unauthorized = 5,
+    AliasExpired = 6,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alias {
+    pub name: String,
+    pub owner: Address,
+    /// Address the name resolves to
+    pub target: Address,
+    pub registered_at: u64,
+    pub expires_at: u64,
+}
+
+#[contract]
+pub struct AliasRegistry;
+
+#[contractimpl]
+impl AliasRegistry {
+    /// Register `name` for `duration` seconds, pointing at `target`
+    ///
+    /// # Returns
+    /// Ok(expires_at)
+    /// Err(Error::AliasTaken) if someone holds the name and it has not expired
+    pub fn register(
+        env: Env,
+        owner: Address,
+        name: String,
+        target: Address,
+        duration: u64,
+    ) -> Result<u64, Error> {
+        owner.require_auth();
+        Self::validate_name(&name)?;
+        Self::validate_duration(duration)?;
+        let now = env.ledger().timestamp();
+        if let Some(existing) = Self::get_alias(env.clone(), name.clone()) {
+            if existing.expires_at > now {
+                return Err(Error::AliasTaken);
+            }
+        }
+
+        let alias = Alias {
+            name: name.clone(),
+            owner: owner.clone(),
+            target: target.clone(),
+            registered_at: now,
+            expires_at: now + duration,
+        };
+        Self::save_alias(&env, &alias);
+
+        env.events()
+            .publish((ALIAS, symbol_short!("register")), (name, owner, target));
+        Ok(alias.expires_at)
+    }
+
+    /// Point `name` at a new address
+    pub fn update_target(
+        env: Env,
+        owner: Address,
+        name: String,
+        target: Address,
+    ) -> Result<(), Error> {
+        let mut alias = Self::load_owned(&env, &owner, &name)?;
+        alias.target = target.clone();
+        Self::save_alias(&env, &alias);
+
+        env.events()
+            .publish((ALIAS, symbol_short!("update")), (name, target));
+        Ok(())
+    }
+
+    /// Extend a registration by `duration` seconds
+    ///
+    /// Renewal counts from the current expiry, so renewing early loses no
+    /// time. Expired names must be registered again instead.
+    ///
+    /// # Returns
+    /// Ok(expires_at) after renewal
+    pub fn renew(env: Env, owner: Address, name: String, duration: u64) -> Result<u64, Error> {
+        Self::validate_duration(duration)?;
+        let mut alias = Self::load_owned(&env, &owner, &name)?;
+        alias.expires_at += duration;
+        Self::save_alias(&env, &alias);
+
+        env.events()
+            .publish((ALIAS, symbol_short!("renew")), (name, alias.expires_at));
+        Ok(alias.expires_at)
+    }
+
+    /// Hand ownership of `name` to `new_owner`; the target is unchanged
+    pub fn transfer(
+        env: Env,
+        owner: Address,
+        name: String,
+        new_owner: Address,
+    ) -> Result<(), Error> {
+        let mut alias = Self::load_owned(&env, &owner, &name)?;
+        alias.owner = new_owner.clone();
+        Self::save_alias(&env, &alias);
+
+        env.events()
+            .publish((ALIAS, symbol_short!("transfer")), (name, owner, new_owner));
+        Ok(())
+    }
+
+    /// Give up `name` so anyone can register it
+    pub fn release(env: Env, owner: Address, name: String) -> Result<(), Error> {
+        Self::load_owned(&env, &owner, &name)?;
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("ALIAS"), name.clone()));
+
+        env.events()
+            .publish((ALIAS, symbol_short!("release")), (name, owner));
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Resolution
+    // -----------------------------------------------------------------------
+
+    /// Address `name` points at, or None if it is unregistered or expired
+    pub fn resolve(env: Env, name: String) -> Option<Address> {
+        let now = env.ledger().timestamp();
+        Self::get_alias(env, name)
+            .filter(|alias| alias.expires_at > now)
+            .map(|alias| alias.target)
+    }
+
+    /// Full registration record, including expired ones not yet re-registered
+    pub fn get_alias(env: Env, name: String) -> Option<Alias> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ALIAS"), name))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn validate_name(name: &String) -> Result<(), Error> {
+        let len = name.len();
+        if !(MIN_NAME_LEN..=MAX_NAME_LEN).contains(&len) {
+            return Err(Error::InvalidName);
+        }
+        let mut buf = [0u8; MAX_NAME_LEN as usize];
+        let bytes = &mut buf[..len as usize];
+        name.copy_into_slice(bytes);
+        let valid = bytes
+            .iter()
+            .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_'));
+        if !valid {
+            return Err(Error::InvalidName);
+        }
+        Ok(())
+    }
+
+    fn validate_duration(duration: u64) -> Result<(), Error> {
+        if duration == 0 || duration > MAX_DURATION {
+            return Err(Error::InvalidDuration);
+        }
+        Ok(())
+    }
+
+    /// Live alias owned by `owner`, after checking their auth
+    fn load_owned(env: &Env, owner: &Address, name: &String) -> Result<Alias, Error> {
+        owner.require_auth();
+        let alias = Self::get_alias(env.clone(), name.clone()).ok_or(Error::AliasNotFound)?;
+        if alias.owner != *owner {
+            return Err(Error::Unauthorized);
+        }
+        if alias.expires_at <= env.ledger().timestamp() {
+            return Err(Error::AliasExpired);
+        }
+        Ok(alias)
+    }
+
+    fn save_alias(env: &Env, alias: &Alias) {
+        let key = (symbol_short!("ALIAS"), alias.name.clone());
+        env.storage().persistent().set(&key, alias);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,120 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn setup() -> (Env, AliasRegistryClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let contract_id = env.register_contract(None, AliasRegistry);
+    let client = AliasRegistryClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    (env, client, owner)
+}
+
+fn name(env: &Env, s: &str) -> String {
+    String::from_str(env, s)
+}
+
+#[test]
+fn test_register_and_resolve() {
+    let (env, client, owner) = setup();
+    let mama = Address::generate(&env);
+    let alias = name(&env, "mama.nairobi");
+
+    assert_eq!(client.resolve(&alias), None);
+    assert_eq!(client.register(&owner, &alias, &mama, &DAY), 1_000 + DAY);
+    assert_eq!(client.resolve(&alias), Some(mama.clone()));
+
+    let record = client.get_alias(&alias).unwrap();
+    assert_eq!(record.owner, owner);
+    assert_eq!(record.target, mama);
+
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_register(&other, &alias, &other, &DAY),
+        Err(Ok(Error::AliasTaken))
+    );
+}
+
+#[test]
+fn test_owner_controls_updates() {
+    let (env, client, owner) = setup();
+    let alias = name(&env, "school-fees");
+    client.register(&owner, &alias, &Address::generate(&env), &DAY);
+
+    let stranger = Address::generate(&env);
+    let new_target = Address::generate(&env);
+    assert_eq!(
+        client.try_update_target(&stranger, &alias, &new_target),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.update_target(&owner, &alias, &new_target);
+    assert_eq!(client.resolve(&alias), Some(new_target.clone()));
+
+    client.transfer(&owner, &alias, &stranger);
+    assert_eq!(
+        client.try_update_target(&owner, &alias, &owner),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.release(&stranger, &alias);
+    assert_eq!(client.get_alias(&alias), None);
+    assert_eq!(
+        client.try_release(&stranger, &alias),
+        Err(Ok(Error::AliasNotFound))
+    );
+}
+
+#[test]
+fn test_expiry_and_renewal() {
+    let (env, client, owner) = setup();
+    let alias = name(&env, "kid_1");
+    let target = Address::generate(&env);
+    client.register(&owner, &alias, &target, &DAY);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000 + DAY / 2);
+    assert_eq!(client.renew(&owner, &alias, &DAY), 1_000 + 2 * DAY);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000 + 2 * DAY);
+    assert_eq!(client.resolve(&alias), None);
+    assert_eq!(
+        client.try_renew(&owner, &alias, &DAY),
+        Err(Ok(Error::AliasExpired))
+    );
+
+    // Expired names are free for anyone
+    let newcomer = Address::generate(&env);
+    client.register(&newcomer, &alias, &newcomer, &DAY);
+    assert_eq!(client.resolve(&alias), Some(newcomer));
+}
+
+#[test]
+fn test_name_and_duration_validation() {
+    let (env, client, owner) = setup();
+    for bad in ["ab", "Mama.Nairobi", "mama nairobi", "mama@home"] {
+        assert_eq!(
+            client.try_register(&owner, &name(&env, bad), &owner, &DAY),
+            Err(Ok(Error::InvalidName))
+        );
+    }
+    let long = "a".repeat(65);
+    assert_eq!(
+        client.try_register(&owner, &name(&env, &long), &owner, &DAY),
+        Err(Ok(Error::InvalidName))
+    );
+    let ok = name(&env, "abc");
+    assert_eq!(
+        client.try_register(&owner, &ok, &owner, &0),
+        Err(Ok(Error::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_register(&owner, &ok, &owner, &(MAX_DURATION + 1)),
+        Err(Ok(Error::InvalidDuration))
+    );
+}
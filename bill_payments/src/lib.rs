@@ -4,8 +4,8 @@ mod events;
 use events::{EventCategory, EventPriority, RemitwiseEvents};
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Map, String,
-    Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short,
+    token::TokenClient, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
 };
 
 // Storage TTL constants
@@ -13,6 +13,8 @@ const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280;
 const INSTANCE_BUMP_AMOUNT: u32 = 518400;
 const ARCHIVE_LIFETIME_THRESHOLD: u32 = 17280;
 const ARCHIVE_BUMP_AMOUNT: u32 = 2592000;
+const BILL_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const BILL_BUMP_AMOUNT: u32 = 518400; // ~30 days
 
 /// Pagination limits
 pub const DEFAULT_PAGE_LIMIT: u32 = 20;
@@ -32,6 +34,352 @@ pub struct Bill {
     pub created_at: u64,
     pub paid_at: Option<u64>,
     pub schedule_id: Option<u32>,
+    /// Who receives the payment; `None` for a bill that is only tracked
+    pub payee: Option<Address>,
+    /// Token `pay_bill` transfers from the payer to `payee`
+    pub token: Option<Address>,
+    /// Registry entry the bill was created for, see `create_payee_bill`
+    pub payee_id: Option<u32>,
+    /// A paused recurring bill does not queue its next occurrence when paid
+    pub series_paused: bool,
+    /// ID of the first bill of a recurring series, see `get_series`
+    pub series_id: Option<u32>,
+    /// Budget category, e.g. `utility`; `other` unless set
+    pub category: Symbol,
+    /// Local currency `local_amount` is quoted in, e.g. `PHP`; `None` when
+    /// the bill is only denominated in its settlement unit
+    pub local_currency: Option<Symbol>,
+    /// What the bill costs in `local_currency`; `amount` is what is settled
+    pub local_amount: i128,
+    /// Oracle or quoting service the conversion rate came from, if any
+    pub rate_source: Option<Address>,
+}
+
+/// A recurring series and the bills it has produced.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillSeries {
+    pub series_id: u32,
+    /// No occurrence is queued with a due date after this
+    pub end_date: Option<u64>,
+    /// No occurrence is queued once this many bills exist in the series
+    pub max_occurrences: Option<u32>,
+    pub occurrences: u32,
+    pub bill_ids: Vec<u32>,
+    /// Occurrences the keeper only created after their due date had passed
+    pub missed_generations: u32,
+}
+
+/// A payee in the directory.
+///
+/// Owners register their own payees; payees registered by the pause admin
+/// are shared with everyone and start out verified.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payee {
+    pub id: u32,
+    pub owner: Address,
+    pub address: Address,
+    pub name: String,
+    pub category: Symbol,
+    pub verified: bool,
+    pub created_at: u64,
+    /// Alias the payee was registered by; bills resolve it again so they
+    /// follow updates in the alias registry
+    pub alias: Option<String>,
+}
+
+/// How a late fee grows once a bill is past its due date.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LateFeePolicy {
+    /// A one-off charge as soon as the bill is overdue
+    Flat(i128),
+    /// Basis points of the bill amount for every full day overdue
+    BpsPerDay(u32),
+}
+
+/// Late-fee configuration attached to a single bill.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LateFeeConfig {
+    pub policy: LateFeePolicy,
+    /// Upper bound on the accrued fee; 0 means uncapped
+    pub max_fee: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SettlementMethod {
+    /// Tokens were transferred to the payee by `pay_bill`
+    Token,
+    /// A bill without a token was marked paid by `pay_bill`
+    Manual,
+    /// Paid outside the contract and recorded with `mark_paid_offchain`
+    OffChain,
+    /// Every share of a split bill was paid with `pay_my_share`
+    Shared,
+    /// A disputed payment was returned by `resolve_dispute`
+    Refund,
+    /// Paid from a gift voucher with `pay_bill_with_voucher`
+    Voucher,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisputeStatus {
+    Open,
+    /// The payment stands
+    Rejected,
+    /// Refunded and the bill is unpaid again
+    Reopened,
+    /// Refunded and the bill removed
+    Voided,
+}
+
+/// A challenge to a bill payment, see `dispute_payment`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub bill_id: u32,
+    pub raised_by: Address,
+    /// Hash of the off-chain reason, e.g. a support ticket
+    pub reason_hash: BytesN<32>,
+    pub raised_at: u64,
+    pub status: DisputeStatus,
+    pub resolved_by: Option<Address>,
+    pub resolved_at: Option<u64>,
+    /// Amount returned to the payer, including any late fee
+    pub refunded: i128,
+}
+
+/// One family member's portion of a split bill.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillShare {
+    pub payer: Address,
+    pub amount: i128,
+    pub paid: bool,
+    pub paid_at: Option<u64>,
+}
+
+/// A share a member still owes, see `get_member_outstanding`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShareDue {
+    pub bill_id: u32,
+    pub owner: Address,
+    pub amount: i128,
+    pub due_date: u64,
+}
+
+/// How and when a bill was paid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub bill_id: u32,
+    pub payer: Address,
+    pub payee: Option<Address>,
+    pub token: Option<Address>,
+    /// Bill amount, excluding any late fee
+    pub amount: i128,
+    /// Late fee charged on top of `amount`
+    pub late_fee: i128,
+    pub method: SettlementMethod,
+    /// Bank or receipt reference for off-chain payments; empty otherwise
+    pub reference: String,
+    pub paid_at: u64,
+    pub ledger_sequence: u32,
+}
+
+/// One change made with `update_bill`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillAmendment {
+    pub old_amount: i128,
+    pub new_amount: i128,
+    pub old_due_date: u64,
+    pub new_due_date: u64,
+    pub old_frequency_days: u32,
+    pub new_frequency_days: u32,
+    pub timestamp: u64,
+}
+
+/// Bills above `threshold` need approval from `wallet` before they can be
+/// paid, see `approve_bill`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalPolicy {
+    pub wallet: Address,
+    pub threshold: i128,
+}
+
+/// When an owner's bills trigger reminders.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReminderConfig {
+    /// Days before the due date to remind at, e.g. `[7, 3, 1]`
+    pub days_before: Vec<u32>,
+    /// Once overdue, escalate again every this many days
+    pub overdue_every_days: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReminderKind {
+    Upcoming,
+    Overdue,
+}
+
+/// Payload of a `reminder` event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillReminder {
+    pub bill_id: u32,
+    pub owner: Address,
+    pub amount: i128,
+    pub due_date: u64,
+    pub kind: ReminderKind,
+    /// Reminder window for upcoming bills, days past due for overdue ones
+    pub days: u32,
+    /// 1 for the first overdue reminder, rising with each escalation
+    pub level: u32,
+}
+
+/// Which reminders a bill has already had.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct ReminderState {
+    /// Smallest upcoming window reminded so far
+    upcoming: Option<u32>,
+    overdue_level: u32,
+}
+
+/// Which bills `get_bills_page` returns.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BillFilter {
+    All,
+    Unpaid,
+    Paid,
+    /// Unpaid and past the due date
+    Overdue,
+}
+
+/// One bill to create in `batch_create_bills`.
+#[contracttype]
+#[derive(Clone)]
+pub struct NewBillItem {
+    pub name: String,
+    pub amount: i128,
+    pub due_date: u64,
+    pub recurring: bool,
+    pub frequency_days: u32,
+}
+
+/// One settled bill in an owner's payment history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRecord {
+    /// Position in the owner's history, starting at 1
+    pub seq: u32,
+    pub bill_id: u32,
+    pub payee_id: Option<u32>,
+    pub payee: Option<Address>,
+    pub payer: Address,
+    /// Bill amount, excluding any late fee
+    pub amount: i128,
+    pub late_fee: i128,
+    pub method: SettlementMethod,
+    pub timestamp: u64,
+    /// Local-currency value of the bill when it was paid, see `Bill`
+    pub local_currency: Option<Symbol>,
+    pub local_amount: i128,
+    pub rate_source: Option<Address>,
+}
+
+/// Paginated result for payment history queries
+#[contracttype]
+#[derive(Clone)]
+pub struct PaymentPage {
+    pub items: Vec<PaymentRecord>,
+    /// The `seq` to pass as `cursor` for the next page. 0 means no more pages.
+    pub next_cursor: u32,
+    pub count: u32,
+}
+
+/// Projected obligations for one calendar month, see `get_monthly_forecast`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MonthlyForecast {
+    /// `YYYYMM`
+    pub month: u32,
+    pub bills: i128,
+    pub premiums: i128,
+    pub savings: i128,
+    pub total: i128,
+}
+
+/// Contracts `get_monthly_forecast` reads premiums and savings from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ForecastSources {
+    pub insurance: Option<Address>,
+    pub savings: Option<Address>,
+}
+
+#[contractclient(name = "InsuranceClient")]
+pub trait InsuranceTrait {
+    fn get_total_monthly_premium(env: Env, owner: Address) -> i128;
+}
+
+#[contractclient(name = "SavingsGoalsClient")]
+pub trait SavingsGoalsTrait {
+    fn get_savings_schedules(env: Env, owner: Address) -> Vec<SavingsSchedule>;
+}
+
+#[contractclient(name = "AliasRegistryClient")]
+pub trait AliasRegistryTrait {
+    fn resolve(env: Env, name: String) -> Option<Address>;
+}
+
+#[contractclient(name = "ReputationClient")]
+pub trait ReputationTrait {
+    fn record_bill_payment(env: Env, reporter: Address, subject: Address, on_time: bool) -> u32;
+}
+
+#[contractclient(name = "VouchersClient")]
+pub trait VouchersTrait {
+    #[allow(clippy::too_many_arguments)]
+    fn redeem(
+        env: Env,
+        redeemer: Address,
+        voucher_id: u32,
+        holder: Address,
+        token: Address,
+        amount: i128,
+        category: Symbol,
+        to: Address,
+    ) -> i128;
+}
+
+/// Mirror of the savings_goals schedule, needed for `SavingsGoalsClient`.
+#[contracttype]
+#[derive(Clone)]
+pub struct SavingsSchedule {
+    pub id: u32,
+    pub owner: Address,
+    pub goal_id: u32,
+    pub amount: i128,
+    pub next_due: u64,
+    pub interval: u64,
+    pub recurring: bool,
+    pub active: bool,
+    pub created_at: u64,
+    pub last_executed: Option<u64>,
+    pub missed_count: u32,
+    pub funder: Address,
+    pub skipped_count: u32,
 }
 
 /// Paginated result for bill queries
@@ -57,6 +405,15 @@ pub mod pause_functions {
 
 const CONTRACT_VERSION: u32 = 1;
 const MAX_BATCH_SIZE: u32 = 50;
+const MAX_BILL_AMENDMENTS: u32 = 20;
+const MAX_GENERATIONS_PER_RUN: u32 = 50;
+const MAX_REMINDERS_PER_RUN: u32 = 50;
+const MAX_REMINDER_WINDOWS: u32 = 10;
+/// How long after payment a bill can be disputed
+const DISPUTE_WINDOW: u64 = 7 * 86400;
+const MAX_FORECAST_MONTHS: u32 = 24;
+const SNAPSHOT_VERSION: u32 = 1;
+const MAX_RETENTION_CHECKS: u32 = 10;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -73,6 +430,35 @@ pub enum Error {
     BatchTooLarge = 9,
     BatchValidationFailed = 10,
     InvalidLimit = 11,
+    InvalidPayee = 12,
+    PayeeNotFound = 13,
+    DuplicatePayee = 14,
+    DuplicateBill = 15,
+    InvalidLateFee = 16,
+    LateFeeLocked = 17,
+    ApprovalRequired = 18,
+    InvalidShares = 19,
+    SharedBill = 20,
+    InvalidDueDate = 21,
+    PartiallyPaid = 22,
+    NotRecurring = 23,
+    InvalidSeriesEnd = 24,
+    InvalidReminderConfig = 25,
+    BillNotPaid = 26,
+    DisputeWindowClosed = 27,
+    AlreadyDisputed = 28,
+    NoOpenDispute = 29,
+    InvalidResolution = 30,
+    UnsupportedVersion = 31,
+    ChecksumMismatch = 32,
+    InvalidNonce = 33,
+    DelegateLimitExceeded = 34,
+    AliasNotResolved = 35,
+    /// No vouchers contract, a bill without token or payee, or the voucher
+    /// cannot pay this bill
+    VoucherNotUsable = 36,
+    /// A voucher payment can only be resolved as `Rejected`
+    VoucherNotRefundable = 37,
 }
 
 #[contracttype]
@@ -86,6 +472,45 @@ pub struct ArchivedBill {
     pub archived_at: u64,
 }
 
+/// Contract state exported for migration.
+///
+/// `checksum` is the sha256 of the big-endian version followed by the XDR of
+/// every other field.
+#[contracttype]
+#[derive(Clone)]
+pub struct BillsExportSnapshot {
+    pub version: u32,
+    pub checksum: Bytes,
+    pub next_id: u32,
+    pub bills: Vec<Bill>,
+    pub archived: Vec<ArchivedBill>,
+    pub series: Vec<BillSeries>,
+}
+
+/// Someone allowed to pay an owner's bills, see `set_payment_delegate`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentDelegate {
+    pub delegate: Address,
+    /// Largest single payment, late fee included; 0 = no cap
+    pub per_bill_cap: i128,
+    /// Most the delegate may pay per calendar month; 0 = no cap
+    pub monthly_cap: i128,
+    /// `YYYYMM` that `spent` counts
+    pub month: u32,
+    pub spent: i128,
+}
+
+/// How long paid and archived bills are kept, see `set_retention_policy`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetentionPolicy {
+    /// Days after payment a bill is archived; 0 = never
+    pub archive_after_days: u32,
+    /// Days after archiving an archived bill is deleted; 0 = never
+    pub delete_after_days: u32,
+}
+
 /// Paginated result for archived bill queries
 #[contracttype]
 #[derive(Clone)]
@@ -353,6 +778,126 @@ impl BillPayments {
         due_date: u64,
         recurring: bool,
         frequency_days: u32,
+    ) -> Result<u32, Error> {
+        Self::create_bill_internal(
+            env,
+            owner,
+            name,
+            amount,
+            due_date,
+            recurring,
+            frequency_days,
+            None,
+            None,
+            symbol_short!("other"),
+        )
+    }
+
+    /// Create a bill that settles in `token`.
+    ///
+    /// `pay_bill` and `batch_pay_bills` transfer `amount` of `token` from the
+    /// payer to `payee`, so paying the bill actually moves value.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_token_bill(
+        env: Env,
+        owner: Address,
+        name: String,
+        amount: i128,
+        due_date: u64,
+        recurring: bool,
+        frequency_days: u32,
+        payee: Address,
+        token: Address,
+    ) -> Result<u32, Error> {
+        if payee == owner {
+            return Err(Error::InvalidPayee);
+        }
+        Self::create_bill_internal(
+            env,
+            owner,
+            name,
+            amount,
+            due_date,
+            recurring,
+            frequency_days,
+            Some((payee, token)),
+            None,
+            symbol_short!("other"),
+        )
+    }
+
+    /// Create a bill for a registered payee, named after it.
+    ///
+    /// With `token`, the bill settles to the payee's address like
+    /// `create_token_bill`. An unpaid bill for the same payee, amount and due
+    /// date is rejected as a duplicate.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_payee_bill(
+        env: Env,
+        owner: Address,
+        payee_id: u32,
+        amount: i128,
+        due_date: u64,
+        recurring: bool,
+        frequency_days: u32,
+        token: Option<Address>,
+    ) -> Result<u32, Error> {
+        let payee = Self::get_payee(env.clone(), payee_id).ok_or(Error::PayeeNotFound)?;
+        if payee.owner != owner && Some(payee.owner.clone()) != Self::get_pause_admin(&env) {
+            return Err(Error::PayeeNotFound);
+        }
+        for id in Self::owner_index(&env, &owner).iter() {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            if !bill.paid
+                && bill.payee_id == Some(payee_id)
+                && bill.amount == amount
+                && bill.due_date == due_date
+            {
+                return Err(Error::DuplicateBill);
+            }
+        }
+        let settlement = match token {
+            Some(token) => {
+                let address = match &payee.alias {
+                    Some(alias) => Self::resolve_alias(&env, alias)?,
+                    None => payee.address,
+                };
+                if address == owner {
+                    return Err(Error::InvalidPayee);
+                }
+                Some((address, token))
+            }
+            None => None,
+        };
+        Self::create_bill_internal(
+            env,
+            owner,
+            payee.name,
+            amount,
+            due_date,
+            recurring,
+            frequency_days,
+            settlement,
+            Some(payee_id),
+            payee.category,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_bill_internal(
+        env: Env,
+        owner: Address,
+        name: String,
+        amount: i128,
+        due_date: u64,
+        recurring: bool,
+        frequency_days: u32,
+        settlement: Option<(Address, Address)>,
+        payee_id: Option<u32>,
+        category: Symbol,
     ) -> Result<u32, Error> {
         owner.require_auth();
         Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
@@ -365,20 +910,13 @@ impl BillPayments {
         }
 
         Self::extend_instance_ttl(&env);
-        let mut bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let next_id = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("NEXT_ID"))
-            .unwrap_or(0u32)
-            + 1;
 
+        let next_id = Self::last_bill_id(&env) + 1;
         let current_time = env.ledger().timestamp();
+        let (payee, token) = match settlement {
+            Some((payee, token)) => (Some(payee), Some(token)),
+            None => (None, None),
+        };
         let bill = Bill {
             id: next_id,
             owner: owner.clone(),
@@ -391,1423 +929,5943 @@ impl BillPayments {
             created_at: current_time,
             paid_at: None,
             schedule_id: None,
+            payee,
+            token,
+            payee_id,
+            series_paused: false,
+            series_id: if recurring { Some(next_id) } else { None },
+            category,
+            local_currency: None,
+            local_amount: 0,
+            rate_source: None,
         };
-
-        let bill_owner = bill.owner.clone();
-        bills.set(next_id, bill);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("BILLS"), &bills);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("NEXT_ID"), &next_id);
+        Self::insert_new_bill(&env, &bill);
 
         RemitwiseEvents::emit(
             &env,
             EventCategory::State,
             EventPriority::Medium,
             symbol_short!("created"),
-            (next_id, bill_owner, amount, due_date),
+            (next_id, owner, amount, due_date),
         );
 
         Ok(next_id)
     }
 
-    pub fn pay_bill(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
-
-        Self::extend_instance_ttl(&env);
-        let mut bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let mut bill = bills.get(bill_id).ok_or(Error::BillNotFound)?;
-
-        if bill.owner != caller {
-            return Err(Error::Unauthorized);
+    /// Create several manual bills for `owner` in one call.
+    ///
+    /// Every item is validated before any bill is written, so the batch
+    /// either creates all bills or none. Emits a single `batch_crt` event
+    /// with the new IDs instead of one `created` event per bill.
+    pub fn batch_create_bills(
+        env: Env,
+        owner: Address,
+        items: Vec<NewBillItem>,
+    ) -> Result<Vec<u32>, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
+        if items.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
         }
-        if bill.paid {
-            return Err(Error::BillAlreadyPaid);
+        for item in items.iter() {
+            if item.amount <= 0 {
+                return Err(Error::InvalidAmount);
+            }
+            if item.recurring && item.frequency_days == 0 {
+                return Err(Error::InvalidFrequency);
+            }
         }
 
+        Self::extend_instance_ttl(&env);
         let current_time = env.ledger().timestamp();
-        bill.paid = true;
-        bill.paid_at = Some(current_time);
-
-        if bill.recurring {
-            let next_due_date = bill.due_date + (bill.frequency_days as u64 * 86400);
-            let next_id = env
-                .storage()
-                .instance()
-                .get(&symbol_short!("NEXT_ID"))
-                .unwrap_or(0u32)
-                + 1;
-
-            let next_bill = Bill {
-                id: next_id,
-                owner: bill.owner.clone(),
-                name: bill.name.clone(),
-                amount: bill.amount,
-                due_date: next_due_date,
-                recurring: true,
-                frequency_days: bill.frequency_days,
+        let mut ids = Vec::new(&env);
+        for item in items.iter() {
+            let id = Self::last_bill_id(&env) + 1;
+            let bill = Bill {
+                id,
+                owner: owner.clone(),
+                name: item.name,
+                amount: item.amount,
+                due_date: item.due_date,
+                recurring: item.recurring,
+                frequency_days: item.frequency_days,
                 paid: false,
                 created_at: current_time,
                 paid_at: None,
-                schedule_id: bill.schedule_id,
+                schedule_id: None,
+                payee: None,
+                token: None,
+                payee_id: None,
+                series_paused: false,
+                series_id: if item.recurring { Some(id) } else { None },
+                category: symbol_short!("other"),
+                local_currency: None,
+                local_amount: 0,
+                rate_source: None,
             };
-            bills.set(next_id, next_bill);
-            env.storage()
-                .instance()
-                .set(&symbol_short!("NEXT_ID"), &next_id);
+            Self::insert_new_bill(&env, &bill);
+            ids.push_back(id);
         }
 
-        let paid_amount = bill.amount;
-        bills.set(bill_id, bill);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("BILLS"), &bills);
-
         RemitwiseEvents::emit(
             &env,
-            EventCategory::Transaction,
-            EventPriority::High,
-            symbol_short!("paid"),
-            (bill_id, caller, paid_amount),
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("batch_crt"),
+            (ids.clone(), owner),
         );
-
-        Ok(())
+        Ok(ids)
     }
 
-    pub fn get_bill(env: Env, bill_id: u32) -> Option<Bill> {
-        let bills: Map<u32, Bill> = env
-            .storage()
+    /// Store a freshly built bill, index it and advance `NEXT_ID` to its ID.
+    fn insert_new_bill(env: &Env, bill: &Bill) {
+        Self::save_bill(env, bill);
+        Self::index_bill(env, &bill.owner, bill.id);
+        Self::adjust_unpaid(env, &bill.owner, bill.amount);
+        env.storage()
             .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        bills.get(bill_id)
+            .set(&symbol_short!("NEXT_ID"), &bill.id);
     }
 
-    // -----------------------------------------------------------------------
-    // PAGINATED LIST QUERIES
-    // -----------------------------------------------------------------------
-
-    /// Get a page of unpaid bills for `owner`.
-    ///
-    /// # Arguments
-    /// * `owner`  – whose bills to return
-    /// * `cursor` – start after this bill ID (pass 0 for the first page)
-    /// * `limit`  – max items per page (0 → DEFAULT_PAGE_LIMIT, capped at MAX_PAGE_LIMIT)
+    /// Change the amount, due date or frequency of an unpaid bill.
     ///
-    /// # Returns
-    /// `BillPage { items, next_cursor, count }`.
-    /// When `next_cursor == 0` there are no more pages.
-    pub fn get_unpaid_bills(env: Env, owner: Address, cursor: u32, limit: u32) -> BillPage {
-        let limit = Self::clamp_limit(limit);
-        let bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
+    /// Once a share of a split bill has been paid the amount is fixed; an
+    /// unpaid split is dropped when the amount changes and has to be redone.
+    /// An overdue bill with a late fee keeps its due date. A new amount also
+    /// clears any family wallet approval.
+    pub fn update_bill(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        amount: i128,
+        due_date: u64,
+        frequency_days: u32,
+    ) -> Result<BillAmendment, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
 
-        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
-        for (id, bill) in bills.iter() {
-            if id <= cursor {
-                continue;
+        Self::extend_instance_ttl(&env);
+        let mut bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if bill.recurring && frequency_days == 0 {
+            return Err(Error::InvalidFrequency);
+        }
+        let now = env.ledger().timestamp();
+        if due_date != bill.due_date {
+            if due_date < now {
+                return Err(Error::InvalidDueDate);
             }
-            if bill.owner != owner || bill.paid {
-                continue;
+            if now > bill.due_date && Self::get_late_fee_config(env.clone(), bill_id).is_some() {
+                return Err(Error::LateFeeLocked);
             }
-            staging.push_back((id, bill));
-            if staging.len() > limit {
-                break;
+        }
+        if amount != bill.amount {
+            let shares = Self::get_bill_shares(env.clone(), bill_id);
+            if shares.iter().any(|share| share.paid) {
+                return Err(Error::PartiallyPaid);
             }
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("SHARES"), bill_id));
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("BILL_APR"), bill_id));
+            Self::adjust_unpaid(&env, &caller, amount - bill.amount);
         }
 
-        Self::build_page(&env, staging, limit)
-    }
+        let amendment = BillAmendment {
+            old_amount: bill.amount,
+            new_amount: amount,
+            old_due_date: bill.due_date,
+            new_due_date: due_date,
+            old_frequency_days: bill.frequency_days,
+            new_frequency_days: frequency_days,
+            timestamp: now,
+        };
+        bill.amount = amount;
+        bill.due_date = due_date;
+        bill.frequency_days = frequency_days;
+        Self::save_bill(&env, &bill);
 
-    /// Get a page of ALL bills (paid + unpaid) for `owner`.
-    ///
-    /// Same cursor/limit semantics as `get_unpaid_bills`.
-    pub fn get_all_bills_for_owner(env: Env, owner: Address, cursor: u32, limit: u32) -> BillPage {
-        owner.require_auth();
-        let limit = Self::clamp_limit(limit);
-        let bills: Map<u32, Bill> = env
+        let key = (symbol_short!("BILL_AMD"), bill_id);
+        let mut history: Vec<BillAmendment> = env
             .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(&env));
+        if history.len() >= MAX_BILL_AMENDMENTS {
+            history.remove(0);
+        }
+        history.push_back(amendment.clone());
+        env.storage().persistent().set(&key, &history);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
 
-        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
-        for (id, bill) in bills.iter() {
-            if id <= cursor {
-                continue;
-            }
-            if bill.owner != owner {
-                continue;
-            }
-            staging.push_back((id, bill));
-            if staging.len() > limit {
-                break;
-            }
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("updated"),
+            (bill_id, amendment.clone()),
+        );
+        Ok(amendment)
+    }
+
+    /// Changes made to a bill with `update_bill`, oldest first.
+    pub fn get_bill_amendments(env: Env, bill_id: u32) -> Vec<BillAmendment> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("BILL_AMD"), bill_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn pay_bill(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
+
+        Self::extend_instance_ttl(&env);
+
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+
+        // Anyone other than the owner pays as a delegate, within its caps
+        let delegated = bill.owner != caller;
+        if delegated
+            && Self::get_payment_delegate(env.clone(), bill.owner.clone(), caller.clone()).is_none()
+        {
+            return Err(Error::Unauthorized);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        if Self::is_shared(&env, bill_id) {
+            return Err(Error::SharedBill);
+        }
+        Self::require_approval(&env, &bill)?;
+        if delegated {
+            Self::charge_delegate(&env, &bill, &caller)?;
         }
 
-        Self::build_page(&env, staging, limit)
+        let paid_amount = bill.amount;
+        let method = if bill.token.is_some() {
+            SettlementMethod::Token
+        } else {
+            SettlementMethod::Manual
+        };
+        let late_fee = Self::settle_bill(&env, bill, &caller, method, String::from_str(&env, ""));
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Transaction,
+            EventPriority::High,
+            symbol_short!("paid"),
+            (bill_id, caller, paid_amount, late_fee),
+        );
+        Self::apply_retention(&env);
+
+        Ok(())
     }
 
-    /// Get a page of overdue (unpaid + past due_date) bills across all owners.
-    ///
-    /// Same cursor/limit semantics.
-    pub fn get_overdue_bills(env: Env, cursor: u32, limit: u32) -> BillPage {
-        let limit = Self::clamp_limit(limit);
-        let current_time = env.ledger().timestamp();
-        let bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
+    /// Record a bill as paid outside the contract, e.g. by bank transfer or
+    /// cash. No tokens move; `reference` is kept with the settlement.
+    pub fn mark_paid_offchain(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        reference: String,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
 
-        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
-        for (id, bill) in bills.iter() {
-            if id <= cursor {
-                continue;
-            }
-            if bill.paid || bill.due_date >= current_time {
-                continue;
-            }
-            staging.push_back((id, bill));
-            if staging.len() > limit {
-                break;
-            }
+        Self::extend_instance_ttl(&env);
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
         }
 
-        Self::build_page(&env, staging, limit)
+        if Self::is_shared(&env, bill_id) {
+            return Err(Error::SharedBill);
+        }
+
+        let paid_amount = bill.amount;
+        let late_fee = Self::settle_bill(
+            &env,
+            bill,
+            &caller,
+            SettlementMethod::OffChain,
+            reference.clone(),
+        );
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Transaction,
+            EventPriority::High,
+            symbol_short!("paid_off"),
+            (bill_id, caller, paid_amount, late_fee, reference),
+        );
+        Ok(())
     }
 
-    /// Admin-only: get ALL bills (any owner), paginated.
-    pub fn get_all_bills(
+    /// Pay a token bill from a gift voucher held by its owner.
+    ///
+    /// The bill must be for a verified payee, so the holder cannot pick where
+    /// the voucher's funds go. The vouchers contract sends the amount plus any
+    /// late fee straight to the payee, provided the voucher is for bills and
+    /// allows the payee's category.
+    pub fn pay_bill_with_voucher(
         env: Env,
         caller: Address,
-        cursor: u32,
-        limit: u32,
-    ) -> Result<BillPage, Error> {
+        bill_id: u32,
+        voucher_id: u32,
+    ) -> Result<(), Error> {
         caller.require_auth();
-        let admin = Self::get_pause_admin(&env).ok_or(Error::Unauthorized)?;
-        if admin != caller {
+        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
+
+        Self::extend_instance_ttl(&env);
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
             return Err(Error::Unauthorized);
         }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        if Self::is_shared(&env, bill_id) {
+            return Err(Error::SharedBill);
+        }
+        Self::require_approval(&env, &bill)?;
+
+        let (vouchers, token, payee) = match (
+            Self::get_vouchers_contract(env.clone()),
+            bill.token.clone(),
+            bill.payee.clone(),
+        ) {
+            (Some(vouchers), Some(token), Some(payee)) => (vouchers, token, payee),
+            _ => return Err(Error::VoucherNotUsable),
+        };
+        // The category comes from the payee, since the owner can relabel the bill
+        let category = match bill
+            .payee_id
+            .and_then(|payee_id| Self::get_payee(env.clone(), payee_id))
+        {
+            Some(registered) if registered.verified => registered.category,
+            _ => return Err(Error::VoucherNotUsable),
+        };
+        let total = bill
+            .amount
+            .saturating_add(Self::accrued_late_fee(&env, &bill));
+        match VouchersClient::new(&env, &vouchers).try_redeem(
+            &env.current_contract_address(),
+            &voucher_id,
+            &caller,
+            &token,
+            &total,
+            &category,
+            &payee,
+        ) {
+            Ok(Ok(_)) => {}
+            _ => return Err(Error::VoucherNotUsable),
+        }
 
-        let limit = Self::clamp_limit(limit);
-        let bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
+        let paid_amount = bill.amount;
+        let late_fee = Self::settle_bill(
+            &env,
+            bill,
+            &caller,
+            SettlementMethod::Voucher,
+            String::from_str(&env, ""),
+        );
 
-        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
-        for (id, bill) in bills.iter() {
-            if id <= cursor {
-                continue;
-            }
-            staging.push_back((id, bill));
-            if staging.len() > limit {
-                break;
-            }
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Transaction,
+            EventPriority::High,
+            symbol_short!("paid_vch"),
+            (bill_id, caller, voucher_id, paid_amount, late_fee),
+        );
+        Self::apply_retention(&env);
+        Ok(())
+    }
+
+    /// Admin-only: set the vouchers contract `pay_bill_with_voucher` redeems from.
+    pub fn set_vouchers_contract(
+        env: Env,
+        caller: Address,
+        vouchers: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller) {
+            return Err(Error::Unauthorized);
+        }
+        Self::extend_instance_ttl(&env);
+        match vouchers {
+            Some(vouchers) => env
+                .storage()
+                .instance()
+                .set(&symbol_short!("VCH_CTR"), &vouchers),
+            None => env.storage().instance().remove(&symbol_short!("VCH_CTR")),
         }
+        Ok(())
+    }
 
-        Ok(Self::build_page(&env, staging, limit))
+    pub fn get_vouchers_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("VCH_CTR"))
     }
 
-    /// Build a `BillPage` from a staging buffer of up to `limit+1` matching items.
-    /// `next_cursor` is set to the last *returned* item's ID so the next call's
-    /// `id <= cursor` filter correctly skips past it.
-    fn build_page(env: &Env, staging: Vec<(u32, Bill)>, limit: u32) -> BillPage {
-        let n = staging.len();
-        let has_next = n > limit;
-        let mut items = Vec::new(env);
-        let mut next_cursor: u32 = 0;
+    /// How a paid bill was settled.
+    pub fn get_settlement(env: Env, bill_id: u32) -> Option<Settlement> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SETTLE"), bill_id))
+    }
 
-        // Emit all items, or all-but-last if there is a next page
-        let take = if has_next { n - 1 } else { n };
+    /// Mark `bill` paid, move tokens for `SettlementMethod::Token`, queue the
+    /// next occurrence of a recurring bill and store the settlement record.
+    ///
+    /// Returns the late fee charged on top of the bill amount.
+    fn settle_bill(
+        env: &Env,
+        mut bill: Bill,
+        payer: &Address,
+        method: SettlementMethod,
+        reference: String,
+    ) -> i128 {
+        let late_fee = Self::accrued_late_fee(env, &bill);
+        let total = bill.amount.saturating_add(late_fee);
+        if let (SettlementMethod::Token, Some(token), Some(payee)) =
+            (method, &bill.token, &bill.payee)
+        {
+            TokenClient::new(env, token).transfer(payer, payee, &total);
+        }
 
-        for i in 0..take {
-            if let Some((_, bill)) = staging.get(i) {
-                items.push_back(bill);
+        let current_time = env.ledger().timestamp();
+        bill.paid = true;
+        bill.paid_at = Some(current_time);
+        Self::adjust_unpaid(env, &bill.owner, -bill.amount);
+
+        // The keeper may already have queued the next occurrence
+        if bill.recurring && !bill.series_paused && Self::is_series_tail(env, &bill) {
+            let next_due_date = bill.due_date + (bill.frequency_days as u64 * 86400);
+            if Self::series_allows(env, &bill, next_due_date) {
+                Self::queue_next_occurrence(env, &bill, next_due_date);
+            } else {
+                RemitwiseEvents::emit(
+                    env,
+                    EventCategory::State,
+                    EventPriority::Low,
+                    symbol_short!("ser_done"),
+                    (bill.series_id.unwrap_or(bill.id), bill.id),
+                );
             }
         }
 
-        // next_cursor = last returned item's ID (NOT the first skipped item)
-        if has_next {
-            if let Some((id, _)) = staging.get(take - 1) {
-                next_cursor = id;
-            }
+        let settlement = Settlement {
+            bill_id: bill.id,
+            payer: payer.clone(),
+            payee: bill.payee.clone(),
+            token: bill.token.clone(),
+            amount: bill.amount,
+            late_fee,
+            method,
+            reference,
+            paid_at: current_time,
+            ledger_sequence: env.ledger().sequence(),
+        };
+        let key = (symbol_short!("SETTLE"), bill.id);
+        env.storage().persistent().set(&key, &settlement);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+        if let Some(payee_id) = bill.payee_id {
+            Self::add_payee_total(env, &bill.owner, payee_id, bill.amount + late_fee);
+        }
+        Self::record_spend(env, &bill, bill.amount + late_fee, current_time);
+        Self::record_payment(
+            env,
+            &bill,
+            payer,
+            bill.amount,
+            late_fee,
+            method,
+            current_time,
+        );
+        if method == SettlementMethod::Token {
+            Self::report_reputation(env, &bill.owner, current_time <= bill.due_date);
         }
+        Self::save_bill(env, &bill);
+        late_fee
+    }
 
-        let count = items.len();
-        BillPage {
-            items,
-            next_cursor,
-            count,
+    /// Admin-only: set the reputation contract token payments are reported to.
+    pub fn set_reputation_contract(
+        env: Env,
+        caller: Address,
+        reputation: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller) {
+            return Err(Error::Unauthorized);
+        }
+        Self::extend_instance_ttl(&env);
+        match reputation {
+            Some(reputation) => env
+                .storage()
+                .instance()
+                .set(&symbol_short!("REP_CTR"), &reputation),
+            None => env.storage().instance().remove(&symbol_short!("REP_CTR")),
         }
+        Ok(())
     }
 
-    // -----------------------------------------------------------------------
-    // Backward-compat helpers
-    // -----------------------------------------------------------------------
+    pub fn get_reputation_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("REP_CTR"))
+    }
 
-    /// Legacy helper: returns ALL unpaid bills for owner in one Vec.
-    /// Only safe for owners with a small number of bills. Prefer the
-    /// paginated `get_unpaid_bills` for production use.
-    pub fn get_all_unpaid_bills_legacy(env: Env, owner: Address) -> Vec<Bill> {
-        let bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let mut result = Vec::new(&env);
-        for (_, bill) in bills.iter() {
-            if !bill.paid && bill.owner == owner {
-                result.push_back(bill);
-            }
+    /// Tell the reputation contract whether `owner` paid on time. A failed
+    /// report is ignored so scoring can never block a payment.
+    fn report_reputation(env: &Env, owner: &Address, on_time: bool) {
+        if let Some(reputation) = Self::get_reputation_contract(env.clone()) {
+            let _ = ReputationClient::new(env, &reputation).try_record_bill_payment(
+                &env.current_contract_address(),
+                owner,
+                &on_time,
+            );
         }
-        result
+    }
+
+    fn add_payee_total(env: &Env, owner: &Address, payee_id: u32, delta: i128) {
+        let key = (symbol_short!("PAY_TOT"), owner.clone(), payee_id);
+        let total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&key, &total.saturating_add(delta));
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
     }
 
     // -----------------------------------------------------------------------
-    // Archived bill queries (paginated)
+    // Payment history
     // -----------------------------------------------------------------------
 
-    /// Get a page of archived bills for `owner`.
-    pub fn get_archived_bills(
-        env: Env,
+    /// Page through `owner`'s payment history, oldest first.
+    ///
+    /// `cursor` is the `seq` of the last record already seen (0 = start).
+    /// Records outlive the bills they describe, so archived and cancelled
+    /// successors do not leave gaps in a statement.
+    pub fn get_payment_history(env: Env, owner: Address, cursor: u32, limit: u32) -> PaymentPage {
+        let limit = Self::clamp_limit(limit);
+        let count = Self::payment_count(&env, &owner);
+        let mut items = Vec::new(&env);
+        let mut seq = cursor + 1;
+        while seq <= count && items.len() < limit {
+            if let Some(record) = Self::load_payment(&env, &owner, seq) {
+                items.push_back(record);
+            }
+            seq += 1;
+        }
+        let next_cursor = if seq <= count { seq - 1 } else { 0 };
+        PaymentPage {
+            count: items.len(),
+            items,
+            next_cursor,
+        }
+    }
+
+    /// Page through `owner`'s payments to one registered payee.
+    ///
+    /// Same cursor/limit semantics as `get_payment_history`.
+    pub fn get_payee_payments(
+        env: Env,
         owner: Address,
+        payee_id: u32,
         cursor: u32,
         limit: u32,
-    ) -> ArchivedBillPage {
+    ) -> PaymentPage {
         let limit = Self::clamp_limit(limit);
-        let archived: Map<u32, ArchivedBill> = env
+        let seqs: Vec<u32> = env
             .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_BILL"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let mut staging: Vec<(u32, ArchivedBill)> = Vec::new(&env);
-        for (id, bill) in archived.iter() {
-            if id <= cursor {
-                continue;
-            }
-            if bill.owner != owner {
+            .persistent()
+            .get(&(symbol_short!("PAY_PIDX"), owner.clone(), payee_id))
+            .unwrap_or_else(|| Vec::new(&env));
+        let mut items = Vec::new(&env);
+        let mut has_more = false;
+        for seq in seqs.iter() {
+            if seq <= cursor {
                 continue;
             }
-            staging.push_back((id, bill));
-            if staging.len() > limit {
+            if items.len() == limit {
+                has_more = true;
                 break;
             }
+            if let Some(record) = Self::load_payment(&env, &owner, seq) {
+                items.push_back(record);
+            }
         }
-
-        let has_next = staging.len() > limit;
-        let mut items = Vec::new(&env);
-        let mut next_cursor: u32 = 0;
-        let take = if has_next {
-            staging.len() - 1
-        } else {
-            staging.len()
+        let next_cursor = match items.last() {
+            Some(record) if has_more => record.seq,
+            _ => 0,
         };
+        PaymentPage {
+            count: items.len(),
+            items,
+            next_cursor,
+        }
+    }
 
-        for i in 0..take {
-            if let Some((_, bill)) = staging.get(i) {
-                items.push_back(bill);
+    /// Payments `owner` made with `from <= timestamp < to`, oldest first.
+    ///
+    /// History is in time order, so the start of the period is found by
+    /// binary search and only records inside it are read. At most
+    /// `MAX_PAGE_LIMIT` records are returned; a longer period can be read
+    /// in pieces by moving `from` past the last timestamp returned, or with
+    /// `get_payment_history`.
+    pub fn get_payments_between(
+        env: Env,
+        owner: Address,
+        from: u64,
+        to: u64,
+    ) -> Vec<PaymentRecord> {
+        let mut items = Vec::new(&env);
+        if from >= to {
+            return items;
+        }
+        let count = Self::payment_count(&env, &owner);
+        // First seq with timestamp >= from
+        let (mut lo, mut hi) = (1u32, count + 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match Self::load_payment(&env, &owner, mid) {
+                Some(record) if record.timestamp < from => lo = mid + 1,
+                _ => hi = mid,
             }
         }
-        if has_next {
-            if let Some((id, _)) = staging.get(take - 1) {
-                next_cursor = id;
+        for seq in lo..=count {
+            let record = match Self::load_payment(&env, &owner, seq) {
+                Some(record) => record,
+                None => continue,
+            };
+            if record.timestamp >= to || items.len() >= MAX_PAGE_LIMIT {
+                break;
             }
+            items.push_back(record);
         }
+        items
+    }
 
-        let count = items.len();
-        ArchivedBillPage {
-            items,
-            next_cursor,
-            count,
-        }
+    fn payment_count(env: &Env, owner: &Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PAY_CNT"), owner.clone()))
+            .unwrap_or(0)
     }
 
-    pub fn get_archived_bill(env: Env, bill_id: u32) -> Option<ArchivedBill> {
-        let archived: Map<u32, ArchivedBill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_BILL"))
-            .unwrap_or_else(|| Map::new(&env));
-        archived.get(bill_id)
+    fn load_payment(env: &Env, owner: &Address, seq: u32) -> Option<PaymentRecord> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PAY_REC"), owner.clone(), seq))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn record_payment(
+        env: &Env,
+        bill: &Bill,
+        payer: &Address,
+        amount: i128,
+        late_fee: i128,
+        method: SettlementMethod,
+        timestamp: u64,
+    ) {
+        let seq = Self::payment_count(env, &bill.owner) + 1;
+        let record = PaymentRecord {
+            seq,
+            bill_id: bill.id,
+            payee_id: bill.payee_id,
+            payee: bill.payee.clone(),
+            payer: payer.clone(),
+            amount,
+            late_fee,
+            method,
+            timestamp,
+            local_currency: bill.local_currency.clone(),
+            local_amount: bill.local_amount,
+            rate_source: bill.rate_source.clone(),
+        };
+        let key = (symbol_short!("PAY_REC"), bill.owner.clone(), seq);
+        env.storage().persistent().set(&key, &record);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+        let key = (symbol_short!("PAY_CNT"), bill.owner.clone());
+        env.storage().persistent().set(&key, &seq);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        if let Some(payee_id) = bill.payee_id {
+            let key = (symbol_short!("PAY_PIDX"), bill.owner.clone(), payee_id);
+            let mut seqs: Vec<u32> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or_else(|| Vec::new(env));
+            seqs.push_back(seq);
+            env.storage().persistent().set(&key, &seqs);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ARCHIVE_LIFETIME_THRESHOLD,
+                ARCHIVE_BUMP_AMOUNT,
+            );
+        }
     }
 
     // -----------------------------------------------------------------------
-    // Remaining operations
+    // Disputes and refunds
     // -----------------------------------------------------------------------
 
-    pub fn cancel_bill(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
+    /// Dispute a payment within `DISPUTE_WINDOW` of it being made.
+    ///
+    /// Either the bill owner or whoever paid may raise it. `reason_hash`
+    /// commits to an off-chain description. A bill under dispute is kept out
+    /// of `archive_paid_bills` until it is resolved.
+    pub fn dispute_payment(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        reason_hash: BytesN<32>,
+    ) -> Result<(), Error> {
         caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::CANCEL_BILL)?;
-        let mut bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let bill = bills.get(bill_id).ok_or(Error::BillNotFound)?;
-        if bill.owner != caller {
+        Self::extend_instance_ttl(&env);
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        let settlement = match Self::get_settlement(env.clone(), bill_id) {
+            Some(settlement) if bill.paid => settlement,
+            _ => return Err(Error::BillNotPaid),
+        };
+        if caller != bill.owner && caller != settlement.payer {
             return Err(Error::Unauthorized);
         }
-        bills.remove(bill_id);
+        if settlement.method == SettlementMethod::Shared {
+            return Err(Error::SharedBill);
+        }
+        let now = env.ledger().timestamp();
+        if now > settlement.paid_at + DISPUTE_WINDOW {
+            return Err(Error::DisputeWindowClosed);
+        }
+        if let Some(existing) = Self::get_dispute(env.clone(), bill_id) {
+            if existing.raised_at >= settlement.paid_at {
+                return Err(Error::AlreadyDisputed);
+            }
+        }
+
+        let dispute = Dispute {
+            bill_id,
+            raised_by: caller.clone(),
+            reason_hash,
+            raised_at: now,
+            status: DisputeStatus::Open,
+            resolved_by: None,
+            resolved_at: None,
+            refunded: 0,
+        };
+        Self::store_dispute(&env, &dispute);
+        let mut open = Self::get_open_disputes(env.clone());
+        open.push_back(bill_id);
         env.storage()
             .instance()
-            .set(&symbol_short!("BILLS"), &bills);
+            .set(&symbol_short!("DISP_OPEN"), &open);
         RemitwiseEvents::emit(
             &env,
-            EventCategory::State,
-            EventPriority::Medium,
-            symbol_short!("canceled"),
-            bill_id,
+            EventCategory::Alert,
+            EventPriority::High,
+            symbol_short!("disputed"),
+            (bill_id, caller),
         );
         Ok(())
     }
 
-    pub fn archive_paid_bills(
+    /// Settle an open dispute as `Rejected`, `Reopened` or `Voided`.
+    ///
+    /// The pause admin or the bill's payee may resolve. `Reopened` and
+    /// `Voided` refund the payment: a token settlement is transferred back
+    /// from the payee to the payer, so the payee has to authorize the call
+    /// even when the admin resolves it. Voucher settlements are not refunded,
+    /// since the vouchers contract cannot take funds back onto a voucher. `Reopened` marks the bill unpaid again; `Voided`
+    /// removes it. The refund is added to the owner's payment history as a
+    /// negative `Refund` record.
+    pub fn resolve_dispute(
         env: Env,
-        caller: Address,
-        before_timestamp: u64,
-    ) -> Result<u32, Error> {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::ARCHIVE)?;
+        resolver: Address,
+        bill_id: u32,
+        outcome: DisputeStatus,
+    ) -> Result<(), Error> {
+        resolver.require_auth();
         Self::extend_instance_ttl(&env);
+        let mut dispute = match Self::get_dispute(env.clone(), bill_id) {
+            Some(dispute) if dispute.status == DisputeStatus::Open => dispute,
+            _ => return Err(Error::NoOpenDispute),
+        };
+        if outcome == DisputeStatus::Open {
+            return Err(Error::InvalidResolution);
+        }
+        let mut bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        let settlement = Self::get_settlement(env.clone(), bill_id).ok_or(Error::BillNotPaid)?;
+        let payee = match bill.payee_id {
+            Some(payee_id) => Self::get_payee(env.clone(), payee_id).map(|payee| payee.address),
+            None => None,
+        };
+        if Some(resolver.clone()) != Self::get_pause_admin(&env)
+            && Some(&resolver) != bill.payee.as_ref()
+            && Some(&resolver) != payee.as_ref()
+        {
+            return Err(Error::Unauthorized);
+        }
 
-        let mut bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let mut archived: Map<u32, ArchivedBill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_BILL"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let current_time = env.ledger().timestamp();
-        let mut archived_count = 0u32;
-        let mut to_remove: Vec<u32> = Vec::new(&env);
-
-        for (id, bill) in bills.iter() {
-            if let Some(paid_at) = bill.paid_at {
-                if bill.paid && paid_at < before_timestamp {
-                    let archived_bill = ArchivedBill {
-                        id: bill.id,
-                        owner: bill.owner.clone(),
-                        name: bill.name.clone(),
-                        amount: bill.amount,
-                        paid_at,
-                        archived_at: current_time,
-                    };
-                    archived.set(id, archived_bill);
-                    to_remove.push_back(id);
-                    archived_count += 1;
-                }
+        if outcome != DisputeStatus::Rejected {
+            if settlement.method == SettlementMethod::Voucher {
+                return Err(Error::VoucherNotRefundable);
+            }
+            let total = settlement.amount.saturating_add(settlement.late_fee);
+            if let (SettlementMethod::Token, Some(token), Some(payee)) =
+                (settlement.method, &settlement.token, &settlement.payee)
+            {
+                TokenClient::new(&env, token).transfer(payee, &settlement.payer, &total);
+            }
+            if let Some(payee_id) = bill.payee_id {
+                Self::add_payee_total(&env, &bill.owner, payee_id, -total);
+            }
+            Self::record_spend(&env, &bill, -total, settlement.paid_at);
+            Self::record_payment(
+                &env,
+                &bill,
+                &settlement.payer,
+                -settlement.amount,
+                -settlement.late_fee,
+                SettlementMethod::Refund,
+                env.ledger().timestamp(),
+            );
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("SETTLE"), bill_id));
+            dispute.refunded = total;
+
+            if outcome == DisputeStatus::Reopened {
+                bill.paid = false;
+                bill.paid_at = None;
+                Self::adjust_unpaid(&env, &bill.owner, bill.amount);
+                Self::save_bill(&env, &bill);
+            } else {
+                Self::delete_bill(&env, bill_id);
+                Self::unindex_bill(&env, &bill.owner, bill_id);
+                env.storage()
+                    .persistent()
+                    .remove(&(symbol_short!("LATE_FEE"), bill_id));
+                env.storage()
+                    .persistent()
+                    .remove(&(symbol_short!("REMIND"), bill_id));
             }
         }
 
-        for id in to_remove.iter() {
-            bills.remove(id);
+        dispute.status = outcome;
+        dispute.resolved_by = Some(resolver.clone());
+        dispute.resolved_at = Some(env.ledger().timestamp());
+        Self::store_dispute(&env, &dispute);
+        let mut open = Self::get_open_disputes(env.clone());
+        if let Some(pos) = open.first_index_of(bill_id) {
+            open.remove(pos);
         }
-
         env.storage()
             .instance()
-            .set(&symbol_short!("BILLS"), &bills);
+            .set(&symbol_short!("DISP_OPEN"), &open);
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Transaction,
+            EventPriority::High,
+            symbol_short!("resolved"),
+            (bill_id, outcome, dispute.refunded),
+        );
+        Ok(())
+    }
+
+    /// Latest dispute raised on a bill's payment.
+    pub fn get_dispute(env: Env, bill_id: u32) -> Option<Dispute> {
         env.storage()
-            .instance()
-            .set(&symbol_short!("ARCH_BILL"), &archived);
+            .persistent()
+            .get(&(symbol_short!("DISPUTE"), bill_id))
+    }
 
-        Self::extend_archive_ttl(&env);
-        Self::update_storage_stats(&env);
+    /// IDs of bills with a dispute waiting to be resolved.
+    pub fn get_open_disputes(env: Env) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("DISP_OPEN"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
 
-        RemitwiseEvents::emit_batch(
-            &env,
-            EventCategory::System,
-            symbol_short!("archived"),
-            archived_count,
+    fn store_dispute(env: &Env, dispute: &Dispute) {
+        let key = (symbol_short!("DISPUTE"), dispute.bill_id);
+        env.storage().persistent().set(&key, dispute);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
         );
-
-        Ok(archived_count)
     }
 
-    pub fn restore_bill(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::RESTORE)?;
-        Self::extend_instance_ttl(&env);
+    fn has_open_dispute(env: &Env, bill_id: u32) -> bool {
+        matches!(
+            Self::get_dispute(env.clone(), bill_id),
+            Some(Dispute {
+                status: DisputeStatus::Open,
+                ..
+            })
+        )
+    }
 
-        let mut archived: Map<u32, ArchivedBill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_BILL"))
-            .unwrap_or_else(|| Map::new(&env));
-        let archived_bill = archived.get(bill_id).ok_or(Error::BillNotFound)?;
+    // -----------------------------------------------------------------------
+    // Payment delegation
+    // -----------------------------------------------------------------------
 
-        if archived_bill.owner != caller {
+    /// Let `delegate`, e.g. a family member or a family wallet, pay
+    /// `owner`'s bills with `pay_bill`.
+    ///
+    /// Each payment, late fee included, must fit `per_bill_cap` and the
+    /// delegate's total for the calendar month must stay within
+    /// `monthly_cap`; a cap of 0 is not enforced. Setting a delegate again
+    /// replaces its caps and keeps this month's spend.
+    pub fn set_payment_delegate(
+        env: Env,
+        owner: Address,
+        delegate: Address,
+        per_bill_cap: i128,
+        monthly_cap: i128,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        if delegate == owner {
             return Err(Error::Unauthorized);
         }
-
-        let mut bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let restored_bill = Bill {
-            id: archived_bill.id,
-            owner: archived_bill.owner.clone(),
-            name: archived_bill.name.clone(),
-            amount: archived_bill.amount,
-            due_date: env.ledger().timestamp() + 2592000,
-            recurring: false,
-            frequency_days: 0,
-            paid: true,
-            created_at: archived_bill.paid_at,
-            paid_at: Some(archived_bill.paid_at),
-            schedule_id: None,
+        if per_bill_cap < 0 || monthly_cap < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        Self::extend_instance_ttl(&env);
+        let existing = Self::get_payment_delegate(env.clone(), owner.clone(), delegate.clone());
+        let (month, spent) = match &existing {
+            Some(existing) => (existing.month, existing.spent),
+            None => (0, 0),
         };
-
-        bills.set(bill_id, restored_bill);
-        archived.remove(bill_id);
-
-        env.storage()
-            .instance()
-            .set(&symbol_short!("BILLS"), &bills);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("ARCH_BILL"), &archived);
-
-        Self::update_storage_stats(&env);
-
+        Self::store_delegate(
+            &env,
+            &owner,
+            &PaymentDelegate {
+                delegate: delegate.clone(),
+                per_bill_cap,
+                monthly_cap,
+                month,
+                spent,
+            },
+        );
+        if existing.is_none() {
+            let mut delegates = Self::delegate_index(&env, &owner);
+            delegates.push_back(delegate.clone());
+            Self::store_delegate_index(&env, &owner, &delegates);
+        }
         RemitwiseEvents::emit(
             &env,
-            EventCategory::State,
+            EventCategory::Access,
             EventPriority::Medium,
-            symbol_short!("restored"),
-            bill_id,
+            symbol_short!("dlg_set"),
+            (owner, delegate, per_bill_cap, monthly_cap),
         );
         Ok(())
     }
 
-    pub fn bulk_cleanup_bills(
+    pub fn revoke_payment_delegate(
         env: Env,
-        caller: Address,
-        before_timestamp: u64,
-    ) -> Result<u32, Error> {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::ARCHIVE)?;
-        Self::extend_instance_ttl(&env);
+        owner: Address,
+        delegate: Address,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        let mut delegates = Self::delegate_index(&env, &owner);
+        let pos = delegates
+            .first_index_of(&delegate)
+            .ok_or(Error::Unauthorized)?;
+        delegates.remove(pos);
+        Self::store_delegate_index(&env, &owner, &delegates);
+        env.storage().persistent().remove(&(
+            symbol_short!("DELEGATE"),
+            owner.clone(),
+            delegate.clone(),
+        ));
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Access,
+            EventPriority::Medium,
+            symbol_short!("dlg_rev"),
+            (owner, delegate),
+        );
+        Ok(())
+    }
 
-        let mut archived: Map<u32, ArchivedBill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_BILL"))
-            .unwrap_or_else(|| Map::new(&env));
-        let mut deleted_count = 0u32;
-        let mut to_remove: Vec<u32> = Vec::new(&env);
+    pub fn get_payment_delegate(
+        env: Env,
+        owner: Address,
+        delegate: Address,
+    ) -> Option<PaymentDelegate> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("DELEGATE"), owner, delegate))
+    }
 
-        for (id, bill) in archived.iter() {
-            if bill.archived_at < before_timestamp {
-                to_remove.push_back(id);
-                deleted_count += 1;
+    pub fn get_payment_delegates(env: Env, owner: Address) -> Vec<PaymentDelegate> {
+        let mut result = Vec::new(&env);
+        for delegate in Self::delegate_index(&env, &owner).iter() {
+            if let Some(entry) = Self::get_payment_delegate(env.clone(), owner.clone(), delegate) {
+                result.push_back(entry);
             }
         }
+        result
+    }
 
-        for id in to_remove.iter() {
-            archived.remove(id);
+    /// Count a delegated payment of `bill` against `delegate`'s caps.
+    fn charge_delegate(env: &Env, bill: &Bill, delegate: &Address) -> Result<(), Error> {
+        let mut entry =
+            Self::get_payment_delegate(env.clone(), bill.owner.clone(), delegate.clone())
+                .ok_or(Error::Unauthorized)?;
+        let total = bill
+            .amount
+            .saturating_add(Self::accrued_late_fee(env, bill));
+        let month = Self::month_of(env.ledger().timestamp());
+        if entry.month != month {
+            entry.month = month;
+            entry.spent = 0;
         }
+        let spent = entry.spent.saturating_add(total);
+        if (entry.per_bill_cap > 0 && total > entry.per_bill_cap)
+            || (entry.monthly_cap > 0 && spent > entry.monthly_cap)
+        {
+            return Err(Error::DelegateLimitExceeded);
+        }
+        entry.spent = spent;
+        Self::store_delegate(env, &bill.owner, &entry);
+        Ok(())
+    }
+
+    fn store_delegate(env: &Env, owner: &Address, entry: &PaymentDelegate) {
+        let key = (
+            symbol_short!("DELEGATE"),
+            owner.clone(),
+            entry.delegate.clone(),
+        );
+        env.storage().persistent().set(&key, entry);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
 
+    fn delegate_index(env: &Env, owner: &Address) -> Vec<Address> {
         env.storage()
-            .instance()
-            .set(&symbol_short!("ARCH_BILL"), &archived);
-        Self::update_storage_stats(&env);
+            .persistent()
+            .get(&(symbol_short!("DELEGS"), owner.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
 
-        RemitwiseEvents::emit_batch(
-            &env,
-            EventCategory::System,
-            symbol_short!("cleaned"),
-            deleted_count,
+    fn store_delegate_index(env: &Env, owner: &Address, delegates: &Vec<Address>) {
+        let key = (symbol_short!("DELEGS"), owner.clone());
+        env.storage().persistent().set(&key, delegates);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
         );
-        Ok(deleted_count)
     }
 
-    pub fn batch_pay_bills(env: Env, caller: Address, bill_ids: Vec<u32>) -> Result<u32, Error> {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
-        if bill_ids.len() > (MAX_BATCH_SIZE as usize).try_into().unwrap() {
-            return Err(Error::BatchTooLarge);
-        }
-        let bills_map: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        for id in bill_ids.iter() {
-            let bill = bills_map.get(id).ok_or(Error::BillNotFound)?;
-            if bill.owner != caller {
-                return Err(Error::Unauthorized);
-            }
-            if bill.paid {
-                return Err(Error::BillAlreadyPaid);
-            }
+    // -----------------------------------------------------------------------
+    // Approval via family wallet
+    // -----------------------------------------------------------------------
+
+    /// Require approval from `wallet` before `owner`'s bills above
+    /// `threshold` can be paid.
+    ///
+    /// Replacing an existing policy needs the current wallet's authorization
+    /// as well, so the owner cannot drop the requirement alone.
+    pub fn set_approval_policy(
+        env: Env,
+        owner: Address,
+        wallet: Address,
+        threshold: i128,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        if threshold < 0 {
+            return Err(Error::InvalidAmount);
         }
-        Self::extend_instance_ttl(&env);
-        let mut bills: Map<u32, Bill> = env
+        if let Some(current) = Self::get_approval_policy(env.clone(), owner.clone()) {
+            current.wallet.require_auth();
+        }
+
+        let policy = ApprovalPolicy { wallet, threshold };
+        let key = (symbol_short!("APPROVAL"), owner.clone());
+        env.storage().persistent().set(&key, &policy);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("appr_set"),
+            (owner, policy.wallet, threshold),
+        );
+        Ok(())
+    }
+
+    /// Drop `owner`'s approval requirement. Only the linked wallet can do this.
+    pub fn remove_approval_policy(env: Env, owner: Address) -> Result<(), Error> {
+        let policy =
+            Self::get_approval_policy(env.clone(), owner.clone()).ok_or(Error::Unauthorized)?;
+        policy.wallet.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("APPROVAL"), owner.clone()));
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("appr_rem"),
+            owner,
+        );
+        Ok(())
+    }
+
+    pub fn get_approval_policy(env: Env, owner: Address) -> Option<ApprovalPolicy> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("APPROVAL"), owner))
+    }
+
+    /// Approve a bill for payment. `wallet` must be the family wallet linked
+    /// in the bill owner's approval policy.
+    pub fn approve_bill(env: Env, wallet: Address, bill_id: u32) -> Result<(), Error> {
+        wallet.require_auth();
+
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        match Self::get_approval_policy(env.clone(), bill.owner.clone()) {
+            Some(policy) if policy.wallet == wallet => {}
+            _ => return Err(Error::Unauthorized),
+        }
+
+        let key = (symbol_short!("BILL_APR"), bill_id);
+        env.storage().persistent().set(&key, &wallet);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Transaction,
+            EventPriority::Medium,
+            symbol_short!("approved"),
+            (bill_id, wallet),
+        );
+        Ok(())
+    }
+
+    pub fn is_bill_approved(env: Env, bill_id: u32) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("BILL_APR"), bill_id))
+    }
+
+    /// Fails with `ApprovalRequired` when `bill` is above its owner's
+    /// approval threshold and the linked wallet has not approved it.
+    fn require_approval(env: &Env, bill: &Bill) -> Result<(), Error> {
+        let policy: Option<ApprovalPolicy> = env
             .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let current_time = env.ledger().timestamp();
-        let mut next_id: u32 = env
+            .persistent()
+            .get(&(symbol_short!("APPROVAL"), bill.owner.clone()));
+        let policy = match policy {
+            Some(policy) => policy,
+            None => return Ok(()),
+        };
+        if bill.amount <= policy.threshold {
+            return Ok(());
+        }
+        let approver: Option<Address> = env
             .storage()
-            .instance()
-            .get(&symbol_short!("NEXT_ID"))
-            .unwrap_or(0u32);
-        let mut paid_count = 0u32;
-        for id in bill_ids.iter() {
-            let mut bill = bills.get(id).ok_or(Error::BillNotFound)?;
-            if bill.owner != caller || bill.paid {
-                return Err(Error::BatchValidationFailed);
-            }
-            let amount = bill.amount;
-            bill.paid = true;
-            bill.paid_at = Some(current_time);
-            if bill.recurring {
-                next_id = next_id.saturating_add(1);
-                let next_due_date = bill.due_date + (bill.frequency_days as u64 * 86400);
-                let next_bill = Bill {
-                    id: next_id,
-                    owner: bill.owner.clone(),
-                    name: bill.name.clone(),
-                    amount: bill.amount,
-                    due_date: next_due_date,
-                    recurring: true,
-                    frequency_days: bill.frequency_days,
-                    paid: false,
-                    created_at: current_time,
-                    paid_at: None,
-                    schedule_id: bill.schedule_id,
-                };
-                bills.set(next_id, next_bill);
+            .persistent()
+            .get(&(symbol_short!("BILL_APR"), bill.id));
+        if approver == Some(policy.wallet) {
+            Ok(())
+        } else {
+            Err(Error::ApprovalRequired)
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Shared bills
+    // -----------------------------------------------------------------------
+
+    /// Split an unpaid bill among family members. `shares` maps each payer
+    /// to their portion and must add up to the bill amount.
+    ///
+    /// A split bill can only be settled share by share with `pay_my_share`.
+    /// It can be re-split until the first share is paid.
+    pub fn split_bill(
+        env: Env,
+        owner: Address,
+        bill_id: u32,
+        shares: Map<Address, i128>,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
+
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != owner {
+            return Err(Error::Unauthorized);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        if Self::get_bill_shares(env.clone(), bill_id)
+            .iter()
+            .any(|share| share.paid)
+        {
+            return Err(Error::InvalidShares);
+        }
+        if Self::get_late_fee_config(env.clone(), bill_id).is_some() {
+            return Err(Error::InvalidShares);
+        }
+
+        let mut total = 0i128;
+        let mut split = Vec::new(&env);
+        for (payer, amount) in shares.iter() {
+            if amount <= 0 {
+                return Err(Error::InvalidShares);
             }
-            bills.set(id, bill);
-            paid_count += 1;
+            total = total.checked_add(amount).ok_or(Error::InvalidShares)?;
+            split.push_back(BillShare {
+                payer,
+                amount,
+                paid: false,
+                paid_at: None,
+            });
+        }
+        if split.is_empty() || total != bill.amount {
+            return Err(Error::InvalidShares);
+        }
+
+        Self::store_shares(&env, bill_id, &split);
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("split"),
+            (bill_id, split.len()),
+        );
+        Ok(())
+    }
+
+    /// Pay the caller's share of a split bill. For token bills the share is
+    /// transferred to the payee; the bill is settled once every share is in.
+    pub fn pay_my_share(env: Env, payer: Address, bill_id: u32) -> Result<(), Error> {
+        payer.require_auth();
+        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
+
+        Self::extend_instance_ttl(&env);
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        Self::require_approval(&env, &bill)?;
+
+        let mut shares = Self::get_bill_shares(env.clone(), bill_id);
+        let index = shares
+            .iter()
+            .position(|share| share.payer == payer)
+            .ok_or(Error::Unauthorized)? as u32;
+        let mut share = shares.get(index).unwrap();
+        if share.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+
+        if let (Some(token), Some(payee)) = (&bill.token, &bill.payee) {
+            TokenClient::new(&env, token).transfer(&payer, payee, &share.amount);
+        }
+        share.paid = true;
+        share.paid_at = Some(env.ledger().timestamp());
+        let amount = share.amount;
+        shares.set(index, share);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("SHARES"), bill_id), &shares);
+
+        let mut open: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("PAYER_BL"), payer.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        if let Some(pos) = open.first_index_of(bill_id) {
+            open.remove(pos);
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("PAYER_BL"), payer.clone()), &open);
+        }
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::Transaction,
+            EventPriority::Medium,
+            symbol_short!("share_pd"),
+            (bill_id, payer.clone(), amount),
+        );
+
+        if shares.iter().all(|share| share.paid) {
+            let owner = bill.owner.clone();
+            let paid_amount = bill.amount;
+            Self::settle_bill(
+                &env,
+                bill,
+                &owner,
+                SettlementMethod::Shared,
+                String::from_str(&env, ""),
+            );
             RemitwiseEvents::emit(
                 &env,
                 EventCategory::Transaction,
                 EventPriority::High,
                 symbol_short!("paid"),
-                (id, caller.clone(), amount),
+                (bill_id, owner, paid_amount, 0i128),
             );
         }
+        Ok(())
+    }
+
+    pub fn get_bill_shares(env: Env, bill_id: u32) -> Vec<BillShare> {
         env.storage()
-            .instance()
-            .set(&symbol_short!("NEXT_ID"), &next_id);
+            .persistent()
+            .get(&(symbol_short!("SHARES"), bill_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Shares of a split bill that have not been paid yet.
+    pub fn get_outstanding_shares(env: Env, bill_id: u32) -> Vec<BillShare> {
+        let mut outstanding = Vec::new(&env);
+        for share in Self::get_bill_shares(env.clone(), bill_id).iter() {
+            if !share.paid {
+                outstanding.push_back(share);
+            }
+        }
+        outstanding
+    }
+
+    /// Unpaid shares `member` owes across all split bills.
+    pub fn get_member_outstanding(env: Env, member: Address) -> Vec<ShareDue> {
+        let open: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("PAYER_BL"), member.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut due = Vec::new(&env);
+        for bill_id in open.iter() {
+            let bill = match Self::load_bill(&env, bill_id) {
+                Some(bill) if !bill.paid => bill,
+                _ => continue,
+            };
+            for share in Self::get_bill_shares(env.clone(), bill_id).iter() {
+                if share.payer == member && !share.paid {
+                    due.push_back(ShareDue {
+                        bill_id,
+                        owner: bill.owner.clone(),
+                        amount: share.amount,
+                        due_date: bill.due_date,
+                    });
+                }
+            }
+        }
+        due
+    }
+
+    /// Store `shares` for `bill_id` and index the bill under each payer.
+    fn store_shares(env: &Env, bill_id: u32, shares: &Vec<BillShare>) {
+        let key = (symbol_short!("SHARES"), bill_id);
+        env.storage().persistent().set(&key, shares);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+        for share in shares.iter() {
+            let key = (symbol_short!("PAYER_BL"), share.payer.clone());
+            let mut open: Vec<u32> = env
+                .storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or_else(|| Vec::new(env));
+            if !open.contains(bill_id) {
+                open.push_back(bill_id);
+                env.storage().persistent().set(&key, &open);
+            }
+            env.storage().persistent().extend_ttl(
+                &key,
+                ARCHIVE_LIFETIME_THRESHOLD,
+                ARCHIVE_BUMP_AMOUNT,
+            );
+        }
+    }
+
+    fn is_shared(env: &Env, bill_id: u32) -> bool {
         env.storage()
-            .instance()
-            .set(&symbol_short!("BILLS"), &bills);
-        Self::update_storage_stats(&env);
+            .persistent()
+            .has(&(symbol_short!("SHARES"), bill_id))
+    }
+
+    // -----------------------------------------------------------------------
+    // Categories and budgets
+    // -----------------------------------------------------------------------
+
+    pub fn set_bill_category(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        category: Symbol,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::extend_instance_ttl(&env);
+        let mut bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        bill.category = category;
+        Self::save_bill(&env, &bill);
+        Ok(())
+    }
+
+    /// Record the local-currency amount an unpaid bill stands for.
+    ///
+    /// `amount` is left alone: it is what `pay_bill` settles. Passing a
+    /// `local_amount` of 0 removes the conversion. Later occurrences of a
+    /// recurring bill inherit it until it is changed.
+    pub fn set_bill_conversion(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        currency: Symbol,
+        local_amount: i128,
+        rate_source: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::extend_instance_ttl(&env);
+        let mut bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        if local_amount < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if local_amount == 0 {
+            bill.local_currency = None;
+            bill.rate_source = None;
+        } else {
+            bill.local_currency = Some(currency);
+            bill.rate_source = rate_source;
+        }
+        bill.local_amount = local_amount;
+        Self::save_bill(&env, &bill);
         RemitwiseEvents::emit(
             &env,
-            EventCategory::System,
-            EventPriority::Medium,
-            symbol_short!("batch_pay"),
-            (paid_count, caller),
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("fx_set"),
+            (bill_id, bill.local_currency, local_amount),
         );
-        Ok(paid_count)
+        Ok(())
     }
 
-    pub fn get_total_unpaid(env: Env, owner: Address) -> i128 {
-        let bills: Map<u32, Bill> = env
+    /// Set `owner`'s monthly budget for `category`. A limit of 0 removes it.
+    pub fn set_category_budget(
+        env: Env,
+        owner: Address,
+        category: Symbol,
+        monthly_limit: i128,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        if monthly_limit < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let mut budgets = Self::get_category_budgets(env.clone(), owner.clone());
+        if monthly_limit == 0 {
+            budgets.remove(category.clone());
+        } else {
+            budgets.set(category.clone(), monthly_limit);
+        }
+        let key = (symbol_short!("BUDGET"), owner.clone());
+        env.storage().persistent().set(&key, &budgets);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("budget"),
+            (owner, category, monthly_limit),
+        );
+        Ok(())
+    }
+
+    pub fn get_category_budgets(env: Env, owner: Address) -> Map<Symbol, i128> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("BUDGET"), owner))
+            .unwrap_or_else(|| Map::new(&env))
+    }
+
+    /// Amount `owner` paid per category in `month`, given as `YYYYMM`
+    /// (UTC), late fees included.
+    pub fn get_spend_by_category(env: Env, owner: Address, month: u32) -> Map<Symbol, i128> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SPEND"), owner, month))
+            .unwrap_or_else(|| Map::new(&env))
+    }
+
+    /// Add a settled bill to its owner's monthly category spend, warning
+    /// when that takes the category over budget.
+    /// Add `amount` to the owner's spend for the month of `timestamp`.
+    /// Refunds pass a negative amount and the original payment time.
+    fn record_spend(env: &Env, bill: &Bill, amount: i128, timestamp: u64) {
+        let month = Self::month_of(timestamp);
+        let key = (symbol_short!("SPEND"), bill.owner.clone(), month);
+        let mut spend: Map<Symbol, i128> = env
             .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let mut total = 0i128;
-        for (_, bill) in bills.iter() {
-            if !bill.paid && bill.owner == owner {
-                total += bill.amount;
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Map::new(env));
+        let total = spend
+            .get(bill.category.clone())
+            .unwrap_or(0)
+            .saturating_add(amount);
+        spend.set(bill.category.clone(), total);
+        env.storage().persistent().set(&key, &spend);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        let limit =
+            Self::get_category_budgets(env.clone(), bill.owner.clone()).get(bill.category.clone());
+        if let Some(limit) = limit {
+            if amount > 0 && total > limit {
+                RemitwiseEvents::emit(
+                    env,
+                    EventCategory::Alert,
+                    EventPriority::Medium,
+                    symbol_short!("over_budg"),
+                    (
+                        bill.owner.clone(),
+                        bill.category.clone(),
+                        month,
+                        total,
+                        limit,
+                    ),
+                );
             }
         }
-        total
     }
 
-    pub fn get_storage_stats(env: Env) -> StorageStats {
+    /// Calendar month of a Unix timestamp as `YYYYMM`.
+    fn month_of(timestamp: u64) -> u32 {
+        // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+        let z = (timestamp / 86400) as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year * 100 + month) as u32
+    }
+
+    // -----------------------------------------------------------------------
+    // Forecast
+    // -----------------------------------------------------------------------
+
+    /// Admin-only: set the insurance and savings contracts the forecast
+    /// reads from. Either may be `None` to leave that part out.
+    pub fn set_forecast_sources(
+        env: Env,
+        caller: Address,
+        insurance: Option<Address>,
+        savings: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller) {
+            return Err(Error::Unauthorized);
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().set(
+            &symbol_short!("FC_SRC"),
+            &ForecastSources { insurance, savings },
+        );
+        Ok(())
+    }
+
+    pub fn get_forecast_sources(env: Env) -> ForecastSources {
         env.storage()
             .instance()
-            .get(&symbol_short!("STOR_STAT"))
-            .unwrap_or(StorageStats {
-                active_bills: 0,
-                archived_bills: 0,
-                total_unpaid_amount: 0,
-                total_archived_amount: 0,
-                last_updated: 0,
+            .get(&symbol_short!("FC_SRC"))
+            .unwrap_or(ForecastSources {
+                insurance: None,
+                savings: None,
             })
     }
 
+    /// Project `owner`'s obligations over the next `months` calendar
+    /// months, starting with the current one (at most
+    /// `MAX_FORECAST_MONTHS`).
+    ///
+    /// Unpaid bills count in the month they fall due, overdue ones in the
+    /// current month. Recurring series are extended up to their end date or
+    /// occurrence cap. Premiums are the owner's total monthly premium from
+    /// the insurance contract, and savings the active schedules in the
+    /// savings contract. A source that is not configured or fails to
+    /// answer contributes nothing.
+    pub fn get_monthly_forecast(env: Env, owner: Address, months: u32) -> Vec<MonthlyForecast> {
+        let months = months.min(MAX_FORECAST_MONTHS);
+        let now = env.ledger().timestamp();
+        let first = Self::month_number(now);
+        // Month slot of a timestamp; overdue amounts land in the first slot
+        let slot = |ts: u64| Self::month_number(ts.max(now)) - first;
+        let mut bills = Vec::new(&env);
+        let mut savings = Vec::new(&env);
+        for _ in 0..months {
+            bills.push_back(0i128);
+            savings.push_back(0i128);
+        }
+        let add = |totals: &mut Vec<i128>, slot: u32, amount: i128| {
+            let total = totals.get(slot).unwrap_or(0).saturating_add(amount);
+            totals.set(slot, total);
+        };
+
+        for id in Self::owner_index(&env, &owner).iter() {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) if !bill.paid => bill,
+                _ => continue,
+            };
+            if slot(bill.due_date) >= months {
+                continue;
+            }
+            add(&mut bills, slot(bill.due_date), bill.amount);
+            if !bill.recurring || bill.series_paused || !Self::is_series_tail(&env, &bill) {
+                continue;
+            }
+            let series = Self::load_series(&env, &bill);
+            let mut occurrences = series.occurrences;
+            let mut due_date = bill.due_date;
+            loop {
+                due_date += bill.frequency_days as u64 * 86400;
+                if series.end_date.is_some_and(|end| due_date > end)
+                    || series.max_occurrences.is_some_and(|max| occurrences >= max)
+                    || slot(due_date) >= months
+                {
+                    break;
+                }
+                add(&mut bills, slot(due_date), bill.amount);
+                occurrences += 1;
+            }
+        }
+
+        let sources = Self::get_forecast_sources(env.clone());
+        let premium = match &sources.insurance {
+            Some(insurance) => {
+                match InsuranceClient::new(&env, insurance).try_get_total_monthly_premium(&owner) {
+                    Ok(Ok(premium)) => premium,
+                    _ => 0,
+                }
+            }
+            None => 0,
+        };
+        if let Some(contract) = &sources.savings {
+            if let Ok(Ok(schedules)) =
+                SavingsGoalsClient::new(&env, contract).try_get_savings_schedules(&owner)
+            {
+                for schedule in schedules.iter() {
+                    if !schedule.active {
+                        continue;
+                    }
+                    let mut due = schedule.next_due;
+                    // Bounded so a very short interval cannot run away
+                    for _ in 0..months * 31 {
+                        if slot(due) >= months {
+                            break;
+                        }
+                        add(&mut savings, slot(due), schedule.amount);
+                        if !schedule.recurring || schedule.interval == 0 {
+                            break;
+                        }
+                        due += schedule.interval;
+                    }
+                }
+            }
+        }
+
+        let mut forecast = Vec::new(&env);
+        for i in 0..months {
+            let number = first + i;
+            let (bills, savings) = (bills.get(i).unwrap(), savings.get(i).unwrap());
+            forecast.push_back(MonthlyForecast {
+                month: (number / 12) * 100 + number % 12 + 1,
+                bills,
+                premiums: premium,
+                savings,
+                total: bills.saturating_add(premium).saturating_add(savings),
+            });
+        }
+        forecast
+    }
+
+    /// Months since year 0 for a timestamp, so slots can be subtracted.
+    fn month_number(timestamp: u64) -> u32 {
+        let month = Self::month_of(timestamp);
+        (month / 100) * 12 + month % 100 - 1
+    }
+
+    // -----------------------------------------------------------------------
+    // Reminders
+    // -----------------------------------------------------------------------
+
+    /// Set when `owner`'s bills trigger reminders. Without a configuration
+    /// the defaults are 7, 3 and 1 days before and every 7 days overdue.
+    pub fn set_reminder_config(
+        env: Env,
+        owner: Address,
+        days_before: Vec<u32>,
+        overdue_every_days: u32,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        if overdue_every_days == 0 || days_before.len() > MAX_REMINDER_WINDOWS {
+            return Err(Error::InvalidReminderConfig);
+        }
+        let config = ReminderConfig {
+            days_before,
+            overdue_every_days,
+        };
+        let key = (symbol_short!("REM_CFG"), owner.clone());
+        env.storage().persistent().set(&key, &config);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("rem_cfg"),
+            (owner, config),
+        );
+        Ok(())
+    }
+
+    pub fn get_reminder_config(env: Env, owner: Address) -> ReminderConfig {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("REM_CFG"), owner))
+            .unwrap_or_else(|| ReminderConfig {
+                days_before: Vec::from_array(&env, [7, 3, 1]),
+                overdue_every_days: 7,
+            })
+    }
+
+    /// Publish a `reminder` event for each unpaid bill that entered a new
+    /// reminder window or overdue escalation level since the last call, so
+    /// off-chain notifiers can forward events without tracking bill state.
+    ///
+    /// Each window and level is reported once per bill. At most
+    /// `MAX_REMINDERS_PER_RUN` reminders go out per call; the cursor carries
+    /// the walk over to the next call and resets at the end. Returns the
+    /// number of reminders published.
+    pub fn emit_due_reminders(env: Env) -> u32 {
+        Self::extend_instance_ttl(&env);
+        let current_time = env.ledger().timestamp();
+        let cursor: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("REM_CUR"))
+            .unwrap_or(0);
+
+        let mut sent = 0u32;
+        let mut last_processed = cursor;
+        let mut next_cursor = 0u32;
+        for bill_id in (cursor + 1)..=Self::last_bill_id(&env) {
+            let bill = match Self::load_bill(&env, bill_id) {
+                Some(bill) if !bill.paid => bill,
+                _ => continue,
+            };
+            let config = Self::get_reminder_config(env.clone(), bill.owner.clone());
+            let key = (symbol_short!("REMIND"), bill_id);
+            let mut state: ReminderState = env.storage().persistent().get(&key).unwrap_or_default();
+
+            let reminder = if current_time > bill.due_date {
+                let days = ((current_time - bill.due_date) / 86400) as u32;
+                let level = days / config.overdue_every_days + 1;
+                if level <= state.overdue_level {
+                    continue;
+                }
+                state.overdue_level = level;
+                (ReminderKind::Overdue, days, level)
+            } else {
+                let window = config
+                    .days_before
+                    .iter()
+                    .filter(|days| bill.due_date - current_time <= *days as u64 * 86400)
+                    .min();
+                match window {
+                    Some(window) if state.upcoming.is_none_or(|sent| window < sent) => {
+                        state.upcoming = Some(window);
+                        (ReminderKind::Upcoming, window, 0)
+                    }
+                    _ => continue,
+                }
+            };
+
+            if sent >= MAX_REMINDERS_PER_RUN {
+                next_cursor = last_processed;
+                break;
+            }
+            sent += 1;
+            last_processed = bill_id;
+
+            env.storage().persistent().set(&key, &state);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ARCHIVE_LIFETIME_THRESHOLD,
+                ARCHIVE_BUMP_AMOUNT,
+            );
+            let (kind, days, level) = reminder;
+            let priority = match kind {
+                ReminderKind::Upcoming => EventPriority::Medium,
+                ReminderKind::Overdue => EventPriority::High,
+            };
+            RemitwiseEvents::emit(
+                &env,
+                EventCategory::Alert,
+                priority,
+                symbol_short!("reminder"),
+                BillReminder {
+                    bill_id,
+                    owner: bill.owner.clone(),
+                    amount: bill.amount,
+                    due_date: bill.due_date,
+                    kind,
+                    days,
+                    level,
+                },
+            );
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("REM_CUR"), &next_cursor);
+        sent
+    }
+
+    // -----------------------------------------------------------------------
+    // Late fees
+    // -----------------------------------------------------------------------
+
+    /// Attach a late fee to an unpaid bill.
+    ///
+    /// The fee accrues lazily from the due date and is charged on top of the
+    /// amount when the bill is paid. Once a bill is overdue its configuration
+    /// can no longer be changed, so an accrued fee cannot be waived.
+    pub fn set_late_fee(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        policy: LateFeePolicy,
+        max_fee: i128,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
+
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+        if env.ledger().timestamp() > bill.due_date {
+            return Err(Error::LateFeeLocked);
+        }
+        if Self::is_shared(&env, bill_id) {
+            return Err(Error::InvalidLateFee);
+        }
+        let valid = match policy {
+            LateFeePolicy::Flat(fee) => fee > 0,
+            LateFeePolicy::BpsPerDay(bps) => bps > 0 && bps <= 10_000,
+        };
+        if !valid || max_fee < 0 {
+            return Err(Error::InvalidLateFee);
+        }
+
+        let config = LateFeeConfig { policy, max_fee };
+        Self::store_late_fee(&env, bill_id, &config);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("late_cfg"),
+            (bill_id, config),
+        );
+        Ok(())
+    }
+
+    pub fn get_late_fee_config(env: Env, bill_id: u32) -> Option<LateFeeConfig> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("LATE_FEE"), bill_id))
+    }
+
+    /// Late fee accrued on an unpaid bill as of the current ledger time.
+    pub fn get_accrued_late_fee(env: Env, bill_id: u32) -> i128 {
+        match Self::load_bill(&env, bill_id) {
+            Some(bill) if !bill.paid => Self::accrued_late_fee(&env, &bill),
+            _ => 0,
+        }
+    }
+
+    fn accrued_late_fee(env: &Env, bill: &Bill) -> i128 {
+        let now = env.ledger().timestamp();
+        if now <= bill.due_date {
+            return 0;
+        }
+        let config: LateFeeConfig = match env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("LATE_FEE"), bill.id))
+        {
+            Some(config) => config,
+            None => return 0,
+        };
+        let fee = match config.policy {
+            LateFeePolicy::Flat(fee) => fee,
+            LateFeePolicy::BpsPerDay(bps) => {
+                let days = ((now - bill.due_date) / 86400) as i128;
+                bill.amount.saturating_mul(bps as i128).saturating_mul(days) / 10_000
+            }
+        };
+        if config.max_fee > 0 {
+            fee.min(config.max_fee)
+        } else {
+            fee
+        }
+    }
+
+    fn store_late_fee(env: &Env, bill_id: u32, config: &LateFeeConfig) {
+        let key = (symbol_short!("LATE_FEE"), bill_id);
+        env.storage().persistent().set(&key, config);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
+
+    /// Add the occurrence of recurring `bill` due at `due_date`, carrying
+    /// over its late fee and split. Returns the new bill's ID.
+    fn queue_next_occurrence(env: &Env, bill: &Bill, due_date: u64) -> u32 {
+        let mut series = Self::load_series(env, bill);
+        let next_id = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(0u32)
+            + 1;
+
+        let next_bill = Bill {
+            id: next_id,
+            owner: bill.owner.clone(),
+            name: bill.name.clone(),
+            amount: bill.amount,
+            due_date,
+            recurring: true,
+            frequency_days: bill.frequency_days,
+            paid: false,
+            created_at: env.ledger().timestamp(),
+            paid_at: None,
+            schedule_id: bill.schedule_id,
+            payee: bill.payee.clone(),
+            token: bill.token.clone(),
+            payee_id: bill.payee_id,
+            series_paused: false,
+            series_id: Some(series.series_id),
+            category: bill.category.clone(),
+            local_currency: bill.local_currency.clone(),
+            local_amount: bill.local_amount,
+            rate_source: bill.rate_source.clone(),
+        };
+        Self::save_bill(env, &next_bill);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_ID"), &next_id);
+        Self::index_bill(env, &bill.owner, next_id);
+        Self::adjust_unpaid(env, &bill.owner, bill.amount);
+        if let Some(config) = Self::get_late_fee_config(env.clone(), bill.id) {
+            Self::store_late_fee(env, next_id, &config);
+        }
+        let shares = Self::get_bill_shares(env.clone(), bill.id);
+        if !shares.is_empty() {
+            let mut next_shares = Vec::new(env);
+            for share in shares.iter() {
+                next_shares.push_back(BillShare {
+                    paid: false,
+                    paid_at: None,
+                    ..share
+                });
+            }
+            Self::store_shares(env, next_id, &next_shares);
+        }
+        series.occurrences += 1;
+        series.bill_ids.push_back(next_id);
+        Self::store_series(env, &series);
+        next_id
+    }
+
+    /// Stop a recurring bill from queuing its next occurrence when paid,
+    /// e.g. school fees over the holidays. The series is kept and can be
+    /// picked up again with `resume_recurring`.
+    pub fn pause_recurring(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        Self::extend_instance_ttl(&env);
+        let mut bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if !bill.recurring {
+            return Err(Error::NotRecurring);
+        }
+        if bill.paid {
+            return Err(Error::BillAlreadyPaid);
+        }
+
+        bill.series_paused = true;
+        Self::save_bill(&env, &bill);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("rec_pause"),
+            bill_id,
+        );
+        Ok(())
+    }
+
+    /// Resume a paused series. If the paused bill was paid in the meantime,
+    /// the next occurrence is queued at the first due date not in the past.
+    ///
+    /// Returns the ID of the queued occurrence, if one was created.
+    pub fn resume_recurring(env: Env, caller: Address, bill_id: u32) -> Result<Option<u32>, Error> {
+        caller.require_auth();
+        Self::extend_instance_ttl(&env);
+        let mut bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if !bill.recurring {
+            return Err(Error::NotRecurring);
+        }
+        if !bill.series_paused {
+            return Ok(None);
+        }
+
+        bill.series_paused = false;
+        let mut next_id = None;
+        if bill.paid && Self::is_series_tail(&env, &bill) {
+            let period = bill.frequency_days as u64 * 86400;
+            let now = env.ledger().timestamp();
+            let mut due_date = bill.due_date + period;
+            if due_date < now {
+                due_date += (now - due_date).div_ceil(period) * period;
+            }
+            if Self::series_allows(&env, &bill, due_date) {
+                next_id = Some(Self::queue_next_occurrence(&env, &bill, due_date));
+            }
+        }
+        Self::save_bill(&env, &bill);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("rec_resum"),
+            (bill_id, next_id),
+        );
+        Ok(next_id)
+    }
+
+    /// Set when a recurring series stops: no occurrence is queued past
+    /// `end_date`, or once `max_occurrences` bills have been created.
+    /// Either can be `None` to leave that side open.
+    pub fn set_series_end(
+        env: Env,
+        caller: Address,
+        bill_id: u32,
+        end_date: Option<u64>,
+        max_occurrences: Option<u32>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
+
+        let bill = Self::get_bill(env.clone(), bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        if !bill.recurring {
+            return Err(Error::NotRecurring);
+        }
+        let mut series = Self::load_series(&env, &bill);
+        if let Some(end_date) = end_date {
+            if end_date < bill.due_date {
+                return Err(Error::InvalidSeriesEnd);
+            }
+        }
+        if let Some(max) = max_occurrences {
+            if max == 0 || max < series.occurrences {
+                return Err(Error::InvalidSeriesEnd);
+            }
+        }
+
+        series.end_date = end_date;
+        series.max_occurrences = max_occurrences;
+        Self::store_series(&env, &series);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("ser_end"),
+            (series.series_id, end_date, max_occurrences),
+        );
+        Ok(())
+    }
+
+    /// The series `bill_id` belongs to, with the IDs of every bill it has
+    /// produced so far.
+    pub fn get_series(env: Env, bill_id: u32) -> Option<BillSeries> {
+        let bill = Self::get_bill(env.clone(), bill_id)?;
+        if !bill.recurring {
+            return None;
+        }
+        Some(Self::load_series(&env, &bill))
+    }
+
+    /// Stored series for `bill`, or a fresh one rooted at the bill for
+    /// recurring bills that predate series tracking.
+    fn load_series(env: &Env, bill: &Bill) -> BillSeries {
+        let series_id = bill.series_id.unwrap_or(bill.id);
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SERIES"), series_id))
+            .unwrap_or_else(|| BillSeries {
+                series_id,
+                end_date: None,
+                max_occurrences: None,
+                occurrences: 1,
+                bill_ids: Vec::from_array(env, [bill.id]),
+                missed_generations: 0,
+            })
+    }
+
+    fn store_series(env: &Env, series: &BillSeries) {
+        let key = (symbol_short!("SERIES"), series.series_id);
+        env.storage().persistent().set(&key, series);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
+
+    /// Queue the next occurrence of every recurring series whose latest bill
+    /// is past due, whether or not that bill has been paid. At most
+    /// `MAX_GENERATIONS_PER_RUN` bills are created per call; the cursor
+    /// carries the walk over to the next call and resets at the end.
+    ///
+    /// An occurrence that is already past due when it is created counts as
+    /// a missed generation on its series. Each call adds at most one bill
+    /// per series, so a keeper that fell behind catches up over several
+    /// runs. Returns the IDs of the new bills.
+    pub fn generate_due_recurring_bills(env: Env) -> Vec<u32> {
+        Self::extend_instance_ttl(&env);
+        let mut created = Vec::new(&env);
+        if Self::require_not_paused(&env, pause_functions::CREATE_BILL).is_err() {
+            return created;
+        }
+
+        let current_time = env.ledger().timestamp();
+        let cursor = Self::get_generation_cursor(env.clone());
+        // Occurrences queued below get IDs past this and wait for a later run
+        let last_id = Self::last_bill_id(&env);
+
+        let mut processed = 0u32;
+        let mut last_processed = cursor;
+        let mut next_cursor = 0u32;
+        for bill_id in (cursor + 1)..=last_id {
+            let bill = match Self::load_bill(&env, bill_id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            if !bill.recurring || bill.series_paused || bill.due_date > current_time {
+                continue;
+            }
+            let next_due_date = bill.due_date + (bill.frequency_days as u64 * 86400);
+            if !Self::is_series_tail(&env, &bill)
+                || !Self::series_allows(&env, &bill, next_due_date)
+            {
+                continue;
+            }
+            if processed >= MAX_GENERATIONS_PER_RUN {
+                next_cursor = last_processed;
+                break;
+            }
+            processed += 1;
+            last_processed = bill_id;
+
+            let next_id = Self::queue_next_occurrence(&env, &bill, next_due_date);
+            created.push_back(next_id);
+            RemitwiseEvents::emit(
+                &env,
+                EventCategory::State,
+                EventPriority::Low,
+                symbol_short!("generated"),
+                (bill_id, next_id, next_due_date),
+            );
+            if next_due_date < current_time {
+                let mut series = Self::load_series(&env, &bill);
+                series.missed_generations += 1;
+                Self::store_series(&env, &series);
+                RemitwiseEvents::emit(
+                    &env,
+                    EventCategory::Alert,
+                    EventPriority::Medium,
+                    symbol_short!("gen_miss"),
+                    (series.series_id, next_id, series.missed_generations),
+                );
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("GEN_CUR"), &next_cursor);
+        created
+    }
+
+    /// Bill ID after which the next `generate_due_recurring_bills` call
+    /// resumes (0 = start).
+    pub fn get_generation_cursor(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("GEN_CUR"))
+            .unwrap_or(0)
+    }
+
+    /// Whether `bill` is the latest bill of its series, i.e. the one the
+    /// next occurrence is generated from.
+    fn is_series_tail(env: &Env, bill: &Bill) -> bool {
+        Self::load_series(env, bill).bill_ids.last() == Some(bill.id)
+    }
+
+    /// Whether `bill`'s series may add another occurrence due at `due_date`.
+    fn series_allows(env: &Env, bill: &Bill, due_date: u64) -> bool {
+        let series = Self::load_series(env, bill);
+        if series.end_date.is_some_and(|end| due_date > end) {
+            return false;
+        }
+        !series
+            .max_occurrences
+            .is_some_and(|max| series.occurrences >= max)
+    }
+
+    pub fn get_bill(env: Env, bill_id: u32) -> Option<Bill> {
+        Self::load_bill(&env, bill_id)
+    }
+
     // -----------------------------------------------------------------------
-    // Internal helpers
-    // -----------------------------------------------------------------------
+    // Payee registry
+    // -----------------------------------------------------------------------
+
+    /// Add a payee to `owner`'s directory.
+    ///
+    /// The pause admin registers shared payees, which start out verified. An
+    /// owner cannot register the same address twice.
+    pub fn register_payee(
+        env: Env,
+        owner: Address,
+        address: Address,
+        name: String,
+        category: Symbol,
+    ) -> Result<u32, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
+        Self::register_payee_internal(&env, owner, address, None, name, category)
+    }
+
+    /// Add a payee by its name in the alias registry.
+    ///
+    /// The alias is resolved now to check it exists, and again whenever a
+    /// token bill is created for the payee.
+    pub fn register_payee_alias(
+        env: Env,
+        owner: Address,
+        alias: String,
+        name: String,
+        category: Symbol,
+    ) -> Result<u32, Error> {
+        owner.require_auth();
+        Self::require_not_paused(&env, pause_functions::CREATE_BILL)?;
+        let address = Self::resolve_alias(&env, &alias)?;
+        Self::register_payee_internal(&env, owner, address, Some(alias), name, category)
+    }
+
+    /// Admin-only: set the alias registry payee aliases resolve against.
+    pub fn set_alias_registry(
+        env: Env,
+        caller: Address,
+        registry: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller) {
+            return Err(Error::Unauthorized);
+        }
+        Self::extend_instance_ttl(&env);
+        match registry {
+            Some(registry) => env
+                .storage()
+                .instance()
+                .set(&symbol_short!("ALIAS_REG"), &registry),
+            None => env.storage().instance().remove(&symbol_short!("ALIAS_REG")),
+        }
+        Ok(())
+    }
+
+    pub fn get_alias_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ALIAS_REG"))
+    }
+
+    fn resolve_alias(env: &Env, alias: &String) -> Result<Address, Error> {
+        let registry = Self::get_alias_registry(env.clone()).ok_or(Error::AliasNotResolved)?;
+        match AliasRegistryClient::new(env, &registry).try_resolve(alias) {
+            Ok(Ok(Some(address))) => Ok(address),
+            _ => Err(Error::AliasNotResolved),
+        }
+    }
+
+    fn register_payee_internal(
+        env: &Env,
+        owner: Address,
+        address: Address,
+        alias: Option<String>,
+        name: String,
+        category: Symbol,
+    ) -> Result<u32, Error> {
+        let env = env.clone();
+
+        Self::extend_instance_ttl(&env);
+        let mut payees: Map<u32, Payee> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAYEES"))
+            .unwrap_or_else(|| Map::new(&env));
+        for (_, payee) in payees.iter() {
+            if payee.owner == owner
+                && (payee.address == address || (alias.is_some() && payee.alias == alias))
+            {
+                return Err(Error::DuplicatePayee);
+            }
+        }
+
+        let id = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_PAY"))
+            .unwrap_or(0u32)
+            + 1;
+        let payee = Payee {
+            id,
+            owner: owner.clone(),
+            address: address.clone(),
+            name,
+            category,
+            verified: Self::get_pause_admin(&env) == Some(owner.clone()),
+            created_at: env.ledger().timestamp(),
+            alias,
+        };
+        payees.set(id, payee);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAYEES"), &payees);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_PAY"), &id);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Low,
+            symbol_short!("payee_reg"),
+            (id, owner, address),
+        );
+        Ok(id)
+    }
+
+    /// Mark a payee as verified or not. Pause admin only.
+    pub fn set_payee_verified(
+        env: Env,
+        caller: Address,
+        payee_id: u32,
+        verified: bool,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        let admin = Self::get_pause_admin(&env).ok_or(Error::Unauthorized)?;
+        if admin != caller {
+            return Err(Error::Unauthorized);
+        }
+        let mut payees: Map<u32, Payee> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAYEES"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut payee = payees.get(payee_id).ok_or(Error::PayeeNotFound)?;
+        payee.verified = verified;
+        payees.set(payee_id, payee);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAYEES"), &payees);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("payee_ver"),
+            (payee_id, verified),
+        );
+        Ok(())
+    }
+
+    pub fn get_payee(env: Env, payee_id: u32) -> Option<Payee> {
+        let payees: Map<u32, Payee> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAYEES"))
+            .unwrap_or_else(|| Map::new(&env));
+        payees.get(payee_id)
+    }
+
+    /// Payees `owner` can bill against: their own and the shared ones.
+    pub fn get_payees(env: Env, owner: Address) -> Vec<Payee> {
+        let payees: Map<u32, Payee> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAYEES"))
+            .unwrap_or_else(|| Map::new(&env));
+        let admin = Self::get_pause_admin(&env);
+        let mut result = Vec::new(&env);
+        for (_, payee) in payees.iter() {
+            if payee.owner == owner || Some(payee.owner.clone()) == admin {
+                result.push_back(payee);
+            }
+        }
+        result
+    }
+
+    /// Page through `owner`'s bills for one payee, paid and unpaid.
+    ///
+    /// Same cursor/limit semantics as `get_unpaid_bills`.
+    pub fn get_payee_bills(
+        env: Env,
+        owner: Address,
+        payee_id: u32,
+        cursor: u32,
+        limit: u32,
+    ) -> BillPage {
+        let limit = Self::clamp_limit(limit);
+
+        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
+        for id in Self::owner_index(&env, &owner).iter() {
+            if id <= cursor {
+                continue;
+            }
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) if bill.payee_id == Some(payee_id) => bill,
+                _ => continue,
+            };
+            staging.push_back((id, bill));
+            if staging.len() > limit {
+                break;
+            }
+        }
+
+        Self::build_page(&env, staging, limit)
+    }
+
+    /// Total `owner` has paid to a payee, including bills since archived.
+    pub fn get_payee_total(env: Env, owner: Address, payee_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PAY_TOT"), owner, payee_id))
+            .unwrap_or(0)
+    }
+
+    // -----------------------------------------------------------------------
+    // PAGINATED LIST QUERIES
+    // -----------------------------------------------------------------------
+
+    /// Get a page of unpaid bills for `owner`.
+    ///
+    /// # Arguments
+    /// * `owner`  – whose bills to return
+    /// * `cursor` – start after this bill ID (pass 0 for the first page)
+    /// * `limit`  – max items per page (0 → DEFAULT_PAGE_LIMIT, capped at MAX_PAGE_LIMIT)
+    ///
+    /// # Returns
+    /// `BillPage { items, next_cursor, count }`.
+    /// When `next_cursor == 0` there are no more pages.
+    pub fn get_unpaid_bills(env: Env, owner: Address, cursor: u32, limit: u32) -> BillPage {
+        let limit = Self::clamp_limit(limit);
+
+        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
+        for id in Self::owner_index(&env, &owner).iter() {
+            if id <= cursor {
+                continue;
+            }
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) if !bill.paid => bill,
+                _ => continue,
+            };
+            staging.push_back((id, bill));
+            if staging.len() > limit {
+                break;
+            }
+        }
+
+        Self::build_page(&env, staging, limit)
+    }
+
+    /// Get a page of ALL bills (paid + unpaid) for `owner`.
+    ///
+    /// Same cursor/limit semantics as `get_unpaid_bills`.
+    pub fn get_all_bills_for_owner(env: Env, owner: Address, cursor: u32, limit: u32) -> BillPage {
+        owner.require_auth();
+        let limit = Self::clamp_limit(limit);
+
+        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
+        for id in Self::owner_index(&env, &owner).iter() {
+            if id <= cursor {
+                continue;
+            }
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            staging.push_back((id, bill));
+            if staging.len() > limit {
+                break;
+            }
+        }
+
+        Self::build_page(&env, staging, limit)
+    }
+
+    /// Get a page of overdue (unpaid + past due_date) bills across all owners.
+    ///
+    /// Same cursor/limit semantics.
+    pub fn get_overdue_bills(env: Env, cursor: u32, limit: u32) -> BillPage {
+        let limit = Self::clamp_limit(limit);
+        let current_time = env.ledger().timestamp();
+
+        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
+        for id in (cursor + 1)..=Self::last_bill_id(&env) {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) if !bill.paid && bill.due_date < current_time => bill,
+                _ => continue,
+            };
+            staging.push_back((id, bill));
+            if staging.len() > limit {
+                break;
+            }
+        }
+
+        Self::build_page(&env, staging, limit)
+    }
+
+    /// Page through `owner`'s active bills matching `filter`, oldest first.
+    ///
+    /// Reads only the owner's index rather than every bill. `offset` counts
+    /// matching bills to skip; the returned `next_cursor` is the offset of
+    /// the next page, or 0 when there are no more.
+    pub fn get_bills_page(
+        env: Env,
+        owner: Address,
+        filter: BillFilter,
+        offset: u32,
+        limit: u32,
+    ) -> BillPage {
+        let limit = Self::clamp_limit(limit);
+        let current_time = env.ledger().timestamp();
+
+        let mut items = Vec::new(&env);
+        let mut skipped = 0u32;
+        let mut has_next = false;
+        for id in Self::owner_index(&env, &owner).iter() {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            let matches = match filter {
+                BillFilter::All => true,
+                BillFilter::Unpaid => !bill.paid,
+                BillFilter::Paid => bill.paid,
+                BillFilter::Overdue => !bill.paid && bill.due_date < current_time,
+            };
+            if !matches {
+                continue;
+            }
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            if items.len() == limit {
+                has_next = true;
+                break;
+            }
+            items.push_back(bill);
+        }
+
+        let count = items.len();
+        BillPage {
+            items,
+            next_cursor: if has_next { offset + count } else { 0 },
+            count,
+        }
+    }
+
+    /// Admin-only: get ALL bills (any owner), paginated.
+    pub fn get_all_bills(
+        env: Env,
+        caller: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<BillPage, Error> {
+        caller.require_auth();
+        let admin = Self::get_pause_admin(&env).ok_or(Error::Unauthorized)?;
+        if admin != caller {
+            return Err(Error::Unauthorized);
+        }
+
+        let limit = Self::clamp_limit(limit);
+
+        let mut staging: Vec<(u32, Bill)> = Vec::new(&env);
+        for id in (cursor + 1)..=Self::last_bill_id(&env) {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            staging.push_back((id, bill));
+            if staging.len() > limit {
+                break;
+            }
+        }
+
+        Ok(Self::build_page(&env, staging, limit))
+    }
+
+    /// Build a `BillPage` from a staging buffer of up to `limit+1` matching items.
+    /// `next_cursor` is set to the last *returned* item's ID so the next call's
+    /// `id <= cursor` filter correctly skips past it.
+    fn build_page(env: &Env, staging: Vec<(u32, Bill)>, limit: u32) -> BillPage {
+        let n = staging.len();
+        let has_next = n > limit;
+        let mut items = Vec::new(env);
+        let mut next_cursor: u32 = 0;
+
+        // Emit all items, or all-but-last if there is a next page
+        let take = if has_next { n - 1 } else { n };
+
+        for i in 0..take {
+            if let Some((_, bill)) = staging.get(i) {
+                items.push_back(bill);
+            }
+        }
+
+        // next_cursor = last returned item's ID (NOT the first skipped item)
+        if has_next {
+            if let Some((id, _)) = staging.get(take - 1) {
+                next_cursor = id;
+            }
+        }
+
+        let count = items.len();
+        BillPage {
+            items,
+            next_cursor,
+            count,
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Backward-compat helpers
+    // -----------------------------------------------------------------------
+
+    /// Legacy helper: returns ALL unpaid bills for owner in one Vec.
+    /// Only safe for owners with a small number of bills. Prefer the
+    /// paginated `get_unpaid_bills` for production use.
+    pub fn get_all_unpaid_bills_legacy(env: Env, owner: Address) -> Vec<Bill> {
+        let mut result = Vec::new(&env);
+        for id in Self::owner_index(&env, &owner).iter() {
+            if let Some(bill) = Self::load_bill(&env, id).filter(|bill| !bill.paid) {
+                result.push_back(bill);
+            }
+        }
+        result
+    }
+
+    // -----------------------------------------------------------------------
+    // Archived bill queries (paginated)
+    // -----------------------------------------------------------------------
+
+    /// Get a page of archived bills for `owner`.
+    pub fn get_archived_bills(
+        env: Env,
+        owner: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> ArchivedBillPage {
+        let limit = Self::clamp_limit(limit);
+        let index: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("OWN_ARCH"), owner))
+            .unwrap_or_else(|| Vec::new(&env));
+
+        let mut staging: Vec<(u32, ArchivedBill)> = Vec::new(&env);
+        for id in index.iter() {
+            if id <= cursor {
+                continue;
+            }
+            let bill = match Self::get_archived_bill(env.clone(), id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            staging.push_back((id, bill));
+            if staging.len() > limit {
+                break;
+            }
+        }
+
+        let has_next = staging.len() > limit;
+        let mut items = Vec::new(&env);
+        let mut next_cursor: u32 = 0;
+        let take = if has_next {
+            staging.len() - 1
+        } else {
+            staging.len()
+        };
+
+        for i in 0..take {
+            if let Some((_, bill)) = staging.get(i) {
+                items.push_back(bill);
+            }
+        }
+        if has_next {
+            if let Some((id, _)) = staging.get(take - 1) {
+                next_cursor = id;
+            }
+        }
+
+        let count = items.len();
+        ArchivedBillPage {
+            items,
+            next_cursor,
+            count,
+        }
+    }
+
+    pub fn get_archived_bill(env: Env, bill_id: u32) -> Option<ArchivedBill> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ARCH"), bill_id))
+    }
+
+    // -----------------------------------------------------------------------
+    // Remaining operations
+    // -----------------------------------------------------------------------
+
+    pub fn cancel_bill(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::CANCEL_BILL)?;
+        let bill = Self::load_bill(&env, bill_id).ok_or(Error::BillNotFound)?;
+        if bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+        Self::delete_bill(&env, bill_id);
+        Self::unindex_bill(&env, &caller, bill_id);
+        if !bill.paid {
+            Self::adjust_unpaid(&env, &caller, -bill.amount);
+        }
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("LATE_FEE"), bill_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SHARES"), bill_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("REMIND"), bill_id));
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("canceled"),
+            bill_id,
+        );
+        Self::apply_retention(&env);
+        Ok(())
+    }
+
+    pub fn archive_paid_bills(
+        env: Env,
+        caller: Address,
+        before_timestamp: u64,
+    ) -> Result<u32, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::ARCHIVE)?;
+        Self::extend_instance_ttl(&env);
+
+        let current_time = env.ledger().timestamp();
+        let mut archived_count = 0u32;
+        let mut archived_amount = 0i128;
+        let mut archive_index = Self::archive_index(&env);
+
+        for id in 1..=Self::last_bill_id(&env) {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            if let Some(paid_at) = bill.paid_at {
+                if bill.paid && paid_at < before_timestamp && !Self::has_open_dispute(&env, id) {
+                    Self::archive_bill(&env, &bill, paid_at, current_time, &mut archive_index);
+                    archived_count += 1;
+                    archived_amount = archived_amount.saturating_add(bill.amount);
+                }
+            }
+        }
+
+        Self::store_archive_index(&env, &archive_index);
+        Self::update_stats(&env, |stats| {
+            stats.archived_bills += archived_count;
+            stats.total_archived_amount =
+                stats.total_archived_amount.saturating_add(archived_amount);
+        });
+
+        RemitwiseEvents::emit_batch(
+            &env,
+            EventCategory::System,
+            symbol_short!("archived"),
+            archived_count,
+        );
+
+        Ok(archived_count)
+    }
+
+    pub fn restore_bill(env: Env, caller: Address, bill_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::RESTORE)?;
+        Self::extend_instance_ttl(&env);
+
+        let archived_bill =
+            Self::get_archived_bill(env.clone(), bill_id).ok_or(Error::BillNotFound)?;
+
+        if archived_bill.owner != caller {
+            return Err(Error::Unauthorized);
+        }
+
+        let restored_bill = Bill {
+            id: archived_bill.id,
+            owner: archived_bill.owner.clone(),
+            name: archived_bill.name.clone(),
+            amount: archived_bill.amount,
+            due_date: env.ledger().timestamp() + 2592000,
+            recurring: false,
+            frequency_days: 0,
+            paid: true,
+            created_at: archived_bill.paid_at,
+            paid_at: Some(archived_bill.paid_at),
+            schedule_id: None,
+            payee: None,
+            token: None,
+            payee_id: None,
+            series_paused: false,
+            series_id: None,
+            category: symbol_short!("other"),
+            local_currency: None,
+            local_amount: 0,
+            rate_source: None,
+        };
+
+        Self::save_bill(&env, &restored_bill);
+        Self::index_bill(&env, &caller, bill_id);
+        Self::remove_archived(&env, &archived_bill);
+        let mut archive_index = Self::archive_index(&env);
+        if let Some(pos) = archive_index.first_index_of(bill_id) {
+            archive_index.remove(pos);
+            Self::store_archive_index(&env, &archive_index);
+        }
+        Self::update_stats(&env, |stats| {
+            stats.archived_bills = stats.archived_bills.saturating_sub(1);
+            stats.total_archived_amount -= archived_bill.amount;
+        });
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::State,
+            EventPriority::Medium,
+            symbol_short!("restored"),
+            bill_id,
+        );
+        Ok(())
+    }
+
+    pub fn bulk_cleanup_bills(
+        env: Env,
+        caller: Address,
+        before_timestamp: u64,
+    ) -> Result<u32, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::ARCHIVE)?;
+        Self::extend_instance_ttl(&env);
+
+        let mut deleted_count = 0u32;
+        let mut deleted_amount = 0i128;
+        let mut kept = Vec::new(&env);
+
+        for id in Self::archive_index(&env).iter() {
+            let bill = match Self::get_archived_bill(env.clone(), id) {
+                Some(bill) => bill,
+                None => continue,
+            };
+            if bill.archived_at < before_timestamp {
+                Self::remove_archived(&env, &bill);
+                deleted_count += 1;
+                deleted_amount = deleted_amount.saturating_add(bill.amount);
+            } else {
+                kept.push_back(id);
+            }
+        }
+
+        Self::store_archive_index(&env, &kept);
+        Self::update_stats(&env, |stats| {
+            stats.archived_bills = stats.archived_bills.saturating_sub(deleted_count);
+            stats.total_archived_amount -= deleted_amount;
+        });
+
+        RemitwiseEvents::emit_batch(
+            &env,
+            EventCategory::System,
+            symbol_short!("cleaned"),
+            deleted_count,
+        );
+        Ok(deleted_count)
+    }
+
+    /// Admin-only: archive paid bills `archive_after_days` after payment
+    /// and delete archived bills `delete_after_days` after archiving.
+    /// Either may be 0 to turn that step off.
+    ///
+    /// The policy is applied a few bills at a time by `pay_bill` and
+    /// `cancel_bill`, so no keeper has to call `archive_paid_bills` or
+    /// `bulk_cleanup_bills`.
+    pub fn set_retention_policy(
+        env: Env,
+        caller: Address,
+        archive_after_days: u32,
+        delete_after_days: u32,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        Self::extend_instance_ttl(&env);
+        let policy = RetentionPolicy {
+            archive_after_days,
+            delete_after_days,
+        };
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RETAIN"), &policy);
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::System,
+            EventPriority::Low,
+            symbol_short!("retention"),
+            (caller, archive_after_days, delete_after_days),
+        );
+        Ok(())
+    }
+
+    pub fn get_retention_policy(env: Env) -> RetentionPolicy {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("RETAIN"))
+            .unwrap_or(RetentionPolicy {
+                archive_after_days: 0,
+                delete_after_days: 0,
+            })
+    }
+
+    /// Apply the retention policy to at most `MAX_RETENTION_CHECKS` bills
+    /// and as many expired archives.
+    ///
+    /// Archives are deleted from the front of the archive index, which is in
+    /// archive order, so the walk stops at the first one still retained.
+    /// Active bills are visited round-robin from a cursor.
+    fn apply_retention(env: &Env) {
+        let policy = Self::get_retention_policy(env.clone());
+        if (policy.archive_after_days == 0 && policy.delete_after_days == 0)
+            || Self::require_not_paused(env, pause_functions::ARCHIVE).is_err()
+        {
+            return;
+        }
+        let now = env.ledger().timestamp();
+        let mut archive_index = Self::archive_index(env);
+        let (mut deleted, mut deleted_amount) = (0u32, 0i128);
+        let (mut archived, mut archived_amount) = (0u32, 0i128);
+
+        if policy.delete_after_days > 0 {
+            let retain = policy.delete_after_days as u64 * 86400;
+            for _ in 0..MAX_RETENTION_CHECKS {
+                let id = match archive_index.first() {
+                    Some(id) => id,
+                    None => break,
+                };
+                match Self::get_archived_bill(env.clone(), id) {
+                    Some(bill) if bill.archived_at + retain > now => break,
+                    Some(bill) => {
+                        Self::remove_archived(env, &bill);
+                        deleted += 1;
+                        deleted_amount = deleted_amount.saturating_add(bill.amount);
+                    }
+                    None => {}
+                }
+                archive_index.pop_front();
+            }
+        }
+
+        let last_id = Self::last_bill_id(env);
+        if policy.archive_after_days > 0 && last_id > 0 {
+            let retain = policy.archive_after_days as u64 * 86400;
+            let mut cursor: u32 = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("RET_CUR"))
+                .unwrap_or(0);
+            for _ in 0..MAX_RETENTION_CHECKS.min(last_id) {
+                cursor = if cursor >= last_id { 1 } else { cursor + 1 };
+                let bill = match Self::load_bill(env, cursor) {
+                    Some(bill) if bill.paid => bill,
+                    _ => continue,
+                };
+                match bill.paid_at {
+                    Some(paid_at)
+                        if paid_at + retain <= now && !Self::has_open_dispute(env, bill.id) =>
+                    {
+                        Self::archive_bill(env, &bill, paid_at, now, &mut archive_index);
+                        archived += 1;
+                        archived_amount = archived_amount.saturating_add(bill.amount);
+                    }
+                    _ => {}
+                }
+            }
+            env.storage()
+                .instance()
+                .set(&symbol_short!("RET_CUR"), &cursor);
+        }
+
+        if archived == 0 && deleted == 0 {
+            return;
+        }
+        Self::store_archive_index(env, &archive_index);
+        Self::update_stats(env, |stats| {
+            stats.archived_bills = (stats.archived_bills + archived).saturating_sub(deleted);
+            stats.total_archived_amount = stats
+                .total_archived_amount
+                .saturating_add(archived_amount)
+                .saturating_sub(deleted_amount);
+        });
+        RemitwiseEvents::emit(
+            env,
+            EventCategory::System,
+            EventPriority::Low,
+            symbol_short!("auto_arch"),
+            (archived, deleted),
+        );
+    }
+
+    /// Move a paid bill to the archive. The caller stores `archive_index`
+    /// and updates the archive stats.
+    fn archive_bill(env: &Env, bill: &Bill, paid_at: u64, now: u64, archive_index: &mut Vec<u32>) {
+        let archived_bill = ArchivedBill {
+            id: bill.id,
+            owner: bill.owner.clone(),
+            name: bill.name.clone(),
+            amount: bill.amount,
+            paid_at,
+            archived_at: now,
+        };
+        Self::save_archived(env, &archived_bill);
+        Self::delete_bill(env, bill.id);
+        Self::unindex_bill(env, &bill.owner, bill.id);
+        Self::update_owner_archive(env, &bill.owner, |index| {
+            Self::insert_sorted(index, bill.id)
+        });
+        archive_index.push_back(bill.id);
+    }
+
+    /// Delete an archived bill and its owner index entry. The caller
+    /// removes it from the archive index and updates the stats.
+    fn remove_archived(env: &Env, bill: &ArchivedBill) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("ARCH"), bill.id));
+        Self::update_owner_archive(env, &bill.owner, |index| {
+            if let Some(pos) = index.first_index_of(bill.id) {
+                index.remove(pos);
+            }
+        });
+    }
+
+    pub fn batch_pay_bills(env: Env, caller: Address, bill_ids: Vec<u32>) -> Result<u32, Error> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::PAY_BILL)?;
+        if bill_ids.len() > (MAX_BATCH_SIZE as usize).try_into().unwrap() {
+            return Err(Error::BatchTooLarge);
+        }
+        for id in bill_ids.iter() {
+            let bill = Self::load_bill(&env, id).ok_or(Error::BillNotFound)?;
+            if bill.owner != caller {
+                return Err(Error::Unauthorized);
+            }
+            if bill.paid {
+                return Err(Error::BillAlreadyPaid);
+            }
+            if Self::is_shared(&env, id) {
+                return Err(Error::SharedBill);
+            }
+            Self::require_approval(&env, &bill)?;
+        }
+        Self::extend_instance_ttl(&env);
+        let mut paid_count = 0u32;
+        for id in bill_ids.iter() {
+            let bill = Self::load_bill(&env, id).ok_or(Error::BillNotFound)?;
+            if bill.owner != caller || bill.paid {
+                return Err(Error::BatchValidationFailed);
+            }
+            let amount = bill.amount;
+            let method = if bill.token.is_some() {
+                SettlementMethod::Token
+            } else {
+                SettlementMethod::Manual
+            };
+            let late_fee =
+                Self::settle_bill(&env, bill, &caller, method, String::from_str(&env, ""));
+            paid_count += 1;
+            RemitwiseEvents::emit(
+                &env,
+                EventCategory::Transaction,
+                EventPriority::High,
+                symbol_short!("paid"),
+                (id, caller.clone(), amount, late_fee),
+            );
+        }
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::System,
+            EventPriority::Medium,
+            symbol_short!("batch_pay"),
+            (paid_count, caller),
+        );
+        Ok(paid_count)
+    }
+
+    pub fn get_total_unpaid(env: Env, owner: Address) -> i128 {
+        let mut total = Self::unpaid_principal(&env, &owner);
+        for id in Self::owner_index(&env, &owner).iter() {
+            let bill = match Self::load_bill(&env, id) {
+                Some(bill) if !bill.paid => bill,
+                _ => continue,
+            };
+            total += Self::accrued_late_fee(&env, &bill);
+            for share in Self::get_bill_shares(env.clone(), id).iter() {
+                if share.paid {
+                    total -= share.amount;
+                }
+            }
+        }
+        total
+    }
+
+    pub fn get_storage_stats(env: Env) -> StorageStats {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("STOR_STAT"))
+            .unwrap_or(StorageStats {
+                active_bills: 0,
+                archived_bills: 0,
+                total_unpaid_amount: 0,
+                total_archived_amount: 0,
+                last_updated: 0,
+            })
+    }
+
+    // -----------------------------------------------------------------------
+    // Snapshot
+    // -----------------------------------------------------------------------
+
+    /// Export active and archived bills, recurring series and the bill
+    /// counter.
+    pub fn export_snapshot(env: Env, caller: Address) -> BillsExportSnapshot {
+        caller.require_auth();
+        let next_id = Self::last_bill_id(&env);
+        let mut bills = Vec::new(&env);
+        let mut series = Vec::new(&env);
+        for id in 1..=next_id {
+            if let Some(bill) = Self::load_bill(&env, id) {
+                bills.push_back(bill);
+            }
+            let stored: Option<BillSeries> = env
+                .storage()
+                .persistent()
+                .get(&(symbol_short!("SERIES"), id));
+            if let Some(stored) = stored {
+                series.push_back(stored);
+            }
+        }
+        let mut archived = Vec::new(&env);
+        for id in Self::archive_index(&env).iter() {
+            if let Some(bill) = Self::get_archived_bill(env.clone(), id) {
+                archived.push_back(bill);
+            }
+        }
+        let mut snapshot = BillsExportSnapshot {
+            version: SNAPSHOT_VERSION,
+            checksum: Bytes::new(&env),
+            next_id,
+            bills,
+            archived,
+            series,
+        };
+        snapshot.checksum = Self::compute_snapshot_checksum(&env, &snapshot);
+        snapshot
+    }
+
+    /// Admin-only: replace all bills, archives and series with a snapshot's.
+    ///
+    /// `nonce` must equal `get_nonce(caller)`, so a signed import cannot be
+    /// replayed. Owner indexes, unpaid totals and storage stats are rebuilt
+    /// from the imported bills and the keeper cursors restart. Per-bill
+    /// records outside the snapshot (late fees, shares, settlements,
+    /// disputes and payment history) are left untouched, so import into a
+    /// fresh deployment.
+    pub fn import_snapshot(
+        env: Env,
+        caller: Address,
+        nonce: u64,
+        snapshot: BillsExportSnapshot,
+    ) -> Result<bool, Error> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        if nonce != Self::get_nonce(env.clone(), caller.clone()) {
+            return Err(Error::InvalidNonce);
+        }
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::UnsupportedVersion);
+        }
+        if snapshot.checksum != Self::compute_snapshot_checksum(&env, &snapshot) {
+            return Err(Error::ChecksumMismatch);
+        }
+        Self::extend_instance_ttl(&env);
+
+        // Drop current bills, archives, series and the records derived from them
+        let storage = env.storage().persistent();
+        for id in 1..=Self::last_bill_id(&env) {
+            if let Some(bill) = Self::load_bill(&env, id) {
+                Self::delete_bill(&env, id);
+                storage.remove(&(symbol_short!("OWN_IDX"), bill.owner.clone()));
+                storage.remove(&(symbol_short!("UNP_AMT"), bill.owner));
+            }
+            storage.remove(&(symbol_short!("SERIES"), id));
+        }
+        for id in Self::archive_index(&env).iter() {
+            if let Some(bill) = Self::get_archived_bill(env.clone(), id) {
+                storage.remove(&(symbol_short!("ARCH"), id));
+                storage.remove(&(symbol_short!("OWN_ARCH"), bill.owner));
+            }
+        }
+        env.storage().instance().remove(&symbol_short!("STOR_STAT"));
+
+        for bill in snapshot.bills.iter() {
+            Self::save_bill(&env, &bill);
+            Self::index_bill(&env, &bill.owner, bill.id);
+            if !bill.paid {
+                Self::adjust_unpaid(&env, &bill.owner, bill.amount);
+            }
+        }
+        let mut archive_index = Vec::new(&env);
+        let mut archived_amount = 0i128;
+        for bill in snapshot.archived.iter() {
+            Self::save_archived(&env, &bill);
+            Self::update_owner_archive(&env, &bill.owner, |index| {
+                Self::insert_sorted(index, bill.id)
+            });
+            archive_index.push_back(bill.id);
+            archived_amount = archived_amount.saturating_add(bill.amount);
+        }
+        Self::store_archive_index(&env, &archive_index);
+        Self::update_stats(&env, |stats| {
+            stats.archived_bills = snapshot.archived.len();
+            stats.total_archived_amount = archived_amount;
+        });
+        for series in snapshot.series.iter() {
+            Self::store_series(&env, &series);
+        }
+
+        let instance = env.storage().instance();
+        instance.set(&symbol_short!("NEXT_ID"), &snapshot.next_id);
+        instance.set(&symbol_short!("GEN_CUR"), &0u32);
+        instance.set(&symbol_short!("REM_CUR"), &0u32);
+        let mut nonces: Map<Address, u64> = instance
+            .get(&symbol_short!("NONCES"))
+            .unwrap_or_else(|| Map::new(&env));
+        nonces.set(caller.clone(), nonce + 1);
+        instance.set(&symbol_short!("NONCES"), &nonces);
+
+        RemitwiseEvents::emit(
+            &env,
+            EventCategory::System,
+            EventPriority::High,
+            symbol_short!("imported"),
+            (caller, snapshot.bills.len(), snapshot.archived.len()),
+        );
+        Ok(true)
+    }
+
+    pub fn get_nonce(env: Env, address: Address) -> u64 {
+        let nonces: Option<Map<Address, u64>> =
+            env.storage().instance().get(&symbol_short!("NONCES"));
+        nonces.and_then(|m| m.get(address)).unwrap_or(0)
+    }
+
+    /// sha256 over the big-endian version followed by the XDR of each field.
+    fn compute_snapshot_checksum(env: &Env, snapshot: &BillsExportSnapshot) -> Bytes {
+        let mut data = Bytes::from_array(env, &snapshot.version.to_be_bytes());
+        data.append(&snapshot.next_id.to_xdr(env));
+        data.append(&snapshot.bills.clone().to_xdr(env));
+        data.append(&snapshot.archived.clone().to_xdr(env));
+        data.append(&snapshot.series.clone().to_xdr(env));
+        env.crypto().sha256(&data).into()
+    }
+
+    // -----------------------------------------------------------------------
+    // Bill storage
+    //
+    // Each bill and archived bill is its own persistent entry, so storage
+    // grows per entry rather than in the instance. Owner indexes and the
+    // archive index stand in for the old maps when listing.
+    // -----------------------------------------------------------------------
+
+    fn load_bill(env: &Env, bill_id: u32) -> Option<Bill> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("BILL"), bill_id))
+    }
+
+    fn save_bill(env: &Env, bill: &Bill) {
+        let key = (symbol_short!("BILL"), bill.id);
+        env.storage().persistent().set(&key, bill);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BILL_LIFETIME_THRESHOLD, BILL_BUMP_AMOUNT);
+    }
+
+    fn delete_bill(env: &Env, bill_id: u32) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("BILL"), bill_id));
+    }
+
+    /// Highest bill ID handed out so far.
+    fn last_bill_id(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(0)
+    }
+
+    fn save_archived(env: &Env, bill: &ArchivedBill) {
+        let key = (symbol_short!("ARCH"), bill.id);
+        env.storage().persistent().set(&key, bill);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
+
+    /// IDs of all archived bills, in archive order.
+    fn archive_index(env: &Env) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&symbol_short!("ARCH_IDX"))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn store_archive_index(env: &Env, index: &Vec<u32>) {
+        let key = symbol_short!("ARCH_IDX");
+        env.storage().persistent().set(&key, index);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
+
+    fn update_owner_archive(env: &Env, owner: &Address, f: impl FnOnce(&mut Vec<u32>)) {
+        let key = (symbol_short!("OWN_ARCH"), owner.clone());
+        let mut index: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        f(&mut index);
+        env.storage().persistent().set(&key, &index);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    /// IDs of `owner`'s active (not archived) bills, ascending.
+    fn owner_index(env: &Env, owner: &Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("OWN_IDX"), owner.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn index_bill(env: &Env, owner: &Address, bill_id: u32) {
+        let mut index = Self::owner_index(env, owner);
+        Self::insert_sorted(&mut index, bill_id);
+        Self::store_owner_index(env, owner, &index);
+        Self::update_stats(env, |stats| stats.active_bills += 1);
+    }
+
+    /// Restored bills go back in ID order; new bills land at the end.
+    fn insert_sorted(index: &mut Vec<u32>, bill_id: u32) {
+        let mut pos = index.len();
+        while pos > 0 && index.get(pos - 1).unwrap() > bill_id {
+            pos -= 1;
+        }
+        index.insert(pos, bill_id);
+    }
+
+    fn unindex_bill(env: &Env, owner: &Address, bill_id: u32) {
+        let mut index = Self::owner_index(env, owner);
+        if let Some(pos) = index.first_index_of(bill_id) {
+            index.remove(pos);
+            Self::store_owner_index(env, owner, &index);
+            Self::update_stats(env, |stats| {
+                stats.active_bills = stats.active_bills.saturating_sub(1)
+            });
+        }
+    }
+
+    fn store_owner_index(env: &Env, owner: &Address, index: &Vec<u32>) {
+        let key = (symbol_short!("OWN_IDX"), owner.clone());
+        env.storage().persistent().set(&key, index);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+    }
+
+    /// Principal of `owner`'s unpaid bills, kept up to date on create, pay,
+    /// update and cancel.
+    fn unpaid_principal(env: &Env, owner: &Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("UNP_AMT"), owner.clone()))
+            .unwrap_or(0)
+    }
+
+    fn adjust_unpaid(env: &Env, owner: &Address, delta: i128) {
+        let key = (symbol_short!("UNP_AMT"), owner.clone());
+        let total = Self::unpaid_principal(env, owner).saturating_add(delta);
+        env.storage().persistent().set(&key, &total);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ARCHIVE_LIFETIME_THRESHOLD,
+            ARCHIVE_BUMP_AMOUNT,
+        );
+        Self::update_stats(env, |stats| {
+            stats.total_unpaid_amount = stats.total_unpaid_amount.saturating_add(delta)
+        });
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+
+    /// Apply `f` to the running storage stats. Counts are maintained as
+    /// bills move between states instead of being recomputed by a scan.
+    fn update_stats(env: &Env, f: impl FnOnce(&mut StorageStats)) {
+        let mut stats = Self::get_storage_stats(env.clone());
+        f(&mut stats);
+        stats.last_updated = env.ledger().timestamp();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("STOR_STAT"), &stats);
+    }
+}
+
+// -----------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+        Env, IntoVal, String,
+    };
+
+    fn make_env() -> Env {
+        Env::default()
+    }
+
+    /// Create `count` bills with a static name. Returns their IDs.
+    /// Due dates are set in the future so they are NOT overdue.
+    fn setup_bills(
+        env: &Env,
+        client: &BillPaymentsClient,
+        owner: &Address,
+        count: u32,
+    ) -> Vec<u32> {
+        let mut ids = Vec::new(env);
+        for i in 0..count {
+            let id = client.create_bill(
+                owner,
+                &String::from_str(env, "Test Bill"),
+                &(100i128 * (i as i128 + 1)),
+                &(env.ledger().timestamp() + 86400 * (i as u64 + 1)),
+                &false,
+                &0,
+            );
+            ids.push_back(id);
+        }
+        ids
+    }
+
+    // --- get_unpaid_bills ---
+
+    #[test]
+    fn test_get_unpaid_bills_empty() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let page = client.get_unpaid_bills(&owner, &0, &0);
+        assert_eq!(page.count, 0);
+        assert_eq!(page.next_cursor, 0);
+        assert_eq!(page.items.len(), 0);
+    }
+
+    #[test]
+    fn test_get_unpaid_bills_single_page() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        setup_bills(&env, &client, &owner, 5);
+
+        let page = client.get_unpaid_bills(&owner, &0, &10);
+        assert_eq!(page.count, 5);
+        assert_eq!(page.next_cursor, 0);
+    }
+
+    #[test]
+    fn test_get_unpaid_bills_multiple_pages() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        setup_bills(&env, &client, &owner, 7);
+
+        let page1 = client.get_unpaid_bills(&owner, &0, &3);
+        assert_eq!(page1.count, 3);
+        assert!(page1.next_cursor > 0, "expected a next cursor");
+
+        let page2 = client.get_unpaid_bills(&owner, &page1.next_cursor, &3);
+        assert_eq!(page2.count, 3);
+        assert!(page2.next_cursor > 0);
+
+        let page3 = client.get_unpaid_bills(&owner, &page2.next_cursor, &3);
+        assert_eq!(page3.count, 1);
+        assert_eq!(page3.next_cursor, 0);
+    }
+
+    #[test]
+    fn test_get_unpaid_bills_excludes_paid() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let ids = setup_bills(&env, &client, &owner, 4);
+        let second_id = ids.get(1).unwrap();
+        client.pay_bill(&owner, &second_id);
+
+        let page = client.get_unpaid_bills(&owner, &0, &10);
+        assert_eq!(page.count, 3);
+    }
+
+    #[test]
+    fn test_get_unpaid_bills_excludes_other_owner() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner_a = Address::generate(&env);
+        let owner_b = Address::generate(&env);
+
+        setup_bills(&env, &client, &owner_a, 3);
+        setup_bills(&env, &client, &owner_b, 2);
+
+        let page = client.get_unpaid_bills(&owner_a, &0, &10);
+        assert_eq!(page.count, 3);
+        for bill in page.items.iter() {
+            assert_eq!(bill.owner, owner_a);
+        }
+    }
+
+    // --- get_overdue_bills ---
+
+    #[test]
+    fn test_get_overdue_bills_not_overdue() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        setup_bills(&env, &client, &owner, 3);
+        let page = client.get_overdue_bills(&0, &10);
+        assert_eq!(page.count, 0);
+    }
+
+    #[test]
+    fn test_get_overdue_bills_pagination() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        for _ in 0..6u32 {
+            client.create_bill(
+                &owner,
+                &String::from_str(&env, "Overdue Bill"),
+                &100,
+                &0,
+                &false,
+                &0,
+            );
+        }
+
+        env.ledger().set_timestamp(1);
+
+        let page1 = client.get_overdue_bills(&0, &4);
+        assert_eq!(page1.count, 4);
+        assert!(page1.next_cursor > 0);
+
+        let page2 = client.get_overdue_bills(&page1.next_cursor, &4);
+        assert_eq!(page2.count, 2);
+        assert_eq!(page2.next_cursor, 0);
+    }
+
+    // --- get_all_bills_for_owner ---
+
+    #[test]
+    fn test_get_all_bills_for_owner_includes_paid() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let ids = setup_bills(&env, &client, &owner, 5);
+        let first_id = ids.get(0).unwrap();
+        client.pay_bill(&owner, &first_id);
+
+        let page = client.get_all_bills_for_owner(&owner, &0, &10);
+        assert_eq!(page.count, 5);
+    }
+
+    // --- limit clamping ---
+
+    #[test]
+    fn test_limit_zero_uses_default() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        setup_bills(&env, &client, &owner, 3);
+        let page = client.get_unpaid_bills(&owner, &0, &0);
+        assert_eq!(page.count, 3);
+    }
+
+    #[test]
+    fn test_limit_clamped_to_max() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        setup_bills(&env, &client, &owner, 55);
+        let page = client.get_unpaid_bills(&owner, &0, &9999);
+        assert_eq!(page.count, MAX_PAGE_LIMIT);
+        assert!(page.next_cursor > 0);
+    }
+
+    // --- archived bill pagination ---
+
+    #[test]
+    fn test_get_archived_bills_pagination() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        client.set_pause_admin(&owner, &owner);
+
+        let ids = setup_bills(&env, &client, &owner, 6);
+        for bill_id in ids.iter() {
+            client.pay_bill(&owner, &bill_id);
+        }
+        client.archive_paid_bills(&owner, &u64::MAX);
+
+        let page1 = client.get_archived_bills(&owner, &0, &4);
+        assert_eq!(page1.count, 4);
+        assert!(page1.next_cursor > 0);
+
+        let page2 = client.get_archived_bills(&owner, &page1.next_cursor, &4);
+        assert_eq!(page2.count, 2);
+        assert_eq!(page2.next_cursor, 0);
+    }
+
+    #[test]
+    fn test_archive_restore_cleanup_keep_entries_and_stats_in_step() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let ids = setup_bills(&env, &client, &owner, 3);
+        let stats = client.get_storage_stats();
+        assert_eq!(stats.active_bills, 3);
+        assert_eq!(stats.total_unpaid_amount, 600);
+
+        client.pay_bill(&owner, &ids.get(0).unwrap());
+        client.pay_bill(&owner, &ids.get(1).unwrap());
+        assert_eq!(client.archive_paid_bills(&owner, &u64::MAX), 2);
+        assert!(client.get_bill(&ids.get(0).unwrap()).is_none());
+        assert!(client.get_archived_bill(&ids.get(0).unwrap()).is_some());
+
+        let stats = client.get_storage_stats();
+        assert_eq!(stats.active_bills, 1);
+        assert_eq!(stats.archived_bills, 2);
+        assert_eq!(stats.total_unpaid_amount, 300);
+        assert_eq!(stats.total_archived_amount, 300);
+
+        client.restore_bill(&owner, &ids.get(0).unwrap());
+        assert!(client.get_bill(&ids.get(0).unwrap()).unwrap().paid);
+        assert!(client.get_archived_bill(&ids.get(0).unwrap()).is_none());
+        assert_eq!(client.get_archived_bills(&owner, &0, &10).count, 1);
+        assert_eq!(client.get_all_bills_for_owner(&owner, &0, &10).count, 2);
+
+        env.ledger().with_mut(|li| li.timestamp += 10);
+        assert_eq!(client.bulk_cleanup_bills(&owner, &u64::MAX), 1);
+        assert!(client.get_archived_bill(&ids.get(1).unwrap()).is_none());
+        let stats = client.get_storage_stats();
+        assert_eq!(stats.active_bills, 2);
+        assert_eq!(stats.archived_bills, 0);
+        assert_eq!(stats.total_archived_amount, 0);
+    }
+
+    // -----------------------------------------------------------------------
+    // RECURRING BILLS DATE MATH TESTS
+    // -----------------------------------------------------------------------
+    // These tests verify the core date math for recurring bills:
+    // next_due_date = due_date + (frequency_days * 86400)
+    // Ensures paid_at does not affect next bill's due_date calculation.
+
+    #[test]
+    fn test_recurring_date_math_frequency_1_day() {
+        // Test: frequency_days = 1 → next due date is +1 day (86400 seconds)
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Daily Bill"),
+            &100,
+            &base_due_date,
+            &true, // recurring
+            &1,    // frequency_days = 1
+        );
+
+        // Pay the bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Verify next bill's due_date = base_due_date + (1 * 86400)
+        let next_bill = client.get_bill(&2).unwrap();
+        assert!(!next_bill.paid, "Next bill should be unpaid");
+        assert_eq!(
+            next_bill.due_date,
+            base_due_date + 86400,
+            "Next due date should be exactly 1 day later"
+        );
+        assert_eq!(next_bill.frequency_days, 1, "Frequency should be preserved");
+    }
+
+    #[test]
+    fn test_recurring_date_math_frequency_30_days() {
+        // Test: frequency_days = 30 → next due date is +30 days (2,592,000 seconds)
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Monthly Bill"),
+            &500,
+            &base_due_date,
+            &true, // recurring
+            &30,   // frequency_days = 30
+        );
+
+        // Pay the bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Verify next bill's due_date = base_due_date + (30 * 86400)
+        let next_bill = client.get_bill(&2).unwrap();
+        assert!(!next_bill.paid, "Next bill should be unpaid");
+        let expected_due_date = base_due_date + (30u64 * 86400);
+        assert_eq!(
+            next_bill.due_date, expected_due_date,
+            "Next due date should be exactly 30 days later"
+        );
+        assert_eq!(
+            next_bill.frequency_days, 30,
+            "Frequency should be preserved"
+        );
+    }
+
+    #[test]
+    fn test_recurring_date_math_frequency_365_days() {
+        // Test: frequency_days = 365 → next due date is +365 days (31,536,000 seconds)
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Annual Bill"),
+            &1200,
+            &base_due_date,
+            &true, // recurring
+            &365,  // frequency_days = 365
+        );
+
+        // Pay the bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Verify next bill's due_date = base_due_date + (365 * 86400)
+        let next_bill = client.get_bill(&2).unwrap();
+        assert!(!next_bill.paid, "Next bill should be unpaid");
+        let expected_due_date = base_due_date + (365u64 * 86400);
+        assert_eq!(
+            next_bill.due_date, expected_due_date,
+            "Next due date should be exactly 365 days later"
+        );
+        assert_eq!(
+            next_bill.frequency_days, 365,
+            "Frequency should be preserved"
+        );
+    }
+
+    #[test]
+    fn test_recurring_date_math_paid_at_does_not_affect_next_due() {
+        // Test: paid_at timestamp does NOT affect next bill's due_date calculation
+        // Bill 1: due_date=1000000, paid_at=1000500 (paid 500 seconds late)
+        // Bill 2: due_date should be 1000000 + (30*86400), NOT 1000500 + (30*86400)
+        let env = make_env();
+        env.ledger().set_timestamp(1_000_500); // Set current time to 500 seconds after due date
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Late Payment Test"),
+            &300,
+            &base_due_date,
+            &true, // recurring
+            &30,   // frequency_days = 30
+        );
+
+        // Pay the bill (at time 1_000_500, which is 500 seconds after due_date)
+        client.pay_bill(&owner, &bill_id);
+
+        // Verify original bill has paid_at set
+        let paid_bill = client.get_bill(&bill_id).unwrap();
+        assert!(paid_bill.paid, "Bill should be marked as paid");
+        assert_eq!(
+            paid_bill.paid_at,
+            Some(1_000_500),
+            "paid_at should be set to current time"
+        );
+
+        // Verify next bill's due_date is based on original due_date, NOT paid_at
+        let next_bill = client.get_bill(&2).unwrap();
+        let expected_due_date = base_due_date + (30u64 * 86400);
+        assert_eq!(
+            next_bill.due_date, expected_due_date,
+            "Next due date should be based on original due_date, not paid_at"
+        );
+        assert!(!next_bill.paid, "Next bill should be unpaid");
+    }
+
+    #[test]
+    fn test_recurring_date_math_multiple_pay_cycles_2nd_bill() {
+        // Test: Multiple pay cycles - verify 2nd bill's due date advances correctly
+        // Bill 1: due_date=1000000, frequency=30
+        // Bill 2: due_date=1000000 + (30*86400)
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Multi-Cycle Bill"),
+            &250,
+            &base_due_date,
+            &true, // recurring
+            &30,   // frequency_days = 30
+        );
+
+        // Pay first bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Verify second bill
+        let bill2 = client.get_bill(&2).unwrap();
+        let expected_bill2_due = base_due_date + (30u64 * 86400);
+        assert_eq!(bill2.due_date, expected_bill2_due);
+        assert!(!bill2.paid);
+
+        // Pay second bill
+        client.pay_bill(&owner, &2);
+
+        // Verify second bill is now paid
+        let bill2_paid = client.get_bill(&2).unwrap();
+        assert!(bill2_paid.paid);
+
+        // Verify third bill was created with correct due_date
+        let bill3 = client.get_bill(&3).unwrap();
+        let expected_bill3_due = expected_bill2_due + (30u64 * 86400);
+        assert_eq!(
+            bill3.due_date, expected_bill3_due,
+            "Bill 3 due_date should be Bill 2 due_date + (30*86400)"
+        );
+        assert!(!bill3.paid);
+    }
+
+    #[test]
+    fn test_recurring_date_math_multiple_pay_cycles_3rd_bill() {
+        // Test: Multiple pay cycles - verify 3rd bill's due date advances correctly
+        // Bill 1: due_date=1000000, frequency=30
+        // Bill 2: due_date=1000000 + (30*86400)
+        // Bill 3: due_date=1000000 + (60*86400)
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Three-Cycle Bill"),
+            &150,
+            &base_due_date,
+            &true, // recurring
+            &30,   // frequency_days = 30
+        );
+
+        // Pay first bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Pay second bill
+        client.pay_bill(&owner, &2);
+
+        // Pay third bill
+        client.pay_bill(&owner, &3);
+
+        // Verify third bill is now paid
+        let bill3_paid = client.get_bill(&3).unwrap();
+        assert!(bill3_paid.paid);
+
+        // Verify fourth bill was created with correct due_date
+        let bill4 = client.get_bill(&4).unwrap();
+        let expected_bill4_due = base_due_date + (90u64 * 86400); // 3 * 30 days
+        assert_eq!(
+            bill4.due_date, expected_bill4_due,
+            "Bill 4 due_date should be base + (90*86400)"
+        );
+        assert!(!bill4.paid);
+    }
+
+    #[test]
+    fn test_recurring_date_math_early_payment_does_not_affect_schedule() {
+        // Test: Paying a bill EARLY should not affect the next bill's due_date
+        // Bill 1: due_date=1000000, paid at time=500000 (paid 500000 seconds early)
+        // Bill 2: due_date should still be 1000000 + (30*86400)
+        let env = make_env();
+        env.ledger().set_timestamp(500_000); // Set time BEFORE due date
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due_date = 1_000_000u64;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Early Payment Test"),
+            &200,
+            &base_due_date,
+            &true, // recurring
+            &30,   // frequency_days = 30
+        );
+
+        // Pay the bill early (at time 500_000)
+        client.pay_bill(&owner, &bill_id);
+
+        // Verify original bill has paid_at set to early time
+        let paid_bill = client.get_bill(&bill_id).unwrap();
+        assert!(paid_bill.paid);
+        assert_eq!(paid_bill.paid_at, Some(500_000));
+
+        // Verify next bill's due_date is still based on original due_date
+        let next_bill = client.get_bill(&2).unwrap();
+        let expected_due_date = base_due_date + (30u64 * 86400);
+        assert_eq!(
+            next_bill.due_date, expected_due_date,
+            "Next due date should not be affected by early payment"
+        );
+    }
+
+    #[test]
+    fn test_recurring_date_math_preserves_frequency_across_cycles() {
+        // Test: frequency_days is preserved across all recurring cycles
+        // Verify that Bill 1, 2, 3 all have the same frequency_days value
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let frequency = 7u32; // Weekly
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Weekly Bill"),
+            &50,
+            &1_000_000,
+            &true,
+            &frequency,
+        );
+
+        // Pay first bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Pay second bill
+        client.pay_bill(&owner, &2);
+
+        // Verify all bills have the same frequency_days
+        let bill1 = client.get_bill(&1).unwrap();
+        let bill2 = client.get_bill(&2).unwrap();
+        let bill3 = client.get_bill(&3).unwrap();
+
+        assert_eq!(bill1.frequency_days, frequency);
+        assert_eq!(bill2.frequency_days, frequency);
+        assert_eq!(bill3.frequency_days, frequency);
+    }
+
+    #[test]
+    fn test_recurring_date_math_amount_preserved_across_cycles() {
+        // Test: Bill amount is preserved across all recurring cycles
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let amount = 999i128;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Fixed Amount Bill"),
+            &amount,
+            &1_000_000,
+            &true,
+            &30,
+        );
+
+        // Pay first bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Pay second bill
+        client.pay_bill(&owner, &2);
+
+        // Verify all bills have the same amount
+        let bill1 = client.get_bill(&1).unwrap();
+        let bill2 = client.get_bill(&2).unwrap();
+        let bill3 = client.get_bill(&3).unwrap();
+
+        assert_eq!(bill1.amount, amount);
+        assert_eq!(bill2.amount, amount);
+        assert_eq!(bill3.amount, amount);
+    }
+
+    #[test]
+    fn test_recurring_date_math_name_preserved_across_cycles() {
+        // Test: Bill name is preserved across all recurring cycles
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let name = String::from_str(&env, "Rent Payment");
+        let bill_id = client.create_bill(&owner, &name, &1000, &1_000_000, &true, &30);
+
+        // Pay first bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Pay second bill
+        client.pay_bill(&owner, &2);
+
+        // Verify all bills have the same name
+        let bill1 = client.get_bill(&1).unwrap();
+        let bill2 = client.get_bill(&2).unwrap();
+        let bill3 = client.get_bill(&3).unwrap();
+
+        assert_eq!(bill1.name, name);
+        assert_eq!(bill2.name, name);
+        assert_eq!(bill3.name, name);
+    }
+
+    #[test]
+    fn test_recurring_date_math_owner_preserved_across_cycles() {
+        // Test: Bill owner is preserved across all recurring cycles
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Owner Test"),
+            &100,
+            &1_000_000,
+            &true,
+            &30,
+        );
+
+        // Pay first bill
+        client.pay_bill(&owner, &bill_id);
+
+        // Pay second bill
+        client.pay_bill(&owner, &2);
+
+        // Verify all bills have the same owner
+        let bill1 = client.get_bill(&1).unwrap();
+        let bill2 = client.get_bill(&2).unwrap();
+        let bill3 = client.get_bill(&3).unwrap();
+
+        assert_eq!(bill1.owner, owner);
+        assert_eq!(bill2.owner, owner);
+        assert_eq!(bill3.owner, owner);
+    }
+
+    #[test]
+    fn test_recurring_date_math_exact_calculation_verification() {
+        // Test: Verify exact date math calculation with known values
+        // due_date = 1_000_000
+        // frequency_days = 14
+        // Expected: 1_000_000 + (14 * 86400) = 1_000_000 + 1_209_600 = 2_209_600
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let base_due = 1_000_000u64;
+        let freq = 14u32;
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Math Verification"),
+            &100,
+            &base_due,
+            &true,
+            &freq,
+        );
 
-    fn extend_instance_ttl(env: &Env) {
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    }
+        client.pay_bill(&owner, &bill_id);
 
-    fn extend_archive_ttl(env: &Env) {
-        env.storage()
-            .instance()
-            .extend_ttl(ARCHIVE_LIFETIME_THRESHOLD, ARCHIVE_BUMP_AMOUNT);
+        let next_bill = client.get_bill(&2).unwrap();
+        let expected = 1_000_000u64 + (14u64 * 86400);
+        assert_eq!(next_bill.due_date, expected);
+        assert_eq!(next_bill.due_date, 2_209_600);
     }
 
-    fn update_storage_stats(env: &Env) {
-        let bills: Map<u32, Bill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("BILLS"))
-            .unwrap_or_else(|| Map::new(env));
-        let archived: Map<u32, ArchivedBill> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("ARCH_BILL"))
-            .unwrap_or_else(|| Map::new(env));
-
-        let mut active_count = 0u32;
-        let mut unpaid_amount = 0i128;
-        for (_, bill) in bills.iter() {
-            active_count += 1;
-            if !bill.paid {
-                unpaid_amount = unpaid_amount.saturating_add(bill.amount);
-            }
-        }
+    // --- token settlement ---
 
-        let mut archived_count = 0u32;
-        let mut archived_amount = 0i128;
-        for (_, bill) in archived.iter() {
-            archived_count += 1;
-            archived_amount = archived_amount.saturating_add(bill.amount);
-        }
+    #[test]
+    fn test_token_bill_transfers_to_payee() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
 
-        let stats = StorageStats {
-            active_bills: active_count,
-            archived_bills: archived_count,
-            total_unpaid_amount: unpaid_amount,
-            total_archived_amount: archived_amount,
-            last_updated: env.ledger().timestamp(),
-        };
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+        let utility = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("STOR_STAT"), &stats);
-    }
-}
+        let bill_id = client.create_token_bill(
+            &owner,
+            &String::from_str(&env, "Power"),
+            &300,
+            &(env.ledger().timestamp() + 86400),
+            &true,
+            &30,
+            &utility,
+            &token.address(),
+        );
+        client.pay_bill(&owner, &bill_id);
+        assert_eq!(balance.balance(&owner), 700);
+        assert_eq!(balance.balance(&utility), 300);
 
-// -----------------------------------------------------------------------
-// Tests
-// -----------------------------------------------------------------------
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{
-        testutils::{Address as _, Ledger},
-        Env, String,
-    };
+        let settlement = client.get_settlement(&bill_id).unwrap();
+        assert_eq!(settlement.method, SettlementMethod::Token);
+        assert_eq!(settlement.payee, Some(utility.clone()));
+        assert_eq!(settlement.amount, 300);
 
-    fn make_env() -> Env {
-        Env::default()
-    }
+        // The next occurrence keeps the payee and token
+        let next = client.get_bill(&2).unwrap();
+        assert_eq!(next.payee, Some(utility.clone()));
+        client.batch_pay_bills(&owner, &Vec::from_array(&env, [2]));
+        assert_eq!(balance.balance(&utility), 600);
 
-    /// Create `count` bills with a static name. Returns their IDs.
-    /// Due dates are set in the future so they are NOT overdue.
-    fn setup_bills(
-        env: &Env,
-        client: &BillPaymentsClient,
-        owner: &Address,
-        count: u32,
-    ) -> Vec<u32> {
-        let mut ids = Vec::new(env);
-        for i in 0..count {
-            let id = client.create_bill(
-                owner,
-                &String::from_str(env, "Test Bill"),
-                &(100i128 * (i as i128 + 1)),
-                &(env.ledger().timestamp() + 86400 * (i as u64 + 1)),
+        assert_eq!(
+            client.try_create_token_bill(
+                &owner,
+                &String::from_str(&env, "Self"),
+                &10,
+                &0,
                 &false,
                 &0,
-            );
-            ids.push_back(id);
-        }
-        ids
+                &owner,
+                &token.address(),
+            ),
+            Err(Ok(Error::InvalidPayee))
+        );
     }
 
-    // --- get_unpaid_bills ---
-
     #[test]
-    fn test_get_unpaid_bills_empty() {
+    fn test_mark_paid_offchain_records_reference() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let ids = setup_bills(&env, &client, &owner, 2);
+
+        let reference = String::from_str(&env, "BANK-2291");
+        client.mark_paid_offchain(&owner, &ids.get(0).unwrap(), &reference);
+        let settlement = client.get_settlement(&ids.get(0).unwrap()).unwrap();
+        assert_eq!(settlement.method, SettlementMethod::OffChain);
+        assert_eq!(settlement.reference, reference);
+        assert!(client.get_bill(&ids.get(0).unwrap()).unwrap().paid);
+        assert_eq!(
+            client.try_mark_paid_offchain(&owner, &ids.get(0).unwrap(), &reference),
+            Err(Ok(Error::BillAlreadyPaid))
+        );
 
-        let page = client.get_unpaid_bills(&owner, &0, &0);
-        assert_eq!(page.count, 0);
-        assert_eq!(page.next_cursor, 0);
-        assert_eq!(page.items.len(), 0);
+        // A bill without a token is simply recorded as paid
+        client.pay_bill(&owner, &ids.get(1).unwrap());
+        assert_eq!(
+            client.get_settlement(&ids.get(1).unwrap()).unwrap().method,
+            SettlementMethod::Manual
+        );
     }
 
+    // --- payee registry ---
+
     #[test]
-    fn test_get_unpaid_bills_single_page() {
+    fn test_payee_registry_drives_bills_and_totals() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let utility = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
 
-        setup_bills(&env, &client, &owner, 5);
+        let school = client.register_payee(
+            &owner,
+            &Address::generate(&env),
+            &String::from_str(&env, "School"),
+            &symbol_short!("education"),
+        );
+        let power = client.register_payee(
+            &admin,
+            &utility,
+            &String::from_str(&env, "City Power"),
+            &symbol_short!("utility"),
+        );
+        assert!(!client.get_payee(&school).unwrap().verified);
+        assert!(client.get_payee(&power).unwrap().verified);
+        assert_eq!(client.get_payees(&owner).len(), 2);
+        assert_eq!(
+            client.try_register_payee(
+                &owner,
+                &client.get_payee(&school).unwrap().address,
+                &String::from_str(&env, "Again"),
+                &symbol_short!("education"),
+            ),
+            Err(Ok(Error::DuplicatePayee))
+        );
+        client.set_payee_verified(&admin, &school, &true);
+        assert!(client.get_payee(&school).unwrap().verified);
 
-        let page = client.get_unpaid_bills(&owner, &0, &10);
-        assert_eq!(page.count, 5);
-        assert_eq!(page.next_cursor, 0);
+        let first = client.create_payee_bill(&owner, &power, &120, &86_400, &true, &30, &None);
+        assert_eq!(
+            client.get_bill(&first).unwrap().name,
+            String::from_str(&env, "City Power")
+        );
+        assert_eq!(
+            client.try_create_payee_bill(&owner, &power, &120, &86_400, &false, &0, &None),
+            Err(Ok(Error::DuplicateBill))
+        );
+        client.create_payee_bill(&owner, &school, &500, &86_400, &false, &0, &None);
+
+        client.pay_bill(&owner, &first);
+        // Paying the recurring bill queues the next one for the same payee
+        let page = client.get_payee_bills(&owner, &power, &0, &0);
+        assert_eq!(page.count, 2);
+        assert_eq!(client.get_payee_total(&owner, &power), 120);
+        assert_eq!(client.get_payee_total(&owner, &school), 0);
+
+        // Another owner's payees are not usable
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_create_payee_bill(&stranger, &school, &10, &86_400, &false, &0, &None),
+            Err(Ok(Error::PayeeNotFound))
+        );
+    }
+
+    #[contract]
+    pub struct MockAliasRegistry;
+
+    #[contractimpl]
+    impl MockAliasRegistry {
+        pub fn point(env: Env, name: String, target: Address) {
+            env.storage().instance().set(&name, &target);
+        }
+
+        pub fn resolve(env: Env, name: String) -> Option<Address> {
+            env.storage().instance().get(&name)
+        }
     }
 
     #[test]
-    fn test_get_unpaid_bills_multiple_pages() {
+    fn test_payee_registered_by_alias_follows_updates() {
+        use soroban_sdk::token::StellarAssetClient;
+
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
 
-        setup_bills(&env, &client, &owner, 7);
+        let alias = String::from_str(&env, "landlord.kisumu");
+        let name = String::from_str(&env, "Rent");
+        assert_eq!(
+            client.try_register_payee_alias(&owner, &alias, &name, &symbol_short!("housing")),
+            Err(Ok(Error::AliasNotResolved))
+        );
 
-        let page1 = client.get_unpaid_bills(&owner, &0, &3);
-        assert_eq!(page1.count, 3);
-        assert!(page1.next_cursor > 0, "expected a next cursor");
+        let registry_id = env.register_contract(None, MockAliasRegistry);
+        let registry = MockAliasRegistryClient::new(&env, &registry_id);
+        let first = Address::generate(&env);
+        registry.point(&alias, &first);
+        assert_eq!(
+            client.try_set_alias_registry(&owner, &Some(registry_id.clone())),
+            Err(Ok(Error::Unauthorized))
+        );
+        client.set_alias_registry(&admin, &Some(registry_id.clone()));
+        assert_eq!(client.get_alias_registry(), Some(registry_id));
 
-        let page2 = client.get_unpaid_bills(&owner, &page1.next_cursor, &3);
-        assert_eq!(page2.count, 3);
-        assert!(page2.next_cursor > 0);
+        let payee = client.register_payee_alias(&owner, &alias, &name, &symbol_short!("housing"));
+        assert_eq!(client.get_payee(&payee).unwrap().alias, Some(alias.clone()));
+        assert_eq!(client.get_payee(&payee).unwrap().address, first);
+        assert_eq!(
+            client.try_register_payee_alias(&owner, &alias, &name, &symbol_short!("housing")),
+            Err(Ok(Error::DuplicatePayee))
+        );
 
-        let page3 = client.get_unpaid_bills(&owner, &page2.next_cursor, &3);
-        assert_eq!(page3.count, 1);
-        assert_eq!(page3.next_cursor, 0);
+        // The landlord moves to a new address; new bills settle there
+        let second = Address::generate(&env);
+        registry.point(&alias, &second);
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &1_000);
+        let bill = client.create_payee_bill(
+            &owner,
+            &payee,
+            &500,
+            &86_400,
+            &false,
+            &0,
+            &Some(token.address()),
+        );
+        assert_eq!(client.get_bill(&bill).unwrap().payee, Some(second));
+    }
+
+    #[contract]
+    pub struct MockReputation;
+
+    #[contractimpl]
+    impl MockReputation {
+        pub fn record_bill_payment(
+            env: Env,
+            reporter: Address,
+            subject: Address,
+            on_time: bool,
+        ) -> u32 {
+            reporter.require_auth();
+            let mut reports: Vec<bool> = env
+                .storage()
+                .instance()
+                .get(&subject)
+                .unwrap_or_else(|| Vec::new(&env));
+            reports.push_back(on_time);
+            env.storage().instance().set(&subject, &reports);
+            500
+        }
+
+        pub fn reports(env: Env, subject: Address) -> Vec<bool> {
+            env.storage()
+                .instance()
+                .get(&subject)
+                .unwrap_or_else(|| Vec::new(&env))
+        }
     }
 
     #[test]
-    fn test_get_unpaid_bills_excludes_paid() {
+    fn test_token_payments_reported_to_reputation() {
+        use soroban_sdk::token::StellarAssetClient;
+
         let env = make_env();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
 
-        let ids = setup_bills(&env, &client, &owner, 4);
-        let second_id = ids.get(1).unwrap();
-        client.pay_bill(&owner, &second_id);
+        let reputation_id = env.register_contract(None, MockReputation);
+        assert_eq!(
+            client.try_set_reputation_contract(&owner, &Some(reputation_id.clone())),
+            Err(Ok(Error::Unauthorized))
+        );
+        client.set_reputation_contract(&admin, &Some(reputation_id.clone()));
+        assert_eq!(
+            client.get_reputation_contract(),
+            Some(reputation_id.clone())
+        );
 
-        let page = client.get_unpaid_bills(&owner, &0, &10);
-        assert_eq!(page.count, 3);
-    }
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &1_000);
+        let utility = Address::generate(&env);
+        let on_time = client.create_token_bill(
+            &owner,
+            &String::from_str(&env, "Power"),
+            &100,
+            &2_000,
+            &false,
+            &0,
+            &utility,
+            &token.address(),
+        );
+        let late = client.create_token_bill(
+            &owner,
+            &String::from_str(&env, "Water"),
+            &100,
+            &3_000,
+            &false,
+            &0,
+            &utility,
+            &token.address(),
+        );
+        client.pay_bill(&owner, &on_time);
+        env.ledger().set_timestamp(3_001);
+        client.pay_bill(&owner, &late);
 
-    #[test]
-    fn test_get_unpaid_bills_excludes_other_owner() {
-        let env = make_env();
-        env.mock_all_auths();
-        let cid = env.register_contract(None, BillPayments);
-        let client = BillPaymentsClient::new(&env, &cid);
-        let owner_a = Address::generate(&env);
-        let owner_b = Address::generate(&env);
+        // Bills without a token are self-declared and not reported
+        let manual = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Rent"),
+            &100,
+            &4_000,
+            &false,
+            &0,
+        );
+        client.pay_bill(&owner, &manual);
 
-        setup_bills(&env, &client, &owner_a, 3);
-        setup_bills(&env, &client, &owner_b, 2);
+        let reports = MockReputationClient::new(&env, &reputation_id).reports(&owner);
+        assert_eq!(reports, Vec::from_array(&env, [true, false]));
+    }
 
-        let page = client.get_unpaid_bills(&owner_a, &0, &10);
-        assert_eq!(page.count, 3);
-        for bill in page.items.iter() {
-            assert_eq!(bill.owner, owner_a);
+    /// Holds one voucher's tokens and pays out only for `school` bills
+    #[contract]
+    pub struct MockVouchers;
+
+    #[contractimpl]
+    impl MockVouchers {
+        #[allow(clippy::too_many_arguments)]
+        pub fn redeem(
+            env: Env,
+            redeemer: Address,
+            _voucher_id: u32,
+            holder: Address,
+            token: Address,
+            amount: i128,
+            category: Symbol,
+            to: Address,
+        ) -> i128 {
+            redeemer.require_auth();
+            holder.require_auth();
+            assert_eq!(category, symbol_short!("school"));
+            TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+            TokenClient::new(&env, &token).balance(&env.current_contract_address())
         }
     }
 
-    // --- get_overdue_bills ---
-
     #[test]
-    fn test_get_overdue_bills_not_overdue() {
+    fn test_pay_bill_with_voucher() {
+        use soroban_sdk::token::StellarAssetClient;
+
         let env = make_env();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let vouchers_id = env.register_contract(None, MockVouchers);
+        StellarAssetClient::new(&env, &token.address()).mint(&vouchers_id, &500);
+        let school = Address::generate(&env);
+        let school_payee = client.register_payee(
+            &admin,
+            &school,
+            &String::from_str(&env, "School"),
+            &symbol_short!("school"),
+        );
+        let fees = client.create_payee_bill(
+            &owner,
+            &school_payee,
+            &300,
+            &2_000,
+            &false,
+            &0,
+            &Some(token.address()),
+        );
 
-        setup_bills(&env, &client, &owner, 3);
-        let page = client.get_overdue_bills(&0, &10);
-        assert_eq!(page.count, 0);
+        assert_eq!(
+            client.try_pay_bill_with_voucher(&owner, &fees, &1),
+            Err(Ok(Error::VoucherNotUsable))
+        );
+        assert_eq!(
+            client.try_set_vouchers_contract(&owner, &Some(vouchers_id.clone())),
+            Err(Ok(Error::Unauthorized))
+        );
+        client.set_vouchers_contract(&admin, &Some(vouchers_id.clone()));
+        assert_eq!(client.get_vouchers_contract(), Some(vouchers_id.clone()));
+
+        client.pay_bill_with_voucher(&owner, &fees, &1);
+        let token_client = TokenClient::new(&env, &token.address());
+        assert_eq!(token_client.balance(&school), 300);
+        assert_eq!(token_client.balance(&owner), 0);
+        assert!(client.get_bill(&fees).unwrap().paid);
+        assert_eq!(
+            client.get_settlement(&fees).unwrap().method,
+            SettlementMethod::Voucher
+        );
+
+        // The voucher decides what it may pay for
+        let power_payee = client.register_payee(
+            &admin,
+            &Address::generate(&env),
+            &String::from_str(&env, "Power"),
+            &symbol_short!("power"),
+        );
+        let power = client.create_payee_bill(
+            &owner,
+            &power_payee,
+            &100,
+            &2_000,
+            &false,
+            &0,
+            &Some(token.address()),
+        );
+        assert_eq!(
+            client.try_pay_bill_with_voucher(&owner, &power, &1),
+            Err(Ok(Error::VoucherNotUsable))
+        );
+
+        // The owner cannot send voucher funds to a payee of their choosing
+        let friend = Address::generate(&env);
+        let raw = client.create_token_bill(
+            &owner,
+            &String::from_str(&env, "Tuition"),
+            &100,
+            &2_000,
+            &false,
+            &0,
+            &friend,
+            &token.address(),
+        );
+        client.set_bill_category(&owner, &raw, &symbol_short!("school"));
+        assert_eq!(
+            client.try_pay_bill_with_voucher(&owner, &raw, &1),
+            Err(Ok(Error::VoucherNotUsable))
+        );
+        let own_payee = client.register_payee(
+            &owner,
+            &friend,
+            &String::from_str(&env, "Tutor"),
+            &symbol_short!("school"),
+        );
+        let unverified = client.create_payee_bill(
+            &owner,
+            &own_payee,
+            &100,
+            &2_000,
+            &false,
+            &0,
+            &Some(token.address()),
+        );
+        assert_eq!(
+            client.try_pay_bill_with_voucher(&owner, &unverified, &1),
+            Err(Ok(Error::VoucherNotUsable))
+        );
+        assert_eq!(token_client.balance(&friend), 0);
+        assert_eq!(token_client.balance(&vouchers_id), 200);
+
+        // Voucher funds are never refunded to the owner as plain tokens
+        let reason = BytesN::from_array(&env, &[3; 32]);
+        client.dispute_payment(&owner, &fees, &reason);
+        for outcome in [DisputeStatus::Reopened, DisputeStatus::Voided] {
+            assert_eq!(
+                client.try_resolve_dispute(&admin, &fees, &outcome),
+                Err(Ok(Error::VoucherNotRefundable))
+            );
+        }
+        client.resolve_dispute(&admin, &fees, &DisputeStatus::Rejected);
+        assert_eq!(token_client.balance(&owner), 0);
+        assert_eq!(token_client.balance(&school), 300);
+        assert!(client.get_bill(&fees).unwrap().paid);
     }
 
+    // --- late fees ---
+
     #[test]
-    fn test_get_overdue_bills_pagination() {
+    fn test_late_fee_accrues_and_is_charged_on_payment() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
         let env = make_env();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let utility = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &10_000);
+        let balance = TokenClient::new(&env, &token.address());
 
-        for _ in 0..6u32 {
-            client.create_bill(
-                &owner,
-                &String::from_str(&env, "Overdue Bill"),
-                &100,
-                &0,
-                &false,
-                &0,
-            );
-        }
-
-        env.ledger().set_timestamp(1);
+        let due = 1_000 + 86_400;
+        let bill_id = client.create_token_bill(
+            &owner,
+            &String::from_str(&env, "Rent"),
+            &1_000,
+            &due,
+            &true,
+            &30,
+            &utility,
+            &token.address(),
+        );
+        assert_eq!(
+            client.try_set_late_fee(&owner, &bill_id, &LateFeePolicy::BpsPerDay(0), &0),
+            Err(Ok(Error::InvalidLateFee))
+        );
+        // 1% per day, capped at 50
+        client.set_late_fee(&owner, &bill_id, &LateFeePolicy::BpsPerDay(100), &50);
+        assert_eq!(client.get_accrued_late_fee(&bill_id), 0);
+
+        // Three full days late
+        env.ledger().set_timestamp(due + 3 * 86_400 + 100);
+        assert_eq!(client.get_accrued_late_fee(&bill_id), 30);
+        assert_eq!(client.get_total_unpaid(&owner), 1_030);
+        assert_eq!(
+            client.try_set_late_fee(&owner, &bill_id, &LateFeePolicy::Flat(1), &0),
+            Err(Ok(Error::LateFeeLocked))
+        );
 
-        let page1 = client.get_overdue_bills(&0, &4);
-        assert_eq!(page1.count, 4);
-        assert!(page1.next_cursor > 0);
+        // The cap applies after five days
+        env.ledger().set_timestamp(due + 10 * 86_400);
+        assert_eq!(client.get_accrued_late_fee(&bill_id), 50);
 
-        let page2 = client.get_overdue_bills(&page1.next_cursor, &4);
-        assert_eq!(page2.count, 2);
-        assert_eq!(page2.next_cursor, 0);
+        client.pay_bill(&owner, &bill_id);
+        assert_eq!(balance.balance(&utility), 1_050);
+        let settlement = client.get_settlement(&bill_id).unwrap();
+        assert_eq!(settlement.amount, 1_000);
+        assert_eq!(settlement.late_fee, 50);
+        assert_eq!(client.get_accrued_late_fee(&bill_id), 0);
+
+        // The next occurrence inherits the configuration
+        let next = client.get_bill(&2).unwrap();
+        assert_eq!(
+            client.get_late_fee_config(&next.id).unwrap().policy,
+            LateFeePolicy::BpsPerDay(100)
+        );
     }
 
-    // --- get_all_bills_for_owner ---
-
     #[test]
-    fn test_get_all_bills_for_owner_includes_paid() {
+    fn test_flat_late_fee_on_manual_bill() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
 
-        let ids = setup_bills(&env, &client, &owner, 5);
-        let first_id = ids.get(0).unwrap();
-        client.pay_bill(&owner, &first_id);
+        let bill_id = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Water"),
+            &200,
+            &1_000,
+            &false,
+            &0,
+        );
+        client.set_late_fee(&owner, &bill_id, &LateFeePolicy::Flat(25), &0);
 
-        let page = client.get_all_bills_for_owner(&owner, &0, &10);
-        assert_eq!(page.count, 5);
+        env.ledger().set_timestamp(1_000);
+        assert_eq!(client.get_total_unpaid(&owner), 200);
+        env.ledger().set_timestamp(1_001);
+        assert_eq!(client.get_total_unpaid(&owner), 225);
+
+        client.mark_paid_offchain(&owner, &bill_id, &String::from_str(&env, "RCPT-9"));
+        let settlement = client.get_settlement(&bill_id).unwrap();
+        assert_eq!(settlement.late_fee, 25);
+        assert_eq!(client.get_total_unpaid(&owner), 0);
     }
 
-    // --- limit clamping ---
+    // --- approval ---
 
     #[test]
-    fn test_limit_zero_uses_default() {
+    fn test_large_bill_needs_wallet_approval() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
+        let other_wallet = Address::generate(&env);
 
-        setup_bills(&env, &client, &owner, 3);
-        let page = client.get_unpaid_bills(&owner, &0, &0);
-        assert_eq!(page.count, 3);
+        client.set_approval_policy(&owner, &wallet, &500);
+        let small = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Phone"),
+            &500,
+            &86_400,
+            &false,
+            &0,
+        );
+        let large = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Tuition"),
+            &2_000,
+            &86_400,
+            &false,
+            &0,
+        );
+
+        client.pay_bill(&owner, &small);
+        assert_eq!(
+            client.try_pay_bill(&owner, &large),
+            Err(Ok(Error::ApprovalRequired))
+        );
+        assert_eq!(
+            client.try_batch_pay_bills(&owner, &Vec::from_array(&env, [large])),
+            Err(Ok(Error::ApprovalRequired))
+        );
+        assert_eq!(
+            client.try_approve_bill(&other_wallet, &large),
+            Err(Ok(Error::Unauthorized))
+        );
+
+        client.approve_bill(&wallet, &large);
+        assert!(client.is_bill_approved(&large));
+        client.pay_bill(&owner, &large);
+        assert!(client.get_bill(&large).unwrap().paid);
+
+        client.remove_approval_policy(&owner);
+        assert_eq!(client.get_approval_policy(&owner), None);
     }
 
     #[test]
-    fn test_limit_clamped_to_max() {
+    fn test_replacing_approval_policy_needs_current_wallet() {
         let env = make_env();
-        env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
 
-        setup_bills(&env, &client, &owner, 55);
-        let page = client.get_unpaid_bills(&owner, &0, &9999);
-        assert_eq!(page.count, MAX_PAGE_LIMIT);
-        assert!(page.next_cursor > 0);
+        env.mock_all_auths();
+        client.set_approval_policy(&owner, &wallet, &100);
+
+        // Only the owner signs the replacement
+        env.mock_auths(&[MockAuth {
+            address: &owner,
+            invoke: &MockAuthInvoke {
+                contract: &cid,
+                fn_name: "set_approval_policy",
+                args: (owner.clone(), owner.clone(), 1_000_000i128).into_val(&env),
+                sub_invokes: &[],
+            },
+        }]);
+        assert!(client
+            .try_set_approval_policy(&owner, &owner, &1_000_000)
+            .is_err());
+        assert_eq!(client.get_approval_policy(&owner).unwrap().wallet, wallet);
     }
 
-    // --- archived bill pagination ---
+    // --- shared bills ---
 
     #[test]
-    fn test_get_archived_bills_pagination() {
+    fn test_shared_bill_completes_when_all_shares_paid() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let sibling = Address::generate(&env);
+        let landlord = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let minter = StellarAssetClient::new(&env, &token.address());
+        minter.mint(&owner, &1_000);
+        minter.mint(&sibling, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
+
+        let bill_id = client.create_token_bill(
+            &owner,
+            &String::from_str(&env, "Rent"),
+            &900,
+            &86_400,
+            &true,
+            &30,
+            &landlord,
+            &token.address(),
+        );
+        let mut shares = Map::new(&env);
+        shares.set(owner.clone(), 600);
+        shares.set(sibling.clone(), 200);
+        assert_eq!(
+            client.try_split_bill(&owner, &bill_id, &shares),
+            Err(Ok(Error::InvalidShares))
+        );
+        shares.set(sibling.clone(), 300);
+        client.split_bill(&owner, &bill_id, &shares);
+        assert_eq!(
+            client.try_pay_bill(&owner, &bill_id),
+            Err(Ok(Error::SharedBill))
+        );
 
-        client.set_pause_admin(&owner, &owner);
-
-        let ids = setup_bills(&env, &client, &owner, 6);
-        for bill_id in ids.iter() {
-            client.pay_bill(&owner, &bill_id);
-        }
-        client.archive_paid_bills(&owner, &u64::MAX);
+        client.pay_my_share(&sibling, &bill_id);
+        assert_eq!(balance.balance(&landlord), 300);
+        assert!(!client.get_bill(&bill_id).unwrap().paid);
+        assert_eq!(client.get_total_unpaid(&owner), 600);
+        assert_eq!(
+            client.try_pay_my_share(&sibling, &bill_id),
+            Err(Ok(Error::BillAlreadyPaid))
+        );
+        assert_eq!(
+            client.try_pay_my_share(&Address::generate(&env), &bill_id),
+            Err(Ok(Error::Unauthorized))
+        );
 
-        let page1 = client.get_archived_bills(&owner, &0, &4);
-        assert_eq!(page1.count, 4);
-        assert!(page1.next_cursor > 0);
+        // Who still owes
+        let outstanding = client.get_outstanding_shares(&bill_id);
+        assert_eq!(outstanding.len(), 1);
+        assert_eq!(outstanding.get(0).unwrap().payer, owner);
+        assert_eq!(client.get_member_outstanding(&sibling).len(), 0);
+        let due = client.get_member_outstanding(&owner);
+        assert_eq!(due.get(0).unwrap().amount, 600);
+
+        client.pay_my_share(&owner, &bill_id);
+        assert_eq!(balance.balance(&landlord), 900);
+        assert!(client.get_bill(&bill_id).unwrap().paid);
+        assert_eq!(
+            client.get_settlement(&bill_id).unwrap().method,
+            SettlementMethod::Shared
+        );
 
-        let page2 = client.get_archived_bills(&owner, &page1.next_cursor, &4);
-        assert_eq!(page2.count, 2);
-        assert_eq!(page2.next_cursor, 0);
+        // The next occurrence is split the same way, with nothing paid
+        let next_shares = client.get_bill_shares(&2);
+        assert_eq!(next_shares.len(), 2);
+        assert!(next_shares.iter().all(|share| !share.paid));
+        assert_eq!(client.get_member_outstanding(&sibling).len(), 1);
     }
 
-    // -----------------------------------------------------------------------
-    // RECURRING BILLS DATE MATH TESTS
-    // -----------------------------------------------------------------------
-    // These tests verify the core date math for recurring bills:
-    // next_due_date = due_date + (frequency_days * 86400)
-    // Ensures paid_at does not affect next bill's due_date calculation.
+    // --- update ---
 
     #[test]
-    fn test_recurring_date_math_frequency_1_day() {
-        // Test: frequency_days = 1 → next due date is +1 day (86400 seconds)
+    fn test_update_bill_records_amendments() {
         let env = make_env();
         env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let sibling = Address::generate(&env);
 
-        let base_due_date = 1_000_000u64;
         let bill_id = client.create_bill(
             &owner,
-            &String::from_str(&env, "Daily Bill"),
-            &100,
-            &base_due_date,
-            &true, // recurring
-            &1,    // frequency_days = 1
+            &String::from_str(&env, "Rent"),
+            &800,
+            &86_400,
+            &true,
+            &30,
+        );
+        assert_eq!(
+            client.try_update_bill(&owner, &bill_id, &900, &86_400, &0),
+            Err(Ok(Error::InvalidFrequency))
+        );
+        assert_eq!(
+            client.try_update_bill(&owner, &bill_id, &900, &500, &30),
+            Err(Ok(Error::InvalidDueDate))
+        );
+        assert_eq!(
+            client.try_update_bill(&sibling, &bill_id, &900, &86_400, &30),
+            Err(Ok(Error::Unauthorized))
         );
 
-        // Pay the bill
-        client.pay_bill(&owner, &bill_id);
-
-        // Verify next bill's due_date = base_due_date + (1 * 86400)
-        let next_bill = client.get_bill(&2).unwrap();
-        assert!(!next_bill.paid, "Next bill should be unpaid");
+        let amendment = client.update_bill(&owner, &bill_id, &900, &172_800, &28);
+        assert_eq!(amendment.old_amount, 800);
+        assert_eq!(amendment.new_due_date, 172_800);
+        let bill = client.get_bill(&bill_id).unwrap();
         assert_eq!(
-            next_bill.due_date,
-            base_due_date + 86400,
-            "Next due date should be exactly 1 day later"
+            (bill.amount, bill.due_date, bill.frequency_days),
+            (900, 172_800, 28)
         );
-        assert_eq!(next_bill.frequency_days, 1, "Frequency should be preserved");
+        assert_eq!(client.get_bill_amendments(&bill_id).len(), 1);
+
+        // The amount is fixed once a share is paid
+        let mut shares = Map::new(&env);
+        shares.set(owner.clone(), 450);
+        shares.set(sibling.clone(), 450);
+        client.split_bill(&owner, &bill_id, &shares);
+        client.pay_my_share(&sibling, &bill_id);
+        assert_eq!(
+            client.try_update_bill(&owner, &bill_id, &1_000, &172_800, &28),
+            Err(Ok(Error::PartiallyPaid))
+        );
+        client.update_bill(&owner, &bill_id, &900, &259_200, &28);
+        assert_eq!(client.get_bill_amendments(&bill_id).len(), 2);
     }
 
+    // --- paused series ---
+
     #[test]
-    fn test_recurring_date_math_frequency_30_days() {
-        // Test: frequency_days = 30 → next due date is +30 days (2,592,000 seconds)
+    fn test_paused_series_skips_successor_until_resumed() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
 
-        let base_due_date = 1_000_000u64;
         let bill_id = client.create_bill(
             &owner,
-            &String::from_str(&env, "Monthly Bill"),
-            &500,
-            &base_due_date,
-            &true, // recurring
-            &30,   // frequency_days = 30
+            &String::from_str(&env, "School fees"),
+            &400,
+            &(30 * 86_400),
+            &true,
+            &30,
+        );
+        let one_off = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Uniform"),
+            &50,
+            &86_400,
+            &false,
+            &0,
+        );
+        assert_eq!(
+            client.try_pause_recurring(&owner, &one_off),
+            Err(Ok(Error::NotRecurring))
         );
 
-        // Pay the bill
+        client.pause_recurring(&owner, &bill_id);
+        assert!(client.get_bill(&bill_id).unwrap().series_paused);
         client.pay_bill(&owner, &bill_id);
+        assert!(client.get_bill(&3).is_none());
+
+        // Resume after the holidays: the next bill lands on the first
+        // billing date that has not passed yet
+        env.ledger().set_timestamp(95 * 86_400);
+        let next_id = client.resume_recurring(&owner, &bill_id).unwrap();
+        let next = client.get_bill(&next_id).unwrap();
+        assert_eq!(next.due_date, 120 * 86_400);
+        assert!(!next.paid);
+        assert!(!client.get_bill(&bill_id).unwrap().series_paused);
+        assert_eq!(client.resume_recurring(&owner, &bill_id), None);
+    }
 
-        // Verify next bill's due_date = base_due_date + (30 * 86400)
-        let next_bill = client.get_bill(&2).unwrap();
-        assert!(!next_bill.paid, "Next bill should be unpaid");
-        let expected_due_date = base_due_date + (30u64 * 86400);
-        assert_eq!(
-            next_bill.due_date, expected_due_date,
-            "Next due date should be exactly 30 days later"
+    // --- series end ---
+
+    #[test]
+    fn test_series_stops_at_max_occurrences_and_end_date() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+
+        let first = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Loan"),
+            &100,
+            &86_400,
+            &true,
+            &30,
         );
         assert_eq!(
-            next_bill.frequency_days, 30,
-            "Frequency should be preserved"
+            client.try_set_series_end(&owner, &first, &None, &Some(0)),
+            Err(Ok(Error::InvalidSeriesEnd))
+        );
+        client.set_series_end(&owner, &first, &None, &Some(3));
+
+        for id in 1..=3 {
+            client.pay_bill(&owner, &id);
+        }
+        let series = client.get_series(&3).unwrap();
+        assert_eq!(series.series_id, first);
+        assert_eq!(series.occurrences, 3);
+        assert_eq!(series.bill_ids, Vec::from_array(&env, [1, 2, 3]));
+        assert!(client.get_bill(&4).is_none());
+
+        // An end date stops the series the same way
+        let lease = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Lease"),
+            &100,
+            &86_400,
+            &true,
+            &30,
         );
+        client.set_series_end(&owner, &lease, &Some(86_400 + 40 * 86_400), &None);
+        client.pay_bill(&owner, &lease);
+        let second = client.get_series(&lease).unwrap().bill_ids.get(1).unwrap();
+        client.pay_bill(&owner, &second);
+        assert_eq!(client.get_series(&lease).unwrap().occurrences, 2);
     }
 
+    // --- recurring generation keeper ---
+
     #[test]
-    fn test_recurring_date_math_frequency_365_days() {
-        // Test: frequency_days = 365 → next due date is +365 days (31,536,000 seconds)
+    fn test_keeper_generates_next_occurrence_of_unpaid_bill() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
 
-        let base_due_date = 1_000_000u64;
-        let bill_id = client.create_bill(
+        let first = client.create_bill(
             &owner,
-            &String::from_str(&env, "Annual Bill"),
-            &1200,
-            &base_due_date,
-            &true, // recurring
-            &365,  // frequency_days = 365
+            &String::from_str(&env, "Internet"),
+            &60,
+            &(10 * 86_400),
+            &true,
+            &30,
         );
-
-        // Pay the bill
-        client.pay_bill(&owner, &bill_id);
-
-        // Verify next bill's due_date = base_due_date + (365 * 86400)
-        let next_bill = client.get_bill(&2).unwrap();
-        assert!(!next_bill.paid, "Next bill should be unpaid");
-        let expected_due_date = base_due_date + (365u64 * 86400);
+        assert_eq!(client.generate_due_recurring_bills().len(), 0);
+
+        env.ledger().set_timestamp(11 * 86_400);
+        let created = client.generate_due_recurring_bills();
+        assert_eq!(created, Vec::from_array(&env, [2]));
+        assert_eq!(client.get_bill(&2).unwrap().due_date, 40 * 86_400);
+        // Already generated: a second run and paying the old bill add nothing
+        assert_eq!(client.generate_due_recurring_bills().len(), 0);
+        client.pay_bill(&owner, &first);
+        assert!(client.get_bill(&3).is_none());
+
+        // A keeper that fell behind catches up one bill per run
+        env.ledger().set_timestamp(100 * 86_400);
         assert_eq!(
-            next_bill.due_date, expected_due_date,
-            "Next due date should be exactly 365 days later"
+            client.generate_due_recurring_bills(),
+            Vec::from_array(&env, [3])
         );
         assert_eq!(
-            next_bill.frequency_days, 365,
-            "Frequency should be preserved"
+            client.generate_due_recurring_bills(),
+            Vec::from_array(&env, [4])
         );
+        let series = client.get_series(&first).unwrap();
+        assert_eq!(series.bill_ids, Vec::from_array(&env, [1, 2, 3, 4]));
+        assert_eq!(series.missed_generations, 1);
+        assert_eq!(client.get_unpaid_bills(&owner, &0, &0).count, 3);
     }
 
+    // --- reminders ---
+
     #[test]
-    fn test_recurring_date_math_paid_at_does_not_affect_next_due() {
-        // Test: paid_at timestamp does NOT affect next bill's due_date calculation
-        // Bill 1: due_date=1000000, paid_at=1000500 (paid 500 seconds late)
-        // Bill 2: due_date should be 1000000 + (30*86400), NOT 1000500 + (30*86400)
+    fn test_due_reminders_fire_once_per_window_and_escalate() {
+        use soroban_sdk::{testutils::Events, TryFromVal};
+
+        fn last_reminder(env: &Env) -> BillReminder {
+            env.events()
+                .all()
+                .iter()
+                .rev()
+                .find_map(|e| BillReminder::try_from_val(env, &e.2).ok())
+                .unwrap()
+        }
+
         let env = make_env();
-        env.ledger().set_timestamp(1_000_500); // Set current time to 500 seconds after due date
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        assert_eq!(
+            client.try_set_reminder_config(&owner, &Vec::from_array(&env, [3]), &0),
+            Err(Ok(Error::InvalidReminderConfig))
+        );
 
-        let base_due_date = 1_000_000u64;
+        let due = 10 * 86_400;
         let bill_id = client.create_bill(
             &owner,
-            &String::from_str(&env, "Late Payment Test"),
-            &300,
-            &base_due_date,
-            &true, // recurring
-            &30,   // frequency_days = 30
+            &String::from_str(&env, "Gas"),
+            &75,
+            &due,
+            &false,
+            &0,
         );
+        assert_eq!(client.emit_due_reminders(), 0);
+
+        // Six days out: the 7-day window
+        env.ledger().set_timestamp(due - 6 * 86_400);
+        assert_eq!(client.emit_due_reminders(), 1);
+        let sent = last_reminder(&env);
+        assert_eq!(sent.bill_id, bill_id);
+        assert_eq!(sent.kind, ReminderKind::Upcoming);
+        assert_eq!(sent.days, 7);
+        assert_eq!(client.emit_due_reminders(), 0);
+
+        // Jumping straight to the last day skips the 3-day reminder
+        env.ledger().set_timestamp(due - 3_600);
+        assert_eq!(client.emit_due_reminders(), 1);
+        assert_eq!(last_reminder(&env).days, 1);
+
+        env.ledger().set_timestamp(due + 1);
+        assert_eq!(client.emit_due_reminders(), 1);
+        let overdue = last_reminder(&env);
+        assert_eq!(overdue.kind, ReminderKind::Overdue);
+        assert_eq!(overdue.level, 1);
+
+        env.ledger().set_timestamp(due + 8 * 86_400);
+        assert_eq!(client.emit_due_reminders(), 1);
+        assert_eq!(last_reminder(&env).level, 2);
 
-        // Pay the bill (at time 1_000_500, which is 500 seconds after due_date)
         client.pay_bill(&owner, &bill_id);
+        env.ledger().set_timestamp(due + 30 * 86_400);
+        assert_eq!(client.emit_due_reminders(), 0);
+    }
 
-        // Verify original bill has paid_at set
-        let paid_bill = client.get_bill(&bill_id).unwrap();
-        assert!(paid_bill.paid, "Bill should be marked as paid");
-        assert_eq!(
-            paid_bill.paid_at,
-            Some(1_000_500),
-            "paid_at should be set to current time"
-        );
+    // --- categories and budgets ---
 
-        // Verify next bill's due_date is based on original due_date, NOT paid_at
-        let next_bill = client.get_bill(&2).unwrap();
-        let expected_due_date = base_due_date + (30u64 * 86400);
-        assert_eq!(
-            next_bill.due_date, expected_due_date,
-            "Next due date should be based on original due_date, not paid_at"
-        );
-        assert!(!next_bill.paid, "Next bill should be unpaid");
+    #[test]
+    fn test_month_of() {
+        assert_eq!(BillPayments::month_of(0), 197001);
+        // 2024-02-29 12:00 UTC
+        assert_eq!(BillPayments::month_of(1_709_208_000), 202402);
+        // 2024-12-31 23:59:59 UTC
+        assert_eq!(BillPayments::month_of(1_735_689_599), 202412);
     }
 
     #[test]
-    fn test_recurring_date_math_multiple_pay_cycles_2nd_bill() {
-        // Test: Multiple pay cycles - verify 2nd bill's due date advances correctly
-        // Bill 1: due_date=1000000, frequency=30
-        // Bill 2: due_date=1000000 + (30*86400)
+    fn test_spend_by_category_and_budget_warning() {
+        use soroban_sdk::{testutils::Events, TryFromVal};
+
         let env = make_env();
         env.mock_all_auths();
+        // 2024-03-10
+        env.ledger().set_timestamp(1_710_028_800);
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
 
-        let base_due_date = 1_000_000u64;
-        let bill_id = client.create_bill(
+        let power = client.register_payee(
             &owner,
-            &String::from_str(&env, "Multi-Cycle Bill"),
-            &250,
-            &base_due_date,
-            &true, // recurring
-            &30,   // frequency_days = 30
+            &Address::generate(&env),
+            &String::from_str(&env, "Power Co"),
+            &symbol_short!("utility"),
         );
+        let light =
+            client.create_payee_bill(&owner, &power, &80, &1_720_000_000, &false, &0, &None);
+        assert_eq!(
+            client.get_bill(&light).unwrap().category,
+            symbol_short!("utility")
+        );
+        let water = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Water"),
+            &50,
+            &1_720_000_000,
+            &false,
+            &0,
+        );
+        assert_eq!(
+            client.get_bill(&water).unwrap().category,
+            symbol_short!("other")
+        );
+        client.set_bill_category(&owner, &water, &symbol_short!("utility"));
+
+        client.set_category_budget(&owner, &symbol_short!("utility"), &100);
+        client.pay_bill(&owner, &light);
+        client.pay_bill(&owner, &water);
+        let over_budget = env.events().all().iter().any(|e| {
+            e.1.get(3)
+                .and_then(|t| Symbol::try_from_val(&env, &t).ok())
+                .is_some_and(|s| s == symbol_short!("over_budg"))
+        });
+        assert!(over_budget);
+
+        let spend = client.get_spend_by_category(&owner, &202403);
+        assert_eq!(spend.get(symbol_short!("utility")), Some(130));
+        assert_eq!(client.get_spend_by_category(&owner, &202404).len(), 0);
+    }
 
-        // Pay first bill
-        client.pay_bill(&owner, &bill_id);
-
-        // Verify second bill
-        let bill2 = client.get_bill(&2).unwrap();
-        let expected_bill2_due = base_due_date + (30u64 * 86400);
-        assert_eq!(bill2.due_date, expected_bill2_due);
-        assert!(!bill2.paid);
+    // --- owner index ---
 
-        // Pay second bill
-        client.pay_bill(&owner, &2);
+    #[test]
+    fn test_bills_page_reads_owner_index() {
+        let env = make_env();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(1_000);
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+        let neighbour = Address::generate(&env);
 
-        // Verify second bill is now paid
-        let bill2_paid = client.get_bill(&2).unwrap();
-        assert!(bill2_paid.paid);
+        for i in 0..5u64 {
+            client.create_bill(
+                &owner,
+                &String::from_str(&env, "Bill"),
+                &100,
+                &(2_000 + i * 86_400),
+                &false,
+                &0,
+            );
+            client.create_bill(
+                &neighbour,
+                &String::from_str(&env, "Other"),
+                &7,
+                &2_000,
+                &false,
+                &0,
+            );
+        }
+        client.pay_bill(&owner, &1);
+        client.cancel_bill(&owner, &5);
+        client.update_bill(&owner, &7, &150, &(2_000 + 3 * 86_400), &0);
+        assert_eq!(client.get_total_unpaid(&owner), 350);
+        assert_eq!(client.get_total_unpaid(&neighbour), 35);
+
+        let page = client.get_bills_page(&owner, &BillFilter::All, &0, &2);
+        assert_eq!(page.count, 2);
+        assert_eq!(page.next_cursor, 2);
+        let page = client.get_bills_page(&owner, &BillFilter::All, &2, &2);
+        assert_eq!(page.items.get(1).unwrap().id, 9);
+        assert_eq!(page.next_cursor, 0);
 
-        // Verify third bill was created with correct due_date
-        let bill3 = client.get_bill(&3).unwrap();
-        let expected_bill3_due = expected_bill2_due + (30u64 * 86400);
         assert_eq!(
-            bill3.due_date, expected_bill3_due,
-            "Bill 3 due_date should be Bill 2 due_date + (30*86400)"
+            client
+                .get_bills_page(&owner, &BillFilter::Paid, &0, &0)
+                .count,
+            1
         );
-        assert!(!bill3.paid);
+        env.ledger().set_timestamp(2_000 + 86_400 + 1);
+        let overdue = client.get_bills_page(&owner, &BillFilter::Overdue, &0, &0);
+        assert_eq!(overdue.count, 1);
+        assert_eq!(overdue.items.get(0).unwrap().id, 3);
     }
 
+    // --- payment history ---
+
     #[test]
-    fn test_recurring_date_math_multiple_pay_cycles_3rd_bill() {
-        // Test: Multiple pay cycles - verify 3rd bill's due date advances correctly
-        // Bill 1: due_date=1000000, frequency=30
-        // Bill 2: due_date=1000000 + (30*86400)
-        // Bill 3: due_date=1000000 + (60*86400)
+    fn test_payment_history_by_period_and_payee() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
 
-        let base_due_date = 1_000_000u64;
-        let bill_id = client.create_bill(
+        let school = client.register_payee(
             &owner,
-            &String::from_str(&env, "Three-Cycle Bill"),
-            &150,
-            &base_due_date,
-            &true, // recurring
-            &30,   // frequency_days = 30
+            &Address::generate(&env),
+            &String::from_str(&env, "School"),
+            &symbol_short!("education"),
+        );
+        let rent = client.create_bill(
+            &owner,
+            &String::from_str(&env, "Rent"),
+            &500,
+            &(40 * 86_400),
+            &false,
+            &0,
+        );
+        let fees =
+            client.create_payee_bill(&owner, &school, &200, &(40 * 86_400), &false, &0, &None);
+        let books =
+            client.create_payee_bill(&owner, &school, &50, &(50 * 86_400), &false, &0, &None);
+
+        env.ledger().with_mut(|li| li.timestamp = 10 * 86_400);
+        client.pay_bill(&owner, &rent);
+        env.ledger().with_mut(|li| li.timestamp = 20 * 86_400);
+        client.pay_bill(&owner, &fees);
+        env.ledger().with_mut(|li| li.timestamp = 35 * 86_400);
+        client.mark_paid_offchain(&owner, &books, &String::from_str(&env, "RCPT-9"));
+
+        let history = client.get_payment_history(&owner, &0, &2);
+        assert_eq!(history.count, 2);
+        assert_eq!(history.items.get(0).unwrap().bill_id, rent);
+        assert_eq!(history.next_cursor, 2);
+        let rest = client.get_payment_history(&owner, &history.next_cursor, &2);
+        assert_eq!(rest.count, 1);
+        assert_eq!(
+            rest.items.get(0).unwrap().method,
+            SettlementMethod::OffChain
         );
+        assert_eq!(rest.next_cursor, 0);
 
-        // Pay first bill
-        client.pay_bill(&owner, &bill_id);
+        let period = client.get_payments_between(&owner, &(15 * 86_400), &(35 * 86_400));
+        assert_eq!(period.len(), 1);
+        assert_eq!(period.get(0).unwrap().bill_id, fees);
+        assert_eq!(period.get(0).unwrap().payee_id, Some(school));
+        assert_eq!(client.get_payments_between(&owner, &0, &u64::MAX).len(), 3);
+        assert_eq!(
+            client
+                .get_payments_between(&owner, &(36 * 86_400), &u64::MAX)
+                .len(),
+            0
+        );
 
-        // Pay second bill
-        client.pay_bill(&owner, &2);
+        let to_school = client.get_payee_payments(&owner, &school, &0, &1);
+        assert_eq!(to_school.items.get(0).unwrap().bill_id, fees);
+        assert_eq!(to_school.next_cursor, 2);
+        let to_school = client.get_payee_payments(&owner, &school, &to_school.next_cursor, &1);
+        assert_eq!(to_school.items.get(0).unwrap().bill_id, books);
+        assert_eq!(to_school.next_cursor, 0);
+    }
 
-        // Pay third bill
-        client.pay_bill(&owner, &3);
+    // --- batch create ---
 
-        // Verify third bill is now paid
-        let bill3_paid = client.get_bill(&3).unwrap();
-        assert!(bill3_paid.paid);
+    #[test]
+    fn test_batch_create_bills_is_all_or_nothing() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
 
-        // Verify fourth bill was created with correct due_date
-        let bill4 = client.get_bill(&4).unwrap();
-        let expected_bill4_due = base_due_date + (90u64 * 86400); // 3 * 30 days
+        let item = |name: &str, amount: i128, recurring: bool, frequency_days: u32| NewBillItem {
+            name: String::from_str(&env, name),
+            amount,
+            due_date: 86_400,
+            recurring,
+            frequency_days,
+        };
+        let mut items = Vec::new(&env);
+        items.push_back(item("Rent", 500, true, 30));
+        items.push_back(item("Water", 40, false, 0));
+        items.push_back(item("Internet", 0, false, 0));
         assert_eq!(
-            bill4.due_date, expected_bill4_due,
-            "Bill 4 due_date should be base + (90*86400)"
+            client.try_batch_create_bills(&owner, &items),
+            Err(Ok(Error::InvalidAmount))
+        );
+        assert_eq!(client.get_storage_stats().active_bills, 0);
+
+        items.set(2, item("Internet", 60, false, 0));
+        let ids = client.batch_create_bills(&owner, &items);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(client.get_total_unpaid(&owner), 600);
+        let rent = client.get_bill(&ids.get(0).unwrap()).unwrap();
+        assert_eq!(rent.series_id, Some(rent.id));
+        assert_eq!(
+            client.create_bill(
+                &owner,
+                &String::from_str(&env, "Gas"),
+                &25,
+                &86_400,
+                &false,
+                &0
+            ),
+            ids.get(2).unwrap() + 1
+        );
+
+        let mut too_many = Vec::new(&env);
+        for _ in 0..=MAX_BATCH_SIZE {
+            too_many.push_back(item("Bill", 1, false, 0));
+        }
+        assert_eq!(
+            client.try_batch_create_bills(&owner, &too_many),
+            Err(Ok(Error::BatchTooLarge))
         );
-        assert!(!bill4.paid);
     }
 
+    // --- local currency ---
+
     #[test]
-    fn test_recurring_date_math_early_payment_does_not_affect_schedule() {
-        // Test: Paying a bill EARLY should not affect the next bill's due_date
-        // Bill 1: due_date=1000000, paid at time=500000 (paid 500000 seconds early)
-        // Bill 2: due_date should still be 1000000 + (30*86400)
+    fn test_local_currency_amount_carried_into_payment_record() {
         let env = make_env();
-        env.ledger().set_timestamp(500_000); // Set time BEFORE due date
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let oracle = Address::generate(&env);
 
-        let base_due_date = 1_000_000u64;
-        let bill_id = client.create_bill(
+        let tuition = client.create_bill(
             &owner,
-            &String::from_str(&env, "Early Payment Test"),
-            &200,
-            &base_due_date,
-            &true, // recurring
-            &30,   // frequency_days = 30
+            &String::from_str(&env, "Tuition"),
+            &180,
+            &86_400,
+            &true,
+            &30,
         );
-
-        // Pay the bill early (at time 500_000)
-        client.pay_bill(&owner, &bill_id);
-
-        // Verify original bill has paid_at set to early time
-        let paid_bill = client.get_bill(&bill_id).unwrap();
-        assert!(paid_bill.paid);
-        assert_eq!(paid_bill.paid_at, Some(500_000));
-
-        // Verify next bill's due_date is still based on original due_date
-        let next_bill = client.get_bill(&2).unwrap();
-        let expected_due_date = base_due_date + (30u64 * 86400);
         assert_eq!(
-            next_bill.due_date, expected_due_date,
-            "Next due date should not be affected by early payment"
+            client.try_set_bill_conversion(&owner, &tuition, &symbol_short!("PHP"), &-1, &None),
+            Err(Ok(Error::InvalidAmount))
+        );
+        client.set_bill_conversion(
+            &owner,
+            &tuition,
+            &symbol_short!("PHP"),
+            &10_000,
+            &Some(oracle.clone()),
+        );
+        let bill = client.get_bill(&tuition).unwrap();
+        assert_eq!(bill.local_currency, Some(symbol_short!("PHP")));
+        assert_eq!(bill.amount, 180);
+
+        client.pay_bill(&owner, &tuition);
+        let record = client
+            .get_payment_history(&owner, &0, &1)
+            .items
+            .get(0)
+            .unwrap();
+        assert_eq!(record.amount, 180);
+        assert_eq!(record.local_amount, 10_000);
+        assert_eq!(record.local_currency, Some(symbol_short!("PHP")));
+        assert_eq!(record.rate_source, Some(oracle));
+        assert_eq!(
+            client.try_set_bill_conversion(&owner, &tuition, &symbol_short!("PHP"), &0, &None),
+            Err(Ok(Error::BillAlreadyPaid))
         );
+
+        let next = client.get_bill(&(tuition + 1)).unwrap();
+        assert_eq!(next.local_amount, 10_000);
+        client.set_bill_conversion(&owner, &next.id, &symbol_short!("PHP"), &0, &None);
+        let next = client.get_bill(&next.id).unwrap();
+        assert_eq!(next.local_currency, None);
+        assert_eq!(next.local_amount, 0);
     }
 
+    // --- disputes ---
+
     #[test]
-    fn test_recurring_date_math_preserves_frequency_across_cycles() {
-        // Test: frequency_days is preserved across all recurring cycles
-        // Verify that Bill 1, 2, 3 all have the same frequency_days value
+    fn test_dispute_refunds_token_payment_and_reopens_bill() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let utility = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
+        let reason = BytesN::from_array(&env, &[7; 32]);
 
-        let frequency = 7u32; // Weekly
-        let bill_id = client.create_bill(
+        let bill_id = client.create_token_bill(
             &owner,
-            &String::from_str(&env, "Weekly Bill"),
-            &50,
-            &1_000_000,
-            &true,
-            &frequency,
+            &String::from_str(&env, "Power"),
+            &300,
+            &86_400,
+            &false,
+            &0,
+            &utility,
+            &token.address(),
+        );
+        assert_eq!(
+            client.try_dispute_payment(&owner, &bill_id, &reason),
+            Err(Ok(Error::BillNotPaid))
         );
-
-        // Pay first bill
         client.pay_bill(&owner, &bill_id);
+        assert_eq!(
+            client.try_dispute_payment(&utility, &bill_id, &reason),
+            Err(Ok(Error::Unauthorized))
+        );
+        client.dispute_payment(&owner, &bill_id, &reason);
+        assert_eq!(
+            client.try_dispute_payment(&owner, &bill_id, &reason),
+            Err(Ok(Error::AlreadyDisputed))
+        );
+        assert_eq!(client.get_open_disputes(), Vec::from_array(&env, [bill_id]));
 
-        // Pay second bill
-        client.pay_bill(&owner, &2);
-
-        // Verify all bills have the same frequency_days
-        let bill1 = client.get_bill(&1).unwrap();
-        let bill2 = client.get_bill(&2).unwrap();
-        let bill3 = client.get_bill(&3).unwrap();
-
-        assert_eq!(bill1.frequency_days, frequency);
-        assert_eq!(bill2.frequency_days, frequency);
-        assert_eq!(bill3.frequency_days, frequency);
+        // Disputed bills stay active until resolved
+        assert_eq!(client.archive_paid_bills(&owner, &u64::MAX), 0);
+        assert_eq!(
+            client.try_resolve_dispute(&owner, &bill_id, &DisputeStatus::Reopened),
+            Err(Ok(Error::Unauthorized))
+        );
+        assert_eq!(
+            client.try_resolve_dispute(&utility, &bill_id, &DisputeStatus::Open),
+            Err(Ok(Error::InvalidResolution))
+        );
+        client.resolve_dispute(&utility, &bill_id, &DisputeStatus::Reopened);
+
+        assert_eq!(balance.balance(&owner), 1_000);
+        assert_eq!(balance.balance(&utility), 0);
+        let dispute = client.get_dispute(&bill_id).unwrap();
+        assert_eq!(dispute.status, DisputeStatus::Reopened);
+        assert_eq!(dispute.refunded, 300);
+        assert_eq!(client.get_open_disputes().len(), 0);
+        assert!(!client.get_bill(&bill_id).unwrap().paid);
+        assert!(client.get_settlement(&bill_id).is_none());
+        assert_eq!(client.get_total_unpaid(&owner), 300);
+
+        let history = client.get_payment_history(&owner, &0, &10);
+        assert_eq!(history.count, 2);
+        let refund = history.items.get(1).unwrap();
+        assert_eq!(refund.method, SettlementMethod::Refund);
+        assert_eq!(refund.amount, -300);
     }
 
     #[test]
-    fn test_recurring_date_math_amount_preserved_across_cycles() {
-        // Test: Bill amount is preserved across all recurring cycles
+    fn test_dispute_window_and_void() {
         let env = make_env();
         env.mock_all_auths();
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
+        let reason = BytesN::from_array(&env, &[1; 32]);
+
+        let ids = setup_bills(&env, &client, &owner, 2);
+        let (late, voided) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+        client.pay_bill(&owner, &late);
+        client.pay_bill(&owner, &voided);
+        env.ledger().with_mut(|li| li.timestamp += DISPUTE_WINDOW);
+        client.dispute_payment(&owner, &voided, &reason);
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        assert_eq!(
+            client.try_dispute_payment(&owner, &late, &reason),
+            Err(Ok(Error::DisputeWindowClosed))
+        );
 
-        let amount = 999i128;
-        let bill_id = client.create_bill(
-            &owner,
-            &String::from_str(&env, "Fixed Amount Bill"),
-            &amount,
-            &1_000_000,
-            &true,
-            &30,
+        client.resolve_dispute(&admin, &voided, &DisputeStatus::Voided);
+        assert!(client.get_bill(&voided).is_none());
+        assert_eq!(client.get_dispute(&voided).unwrap().refunded, 200);
+        assert_eq!(
+            client.try_resolve_dispute(&admin, &voided, &DisputeStatus::Rejected),
+            Err(Ok(Error::NoOpenDispute))
         );
+        assert_eq!(client.get_storage_stats().active_bills, 1);
+    }
 
-        // Pay first bill
-        client.pay_bill(&owner, &bill_id);
+    // --- forecast ---
 
-        // Pay second bill
-        client.pay_bill(&owner, &2);
+    #[contract]
+    pub struct MockInsurance;
 
-        // Verify all bills have the same amount
-        let bill1 = client.get_bill(&1).unwrap();
-        let bill2 = client.get_bill(&2).unwrap();
-        let bill3 = client.get_bill(&3).unwrap();
+    #[contractimpl]
+    impl MockInsurance {
+        pub fn get_total_monthly_premium(_env: Env, _owner: Address) -> i128 {
+            40
+        }
+    }
 
-        assert_eq!(bill1.amount, amount);
-        assert_eq!(bill2.amount, amount);
-        assert_eq!(bill3.amount, amount);
+    #[contract]
+    pub struct MockSavings;
+
+    #[contractimpl]
+    impl MockSavings {
+        pub fn get_savings_schedules(env: Env, owner: Address) -> Vec<SavingsSchedule> {
+            let schedule = |amount: i128, active: bool| SavingsSchedule {
+                id: 1,
+                owner: owner.clone(),
+                goal_id: 1,
+                amount,
+                next_due: 1_769_904_000, // 2026-02-01
+                interval: 14 * 86400,
+                recurring: true,
+                active,
+                created_at: 0,
+                last_executed: None,
+                missed_count: 0,
+                funder: owner.clone(),
+                skipped_count: 0,
+            };
+            Vec::from_array(&env, [schedule(100, true), schedule(999, false)])
+        }
     }
 
     #[test]
-    fn test_recurring_date_math_name_preserved_across_cycles() {
-        // Test: Bill name is preserved across all recurring cycles
+    fn test_monthly_forecast_projects_bills_premiums_and_savings() {
         let env = make_env();
         env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_768_435_200); // 2026-01-15
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
 
-        let name = String::from_str(&env, "Rent Payment");
-        let bill_id = client.create_bill(&owner, &name, &1000, &1_000_000, &true, &30);
-
-        // Pay first bill
-        client.pay_bill(&owner, &bill_id);
-
-        // Pay second bill
-        client.pay_bill(&owner, &2);
+        let name = String::from_str(&env, "Bill");
+        client.create_bill(&owner, &name, &50, &1_767_225_600, &false, &0); // 2026-01-01
+        let rent = client.create_bill(&owner, &name, &500, &1_768_867_200, &true, &30); // 2026-01-20
+        client.set_series_end(&owner, &rent, &None, &Some(2));
+        client.create_bill(&owner, &name, &70, &1_772_668_800, &false, &0); // 2026-03-05
 
-        // Verify all bills have the same name
-        let bill1 = client.get_bill(&1).unwrap();
-        let bill2 = client.get_bill(&2).unwrap();
-        let bill3 = client.get_bill(&3).unwrap();
+        // Without sources only bills are projected
+        let forecast = client.get_monthly_forecast(&owner, &3);
+        assert_eq!(forecast.get(0).unwrap().total, 550);
 
-        assert_eq!(bill1.name, name);
-        assert_eq!(bill2.name, name);
-        assert_eq!(bill3.name, name);
+        let insurance = env.register_contract(None, MockInsurance);
+        let savings = env.register_contract(None, MockSavings);
+        assert_eq!(
+            client.try_set_forecast_sources(&owner, &Some(insurance.clone()), &None),
+            Err(Ok(Error::Unauthorized))
+        );
+        client.set_forecast_sources(&admin, &Some(insurance), &Some(savings));
+
+        let forecast = client.get_monthly_forecast(&owner, &3);
+        assert_eq!(forecast.len(), 3);
+        let expected = [
+            (202601, 550, 0, 590),
+            (202602, 500, 200, 740),
+            (202603, 70, 300, 410),
+        ];
+        for (i, (month, bills, savings, total)) in expected.iter().enumerate() {
+            let entry = forecast.get(i as u32).unwrap();
+            assert_eq!(entry.month, *month);
+            assert_eq!(entry.bills, *bills);
+            assert_eq!(entry.premiums, 40);
+            assert_eq!(entry.savings, *savings);
+            assert_eq!(entry.total, *total);
+        }
+        assert_eq!(
+            client.get_monthly_forecast(&owner, &100).len(),
+            MAX_FORECAST_MONTHS
+        );
     }
 
+    // --- snapshot ---
+
     #[test]
-    fn test_recurring_date_math_owner_preserved_across_cycles() {
-        // Test: Bill owner is preserved across all recurring cycles
+    fn test_snapshot_round_trip_rebuilds_indexes() {
         let env = make_env();
         env.mock_all_auths();
-        let cid = env.register_contract(None, BillPayments);
-        let client = BillPaymentsClient::new(&env, &cid);
+        let old = BillPaymentsClient::new(&env, &env.register_contract(None, BillPayments));
+        let new = BillPaymentsClient::new(&env, &env.register_contract(None, BillPayments));
         let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        new.set_pause_admin(&admin, &admin);
 
-        let bill_id = client.create_bill(
+        let ids = setup_bills(&env, &old, &owner, 3);
+        let rent = old.create_bill(
             &owner,
-            &String::from_str(&env, "Owner Test"),
-            &100,
-            &1_000_000,
+            &String::from_str(&env, "Rent"),
+            &500,
+            &86_400,
             &true,
             &30,
         );
+        old.set_series_end(&owner, &rent, &None, &Some(4));
+        old.pay_bill(&owner, &ids.get(0).unwrap());
+        old.archive_paid_bills(&owner, &u64::MAX);
+
+        let snapshot = old.export_snapshot(&owner);
+        assert_eq!(snapshot.checksum.len(), 32);
+        assert_eq!(snapshot.bills.len(), 3);
+        assert_eq!(snapshot.archived.len(), 1);
+        assert_eq!(snapshot.series.len(), 1);
+
+        let mut tampered = snapshot.clone();
+        tampered.next_id += 1;
+        assert_eq!(
+            new.try_import_snapshot(&admin, &0, &tampered),
+            Err(Ok(Error::ChecksumMismatch))
+        );
+        assert_eq!(
+            new.try_import_snapshot(&owner, &0, &snapshot),
+            Err(Ok(Error::Unauthorized))
+        );
+        new.import_snapshot(&admin, &0, &snapshot);
+        assert_eq!(new.get_nonce(&admin), 1);
+        assert_eq!(
+            new.try_import_snapshot(&admin, &0, &snapshot),
+            Err(Ok(Error::InvalidNonce))
+        );
 
-        // Pay first bill
-        client.pay_bill(&owner, &bill_id);
+        assert_eq!(new.get_total_unpaid(&owner), old.get_total_unpaid(&owner));
+        assert_eq!(new.get_unpaid_bills(&owner, &0, &10).count, 3);
+        assert_eq!(new.get_archived_bills(&owner, &0, &10).count, 1);
+        assert_eq!(new.get_series(&rent).unwrap().max_occurrences, Some(4));
+        let stats = new.get_storage_stats();
+        assert_eq!(stats.active_bills, 3);
+        assert_eq!(stats.archived_bills, 1);
+        assert_eq!(stats.total_archived_amount, 100);
+        let next = new.create_bill(
+            &owner,
+            &String::from_str(&env, "Gas"),
+            &10,
+            &86_400,
+            &false,
+            &0,
+        );
+        assert_eq!(next, rent + 1);
+    }
 
-        // Pay second bill
-        client.pay_bill(&owner, &2);
+    // --- retention ---
 
-        // Verify all bills have the same owner
-        let bill1 = client.get_bill(&1).unwrap();
-        let bill2 = client.get_bill(&2).unwrap();
-        let bill3 = client.get_bill(&3).unwrap();
+    #[test]
+    fn test_retention_policy_archives_and_deletes_on_activity() {
+        let env = make_env();
+        env.mock_all_auths();
+        let cid = env.register_contract(None, BillPayments);
+        let client = BillPaymentsClient::new(&env, &cid);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
+        assert_eq!(
+            client.try_set_retention_policy(&owner, &30, &90),
+            Err(Ok(Error::Unauthorized))
+        );
+        client.set_retention_policy(&admin, &30, &90);
 
-        assert_eq!(bill1.owner, owner);
-        assert_eq!(bill2.owner, owner);
-        assert_eq!(bill3.owner, owner);
+        let ids = setup_bills(&env, &client, &owner, 4);
+        let (old, recent) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+        client.pay_bill(&owner, &old);
+        env.ledger().with_mut(|li| li.timestamp += 20 * 86400);
+        client.pay_bill(&owner, &recent);
+        assert!(client.get_bill(&old).is_some());
+
+        // 30 days after the first payment, the next cancel archives it
+        env.ledger().with_mut(|li| li.timestamp += 10 * 86400);
+        client.cancel_bill(&owner, &ids.get(2).unwrap());
+        assert!(client.get_bill(&old).is_none());
+        assert!(client.get_archived_bill(&old).is_some());
+        assert!(client.get_bill(&recent).is_some());
+
+        // 90 days after archiving, a payment deletes the archive
+        env.ledger().with_mut(|li| li.timestamp += 90 * 86400);
+        client.pay_bill(&owner, &ids.get(3).unwrap());
+        assert!(client.get_archived_bill(&old).is_none());
+        assert!(client.get_archived_bill(&recent).is_some());
+        let stats = client.get_storage_stats();
+        assert_eq!(stats.archived_bills, 1);
+        assert_eq!(stats.total_archived_amount, 200);
+        assert_eq!(stats.active_bills, 1);
     }
 
+    // --- delegation ---
+
     #[test]
-    fn test_recurring_date_math_exact_calculation_verification() {
-        // Test: Verify exact date math calculation with known values
-        // due_date = 1_000_000
-        // frequency_days = 14
-        // Expected: 1_000_000 + (14 * 86400) = 1_000_000 + 1_209_600 = 2_209_600
+    fn test_delegate_pays_within_caps_and_is_recorded_as_payer() {
         let env = make_env();
         env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_768_435_200); // 2026-01-15
         let cid = env.register_contract(None, BillPayments);
         let client = BillPaymentsClient::new(&env, &cid);
         let owner = Address::generate(&env);
+        let sister = Address::generate(&env);
 
-        let base_due = 1_000_000u64;
-        let freq = 14u32;
-        let bill_id = client.create_bill(
-            &owner,
-            &String::from_str(&env, "Math Verification"),
-            &100,
-            &base_due,
-            &true,
-            &freq,
+        let ids = setup_bills(&env, &client, &owner, 4); // 100, 200, 300, 400
+        assert_eq!(
+            client.try_pay_bill(&sister, &ids.get(0).unwrap()),
+            Err(Ok(Error::Unauthorized))
         );
+        client.set_payment_delegate(&owner, &sister, &300, &400);
+        assert_eq!(client.get_payment_delegates(&owner).len(), 1);
 
-        client.pay_bill(&owner, &bill_id);
+        client.pay_bill(&sister, &ids.get(0).unwrap());
+        assert_eq!(
+            client.get_settlement(&ids.get(0).unwrap()).unwrap().payer,
+            sister
+        );
+        let record = client
+            .get_payment_history(&owner, &0, &1)
+            .items
+            .get(0)
+            .unwrap();
+        assert_eq!(record.payer, sister);
+
+        // 400 exceeds the per-bill cap; 100 + 300 would exceed the month
+        assert_eq!(
+            client.try_pay_bill(&sister, &ids.get(3).unwrap()),
+            Err(Ok(Error::DelegateLimitExceeded))
+        );
+        client.pay_bill(&sister, &ids.get(1).unwrap());
+        assert_eq!(
+            client.try_pay_bill(&sister, &ids.get(2).unwrap()),
+            Err(Ok(Error::DelegateLimitExceeded))
+        );
+        assert_eq!(
+            client.get_payment_delegate(&owner, &sister).unwrap().spent,
+            300
+        );
 
-        let next_bill = client.get_bill(&2).unwrap();
-        let expected = 1_000_000u64 + (14u64 * 86400);
-        assert_eq!(next_bill.due_date, expected);
-        assert_eq!(next_bill.due_date, 2_209_600);
+        // The monthly allowance resets with the calendar month
+        env.ledger().with_mut(|li| li.timestamp += 20 * 86400);
+        client.pay_bill(&sister, &ids.get(2).unwrap());
+
+        client.revoke_payment_delegate(&owner, &sister);
+        assert!(client.get_payment_delegate(&owner, &sister).is_none());
+        assert_eq!(
+            client.try_pay_bill(&sister, &ids.get(3).unwrap()),
+            Err(Ok(Error::Unauthorized))
+        );
     }
 }
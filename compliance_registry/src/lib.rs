@@ -0,0 +1,345 @@
+#![no_std]
+
+//! # Compliance Registry
+//!
+//! Shared KYC and sanctions state for the Remitwise contracts.
+//!
+//! Attesters appointed by the admin record a KYC tier for an address, valid
+//! until an expiry, and can revoke it or flag the address as sanctioned. The
+//! admin sets an amount cap per tier. An address without a valid attestation
+//! is tier 0, so capping tier 0 limits what unverified users can move. Tiers
+//! without a cap are unlimited.
+//!
+//! Other contracts consult the registry through `is_compliant(subject,
+//! amount)`, which is false for sanctioned addresses and amounts above the
+//! subject's tier cap. The family wallet checks withdrawal recipients, escrow
+//! checks claimants and the orchestrator checks flow callers, each only once
+//! a registry has been configured there.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Map, Symbol,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const COMPLY: Symbol = symbol_short!("comply");
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    /// Expiry not in the future
+    InvalidExpiry = 2,
+    InvalidCap = 3,
+    /// Tier 0 is reserved for addresses without an attestation
+    InvalidTier = 4,
+    AttestationNotFound = 5,
+    Sanctioned = 6,
+    ExceedsTierCap = 7,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub subject: Address,
+    pub tier: u32,
+    pub attester: Address,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub revoked: bool,
+}
+
+/// Effective compliance state of an address
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceStatus {
+    /// Attested tier, or 0 if there is no valid attestation
+    pub tier: u32,
+    pub sanctioned: bool,
+    /// Expiry of the current attestation
+    pub expires_at: Option<u64>,
+}
+
+#[contract]
+pub struct ComplianceRegistry;
+
+#[contractimpl]
+impl ComplianceRegistry {
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Set the admin who manages attesters and tier caps
+    ///
+    /// The first call must set the caller itself; afterwards only the current
+    /// admin can hand the role over.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
+        caller.require_auth();
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(Error::Unauthorized),
+            Some(admin) if admin != caller => return Err(Error::Unauthorized),
+            _ => {}
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Grant or remove the attester role
+    pub fn set_attester(
+        env: Env,
+        admin: Address,
+        attester: Address,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        let mut attesters = Self::load_attesters(&env);
+        if enabled {
+            attesters.set(attester.clone(), true);
+        } else {
+            attesters.remove(attester.clone());
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ATTESTRS"), &attesters);
+
+        env.events()
+            .publish((COMPLY, symbol_short!("attester")), (attester, enabled));
+        Ok(())
+    }
+
+    pub fn is_attester(env: Env, attester: Address) -> bool {
+        Self::load_attesters(&env).contains_key(attester)
+    }
+
+    /// Cap the amount a single operation may move for addresses of `tier`
+    pub fn set_tier_cap(env: Env, admin: Address, tier: u32, cap: i128) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        if cap < 0 {
+            return Err(Error::InvalidCap);
+        }
+        let mut caps = Self::load_caps(&env);
+        caps.set(tier, cap);
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().set(&symbol_short!("CAPS"), &caps);
+
+        env.events()
+            .publish((COMPLY, symbol_short!("tier_cap")), (tier, cap));
+        Ok(())
+    }
+
+    /// Remove a tier's cap, making it unlimited
+    pub fn clear_tier_cap(env: Env, admin: Address, tier: u32) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        let mut caps = Self::load_caps(&env);
+        caps.remove(tier);
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().set(&symbol_short!("CAPS"), &caps);
+        Ok(())
+    }
+
+    pub fn get_tier_cap(env: Env, tier: u32) -> Option<i128> {
+        Self::load_caps(&env).get(tier)
+    }
+
+    // -----------------------------------------------------------------------
+    // Attestations
+    // -----------------------------------------------------------------------
+
+    /// Record a KYC tier for `subject`, replacing any earlier attestation
+    pub fn attest(
+        env: Env,
+        attester: Address,
+        subject: Address,
+        tier: u32,
+        expires_at: u64,
+    ) -> Result<(), Error> {
+        Self::require_attester(&env, &attester)?;
+        if tier == 0 {
+            return Err(Error::InvalidTier);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidExpiry);
+        }
+
+        let attestation = Attestation {
+            subject: subject.clone(),
+            tier,
+            attester: attester.clone(),
+            issued_at: now,
+            expires_at,
+            revoked: false,
+        };
+        Self::save_attestation(&env, &attestation);
+
+        env.events().publish(
+            (COMPLY, symbol_short!("attested")),
+            (subject, attester, tier, expires_at),
+        );
+        Ok(())
+    }
+
+    /// Revoke `subject`'s attestation, dropping them to tier 0
+    ///
+    /// The record is kept with `revoked` set so the history stays readable.
+    pub fn revoke(env: Env, attester: Address, subject: Address) -> Result<(), Error> {
+        Self::require_attester(&env, &attester)?;
+        let mut attestation = Self::get_attestation(env.clone(), subject.clone())
+            .ok_or(Error::AttestationNotFound)?;
+        attestation.revoked = true;
+        Self::save_attestation(&env, &attestation);
+
+        env.events()
+            .publish((COMPLY, symbol_short!("revoked")), (subject, attester));
+        Ok(())
+    }
+
+    /// Set or clear the sanctions flag on `subject`
+    ///
+    /// Sanctions are independent of attestations and do not expire.
+    pub fn set_sanctioned(
+        env: Env,
+        attester: Address,
+        subject: Address,
+        sanctioned: bool,
+    ) -> Result<(), Error> {
+        Self::require_attester(&env, &attester)?;
+        let key = (symbol_short!("SANCTION"), subject.clone());
+        if sanctioned {
+            env.storage().persistent().set(&key, &true);
+            env.storage().persistent().extend_ttl(
+                &key,
+                PERSISTENT_LIFETIME_THRESHOLD,
+                PERSISTENT_BUMP_AMOUNT,
+            );
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish(
+            (COMPLY, symbol_short!("sanction")),
+            (subject, attester, sanctioned),
+        );
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    /// Latest attestation for `subject`, including expired or revoked ones
+    pub fn get_attestation(env: Env, subject: Address) -> Option<Attestation> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ATTEST"), subject))
+    }
+
+    pub fn get_status(env: Env, subject: Address) -> ComplianceStatus {
+        let sanctioned = env
+            .storage()
+            .persistent()
+            .has(&(symbol_short!("SANCTION"), subject.clone()));
+        let now = env.ledger().timestamp();
+        match Self::get_attestation(env, subject) {
+            Some(a) if !a.revoked && a.expires_at > now => ComplianceStatus {
+                tier: a.tier,
+                sanctioned,
+                expires_at: Some(a.expires_at),
+            },
+            _ => ComplianceStatus {
+                tier: 0,
+                sanctioned,
+                expires_at: None,
+            },
+        }
+    }
+
+    /// Check whether `subject` may move `amount`
+    ///
+    /// # Returns
+    /// Ok(tier) the subject's effective tier
+    /// Err(Error::Sanctioned) if the subject is flagged
+    /// Err(Error::ExceedsTierCap) if amount is above the tier's cap
+    pub fn check_compliance(env: Env, subject: Address, amount: i128) -> Result<u32, Error> {
+        let status = Self::get_status(env.clone(), subject);
+        if status.sanctioned {
+            return Err(Error::Sanctioned);
+        }
+        if let Some(cap) = Self::get_tier_cap(env, status.tier) {
+            if amount > cap {
+                return Err(Error::ExceedsTierCap);
+            }
+        }
+        Ok(status.tier)
+    }
+
+    /// Boolean form of `check_compliance` for consuming contracts
+    pub fn is_compliant(env: Env, subject: Address, amount: i128) -> bool {
+        Self::check_compliance(env, subject, amount).is_ok()
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()).as_ref() != Some(admin) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn require_attester(env: &Env, attester: &Address) -> Result<(), Error> {
+        attester.require_auth();
+        if !Self::load_attesters(env).contains_key(attester.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn load_attesters(env: &Env) -> Map<Address, bool> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("ATTESTRS"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn load_caps(env: &Env) -> Map<u32, i128> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("CAPS"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn save_attestation(env: &Env, attestation: &Attestation) {
+        let key = (symbol_short!("ATTEST"), attestation.subject.clone());
+        env.storage().persistent().set(&key, attestation);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,147 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: ComplianceRegistryClient<'a>,
+    admin: Address,
+    attester: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, ComplianceRegistry);
+    let client = ComplianceRegistryClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let attester = Address::generate(&env);
+    client.set_admin(&admin, &admin);
+    client.set_attester(&admin, &attester, &true);
+
+    Setup {
+        env,
+        client,
+        admin,
+        attester,
+    }
+}
+
+#[test]
+fn test_attestation_sets_tier_until_expiry() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    assert_eq!(s.client.get_status(&user).tier, 0);
+
+    s.client.attest(&s.attester, &user, &2, &5_000);
+    let status = s.client.get_status(&user);
+    assert_eq!(status.tier, 2);
+    assert_eq!(status.expires_at, Some(5_000));
+
+    s.env.ledger().with_mut(|l| l.timestamp = 5_000);
+    assert_eq!(s.client.get_status(&user).tier, 0);
+    assert_eq!(s.client.get_attestation(&user).unwrap().tier, 2);
+}
+
+#[test]
+fn test_revocation_drops_to_tier_zero() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_revoke(&s.attester, &user),
+        Err(Ok(Error::AttestationNotFound))
+    );
+    s.client.attest(&s.attester, &user, &1, &5_000);
+    s.client.revoke(&s.attester, &user);
+
+    assert_eq!(s.client.get_status(&user).tier, 0);
+    assert!(s.client.get_attestation(&user).unwrap().revoked);
+}
+
+#[test]
+fn test_tier_caps_and_sanctions() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    s.client.set_tier_cap(&s.admin, &0, &100);
+    s.client.set_tier_cap(&s.admin, &1, &1_000);
+
+    assert!(s.client.is_compliant(&user, &100));
+    assert_eq!(
+        s.client.try_check_compliance(&user, &101),
+        Err(Ok(Error::ExceedsTierCap))
+    );
+
+    s.client.attest(&s.attester, &user, &1, &5_000);
+    assert_eq!(s.client.check_compliance(&user, &1_000), 1);
+    assert!(!s.client.is_compliant(&user, &1_001));
+
+    // Tier 2 has no cap
+    s.client.attest(&s.attester, &user, &2, &5_000);
+    assert!(s.client.is_compliant(&user, &1_000_000));
+
+    s.client.set_sanctioned(&s.attester, &user, &true);
+    assert_eq!(
+        s.client.try_check_compliance(&user, &1),
+        Err(Ok(Error::Sanctioned))
+    );
+    s.client.set_sanctioned(&s.attester, &user, &false);
+    assert!(s.client.is_compliant(&user, &1));
+
+    s.client.clear_tier_cap(&s.admin, &0);
+    assert_eq!(s.client.get_tier_cap(&0), None);
+}
+
+#[test]
+fn test_roles_are_enforced() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    let outsider = Address::generate(&s.env);
+
+    assert_eq!(
+        s.client.try_attest(&outsider, &user, &1, &5_000),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_set_sanctioned(&outsider, &user, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_set_tier_cap(&s.attester, &0, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_set_admin(&outsider, &outsider),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    s.client.set_attester(&s.admin, &s.attester, &false);
+    assert!(!s.client.is_attester(&s.attester));
+    assert_eq!(
+        s.client.try_attest(&s.attester, &user, &1, &5_000),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_attest_validation() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_attest(&s.attester, &user, &0, &5_000),
+        Err(Ok(Error::InvalidTier))
+    );
+    assert_eq!(
+        s.client.try_attest(&s.attester, &user, &1, &1_000),
+        Err(Ok(Error::InvalidExpiry))
+    );
+    assert_eq!(
+        s.client.try_set_tier_cap(&s.admin, &1, &-1),
+        Err(Ok(Error::InvalidCap))
+    );
+}
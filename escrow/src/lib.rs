@@ -0,0 +1,564 @@
+#![no_std]
+
+//! # Remittance Escrow
+//!
+//! Holds a sender's tokens until the intended recipient claims them.
+//!
+//! An escrow names its recipient either by address or by the sha256 hash of a
+//! claim code that the sender shares out of band (e.g. by SMS). The recipient
+//! claims before the deadline; after it, the sender can reclaim whatever is
+//! left. Before the deadline the sender may also release part of the funds to
+//! an address recipient ahead of the claim.
+//!
+//! Either party can flag an escrow as disputed, which freezes it until the
+//! admin splits the remaining balance between recipient and sender.
+//!
+//! A claim code is revealed when it is claimed, so a code claim has two steps
+//! to keep anyone who sees it from claiming first. The claimant commits to
+//! `sha256(code || claimant)` with `commit_claim` (see `claim_commitment`),
+//! then calls `claim` with the code in a later ledger. The claim pays only the
+//! address whose commitment matches, and a commitment made after the code was
+//! seen comes too late. Codes should still be long and random, and senders
+//! who know the recipient's address should use it instead.
+//!
+//! If the admin configures a compliance registry, claimants and release
+//! recipients must pass its check for the amount they receive.
+//!
+//! ## Events
+//!
+//! Every stage publishes under `("escrow", stage)` with the escrow ID, the
+//! acting address and the amount moved: `created`, `released`, `claimed`,
+//! `reclaimed`, `disputed`, `resolved`.
+
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short,
+    token::TokenClient, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const ESCROW: Symbol = symbol_short!("escrow");
+
+/// Subset of the compliance registry used to screen payouts
+#[contractclient(name = "ComplianceRegistryClient")]
+pub trait ComplianceRegistryTrait {
+    fn is_compliant(env: Env, subject: Address, amount: i128) -> bool;
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidAmount = 1,
+    InvalidDeadline = 2,
+    EscrowNotFound = 3,
+    Unauthorized = 4,
+    /// Escrow was already claimed, reclaimed or resolved
+    NotActive = 5,
+    /// Claim or release after the deadline
+    Expired = 6,
+    /// Reclaim before the deadline
+    NotExpired = 7,
+    InvalidClaimCode = 8,
+    Disputed = 9,
+    NotDisputed = 10,
+    /// Release or resolution larger than the remaining balance
+    ExceedsBalance = 11,
+    /// Partial release needs an address recipient
+    RecipientUnknown = 12,
+    /// Payout recipient failed the compliance registry check
+    ComplianceRejected = 13,
+    /// Code claim without a commitment from an earlier ledger
+    NoClaimCommitment = 14,
+}
+
+/// Commitment a claimant registers with `commit_claim` before revealing
+/// `code`: `sha256(code || claimant)`, with the claimant's address in XDR.
+pub fn claim_commitment(env: &Env, code: &Bytes, claimant: &Address) -> BytesN<32> {
+    let mut preimage = code.clone();
+    preimage.append(&claimant.clone().to_xdr(env));
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+/// A claimant's commitment and the ledger it was made in
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimCommitment {
+    pub commitment: BytesN<32>,
+    pub ledger: u32,
+}
+
+/// Who may claim an escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Recipient {
+    /// Only this address can claim
+    Address(Address),
+    /// Whoever presents the code whose sha256 is this hash, after committing
+    /// to it with `commit_claim`, can claim
+    ClaimCode(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Active,
+    Disputed,
+    /// Recipient received the full amount
+    Claimed,
+    /// Sender took back the remainder after the deadline
+    Reclaimed,
+    /// Admin split a disputed remainder
+    Resolved,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escrow {
+    pub id: u32,
+    pub sender: Address,
+    pub recipient: Recipient,
+    pub token: Address,
+    pub amount: i128,
+    /// Paid out so far through releases and claims
+    pub released: i128,
+    /// Last ledger timestamp at which the recipient can claim
+    pub deadline: u64,
+    pub status: EscrowStatus,
+    pub created_at: u64,
+}
+
+impl Escrow {
+    fn remaining(&self) -> i128 {
+        self.amount - self.released
+    }
+}
+
+#[contract]
+pub struct EscrowContract;
+
+#[contractimpl]
+impl EscrowContract {
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Set the admin who resolves disputes
+    ///
+    /// The first call must set the caller itself; afterwards only the current
+    /// admin can hand the role over.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
+        caller.require_auth();
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(Error::Unauthorized),
+            Some(admin) if admin != caller => return Err(Error::Unauthorized),
+            _ => {}
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Set or clear the compliance registry that payouts are screened against
+    pub fn set_compliance_registry(
+        env: Env,
+        admin: Address,
+        registry: Option<Address>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()) != Some(admin) {
+            return Err(Error::Unauthorized);
+        }
+        Self::extend_instance_ttl(&env);
+        match registry {
+            Some(registry) => env
+                .storage()
+                .instance()
+                .set(&symbol_short!("CMP_REG"), &registry),
+            None => env.storage().instance().remove(&symbol_short!("CMP_REG")),
+        }
+        Ok(())
+    }
+
+    pub fn get_compliance_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("CMP_REG"))
+    }
+
+    // -----------------------------------------------------------------------
+    // Escrow lifecycle
+    // -----------------------------------------------------------------------
+
+    /// Lock `amount` of `token` from the sender for `recipient`
+    ///
+    /// # Returns
+    /// Ok(escrow_id)
+    /// Err(Error::InvalidAmount) if amount is not positive
+    /// Err(Error::InvalidDeadline) if the deadline is not in the future
+    pub fn create_escrow(
+        env: Env,
+        sender: Address,
+        token: Address,
+        amount: i128,
+        recipient: Recipient,
+        deadline: u64,
+    ) -> Result<u32, Error> {
+        sender.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let now = env.ledger().timestamp();
+        if deadline <= now {
+            return Err(Error::InvalidDeadline);
+        }
+
+        TokenClient::new(&env, &token).transfer(&sender, &env.current_contract_address(), &amount);
+
+        Self::extend_instance_ttl(&env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_ID"), &(id + 1));
+
+        let escrow = Escrow {
+            id,
+            sender: sender.clone(),
+            recipient,
+            token,
+            amount,
+            released: 0,
+            deadline,
+            status: EscrowStatus::Active,
+            created_at: now,
+        };
+        Self::save_escrow(&env, &escrow);
+
+        env.events()
+            .publish((ESCROW, symbol_short!("created")), (id, sender, amount));
+        Ok(id)
+    }
+
+    /// Pay part of an escrow to its recipient before the claim
+    ///
+    /// Only escrows with an address recipient can be released, since a claim
+    /// code does not say where the tokens should go. Releasing the whole
+    /// remainder completes the escrow.
+    ///
+    /// # Returns
+    /// Ok(remaining) balance still held
+    pub fn release(env: Env, sender: Address, escrow_id: u32, amount: i128) -> Result<i128, Error> {
+        sender.require_auth();
+        let mut escrow = Self::load_escrow(&env, escrow_id)?;
+        if escrow.sender != sender {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_active(&escrow)?;
+        if env.ledger().timestamp() > escrow.deadline {
+            return Err(Error::Expired);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > escrow.remaining() {
+            return Err(Error::ExceedsBalance);
+        }
+        let Recipient::Address(recipient) = escrow.recipient.clone() else {
+            return Err(Error::RecipientUnknown);
+        };
+        Self::require_compliant(&env, &recipient, amount)?;
+
+        Self::pay_out(&env, &escrow.token, &recipient, amount);
+        escrow.released += amount;
+        if escrow.remaining() == 0 {
+            escrow.status = EscrowStatus::Claimed;
+        }
+        Self::save_escrow(&env, &escrow);
+
+        env.events().publish(
+            (ESCROW, symbol_short!("released")),
+            (escrow_id, sender, amount),
+        );
+        Ok(escrow.remaining())
+    }
+
+    /// Commit to claiming a claim-code escrow without revealing the code
+    ///
+    /// `commitment` is `claim_commitment(code, claimant)`. A later commitment
+    /// by the same claimant replaces this one.
+    ///
+    /// # Returns
+    /// Err(Error::RecipientUnknown) if the escrow names an address recipient
+    pub fn commit_claim(
+        env: Env,
+        claimant: Address,
+        escrow_id: u32,
+        commitment: BytesN<32>,
+    ) -> Result<(), Error> {
+        claimant.require_auth();
+        let escrow = Self::load_escrow(&env, escrow_id)?;
+        Self::require_active(&escrow)?;
+        if env.ledger().timestamp() > escrow.deadline {
+            return Err(Error::Expired);
+        }
+        if !matches!(escrow.recipient, Recipient::ClaimCode(_)) {
+            return Err(Error::RecipientUnknown);
+        }
+
+        let key = (symbol_short!("CLM_CMT"), escrow_id, claimant);
+        let entry = ClaimCommitment {
+            commitment,
+            ledger: env.ledger().sequence(),
+        };
+        env.storage().persistent().set(&key, &entry);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        Ok(())
+    }
+
+    pub fn get_claim_commitment(
+        env: Env,
+        escrow_id: u32,
+        claimant: Address,
+    ) -> Option<ClaimCommitment> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("CLM_CMT"), escrow_id, claimant))
+    }
+
+    /// Claim the remaining balance before the deadline
+    ///
+    /// `claim_code` is ignored for address recipients and required for
+    /// claim-code recipients, whose claimant must have committed to it with
+    /// `commit_claim` in an earlier ledger.
+    ///
+    /// # Returns
+    /// Ok(amount) paid to the claimant
+    /// Err(Error::NoClaimCommitment) if the claimant has no earlier commitment
+    /// Err(Error::InvalidClaimCode) if the code is missing or does not match
+    pub fn claim(
+        env: Env,
+        claimant: Address,
+        escrow_id: u32,
+        claim_code: Option<Bytes>,
+    ) -> Result<i128, Error> {
+        claimant.require_auth();
+        let mut escrow = Self::load_escrow(&env, escrow_id)?;
+        Self::require_active(&escrow)?;
+        if env.ledger().timestamp() > escrow.deadline {
+            return Err(Error::Expired);
+        }
+        match &escrow.recipient {
+            Recipient::Address(recipient) => {
+                if *recipient != claimant {
+                    return Err(Error::Unauthorized);
+                }
+            }
+            Recipient::ClaimCode(hash) => {
+                let code = claim_code.ok_or(Error::InvalidClaimCode)?;
+                let key = (symbol_short!("CLM_CMT"), escrow_id, claimant.clone());
+                let committed: ClaimCommitment = env
+                    .storage()
+                    .persistent()
+                    .get(&key)
+                    .ok_or(Error::NoClaimCommitment)?;
+                if committed.ledger >= env.ledger().sequence() {
+                    return Err(Error::NoClaimCommitment);
+                }
+                if env.crypto().sha256(&code).to_bytes() != *hash
+                    || claim_commitment(&env, &code, &claimant) != committed.commitment
+                {
+                    return Err(Error::InvalidClaimCode);
+                }
+                env.storage().persistent().remove(&key);
+            }
+        }
+
+        let amount = escrow.remaining();
+        Self::require_compliant(&env, &claimant, amount)?;
+        Self::pay_out(&env, &escrow.token, &claimant, amount);
+        escrow.released += amount;
+        escrow.status = EscrowStatus::Claimed;
+        Self::save_escrow(&env, &escrow);
+
+        env.events().publish(
+            (ESCROW, symbol_short!("claimed")),
+            (escrow_id, claimant, amount),
+        );
+        Ok(amount)
+    }
+
+    /// Return the unclaimed balance to the sender after the deadline
+    ///
+    /// # Returns
+    /// Ok(amount) returned to the sender
+    pub fn reclaim(env: Env, sender: Address, escrow_id: u32) -> Result<i128, Error> {
+        sender.require_auth();
+        let mut escrow = Self::load_escrow(&env, escrow_id)?;
+        if escrow.sender != sender {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_active(&escrow)?;
+        if env.ledger().timestamp() <= escrow.deadline {
+            return Err(Error::NotExpired);
+        }
+
+        let amount = escrow.remaining();
+        Self::pay_out(&env, &escrow.token, &sender, amount);
+        escrow.released += amount;
+        escrow.status = EscrowStatus::Reclaimed;
+        Self::save_escrow(&env, &escrow);
+
+        env.events().publish(
+            (ESCROW, symbol_short!("reclaimed")),
+            (escrow_id, sender, amount),
+        );
+        Ok(amount)
+    }
+
+    /// Freeze an escrow until the admin resolves it
+    ///
+    /// The sender or an address recipient may dispute, before or after the
+    /// deadline, as long as the escrow is still active.
+    pub fn dispute(env: Env, caller: Address, escrow_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        let mut escrow = Self::load_escrow(&env, escrow_id)?;
+        let is_recipient = matches!(&escrow.recipient, Recipient::Address(r) if *r == caller);
+        if escrow.sender != caller && !is_recipient {
+            return Err(Error::Unauthorized);
+        }
+        Self::require_active(&escrow)?;
+
+        escrow.status = EscrowStatus::Disputed;
+        Self::save_escrow(&env, &escrow);
+
+        env.events().publish(
+            (ESCROW, symbol_short!("disputed")),
+            (escrow_id, caller, escrow.remaining()),
+        );
+        Ok(())
+    }
+
+    /// Settle a disputed escrow
+    ///
+    /// Pays `to_recipient` to `recipient` and the rest of the remaining
+    /// balance back to the sender. `recipient` is needed for claim-code
+    /// escrows, whose recipient address the contract never learned; for
+    /// address escrows it must match the stored recipient.
+    pub fn resolve_dispute(
+        env: Env,
+        admin: Address,
+        escrow_id: u32,
+        recipient: Address,
+        to_recipient: i128,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()) != Some(admin.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        let mut escrow = Self::load_escrow(&env, escrow_id)?;
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(Error::NotDisputed);
+        }
+        if let Recipient::Address(stored) = &escrow.recipient {
+            if *stored != recipient {
+                return Err(Error::Unauthorized);
+            }
+        }
+        if to_recipient < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let remaining = escrow.remaining();
+        if to_recipient > remaining {
+            return Err(Error::ExceedsBalance);
+        }
+
+        Self::pay_out(&env, &escrow.token, &recipient, to_recipient);
+        Self::pay_out(
+            &env,
+            &escrow.token,
+            &escrow.sender,
+            remaining - to_recipient,
+        );
+        escrow.released = escrow.amount;
+        escrow.status = EscrowStatus::Resolved;
+        Self::save_escrow(&env, &escrow);
+
+        env.events().publish(
+            (ESCROW, symbol_short!("resolved")),
+            (escrow_id, admin, to_recipient),
+        );
+        Ok(())
+    }
+
+    pub fn get_escrow(env: Env, escrow_id: u32) -> Option<Escrow> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ESCROW"), escrow_id))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn load_escrow(env: &Env, escrow_id: u32) -> Result<Escrow, Error> {
+        Self::get_escrow(env.clone(), escrow_id).ok_or(Error::EscrowNotFound)
+    }
+
+    fn require_active(escrow: &Escrow) -> Result<(), Error> {
+        match escrow.status {
+            EscrowStatus::Active => Ok(()),
+            EscrowStatus::Disputed => Err(Error::Disputed),
+            _ => Err(Error::NotActive),
+        }
+    }
+
+    fn require_compliant(env: &Env, recipient: &Address, amount: i128) -> Result<(), Error> {
+        if let Some(registry) = Self::get_compliance_registry(env.clone()) {
+            let client = ComplianceRegistryClient::new(env, &registry);
+            if !matches!(client.try_is_compliant(recipient, &amount), Ok(Ok(true))) {
+                return Err(Error::ComplianceRejected);
+            }
+        }
+        Ok(())
+    }
+
+    fn save_escrow(env: &Env, escrow: &Escrow) {
+        let key = (symbol_short!("ESCROW"), escrow.id);
+        env.storage().persistent().set(&key, escrow);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn pay_out(env: &Env, token: &Address, to: &Address, amount: i128) {
+        if amount > 0 {
+            TokenClient::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+        }
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,342 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Bytes, BytesN, Env,
+};
+
+#[contract]
+pub struct MockCompliance;
+
+#[contractimpl]
+impl MockCompliance {
+    pub fn deny(env: Env, subject: Address) {
+        env.storage().instance().set(&subject, &true);
+    }
+
+    pub fn is_compliant(env: Env, subject: Address, _amount: i128) -> bool {
+        !env.storage().instance().has(&subject)
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: EscrowContractClient<'a>,
+    token: TokenClient<'a>,
+    sender: Address,
+    recipient: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, EscrowContract);
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let sender = Address::generate(&env);
+    StellarAssetClient::new(&env, &asset.address()).mint(&sender, &10_000);
+    let token = TokenClient::new(&env, &asset.address());
+    let recipient = Address::generate(&env);
+
+    Setup {
+        env,
+        client,
+        token,
+        sender,
+        recipient,
+    }
+}
+
+fn set_time(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|l| l.timestamp = timestamp);
+}
+
+fn next_ledger(env: &Env) {
+    env.ledger().with_mut(|l| l.sequence_number += 1);
+}
+
+#[test]
+fn test_create_and_claim_by_address() {
+    let s = setup();
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &1_000,
+        &Recipient::Address(s.recipient.clone()),
+        &100,
+    );
+    assert_eq!(s.token.balance(&s.sender), 9_000);
+    assert_eq!(s.token.balance(&s.client.address), 1_000);
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_claim(&stranger, &id, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert_eq!(s.client.claim(&s.recipient, &id, &None), 1_000);
+    assert_eq!(s.token.balance(&s.recipient), 1_000);
+    let escrow = s.client.get_escrow(&id).unwrap();
+    assert_eq!(escrow.status, EscrowStatus::Claimed);
+    assert_eq!(
+        s.client.try_claim(&s.recipient, &id, &None),
+        Err(Ok(Error::NotActive))
+    );
+}
+
+#[test]
+fn test_claim_with_code() {
+    let s = setup();
+    let code = Bytes::from_slice(&s.env, b"7Q2-K9X-41M");
+    let hash = s.env.crypto().sha256(&code).to_bytes();
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &500,
+        &Recipient::ClaimCode(hash),
+        &100,
+    );
+
+    let claimant = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_claim(&claimant, &id, &Some(code.clone())),
+        Err(Ok(Error::NoClaimCommitment))
+    );
+    s.client
+        .commit_claim(&claimant, &id, &claim_commitment(&s.env, &code, &claimant));
+    // The commitment only counts from the next ledger on
+    assert_eq!(
+        s.client.try_claim(&claimant, &id, &Some(code.clone())),
+        Err(Ok(Error::NoClaimCommitment))
+    );
+    next_ledger(&s.env);
+
+    let wrong = Bytes::from_slice(&s.env, b"000-000-000");
+    assert_eq!(
+        s.client.try_claim(&claimant, &id, &Some(wrong)),
+        Err(Ok(Error::InvalidClaimCode))
+    );
+    assert_eq!(
+        s.client.try_claim(&claimant, &id, &None),
+        Err(Ok(Error::InvalidClaimCode))
+    );
+    assert_eq!(
+        s.client.try_release(&s.sender, &id, &100),
+        Err(Ok(Error::RecipientUnknown))
+    );
+
+    s.client.claim(&claimant, &id, &Some(code));
+    assert_eq!(s.token.balance(&claimant), 500);
+    assert!(s.client.get_claim_commitment(&id, &claimant).is_none());
+}
+
+#[test]
+fn test_revealed_code_cannot_be_claimed_by_another_address() {
+    let s = setup();
+    let code = Bytes::from_slice(&s.env, b"7Q2-K9X-41M");
+    let hash = s.env.crypto().sha256(&code).to_bytes();
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &500,
+        &Recipient::ClaimCode(hash),
+        &100,
+    );
+    let claimant = Address::generate(&s.env);
+    s.client
+        .commit_claim(&claimant, &id, &claim_commitment(&s.env, &code, &claimant));
+    next_ledger(&s.env);
+
+    // Someone who sees the code in the claim cannot reuse the claimant's
+    // commitment, and a commitment of their own comes too late
+    let attacker = Address::generate(&s.env);
+    s.client
+        .commit_claim(&attacker, &id, &claim_commitment(&s.env, &code, &claimant));
+    next_ledger(&s.env);
+    assert_eq!(
+        s.client.try_claim(&attacker, &id, &Some(code.clone())),
+        Err(Ok(Error::InvalidClaimCode))
+    );
+    s.client
+        .commit_claim(&attacker, &id, &claim_commitment(&s.env, &code, &attacker));
+    assert_eq!(
+        s.client.try_claim(&attacker, &id, &Some(code.clone())),
+        Err(Ok(Error::NoClaimCommitment))
+    );
+
+    assert_eq!(s.client.claim(&claimant, &id, &Some(code)), 500);
+    assert_eq!(s.token.balance(&attacker), 0);
+
+    let address_id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &100,
+        &Recipient::Address(s.recipient.clone()),
+        &100,
+    );
+    assert_eq!(
+        s.client.try_commit_claim(
+            &s.recipient,
+            &address_id,
+            &BytesN::from_array(&s.env, &[0; 32])
+        ),
+        Err(Ok(Error::RecipientUnknown))
+    );
+}
+
+#[test]
+fn test_partial_release_then_claim() {
+    let s = setup();
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &1_000,
+        &Recipient::Address(s.recipient.clone()),
+        &100,
+    );
+
+    assert_eq!(
+        s.client.try_release(&s.sender, &id, &1_001),
+        Err(Ok(Error::ExceedsBalance))
+    );
+    assert_eq!(s.client.release(&s.sender, &id, &300), 700);
+    assert_eq!(s.token.balance(&s.recipient), 300);
+
+    assert_eq!(s.client.claim(&s.recipient, &id, &None), 700);
+    assert_eq!(s.token.balance(&s.recipient), 1_000);
+    assert_eq!(s.client.get_escrow(&id).unwrap().released, 1_000);
+}
+
+#[test]
+fn test_reclaim_after_deadline() {
+    let s = setup();
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &1_000,
+        &Recipient::Address(s.recipient.clone()),
+        &100,
+    );
+    s.client.release(&s.sender, &id, &200);
+
+    assert_eq!(
+        s.client.try_reclaim(&s.sender, &id),
+        Err(Ok(Error::NotExpired))
+    );
+    set_time(&s.env, 101);
+    assert_eq!(
+        s.client.try_claim(&s.recipient, &id, &None),
+        Err(Ok(Error::Expired))
+    );
+    assert_eq!(
+        s.client.try_reclaim(&s.recipient, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    assert_eq!(s.client.reclaim(&s.sender, &id), 800);
+    assert_eq!(s.token.balance(&s.sender), 9_800);
+    assert_eq!(
+        s.client.get_escrow(&id).unwrap().status,
+        EscrowStatus::Reclaimed
+    );
+}
+
+#[test]
+fn test_dispute_freezes_until_resolved() {
+    let s = setup();
+    let admin = Address::generate(&s.env);
+    s.client.set_admin(&admin, &admin);
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &1_000,
+        &Recipient::Address(s.recipient.clone()),
+        &100,
+    );
+
+    s.client.dispute(&s.recipient, &id);
+    assert_eq!(
+        s.client.try_claim(&s.recipient, &id, &None),
+        Err(Ok(Error::Disputed))
+    );
+    set_time(&s.env, 200);
+    assert_eq!(
+        s.client.try_reclaim(&s.sender, &id),
+        Err(Ok(Error::Disputed))
+    );
+
+    assert_eq!(
+        s.client
+            .try_resolve_dispute(&s.sender, &id, &s.recipient, &600),
+        Err(Ok(Error::Unauthorized))
+    );
+    s.client.resolve_dispute(&admin, &id, &s.recipient, &600);
+    assert_eq!(s.token.balance(&s.recipient), 600);
+    assert_eq!(s.token.balance(&s.sender), 9_400);
+    assert_eq!(
+        s.client.get_escrow(&id).unwrap().status,
+        EscrowStatus::Resolved
+    );
+}
+
+#[test]
+fn test_create_validation() {
+    let s = setup();
+    set_time(&s.env, 50);
+    let recipient = Recipient::Address(s.recipient.clone());
+    assert_eq!(
+        s.client
+            .try_create_escrow(&s.sender, &s.token.address, &0, &recipient, &100),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.client
+            .try_create_escrow(&s.sender, &s.token.address, &10, &recipient, &50),
+        Err(Ok(Error::InvalidDeadline))
+    );
+    assert_eq!(
+        s.client.try_claim(&s.recipient, &42, &None),
+        Err(Ok(Error::EscrowNotFound))
+    );
+}
+
+#[test]
+fn test_compliance_registry_screens_payouts() {
+    let s = setup();
+    let admin = Address::generate(&s.env);
+    s.client.set_admin(&admin, &admin);
+    let registry = s.env.register_contract(None, MockCompliance);
+    MockComplianceClient::new(&s.env, &registry).deny(&s.recipient);
+
+    assert_eq!(
+        s.client
+            .try_set_compliance_registry(&s.sender, &Some(registry.clone())),
+        Err(Ok(Error::Unauthorized))
+    );
+    s.client
+        .set_compliance_registry(&admin, &Some(registry.clone()));
+    assert_eq!(s.client.get_compliance_registry(), Some(registry));
+
+    let id = s.client.create_escrow(
+        &s.sender,
+        &s.token.address,
+        &1_000,
+        &Recipient::Address(s.recipient.clone()),
+        &100,
+    );
+    assert_eq!(
+        s.client.try_release(&s.sender, &id, &100),
+        Err(Ok(Error::ComplianceRejected))
+    );
+    assert_eq!(
+        s.client.try_claim(&s.recipient, &id, &None),
+        Err(Ok(Error::ComplianceRejected))
+    );
+
+    s.client.set_compliance_registry(&admin, &None);
+    assert_eq!(s.client.claim(&s.recipient, &id, &None), 1_000);
+}
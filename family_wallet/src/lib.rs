@@ -1,9 +1,42 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
-    Env, Map, Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short,
+    token::TokenClient, Address, Env, Map, String, Symbol, Vec,
 };
 
+/// Subset of the insurance contract used to manage wallet-paid policies.
+#[contractclient(name = "InsuranceClient")]
+pub trait InsuranceTrait {
+    fn deactivate_policy(env: Env, caller: Address, policy_id: u32) -> bool;
+    fn set_payer_wallet(env: Env, caller: Address, policy_id: u32, wallet: Option<Address>)
+        -> bool;
+}
+
+/// Subset of the savings goals contract used to release guarded goals.
+#[contractclient(name = "SavingsGoalsClient")]
+pub trait SavingsGoalsTrait {
+    fn unlock_goal(env: Env, caller: Address, goal_id: u32) -> bool;
+    fn withdraw_from_goal(env: Env, caller: Address, goal_id: u32, amount: i128) -> i128;
+}
+
+/// Subset of the bill payments contract used to approve large bills.
+#[contractclient(name = "BillPaymentsClient")]
+pub trait BillPaymentsTrait {
+    fn approve_bill(env: Env, wallet: Address, bill_id: u32);
+}
+
+/// Subset of the alias registry used to resolve named recipients.
+#[contractclient(name = "AliasRegistryClient")]
+pub trait AliasRegistryTrait {
+    fn resolve(env: Env, name: String) -> Option<Address>;
+}
+
+/// Subset of the compliance registry used to screen withdrawal recipients.
+#[contractclient(name = "ComplianceRegistryClient")]
+pub trait ComplianceRegistryTrait {
+    fn is_compliant(env: Env, subject: Address, amount: i128) -> bool;
+}
+
 // Storage TTL constants for active data
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280;
 const INSTANCE_BUMP_AMOUNT: u32 = 518400;
@@ -25,6 +58,10 @@ pub enum TransactionType {
     EmergencyTransfer = 4,
     PolicyCancellation = 5,
     RegularWithdrawal = 6,
+    GoalUnlock = 7,
+    GoalWithdrawal = 8,
+    BillApproval = 9,
+    PayerWalletChange = 10,
 }
 
 #[contracttype]
@@ -65,6 +102,19 @@ pub enum TransactionData {
     RoleChange(Address, FamilyRole),
     EmergencyTransfer(Address, Address, i128),
     PolicyCancellation(u32),
+    GoalUnlock(u32),
+    GoalWithdrawal(u32, i128),
+    BillApproval(u32),
+    PayerWalletChange(u32, Option<Address>),
+}
+
+/// Entry in the withdrawal recipient allowlist. Aliases are resolved each
+/// time a withdrawal executes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AllowedRecipient {
+    Address(Address),
+    Alias(String),
 }
 
 #[contracttype]
@@ -238,6 +288,10 @@ impl FamilyWallet {
             TransactionType::RoleChange,
             TransactionType::EmergencyTransfer,
             TransactionType::PolicyCancellation,
+            TransactionType::GoalUnlock,
+            TransactionType::GoalWithdrawal,
+            TransactionType::BillApproval,
+            TransactionType::PayerWalletChange,
         ] {
             env.storage()
                 .instance()
@@ -750,7 +804,10 @@ impl FamilyWallet {
         )
     }
 
+    /// Propose cancelling a policy this wallet pays for. Refused while no
+    /// insurance contract is set.
     pub fn propose_policy_cancellation(env: Env, proposer: Address, policy_id: u32) -> u64 {
+        Self::insurance_contract(&env);
         Self::propose_transaction(
             env,
             proposer,
@@ -759,6 +816,257 @@ impl FamilyWallet {
         )
     }
 
+    /// Propose handing a policy this wallet pays for to another payer wallet,
+    /// or clearing the payer with `None` so the owner pays again. Refused
+    /// while no insurance contract is set.
+    pub fn propose_payer_wallet_change(
+        env: Env,
+        proposer: Address,
+        policy_id: u32,
+        wallet: Option<Address>,
+    ) -> u64 {
+        Self::insurance_contract(&env);
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::PayerWalletChange,
+            TransactionData::PayerWalletChange(policy_id, wallet),
+        )
+    }
+
+    /// Set the insurance contract that approved `PolicyCancellation` and
+    /// `PayerWalletChange` transactions are forwarded to. Neither can be
+    /// proposed without it.
+    pub fn set_insurance_contract(env: Env, caller: Address, insurance: Address) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can set the insurance contract");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("INS_CTR"), &insurance);
+        Self::append_access_audit(
+            &env,
+            symbol_short!("set_ins"),
+            &caller,
+            Some(insurance),
+            true,
+        );
+
+        true
+    }
+
+    pub fn get_insurance_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("INS_CTR"))
+    }
+
+    /// Propose unlocking a savings goal this wallet guards.
+    pub fn propose_goal_unlock(env: Env, proposer: Address, goal_id: u32) -> u64 {
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::GoalUnlock,
+            TransactionData::GoalUnlock(goal_id),
+        )
+    }
+
+    /// Propose withdrawing from a guarded savings goal. The funds go to the
+    /// goal's owner, not the wallet.
+    pub fn propose_goal_withdrawal(env: Env, proposer: Address, goal_id: u32, amount: i128) -> u64 {
+        if amount <= 0 {
+            panic!("Amount must be positive");
+        }
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::GoalWithdrawal,
+            TransactionData::GoalWithdrawal(goal_id, amount),
+        )
+    }
+
+    /// Set the savings goals contract that approved goal transactions are
+    /// forwarded to. The wallet acts as guardian there under its own address.
+    pub fn set_savings_contract(env: Env, caller: Address, savings: Address) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can set the savings contract");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SAV_CTR"), &savings);
+        Self::append_access_audit(&env, symbol_short!("set_sav"), &caller, Some(savings), true);
+
+        true
+    }
+
+    pub fn get_savings_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("SAV_CTR"))
+    }
+
+    /// Propose approving a bill whose owner linked this wallet in the bill
+    /// payments contract.
+    pub fn propose_bill_approval(env: Env, proposer: Address, bill_id: u32) -> u64 {
+        Self::propose_transaction(
+            env,
+            proposer,
+            TransactionType::BillApproval,
+            TransactionData::BillApproval(bill_id),
+        )
+    }
+
+    /// Set the bill payments contract that approved bills are reported to.
+    pub fn set_bills_contract(env: Env, caller: Address, bills: Address) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can set the bills contract");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("BIL_CTR"), &bills);
+        Self::append_access_audit(&env, symbol_short!("set_bil"), &caller, Some(bills), true);
+
+        true
+    }
+
+    pub fn get_bills_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("BIL_CTR"))
+    }
+
+    /// Set the compliance registry that withdrawal and emergency transfer
+    /// recipients are screened against when the transfer executes. Without
+    /// it, no screening happens.
+    pub fn set_compliance_registry(env: Env, caller: Address, registry: Address) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can set the compliance registry");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CMP_CTR"), &registry);
+        Self::append_access_audit(
+            &env,
+            symbol_short!("set_cmp"),
+            &caller,
+            Some(registry),
+            true,
+        );
+
+        true
+    }
+
+    pub fn get_compliance_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("CMP_CTR"))
+    }
+
+    /// Set the alias registry used by `withdraw_to_alias` and alias entries
+    /// in the recipient allowlist.
+    pub fn set_alias_registry(env: Env, caller: Address, registry: Address) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can set the alias registry");
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ALIAS_CTR"), &registry);
+        Self::append_access_audit(
+            &env,
+            symbol_short!("set_alias"),
+            &caller,
+            Some(registry),
+            true,
+        );
+
+        true
+    }
+
+    pub fn get_alias_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ALIAS_CTR"))
+    }
+
+    /// Add an address or alias to the withdrawal recipient allowlist. While
+    /// the list is non-empty, withdrawals and emergency transfers may only go
+    /// to recipients on it.
+    pub fn add_allowed_recipient(env: Env, caller: Address, entry: AllowedRecipient) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can change allowed recipients");
+        }
+
+        let mut allowed = Self::get_allowed_recipients(env.clone());
+        if !allowed.contains(&entry) {
+            allowed.push_back(entry);
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RCPT_OK"), &allowed);
+        Self::append_access_audit(&env, symbol_short!("rcpt_add"), &caller, None, true);
+
+        true
+    }
+
+    pub fn remove_allowed_recipient(env: Env, caller: Address, entry: AllowedRecipient) -> bool {
+        caller.require_auth();
+        Self::require_not_paused(&env);
+
+        if !Self::is_owner_or_admin(&env, &caller) {
+            panic!("Only Owner or Admin can change allowed recipients");
+        }
+
+        let mut allowed = Self::get_allowed_recipients(env.clone());
+        if let Some(index) = allowed.first_index_of(&entry) {
+            allowed.remove(index);
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RCPT_OK"), &allowed);
+        Self::append_access_audit(&env, symbol_short!("rcpt_rm"), &caller, None, true);
+
+        true
+    }
+
+    pub fn get_allowed_recipients(env: Env) -> Vec<AllowedRecipient> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("RCPT_OK"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Propose a withdrawal to the address an alias currently resolves to.
+    pub fn withdraw_to_alias(
+        env: Env,
+        proposer: Address,
+        token: Address,
+        alias: String,
+        amount: i128,
+    ) -> u64 {
+        let recipient = Self::resolve_alias(&env, &alias).expect("Alias not resolved");
+        Self::withdraw(env, proposer, token, recipient, amount)
+    }
+
     pub fn configure_emergency(
         env: Env,
         caller: Address,
@@ -1394,6 +1702,8 @@ impl FamilyWallet {
                 if require_auth {
                     proposer.require_auth();
                 }
+                Self::require_allowed_recipient(env, recipient);
+                Self::require_compliant_recipient(env, recipient, *amount);
                 let token_client = TokenClient::new(env, token);
                 token_client.transfer(proposer, recipient, amount);
                 0
@@ -1429,11 +1739,54 @@ impl FamilyWallet {
                 if require_auth {
                     proposer.require_auth();
                 }
+                Self::require_allowed_recipient(env, recipient);
+                Self::require_compliant_recipient(env, recipient, *amount);
                 let token_client = TokenClient::new(env, token);
                 token_client.transfer(proposer, recipient, amount);
                 0
             }
-            (TransactionType::PolicyCancellation, TransactionData::PolicyCancellation(..)) => 0,
+            (
+                TransactionType::PolicyCancellation,
+                TransactionData::PolicyCancellation(policy_id),
+            ) => {
+                InsuranceClient::new(env, &Self::insurance_contract(env))
+                    .deactivate_policy(&env.current_contract_address(), policy_id);
+                0
+            }
+            (
+                TransactionType::PayerWalletChange,
+                TransactionData::PayerWalletChange(policy_id, wallet),
+            ) => {
+                InsuranceClient::new(env, &Self::insurance_contract(env)).set_payer_wallet(
+                    &env.current_contract_address(),
+                    policy_id,
+                    wallet,
+                );
+                0
+            }
+            (TransactionType::GoalUnlock, TransactionData::GoalUnlock(goal_id)) => {
+                SavingsGoalsClient::new(env, &Self::savings_contract(env))
+                    .unlock_goal(&env.current_contract_address(), goal_id);
+                0
+            }
+            (TransactionType::GoalWithdrawal, TransactionData::GoalWithdrawal(goal_id, amount)) => {
+                SavingsGoalsClient::new(env, &Self::savings_contract(env)).withdraw_from_goal(
+                    &env.current_contract_address(),
+                    goal_id,
+                    amount,
+                );
+                0
+            }
+            (TransactionType::BillApproval, TransactionData::BillApproval(bill_id)) => {
+                let bills: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("BIL_CTR"))
+                    .expect("Bills contract not set");
+                BillPaymentsClient::new(env, &bills)
+                    .approve_bill(&env.current_contract_address(), bill_id);
+                0
+            }
             _ => panic!("Invalid transaction type or data mismatch"),
         }
     }
@@ -1446,9 +1799,27 @@ impl FamilyWallet {
             TransactionType::EmergencyTransfer => symbol_short!("MS_EMERG"),
             TransactionType::PolicyCancellation => symbol_short!("MS_POL"),
             TransactionType::RegularWithdrawal => symbol_short!("MS_REG"),
+            TransactionType::GoalUnlock => symbol_short!("MS_GUNLK"),
+            TransactionType::GoalWithdrawal => symbol_short!("MS_GWDRW"),
+            TransactionType::BillApproval => symbol_short!("MS_BILL"),
+            TransactionType::PayerWalletChange => symbol_short!("MS_PAYER"),
         }
     }
 
+    fn insurance_contract(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("INS_CTR"))
+            .expect("Insurance contract not set")
+    }
+
+    fn savings_contract(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("SAV_CTR"))
+            .expect("Savings contract not set")
+    }
+
     fn is_family_member(env: &Env, address: &Address) -> bool {
         let members: Map<Address, FamilyMember> = env
             .storage()
@@ -1459,6 +1830,40 @@ impl FamilyWallet {
         members.get(address.clone()).is_some()
     }
 
+    fn resolve_alias(env: &Env, alias: &String) -> Option<Address> {
+        let registry: Address = env.storage().instance().get(&symbol_short!("ALIAS_CTR"))?;
+        match AliasRegistryClient::new(env, &registry).try_resolve(alias) {
+            Ok(Ok(resolved)) => resolved,
+            _ => None,
+        }
+    }
+
+    fn require_allowed_recipient(env: &Env, recipient: &Address) {
+        let allowed = Self::get_allowed_recipients(env.clone());
+        if allowed.is_empty() {
+            return;
+        }
+        let listed = allowed.iter().any(|entry| match entry {
+            AllowedRecipient::Address(address) => address == *recipient,
+            AllowedRecipient::Alias(alias) => {
+                Self::resolve_alias(env, &alias).as_ref() == Some(recipient)
+            }
+        });
+        if !listed {
+            panic!("Recipient not in allowlist");
+        }
+    }
+
+    fn require_compliant_recipient(env: &Env, recipient: &Address, amount: i128) {
+        let registry: Option<Address> = env.storage().instance().get(&symbol_short!("CMP_CTR"));
+        if let Some(registry) = registry {
+            let client = ComplianceRegistryClient::new(env, &registry);
+            if !matches!(client.try_is_compliant(recipient, &amount), Ok(Ok(true))) {
+                panic!("Recipient failed compliance check");
+            }
+        }
+    }
+
     fn is_owner_or_admin(env: &Env, address: &Address) -> bool {
         let members: Map<Address, FamilyMember> = env
             .storage()
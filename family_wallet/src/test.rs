@@ -6,6 +6,116 @@ use soroban_sdk::{
     vec, Env,
 };
 
+#[contract]
+pub struct MockInsurance;
+
+#[contractimpl]
+impl MockInsurance {
+    pub fn deactivate_policy(env: Env, caller: Address, policy_id: u32) -> bool {
+        caller.require_auth();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CANCEL"), &(caller, policy_id));
+        true
+    }
+
+    pub fn set_payer_wallet(
+        env: Env,
+        caller: Address,
+        policy_id: u32,
+        wallet: Option<Address>,
+    ) -> bool {
+        caller.require_auth();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAYER"), &(caller, policy_id, wallet));
+        true
+    }
+
+    pub fn last_cancellation(env: Env) -> Option<(Address, u32)> {
+        env.storage().instance().get(&symbol_short!("CANCEL"))
+    }
+
+    pub fn last_payer_change(env: Env) -> Option<(Address, u32, Option<Address>)> {
+        env.storage().instance().get(&symbol_short!("PAYER"))
+    }
+}
+
+#[contract]
+pub struct MockSavings;
+
+#[contractimpl]
+impl MockSavings {
+    pub fn unlock_goal(env: Env, caller: Address, goal_id: u32) -> bool {
+        caller.require_auth();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("UNLOCK"), &(caller, goal_id));
+        true
+    }
+
+    pub fn withdraw_from_goal(env: Env, caller: Address, goal_id: u32, amount: i128) -> i128 {
+        caller.require_auth();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("WITHDRAW"), &(caller, goal_id, amount));
+        0
+    }
+
+    pub fn last_unlock(env: Env) -> Option<(Address, u32)> {
+        env.storage().instance().get(&symbol_short!("UNLOCK"))
+    }
+
+    pub fn last_withdrawal(env: Env) -> Option<(Address, u32, i128)> {
+        env.storage().instance().get(&symbol_short!("WITHDRAW"))
+    }
+}
+
+#[contract]
+pub struct MockBills;
+
+#[contractimpl]
+impl MockBills {
+    pub fn approve_bill(env: Env, wallet: Address, bill_id: u32) {
+        wallet.require_auth();
+        env.storage()
+            .instance()
+            .set(&symbol_short!("APPROVE"), &(wallet, bill_id));
+    }
+
+    pub fn last_approval(env: Env) -> Option<(Address, u32)> {
+        env.storage().instance().get(&symbol_short!("APPROVE"))
+    }
+}
+
+#[contract]
+pub struct MockCompliance;
+
+#[contractimpl]
+impl MockCompliance {
+    pub fn deny(env: Env, subject: Address) {
+        env.storage().instance().set(&subject, &true);
+    }
+
+    pub fn is_compliant(env: Env, subject: Address, _amount: i128) -> bool {
+        !env.storage().instance().has(&subject)
+    }
+}
+
+#[contract]
+pub struct MockAliasRegistry;
+
+#[contractimpl]
+impl MockAliasRegistry {
+    pub fn point(env: Env, name: String, target: Address) {
+        env.storage().instance().set(&name, &target);
+    }
+
+    pub fn resolve(env: Env, name: String) -> Option<Address> {
+        env.storage().instance().get(&name)
+    }
+}
+
 #[test]
 fn test_init_family_wallet() {
     let env = Env::default();
@@ -917,7 +1027,6 @@ fn test_data_persists_across_repeated_operations() {
     let owner = Address::generate(&env);
     let member1 = Address::generate(&env);
     let member2 = Address::generate(&env);
-    let member3 = Address::generate(&env);
 
     // Phase 1: Initialize wallet at seq 100
     // TTL goes from 100 → 518,400. live_until = 518,500
@@ -1036,3 +1145,315 @@ fn test_archive_ttl_extended_on_archive_transactions() {
         ttl
     );
 }
+
+#[test]
+fn test_policy_cancellation_forwarded_to_insurance_after_multisig() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let insurance_id = env.register_contract(None, MockInsurance);
+    let insurance = MockInsuranceClient::new(&env, &insurance_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::PolicyCancellation,
+        &2,
+        &vec![&env, member1.clone(), member2.clone()],
+        &0,
+    );
+    client.set_insurance_contract(&owner, &insurance_id);
+    assert_eq!(client.get_insurance_contract(), Some(insurance_id.clone()));
+
+    let tx_id = client.propose_policy_cancellation(&member1, &7);
+    assert_eq!(insurance.last_cancellation(), None);
+
+    client.sign_transaction(&member2, &tx_id);
+    assert_eq!(insurance.last_cancellation(), Some((contract_id, 7)));
+}
+
+#[test]
+fn test_payer_wallet_change_forwarded_to_insurance_after_multisig() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let insurance_id = env.register_contract(None, MockInsurance);
+    let insurance = MockInsuranceClient::new(&env, &insurance_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::PayerWalletChange,
+        &2,
+        &vec![&env, member1.clone(), member2.clone()],
+        &0,
+    );
+    client.set_insurance_contract(&owner, &insurance_id);
+
+    let next_wallet = Address::generate(&env);
+    let tx_id = client.propose_payer_wallet_change(&member1, &7, &Some(next_wallet.clone()));
+    assert_eq!(insurance.last_payer_change(), None);
+
+    client.sign_transaction(&member2, &tx_id);
+    assert_eq!(
+        insurance.last_payer_change(),
+        Some((contract_id, 7, Some(next_wallet)))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Insurance contract not set")]
+fn test_policy_cancellation_refused_without_insurance_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env, member.clone()]);
+    client.propose_policy_cancellation(&member, &7);
+}
+
+#[test]
+#[should_panic(expected = "Only Owner or Admin can set the insurance contract")]
+fn test_set_insurance_contract_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env, member.clone()]);
+    client.set_insurance_contract(&member, &Address::generate(&env));
+}
+
+#[test]
+fn test_goal_unlock_and_withdrawal_forwarded_after_multisig() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let savings_id = env.register_contract(None, MockSavings);
+    let savings = MockSavingsClient::new(&env, &savings_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+    for tx_type in [TransactionType::GoalUnlock, TransactionType::GoalWithdrawal] {
+        client.configure_multisig(
+            &owner,
+            &tx_type,
+            &2,
+            &vec![&env, member1.clone(), member2.clone()],
+            &0,
+        );
+    }
+    client.set_savings_contract(&owner, &savings_id);
+    assert_eq!(client.get_savings_contract(), Some(savings_id.clone()));
+
+    let tx_id = client.propose_goal_unlock(&member1, &3);
+    assert_eq!(savings.last_unlock(), None);
+    client.sign_transaction(&member2, &tx_id);
+    assert_eq!(savings.last_unlock(), Some((contract_id.clone(), 3)));
+
+    let tx_id = client.propose_goal_withdrawal(&member2, &3, &500);
+    assert_eq!(savings.last_withdrawal(), None);
+    client.sign_transaction(&member1, &tx_id);
+    assert_eq!(savings.last_withdrawal(), Some((contract_id, 3, 500)));
+}
+
+#[test]
+#[should_panic(expected = "Only Owner or Admin can set the savings contract")]
+fn test_set_savings_contract_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env, member.clone()]);
+    client.set_savings_contract(&member, &Address::generate(&env));
+}
+
+#[test]
+fn test_bill_approval_forwarded_after_multisig() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+    let bills_id = env.register_contract(None, MockBills);
+    let bills = MockBillsClient::new(&env, &bills_id);
+
+    let owner = Address::generate(&env);
+    let member1 = Address::generate(&env);
+    let member2 = Address::generate(&env);
+    client.init(&owner, &vec![&env, member1.clone(), member2.clone()]);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::BillApproval,
+        &2,
+        &vec![&env, member1.clone(), member2.clone()],
+        &0,
+    );
+    client.set_bills_contract(&owner, &bills_id);
+    assert_eq!(client.get_bills_contract(), Some(bills_id.clone()));
+
+    let tx_id = client.propose_bill_approval(&member1, &7);
+    assert_eq!(bills.last_approval(), None);
+    client.sign_transaction(&member2, &tx_id);
+    assert_eq!(bills.last_approval(), Some((contract_id, 7)));
+}
+
+fn setup_screened_withdrawal(env: &Env) -> (FamilyWalletClient<'_>, Address, Address, Address) {
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(env, &contract_id);
+    let compliance_id = env.register_contract(None, MockCompliance);
+
+    let owner = Address::generate(env);
+    let member = Address::generate(env);
+    client.init(&owner, &vec![env, member.clone()]);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::LargeWithdrawal,
+        &1,
+        &vec![env, owner.clone()],
+        &1000_0000000,
+    );
+    client.set_compliance_registry(&owner, &compliance_id);
+
+    let token = env.register_stellar_asset_contract_v2(Address::generate(env));
+    StellarAssetClient::new(env, &token.address()).mint(&owner, &1000_0000000);
+    (client, owner, token.address(), compliance_id)
+}
+
+#[test]
+fn test_withdrawal_to_compliant_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, token, compliance_id) = setup_screened_withdrawal(&env);
+    assert_eq!(client.get_compliance_registry(), Some(compliance_id));
+
+    let recipient = Address::generate(&env);
+    client.withdraw(&owner, &token, &recipient, &100_0000000);
+    assert_eq!(
+        TokenClient::new(&env, &token).balance(&recipient),
+        100_0000000
+    );
+}
+
+#[test]
+#[should_panic(expected = "Recipient failed compliance check")]
+fn test_withdrawal_to_flagged_recipient_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, token, compliance_id) = setup_screened_withdrawal(&env);
+
+    let recipient = Address::generate(&env);
+    MockComplianceClient::new(&env, &compliance_id).deny(&recipient);
+    client.withdraw(&owner, &token, &recipient, &100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Recipient failed compliance check")]
+fn test_emergency_mode_transfer_to_flagged_recipient_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, token, compliance_id) = setup_screened_withdrawal(&env);
+    client.configure_emergency(&owner, &500_0000000, &3600u64, &0);
+    client.set_emergency_mode(&owner, &true);
+
+    let recipient = Address::generate(&env);
+    MockComplianceClient::new(&env, &compliance_id).deny(&recipient);
+    client.propose_emergency_transfer(&owner, &token, &recipient, &100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Recipient failed compliance check")]
+fn test_approved_emergency_transfer_to_flagged_recipient_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, token, compliance_id) = setup_screened_withdrawal(&env);
+    let signer = Address::generate(&env);
+    client.add_family_member(&owner, &signer, &FamilyRole::Admin);
+    client.configure_multisig(
+        &owner,
+        &TransactionType::EmergencyTransfer,
+        &2,
+        &vec![&env, owner.clone(), signer.clone()],
+        &0,
+    );
+
+    let recipient = Address::generate(&env);
+    MockComplianceClient::new(&env, &compliance_id).deny(&recipient);
+    let tx_id = client.propose_emergency_transfer(&owner, &token, &recipient, &100_0000000);
+    client.sign_transaction(&signer, &tx_id);
+}
+
+#[test]
+#[should_panic(expected = "Only Owner or Admin can set the compliance registry")]
+fn test_set_compliance_registry_requires_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, FamilyWallet);
+    let client = FamilyWalletClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let member = Address::generate(&env);
+    client.init(&owner, &vec![&env, member.clone()]);
+    client.set_compliance_registry(&member, &Address::generate(&env));
+}
+
+#[test]
+fn test_recipient_allowlist_with_aliases() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, token, _) = setup_screened_withdrawal(&env);
+    let registry_id = env.register_contract(None, MockAliasRegistry);
+    let registry = MockAliasRegistryClient::new(&env, &registry_id);
+    client.set_alias_registry(&owner, &registry_id);
+
+    let alias = String::from_str(&env, "mama.nairobi");
+    let mama = Address::generate(&env);
+    registry.point(&alias, &mama);
+    let school = Address::generate(&env);
+    client.add_allowed_recipient(&owner, &AllowedRecipient::Alias(alias.clone()));
+    client.add_allowed_recipient(&owner, &AllowedRecipient::Address(school.clone()));
+    assert_eq!(client.get_allowed_recipients().len(), 2);
+
+    client.withdraw_to_alias(&owner, &token, &alias, &100);
+    client.withdraw(&owner, &token, &school, &50);
+    let balances = TokenClient::new(&env, &token);
+    assert_eq!(balances.balance(&mama), 100);
+    assert_eq!(balances.balance(&school), 50);
+
+    // Repointing the alias moves the allowance with it
+    let new_home = Address::generate(&env);
+    registry.point(&alias, &new_home);
+    client.withdraw_to_alias(&owner, &token, &alias, &100);
+    assert_eq!(balances.balance(&new_home), 100);
+
+    client.remove_allowed_recipient(&owner, &AllowedRecipient::Address(school.clone()));
+    assert_eq!(client.get_allowed_recipients().len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Recipient not in allowlist")]
+fn test_withdrawal_outside_allowlist_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, owner, token, _) = setup_screened_withdrawal(&env);
+    client.add_allowed_recipient(&owner, &AllowedRecipient::Address(Address::generate(&env)));
+    client.withdraw(&owner, &token, &Address::generate(&env), &100);
+}
@@ -0,0 +1,638 @@
+#![no_std]
+
+//! # Group Savings
+//!
+//! Rotating savings circles (ROSCA, esusu, tanda): every member pays a fixed
+//! contribution each round and one member takes the whole pot, until everyone
+//! has received it once.
+//!
+//! ## Lifecycle
+//!
+//! 1. The organizer creates a circle with the token, contribution, round
+//!    length, seat count, late penalty and how the payout order is chosen.
+//! 2. Members join while the circle is open. A family wallet can hold a seat:
+//!    one of its owners or admins joins on its behalf, any of its members can
+//!    pay the seat's contributions, and the pot is paid to the wallet itself.
+//! 3. The organizer starts the circle, which fixes the payout order.
+//! 4. Each round, seats contribute. Once the round length has passed anyone
+//!    can close the round, which pays the pot to that round's recipient and
+//!    starts the next round.
+//!
+//! ## Missed contributions
+//!
+//! A seat that has not contributed when a round closes owes that round's
+//! recipient the missed contribution plus `penalty_bps` of it. The round is
+//! added to the seat's arrears. Missing one's own payout round only shrinks
+//! one's own payout, so it is counted but creates no debt.
+//!
+//! Arrears are paid straight to the recipients they shorted:
+//!
+//! - A seat's next contribution must cover all of its arrears as well.
+//! - When a seat in arrears is due a payout, its arrears are deducted from the
+//!   pot first, including in the final round.
+//! - A seat that defaults after its payout still owes its arrears once the
+//!   circle completes. `settle_arrears` pays them off at any time.
+
+use soroban_sdk::{
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short,
+    token::TokenClient, Address, Env, Map, Symbol, TryFromVal, Val, Vec,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const GROUP: Symbol = symbol_short!("group");
+
+/// Upper bound on seats, keeping round closing within one transaction's budget
+pub const MAX_MEMBERS: u32 = 50;
+const MAX_PENALTY_BPS: u32 = 10_000;
+
+/// Subset of the family wallet used to check who may act for a wallet seat
+#[contractclient(name = "FamilyWalletClient")]
+pub trait FamilyWalletTrait {
+    fn get_family_member(env: Env, member: Address) -> Option<Map<Symbol, Val>>;
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidAmount = 1,
+    InvalidPeriod = 2,
+    /// Seat count outside 2..=MAX_MEMBERS
+    InvalidMaxMembers = 3,
+    InvalidPenalty = 4,
+    CircleNotFound = 5,
+    Unauthorized = 6,
+    CircleFull = 7,
+    AlreadyMember = 8,
+    NotMember = 9,
+    /// Joining or changing the order after the circle started
+    NotOpen = 10,
+    NotActive = 11,
+    NotEnoughMembers = 12,
+    /// Custom order is not a permutation of the members
+    InvalidPayoutOrder = 13,
+    PayoutOrderNotSet = 14,
+    AlreadyContributed = 15,
+    RoundNotOver = 16,
+}
+
+/// How the payout order is fixed when the circle starts
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayoutOrder {
+    /// Seats are paid in the order they joined
+    JoinOrder,
+    /// Seats are shuffled with the ledger PRNG at start
+    Random,
+    /// The organizer sets the order before starting
+    Custom,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CircleStatus {
+    Open,
+    Active,
+    Completed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Circle {
+    pub id: u32,
+    pub organizer: Address,
+    pub token: Address,
+    pub contribution: i128,
+    /// Minimum round length in seconds
+    pub period: u64,
+    pub max_members: u32,
+    pub penalty_bps: u32,
+    pub order: PayoutOrder,
+    pub status: CircleStatus,
+    /// Seats in join order
+    pub members: Vec<Address>,
+    /// Recipient of each round; filled in at start (or by the organizer for
+    /// `Custom`)
+    pub payout_queue: Vec<Address>,
+    /// Zero-based index of the current round
+    pub round: u32,
+    pub round_start: u64,
+    /// Collected for the current round so far
+    pub pot: i128,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemberRecord {
+    pub seat: Address,
+    /// Seat is held by a family wallet contract
+    pub family_wallet: bool,
+    pub joined_at: u64,
+    /// Last round this seat contributed to
+    pub last_paid_round: Option<u32>,
+    pub missed: u32,
+    /// Rounds this seat missed while another seat was paid; each owes that
+    /// round's recipient the contribution plus the penalty
+    pub arrears: Vec<u32>,
+    /// Penalties owed for `arrears`
+    pub penalty_due: i128,
+    /// Total pot and repaid arrears paid out to this seat
+    pub received: i128,
+}
+
+#[contract]
+pub struct GroupSavings;
+
+#[contractimpl]
+impl GroupSavings {
+    // -----------------------------------------------------------------------
+    // Circle setup
+    // -----------------------------------------------------------------------
+
+    /// Create an open circle
+    ///
+    /// # Returns
+    /// Ok(circle_id)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_circle(
+        env: Env,
+        organizer: Address,
+        token: Address,
+        contribution: i128,
+        period: u64,
+        max_members: u32,
+        penalty_bps: u32,
+        order: PayoutOrder,
+    ) -> Result<u32, Error> {
+        organizer.require_auth();
+        if contribution <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if period == 0 {
+            return Err(Error::InvalidPeriod);
+        }
+        if !(2..=MAX_MEMBERS).contains(&max_members) {
+            return Err(Error::InvalidMaxMembers);
+        }
+        if penalty_bps > MAX_PENALTY_BPS {
+            return Err(Error::InvalidPenalty);
+        }
+
+        Self::extend_instance_ttl(&env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_ID"), &(id + 1));
+
+        let circle = Circle {
+            id,
+            organizer: organizer.clone(),
+            token,
+            contribution,
+            period,
+            max_members,
+            penalty_bps,
+            order,
+            status: CircleStatus::Open,
+            members: Vec::new(&env),
+            payout_queue: Vec::new(&env),
+            round: 0,
+            round_start: 0,
+            pot: 0,
+            created_at: env.ledger().timestamp(),
+        };
+        Self::save_circle(&env, &circle);
+
+        env.events()
+            .publish((GROUP, symbol_short!("created")), (id, organizer));
+        Ok(id)
+    }
+
+    /// Take a seat in an open circle
+    pub fn join_circle(env: Env, member: Address, circle_id: u32) -> Result<(), Error> {
+        member.require_auth();
+        Self::add_seat(&env, circle_id, member, false)
+    }
+
+    /// Take a seat for a family wallet
+    ///
+    /// `manager` must be an owner or admin of `family_wallet`. The seat and
+    /// its payouts belong to the wallet.
+    pub fn join_circle_as_family(
+        env: Env,
+        manager: Address,
+        circle_id: u32,
+        family_wallet: Address,
+    ) -> Result<(), Error> {
+        manager.require_auth();
+        match Self::family_role(&env, &family_wallet, &manager) {
+            Some(1) | Some(2) => {}
+            _ => return Err(Error::Unauthorized),
+        }
+        Self::add_seat(&env, circle_id, family_wallet, true)
+    }
+
+    /// Set the payout order of a `Custom` circle
+    ///
+    /// The order is checked against the final member list when the circle
+    /// starts, so it can be set before everyone has joined.
+    pub fn set_payout_order(
+        env: Env,
+        organizer: Address,
+        circle_id: u32,
+        order: Vec<Address>,
+    ) -> Result<(), Error> {
+        organizer.require_auth();
+        let mut circle = Self::load_circle(&env, circle_id)?;
+        if circle.organizer != organizer {
+            return Err(Error::Unauthorized);
+        }
+        if circle.status != CircleStatus::Open {
+            return Err(Error::NotOpen);
+        }
+        if circle.order != PayoutOrder::Custom {
+            return Err(Error::InvalidPayoutOrder);
+        }
+        circle.payout_queue = order;
+        Self::save_circle(&env, &circle);
+        Ok(())
+    }
+
+    /// Close membership, fix the payout order and open the first round
+    pub fn start_circle(env: Env, organizer: Address, circle_id: u32) -> Result<(), Error> {
+        organizer.require_auth();
+        let mut circle = Self::load_circle(&env, circle_id)?;
+        if circle.organizer != organizer {
+            return Err(Error::Unauthorized);
+        }
+        if circle.status != CircleStatus::Open {
+            return Err(Error::NotOpen);
+        }
+        if circle.members.len() < 2 {
+            return Err(Error::NotEnoughMembers);
+        }
+
+        circle.payout_queue = match circle.order {
+            PayoutOrder::JoinOrder => circle.members.clone(),
+            PayoutOrder::Random => {
+                let mut queue = circle.members.clone();
+                env.prng().shuffle(&mut queue);
+                queue
+            }
+            PayoutOrder::Custom => {
+                if circle.payout_queue.is_empty() {
+                    return Err(Error::PayoutOrderNotSet);
+                }
+                Self::check_permutation(&circle.payout_queue, &circle.members)?;
+                circle.payout_queue.clone()
+            }
+        };
+        circle.status = CircleStatus::Active;
+        circle.round = 0;
+        circle.round_start = env.ledger().timestamp();
+        Self::save_circle(&env, &circle);
+
+        env.events().publish(
+            (GROUP, symbol_short!("started")),
+            (circle_id, circle.payout_queue),
+        );
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Rounds
+    // -----------------------------------------------------------------------
+
+    /// Pay a seat's contribution for the current round
+    ///
+    /// The payer must be the seat itself or, for a family wallet seat, one of
+    /// the wallet's members. The seat's arrears, with their penalties, are
+    /// paid to the recipients they shorted in the same call.
+    ///
+    /// # Returns
+    /// Ok(amount) transferred, contribution plus arrears and penalties
+    pub fn contribute(
+        env: Env,
+        payer: Address,
+        circle_id: u32,
+        seat: Address,
+    ) -> Result<i128, Error> {
+        payer.require_auth();
+        let mut circle = Self::load_circle(&env, circle_id)?;
+        if circle.status != CircleStatus::Active {
+            return Err(Error::NotActive);
+        }
+        let mut record = Self::load_member(&env, circle_id, &seat)?;
+        Self::require_payer(&env, &payer, &record)?;
+        if record.last_paid_round == Some(circle.round) {
+            return Err(Error::AlreadyContributed);
+        }
+
+        TokenClient::new(&env, &circle.token).transfer(
+            &payer,
+            &env.current_contract_address(),
+            &circle.contribution,
+        );
+        let repaid = Self::repay_arrears(&env, &circle, &mut record, &payer, i128::MAX)?;
+        let amount = circle.contribution + repaid;
+        circle.pot += circle.contribution;
+        record.last_paid_round = Some(circle.round);
+        Self::save_circle(&env, &circle);
+        Self::save_member(&env, circle_id, &record);
+
+        env.events().publish(
+            (GROUP, symbol_short!("contrib")),
+            (circle_id, circle.round, seat, amount),
+        );
+        Ok(amount)
+    }
+
+    /// Pay off a seat's arrears without contributing, e.g. after the circle
+    /// completed
+    ///
+    /// Same payers as `contribute`. Each missed round's contribution and
+    /// penalty go to that round's recipient.
+    ///
+    /// # Returns
+    /// Ok(amount) transferred
+    pub fn settle_arrears(
+        env: Env,
+        payer: Address,
+        circle_id: u32,
+        seat: Address,
+    ) -> Result<i128, Error> {
+        payer.require_auth();
+        let circle = Self::load_circle(&env, circle_id)?;
+        let mut record = Self::load_member(&env, circle_id, &seat)?;
+        Self::require_payer(&env, &payer, &record)?;
+
+        let repaid = Self::repay_arrears(&env, &circle, &mut record, &payer, i128::MAX)?;
+        Self::save_member(&env, circle_id, &record);
+        Ok(repaid)
+    }
+
+    /// Close the current round once its period has passed
+    ///
+    /// Adds the round to the arrears of seats that did not contribute, pays
+    /// the pot to the round's recipient less any arrears of its own, and
+    /// opens the next round, or completes the circle after the last one.
+    /// Anyone may call this.
+    ///
+    /// # Returns
+    /// Ok(payout) sent to the recipient
+    pub fn close_round(env: Env, circle_id: u32) -> Result<i128, Error> {
+        let mut circle = Self::load_circle(&env, circle_id)?;
+        if circle.status != CircleStatus::Active {
+            return Err(Error::NotActive);
+        }
+        let now = env.ledger().timestamp();
+        if now < circle.round_start.saturating_add(circle.period) {
+            return Err(Error::RoundNotOver);
+        }
+
+        let recipient = circle
+            .payout_queue
+            .get(circle.round)
+            .ok_or(Error::NotActive)?;
+        let penalty = Self::penalty(&circle);
+        for seat in circle.members.iter() {
+            let mut record = Self::load_member(&env, circle_id, &seat)?;
+            if record.last_paid_round != Some(circle.round) {
+                record.missed += 1;
+                let charged = if seat == recipient {
+                    0
+                } else {
+                    record.arrears.push_back(circle.round);
+                    record.penalty_due += penalty;
+                    penalty
+                };
+                Self::save_member(&env, circle_id, &record);
+                env.events().publish(
+                    (GROUP, symbol_short!("missed")),
+                    (circle_id, circle.round, seat, charged),
+                );
+            }
+        }
+
+        let last_round = circle.round + 1 == circle.payout_queue.len();
+        let mut record = Self::load_member(&env, circle_id, &recipient)?;
+        let contract = env.current_contract_address();
+        let deduction = Self::repay_arrears(&env, &circle, &mut record, &contract, circle.pot)?;
+        let payout = circle.pot - deduction;
+        if payout > 0 {
+            TokenClient::new(&env, &circle.token).transfer(&contract, &recipient, &payout);
+        }
+        record.received += payout;
+        Self::save_member(&env, circle_id, &record);
+
+        env.events().publish(
+            (GROUP, symbol_short!("payout")),
+            (circle_id, circle.round, recipient, payout),
+        );
+
+        circle.pot = 0;
+        circle.round += 1;
+        circle.round_start = now;
+        if last_round {
+            circle.status = CircleStatus::Completed;
+            env.events()
+                .publish((GROUP, symbol_short!("complete")), circle_id);
+        }
+        Self::save_circle(&env, &circle);
+        Ok(payout)
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_circle(env: Env, circle_id: u32) -> Option<Circle> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("CIRCLE"), circle_id))
+    }
+
+    pub fn get_member(env: Env, circle_id: u32, seat: Address) -> Option<MemberRecord> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("MEMBER"), circle_id, seat))
+    }
+
+    /// Recipient of the current round, if the circle is active
+    pub fn get_current_recipient(env: Env, circle_id: u32) -> Option<Address> {
+        let circle = Self::get_circle(env, circle_id)?;
+        if circle.status != CircleStatus::Active {
+            return None;
+        }
+        circle.payout_queue.get(circle.round)
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn add_seat(
+        env: &Env,
+        circle_id: u32,
+        seat: Address,
+        family_wallet: bool,
+    ) -> Result<(), Error> {
+        let mut circle = Self::load_circle(env, circle_id)?;
+        if circle.status != CircleStatus::Open {
+            return Err(Error::NotOpen);
+        }
+        if circle.members.contains(&seat) {
+            return Err(Error::AlreadyMember);
+        }
+        if circle.members.len() >= circle.max_members {
+            return Err(Error::CircleFull);
+        }
+
+        circle.members.push_back(seat.clone());
+        Self::save_circle(env, &circle);
+        let record = MemberRecord {
+            seat: seat.clone(),
+            family_wallet,
+            joined_at: env.ledger().timestamp(),
+            last_paid_round: None,
+            missed: 0,
+            arrears: Vec::new(env),
+            penalty_due: 0,
+            received: 0,
+        };
+        Self::save_member(env, circle_id, &record);
+
+        env.events().publish(
+            (GROUP, symbol_short!("joined")),
+            (circle_id, seat, family_wallet),
+        );
+        Ok(())
+    }
+
+    /// The seat itself, or any member of a family wallet seat, may pay for it
+    fn require_payer(env: &Env, payer: &Address, record: &MemberRecord) -> Result<(), Error> {
+        if *payer == record.seat {
+            return Ok(());
+        }
+        if record.family_wallet
+            && matches!(Self::family_role(env, &record.seat, payer), Some(1..=3))
+        {
+            return Ok(());
+        }
+        Err(Error::Unauthorized)
+    }
+
+    fn penalty(circle: &Circle) -> i128 {
+        circle.contribution * circle.penalty_bps as i128 / MAX_PENALTY_BPS as i128
+    }
+
+    /// Pay `record`'s arrears from `from` to the recipients of the missed
+    /// rounds, oldest first, without paying more than `limit` in total.
+    /// Rounds that do not fit stay in arrears.
+    ///
+    /// # Returns
+    /// Ok(amount) repaid
+    fn repay_arrears(
+        env: &Env,
+        circle: &Circle,
+        record: &mut MemberRecord,
+        from: &Address,
+        limit: i128,
+    ) -> Result<i128, Error> {
+        let penalty = Self::penalty(circle);
+        let owed = circle.contribution + penalty;
+        let token = TokenClient::new(env, &circle.token);
+        let mut repaid = 0i128;
+        let mut unpaid = Vec::new(env);
+        for round in record.arrears.iter() {
+            if repaid + owed > limit {
+                unpaid.push_back(round);
+                continue;
+            }
+            let creditor = circle.payout_queue.get(round).ok_or(Error::NotActive)?;
+            token.transfer(from, &creditor, &owed);
+            let mut credited = Self::load_member(env, circle.id, &creditor)?;
+            credited.received += owed;
+            Self::save_member(env, circle.id, &credited);
+            repaid += owed;
+            record.penalty_due -= penalty;
+            env.events().publish(
+                (GROUP, symbol_short!("repaid")),
+                (circle.id, round, record.seat.clone(), owed),
+            );
+        }
+        record.arrears = unpaid;
+        Ok(repaid)
+    }
+
+    /// Role of `member` in `family_wallet` (1=Owner, 2=Admin, 3=Member,
+    /// 4=Viewer), or None if the wallet does not know them
+    fn family_role(env: &Env, family_wallet: &Address, member: &Address) -> Option<u32> {
+        let record = match FamilyWalletClient::new(env, family_wallet).try_get_family_member(member)
+        {
+            Ok(Ok(Some(record))) => record,
+            _ => return None,
+        };
+        record
+            .get(symbol_short!("role"))
+            .and_then(|v| u32::try_from_val(env, &v).ok())
+    }
+
+    fn check_permutation(order: &Vec<Address>, members: &Vec<Address>) -> Result<(), Error> {
+        if order.len() != members.len() {
+            return Err(Error::InvalidPayoutOrder);
+        }
+        for (i, seat) in order.iter().enumerate() {
+            if !members.contains(&seat) || order.first_index_of(&seat) != Some(i as u32) {
+                return Err(Error::InvalidPayoutOrder);
+            }
+        }
+        Ok(())
+    }
+
+    fn load_circle(env: &Env, circle_id: u32) -> Result<Circle, Error> {
+        Self::get_circle(env.clone(), circle_id).ok_or(Error::CircleNotFound)
+    }
+
+    fn load_member(env: &Env, circle_id: u32, seat: &Address) -> Result<MemberRecord, Error> {
+        Self::get_member(env.clone(), circle_id, seat.clone()).ok_or(Error::NotMember)
+    }
+
+    fn save_circle(env: &Env, circle: &Circle) {
+        let key = (symbol_short!("CIRCLE"), circle.id);
+        env.storage().persistent().set(&key, circle);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn save_member(env: &Env, circle_id: u32, record: &MemberRecord) {
+        let key = (symbol_short!("MEMBER"), circle_id, record.seat.clone());
+        env.storage().persistent().set(&key, record);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
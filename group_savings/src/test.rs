@@ -0,0 +1,373 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, Vec,
+};
+
+/// Family member record as returned by the family wallet
+#[contracttype]
+#[derive(Clone)]
+pub struct MockMember {
+    pub address: Address,
+    pub role: u32,
+}
+
+#[contract]
+pub struct MockFamilyWallet;
+
+#[contractimpl]
+impl MockFamilyWallet {
+    pub fn add_member_with_role(env: Env, member: Address, role: u32) {
+        env.storage().instance().set(&member, &role);
+    }
+
+    pub fn get_family_member(env: Env, member: Address) -> Option<MockMember> {
+        env.storage()
+            .instance()
+            .get(&member)
+            .map(|role| MockMember {
+                address: member,
+                role,
+            })
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: GroupSavingsClient<'a>,
+    token: TokenClient<'a>,
+    organizer: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, GroupSavings);
+    let client = GroupSavingsClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = TokenClient::new(&env, &asset.address());
+    let organizer = Address::generate(&env);
+
+    Setup {
+        env,
+        client,
+        token,
+        organizer,
+    }
+}
+
+fn funded_member(s: &Setup) -> Address {
+    let member = Address::generate(&s.env);
+    StellarAssetClient::new(&s.env, &s.token.address).mint(&member, &10_000);
+    member
+}
+
+fn create_circle(s: &Setup, order: PayoutOrder) -> u32 {
+    s.client.create_circle(
+        &s.organizer,
+        &s.token.address,
+        &100,
+        &1_000,
+        &3,
+        &1_000,
+        &order,
+    )
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|l| l.timestamp += seconds);
+}
+
+#[test]
+fn test_full_rotation_in_join_order() {
+    let s = setup();
+    let id = create_circle(&s, PayoutOrder::JoinOrder);
+    let members = [funded_member(&s), funded_member(&s), funded_member(&s)];
+    for member in members.iter() {
+        s.client.join_circle(member, &id);
+    }
+    assert_eq!(
+        s.client.try_join_circle(&funded_member(&s), &id),
+        Err(Ok(Error::CircleFull))
+    );
+    s.client.start_circle(&s.organizer, &id);
+
+    for (round, recipient) in members.iter().enumerate() {
+        assert_eq!(s.client.get_current_recipient(&id), Some(recipient.clone()));
+        for member in members.iter() {
+            s.client.contribute(member, &id, member);
+        }
+        assert_eq!(s.client.try_close_round(&id), Err(Ok(Error::RoundNotOver)));
+        advance(&s.env, 1_000);
+        assert_eq!(s.client.close_round(&id), 300);
+        assert_eq!(s.client.get_circle(&id).unwrap().round, round as u32 + 1);
+    }
+
+    for member in members.iter() {
+        assert_eq!(s.token.balance(member), 10_000);
+        assert_eq!(s.client.get_member(&id, member).unwrap().received, 300);
+    }
+    assert_eq!(
+        s.client.get_circle(&id).unwrap().status,
+        CircleStatus::Completed
+    );
+    assert_eq!(s.client.try_close_round(&id), Err(Ok(Error::NotActive)));
+}
+
+#[test]
+fn test_missed_contribution_penalty() {
+    let s = setup();
+    let id = create_circle(&s, PayoutOrder::JoinOrder);
+    let (a, b, c) = (funded_member(&s), funded_member(&s), funded_member(&s));
+    for member in [&a, &b, &c] {
+        s.client.join_circle(member, &id);
+    }
+    s.client.start_circle(&s.organizer, &id);
+
+    // Round 0: c misses and owes a the contribution plus 10%
+    s.client.contribute(&a, &id, &a);
+    s.client.contribute(&b, &id, &b);
+    assert_eq!(
+        s.client.try_contribute(&a, &id, &a),
+        Err(Ok(Error::AlreadyContributed))
+    );
+    advance(&s.env, 1_000);
+    assert_eq!(s.client.close_round(&id), 200);
+    let record = s.client.get_member(&id, &c).unwrap();
+    assert_eq!(record.missed, 1);
+    assert_eq!(record.arrears, Vec::from_array(&s.env, [0]));
+    assert_eq!(record.penalty_due, 10);
+
+    // Round 1: c's next contribution also repays a
+    s.client.contribute(&a, &id, &a);
+    s.client.contribute(&b, &id, &b);
+    assert_eq!(s.client.contribute(&c, &id, &c), 210);
+    advance(&s.env, 1_000);
+    assert_eq!(s.client.close_round(&id), 300);
+    let record = s.client.get_member(&id, &c).unwrap();
+    assert_eq!(record.arrears.len(), 0);
+    assert_eq!(record.penalty_due, 0);
+    assert_eq!(s.client.get_member(&id, &a).unwrap().received, 310);
+}
+
+#[test]
+fn test_penalty_deducted_from_payout() {
+    let s = setup();
+    let id = create_circle(&s, PayoutOrder::Custom);
+    let (a, b, c) = (funded_member(&s), funded_member(&s), funded_member(&s));
+    for member in [&a, &b, &c] {
+        s.client.join_circle(member, &id);
+    }
+    s.client.set_payout_order(
+        &s.organizer,
+        &id,
+        &Vec::from_array(&s.env, [b.clone(), a.clone(), c.clone()]),
+    );
+    s.client.start_circle(&s.organizer, &id);
+
+    // Round 0 pays b; a misses
+    s.client.contribute(&b, &id, &b);
+    s.client.contribute(&c, &id, &c);
+    advance(&s.env, 1_000);
+    s.client.close_round(&id);
+
+    // Round 1 pays a, who misses again: missing one's own round creates no
+    // debt, and the round 0 arrears are repaid to b out of a's payout
+    s.client.contribute(&b, &id, &b);
+    s.client.contribute(&c, &id, &c);
+    advance(&s.env, 1_000);
+    assert_eq!(s.client.close_round(&id), 90);
+    assert_eq!(s.client.get_circle(&id).unwrap().pot, 0);
+    let record = s.client.get_member(&id, &a).unwrap();
+    assert_eq!(record.arrears.len(), 0);
+    assert_eq!(record.penalty_due, 0);
+    assert_eq!(s.client.get_member(&id, &b).unwrap().received, 310);
+
+    for member in [&a, &b, &c] {
+        assert_eq!(s.client.contribute(member, &id, member), 100);
+    }
+    advance(&s.env, 1_000);
+    assert_eq!(s.client.close_round(&id), 300);
+    assert_eq!(s.client.get_member(&id, &a).unwrap().missed, 2);
+}
+
+#[test]
+fn test_default_after_payout_is_owed_to_later_recipients() {
+    let s = setup();
+    let id = create_circle(&s, PayoutOrder::JoinOrder);
+    let (a, b, c) = (funded_member(&s), funded_member(&s), funded_member(&s));
+    for member in [&a, &b, &c] {
+        s.client.join_circle(member, &id);
+    }
+    s.client.start_circle(&s.organizer, &id);
+
+    // Round 0 pays a, who then stops contributing
+    for member in [&a, &b, &c] {
+        s.client.contribute(member, &id, member);
+    }
+    advance(&s.env, 1_000);
+    assert_eq!(s.client.close_round(&id), 300);
+    for _ in 0..2 {
+        s.client.contribute(&b, &id, &b);
+        s.client.contribute(&c, &id, &c);
+        advance(&s.env, 1_000);
+        assert_eq!(s.client.close_round(&id), 200);
+    }
+    assert_eq!(
+        s.client.get_circle(&id).unwrap().status,
+        CircleStatus::Completed
+    );
+
+    // The final round does not forgive anything
+    let record = s.client.get_member(&id, &a).unwrap();
+    assert_eq!(record.missed, 2);
+    assert_eq!(record.arrears, Vec::from_array(&s.env, [1, 2]));
+    assert_eq!(record.penalty_due, 20);
+    assert_eq!(
+        s.client.try_contribute(&a, &id, &a),
+        Err(Ok(Error::NotActive))
+    );
+    assert_eq!(
+        s.client.try_settle_arrears(&b, &id, &a),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // Settling pays each shorted recipient its contribution plus penalty
+    assert_eq!(s.client.settle_arrears(&a, &id, &a), 220);
+    assert_eq!(s.token.balance(&a), 10_000 - 100 - 220 + 300);
+    for member in [&b, &c] {
+        assert_eq!(s.token.balance(member), 10_000 - 300 + 200 + 110);
+        assert_eq!(s.client.get_member(&id, member).unwrap().received, 310);
+    }
+    let record = s.client.get_member(&id, &a).unwrap();
+    assert_eq!(record.arrears.len(), 0);
+    assert_eq!(record.penalty_due, 0);
+    assert_eq!(s.client.settle_arrears(&a, &id, &a), 0);
+}
+
+#[test]
+fn test_payout_order_selection() {
+    let s = setup();
+    let id = create_circle(&s, PayoutOrder::Custom);
+    let (a, b) = (funded_member(&s), funded_member(&s));
+    s.client.join_circle(&a, &id);
+    s.client.join_circle(&b, &id);
+
+    assert_eq!(
+        s.client.try_start_circle(&s.organizer, &id),
+        Err(Ok(Error::PayoutOrderNotSet))
+    );
+    s.client.set_payout_order(
+        &s.organizer,
+        &id,
+        &Vec::from_array(&s.env, [a.clone(), a.clone()]),
+    );
+    assert_eq!(
+        s.client.try_start_circle(&s.organizer, &id),
+        Err(Ok(Error::InvalidPayoutOrder))
+    );
+    s.client.set_payout_order(
+        &s.organizer,
+        &id,
+        &Vec::from_array(&s.env, [b.clone(), a.clone()]),
+    );
+    s.client.start_circle(&s.organizer, &id);
+    assert_eq!(s.client.get_current_recipient(&id), Some(b));
+
+    let random = create_circle(&s, PayoutOrder::Random);
+    assert_eq!(
+        s.client
+            .try_set_payout_order(&s.organizer, &random, &Vec::new(&s.env)),
+        Err(Ok(Error::InvalidPayoutOrder))
+    );
+    s.client.join_circle(&a, &random);
+    assert_eq!(
+        s.client.try_start_circle(&s.organizer, &random),
+        Err(Ok(Error::NotEnoughMembers))
+    );
+    s.client.join_circle(&funded_member(&s), &random);
+    s.client.start_circle(&s.organizer, &random);
+    let circle = s.client.get_circle(&random).unwrap();
+    assert_eq!(circle.payout_queue.len(), 2);
+    assert!(circle.payout_queue.contains(&a));
+    assert_eq!(
+        s.client.try_join_circle(&funded_member(&s), &random),
+        Err(Ok(Error::NotOpen))
+    );
+}
+
+#[test]
+fn test_family_wallet_seat() {
+    let s = setup();
+    let wallet_id = s.env.register_contract(None, MockFamilyWallet);
+    let wallet = MockFamilyWalletClient::new(&s.env, &wallet_id);
+    let owner = funded_member(&s);
+    let member = funded_member(&s);
+    let viewer = funded_member(&s);
+    wallet.add_member_with_role(&owner, &1);
+    wallet.add_member_with_role(&member, &3);
+    wallet.add_member_with_role(&viewer, &4);
+
+    let id = create_circle(&s, PayoutOrder::JoinOrder);
+    assert_eq!(
+        s.client.try_join_circle_as_family(&member, &id, &wallet_id),
+        Err(Ok(Error::Unauthorized))
+    );
+    s.client.join_circle_as_family(&owner, &id, &wallet_id);
+    let other = funded_member(&s);
+    s.client.join_circle(&other, &id);
+    s.client.start_circle(&s.organizer, &id);
+
+    assert_eq!(
+        s.client.try_contribute(&viewer, &id, &wallet_id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_contribute(&member, &id, &other),
+        Err(Ok(Error::Unauthorized))
+    );
+    s.client.contribute(&member, &id, &wallet_id);
+    s.client.contribute(&other, &id, &other);
+    advance(&s.env, 1_000);
+    s.client.close_round(&id);
+
+    assert_eq!(s.token.balance(&wallet_id), 200);
+    assert_eq!(s.token.balance(&member), 9_900);
+    assert!(s.client.get_member(&id, &wallet_id).unwrap().family_wallet);
+}
+
+#[test]
+fn test_create_validation() {
+    let s = setup();
+    let token = s.token.address.clone();
+    let order = PayoutOrder::JoinOrder;
+    assert_eq!(
+        s.client
+            .try_create_circle(&s.organizer, &token, &0, &1_000, &3, &0, &order),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        s.client
+            .try_create_circle(&s.organizer, &token, &100, &0, &3, &0, &order),
+        Err(Ok(Error::InvalidPeriod))
+    );
+    assert_eq!(
+        s.client
+            .try_create_circle(&s.organizer, &token, &100, &1_000, &1, &0, &order),
+        Err(Ok(Error::InvalidMaxMembers))
+    );
+    assert_eq!(
+        s.client
+            .try_create_circle(&s.organizer, &token, &100, &1_000, &3, &10_001, &order),
+        Err(Ok(Error::InvalidPenalty))
+    );
+    assert_eq!(
+        s.client.try_join_circle(&s.organizer, &9),
+        Err(Ok(Error::CircleNotFound))
+    );
+}
@@ -0,0 +1,571 @@
+#![no_std]
+
+//! # Merchant Payments
+//!
+//! Lets local shops take token payments from RemitWise users.
+//!
+//! A merchant registers with a name and a payout address, then issues
+//! invoices on-chain under the hash of its own invoice document. The customer
+//! pays by that hash, so amount and token always match what the merchant
+//! asked for and an invoice can only be paid once.
+//!
+//! Payments stay in this contract until the merchant settles. Each
+//! `settle` call pays out up to `MAX_BATCH_SIZE` of the oldest unsettled
+//! invoices in one token as a single transfer and keeps a record of the
+//! batch. Refunds of unsettled invoices come out of the held funds; once an
+//! invoice is settled the merchant refunds from their own balance.
+//!
+//! The admin can suspend a merchant. A suspended merchant cannot issue or
+//! take payments but can still settle and refund.
+//!
+//! ## Events
+//!
+//! `("merchant", action)` for `register`, `status`, `invoice`, `cancel`,
+//! `paid`, `refund` and `settled`.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
+    BytesN, Env, String, Symbol, Vec,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const MERCHANT: Symbol = symbol_short!("merchant");
+
+/// Most invoices paid out by one `settle` call
+pub const MAX_BATCH_SIZE: u32 = 50;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    MerchantNotFound = 2,
+    MerchantExists = 3,
+    MerchantSuspended = 4,
+    InvalidAmount = 5,
+    /// Expiry not in the future
+    InvalidExpiry = 6,
+    InvoiceNotFound = 7,
+    /// An invoice with this hash was already issued
+    InvoiceExists = 8,
+    /// The invoice was paid or cancelled
+    InvoiceNotOpen = 9,
+    InvoiceExpired = 10,
+    /// Refunding an invoice that was not paid
+    InvoiceNotPaid = 11,
+    /// Refund above what is left of the payment
+    RefundTooLarge = 12,
+    NothingToSettle = 13,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Merchant {
+    pub address: Address,
+    pub name: String,
+    /// Where settlements are sent
+    pub payout: Address,
+    pub active: bool,
+    pub registered_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InvoiceStatus {
+    Open,
+    Paid,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    /// Hash of the merchant's invoice document
+    pub hash: BytesN<32>,
+    pub merchant: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub status: InvoiceStatus,
+    pub customer: Option<Address>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    pub paid_at: Option<u64>,
+    pub refunded: i128,
+    /// Settlement batch that paid the invoice out to the merchant
+    pub batch_id: Option<u32>,
+}
+
+/// One payout of unsettled invoices to a merchant
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementBatch {
+    pub id: u32,
+    pub merchant: Address,
+    pub token: Address,
+    pub payout: Address,
+    /// Paid minus refunded over the batch's invoices
+    pub amount: i128,
+    pub invoices: Vec<BytesN<32>>,
+    pub settled_at: u64,
+}
+
+#[contract]
+pub struct MerchantPay;
+
+#[contractimpl]
+impl MerchantPay {
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Set the admin who can suspend merchants
+    ///
+    /// The first call must set the caller itself; afterwards only the current
+    /// admin can hand the role over.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
+        caller.require_auth();
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(Error::Unauthorized),
+            Some(admin) if admin != caller => return Err(Error::Unauthorized),
+            _ => {}
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Suspend or reinstate a merchant
+    pub fn set_merchant_active(
+        env: Env,
+        admin: Address,
+        merchant: Address,
+        active: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()) != Some(admin) {
+            return Err(Error::Unauthorized);
+        }
+        let mut record = Self::load_merchant(&env, &merchant)?;
+        record.active = active;
+        Self::save_merchant(&env, &record);
+
+        env.events()
+            .publish((MERCHANT, symbol_short!("status")), (merchant, active));
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Merchants
+    // -----------------------------------------------------------------------
+
+    pub fn register_merchant(
+        env: Env,
+        merchant: Address,
+        name: String,
+        payout: Address,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        if Self::get_merchant(env.clone(), merchant.clone()).is_some() {
+            return Err(Error::MerchantExists);
+        }
+        Self::extend_instance_ttl(&env);
+        let record = Merchant {
+            address: merchant.clone(),
+            name: name.clone(),
+            payout,
+            active: true,
+            registered_at: env.ledger().timestamp(),
+        };
+        Self::save_merchant(&env, &record);
+
+        env.events()
+            .publish((MERCHANT, symbol_short!("register")), (merchant, name));
+        Ok(())
+    }
+
+    /// Change the merchant's name or payout address
+    pub fn update_merchant(
+        env: Env,
+        merchant: Address,
+        name: String,
+        payout: Address,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        let mut record = Self::load_merchant(&env, &merchant)?;
+        record.name = name;
+        record.payout = payout;
+        Self::save_merchant(&env, &record);
+        Ok(())
+    }
+
+    pub fn get_merchant(env: Env, merchant: Address) -> Option<Merchant> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("MERCHANT"), merchant))
+    }
+
+    // -----------------------------------------------------------------------
+    // Invoices
+    // -----------------------------------------------------------------------
+
+    /// Ask for `amount` of `token` against the invoice document hashed as
+    /// `invoice_hash`
+    pub fn issue_invoice(
+        env: Env,
+        merchant: Address,
+        invoice_hash: BytesN<32>,
+        token: Address,
+        amount: i128,
+        expires_at: u64,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        let record = Self::load_merchant(&env, &merchant)?;
+        if !record.active {
+            return Err(Error::MerchantSuspended);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidExpiry);
+        }
+        if Self::get_invoice(env.clone(), invoice_hash.clone()).is_some() {
+            return Err(Error::InvoiceExists);
+        }
+
+        Self::extend_instance_ttl(&env);
+        let invoice = Invoice {
+            hash: invoice_hash.clone(),
+            merchant: merchant.clone(),
+            token,
+            amount,
+            status: InvoiceStatus::Open,
+            customer: None,
+            issued_at: now,
+            expires_at,
+            paid_at: None,
+            refunded: 0,
+            batch_id: None,
+        };
+        Self::save_invoice(&env, &invoice);
+
+        env.events().publish(
+            (MERCHANT, symbol_short!("invoice")),
+            (merchant, invoice_hash, amount),
+        );
+        Ok(())
+    }
+
+    /// Withdraw an invoice that has not been paid
+    pub fn cancel_invoice(
+        env: Env,
+        merchant: Address,
+        invoice_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        merchant.require_auth();
+        let mut invoice = Self::load_invoice(&env, &invoice_hash)?;
+        if invoice.merchant != merchant {
+            return Err(Error::Unauthorized);
+        }
+        if invoice.status != InvoiceStatus::Open {
+            return Err(Error::InvoiceNotOpen);
+        }
+        invoice.status = InvoiceStatus::Cancelled;
+        Self::save_invoice(&env, &invoice);
+
+        env.events().publish(
+            (MERCHANT, symbol_short!("cancel")),
+            (merchant, invoice_hash),
+        );
+        Ok(())
+    }
+
+    /// Pay an open invoice in full. The tokens are held until the merchant
+    /// settles.
+    pub fn pay_invoice(env: Env, customer: Address, invoice_hash: BytesN<32>) -> Result<(), Error> {
+        customer.require_auth();
+        let mut invoice = Self::load_invoice(&env, &invoice_hash)?;
+        if invoice.status != InvoiceStatus::Open {
+            return Err(Error::InvoiceNotOpen);
+        }
+        let now = env.ledger().timestamp();
+        if now >= invoice.expires_at {
+            return Err(Error::InvoiceExpired);
+        }
+        if !Self::load_merchant(&env, &invoice.merchant)?.active {
+            return Err(Error::MerchantSuspended);
+        }
+
+        TokenClient::new(&env, &invoice.token).transfer(
+            &customer,
+            &env.current_contract_address(),
+            &invoice.amount,
+        );
+
+        Self::extend_instance_ttl(&env);
+        invoice.status = InvoiceStatus::Paid;
+        invoice.customer = Some(customer.clone());
+        invoice.paid_at = Some(now);
+        Self::save_invoice(&env, &invoice);
+
+        let mut pending = Self::get_pending_invoices(
+            env.clone(),
+            invoice.merchant.clone(),
+            invoice.token.clone(),
+        );
+        pending.push_back(invoice_hash.clone());
+        Self::save_pending(&env, &invoice.merchant, &invoice.token, &pending);
+        Self::adjust_pending_balance(&env, &invoice.merchant, &invoice.token, invoice.amount);
+
+        env.events().publish(
+            (MERCHANT, symbol_short!("paid")),
+            (invoice.merchant, invoice_hash, customer, invoice.amount),
+        );
+        Ok(())
+    }
+
+    /// Return `amount` of a paid invoice to its customer
+    ///
+    /// Before settlement the refund comes out of the held payment; after it,
+    /// from the merchant's own balance.
+    ///
+    /// # Returns
+    /// Ok(total refunded on the invoice)
+    pub fn refund(
+        env: Env,
+        merchant: Address,
+        invoice_hash: BytesN<32>,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        merchant.require_auth();
+        let mut invoice = Self::load_invoice(&env, &invoice_hash)?;
+        if invoice.merchant != merchant {
+            return Err(Error::Unauthorized);
+        }
+        let customer = match (&invoice.status, &invoice.customer) {
+            (InvoiceStatus::Paid, Some(customer)) => customer.clone(),
+            _ => return Err(Error::InvoiceNotPaid),
+        };
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > invoice.amount - invoice.refunded {
+            return Err(Error::RefundTooLarge);
+        }
+
+        let token = TokenClient::new(&env, &invoice.token);
+        if invoice.batch_id.is_some() {
+            token.transfer(&merchant, &customer, &amount);
+        } else {
+            token.transfer(&env.current_contract_address(), &customer, &amount);
+            Self::adjust_pending_balance(&env, &merchant, &invoice.token, -amount);
+            if invoice.refunded + amount == invoice.amount {
+                // Nothing left to settle for it
+                let mut pending = Self::get_pending_invoices(
+                    env.clone(),
+                    merchant.clone(),
+                    invoice.token.clone(),
+                );
+                if let Some(index) = pending.first_index_of(&invoice_hash) {
+                    pending.remove(index);
+                }
+                Self::save_pending(&env, &merchant, &invoice.token, &pending);
+            }
+        }
+        invoice.refunded += amount;
+        Self::save_invoice(&env, &invoice);
+
+        env.events().publish(
+            (MERCHANT, symbol_short!("refund")),
+            (merchant, invoice_hash, customer, amount),
+        );
+        Ok(invoice.refunded)
+    }
+
+    // -----------------------------------------------------------------------
+    // Settlement
+    // -----------------------------------------------------------------------
+
+    /// Pay the oldest unsettled `token` invoices, at most `MAX_BATCH_SIZE`,
+    /// out to the merchant's payout address in one transfer
+    pub fn settle(env: Env, merchant: Address, token: Address) -> Result<SettlementBatch, Error> {
+        merchant.require_auth();
+        let record = Self::load_merchant(&env, &merchant)?;
+        let pending = Self::get_pending_invoices(env.clone(), merchant.clone(), token.clone());
+        if pending.is_empty() {
+            return Err(Error::NothingToSettle);
+        }
+
+        Self::extend_instance_ttl(&env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_BAT"))
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_BAT"), &(id + 1));
+
+        let count = pending.len().min(MAX_BATCH_SIZE);
+        let invoices = pending.slice(0..count);
+        let mut amount = 0i128;
+        for hash in invoices.iter() {
+            let mut invoice = Self::load_invoice(&env, &hash)?;
+            amount += invoice.amount - invoice.refunded;
+            invoice.batch_id = Some(id);
+            Self::save_invoice(&env, &invoice);
+        }
+        Self::save_pending(&env, &merchant, &token, &pending.slice(count..));
+        Self::adjust_pending_balance(&env, &merchant, &token, -amount);
+        TokenClient::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &record.payout,
+            &amount,
+        );
+
+        let batch = SettlementBatch {
+            id,
+            merchant: merchant.clone(),
+            token,
+            payout: record.payout,
+            amount,
+            invoices,
+            settled_at: env.ledger().timestamp(),
+        };
+        let key = (symbol_short!("BATCH"), id);
+        env.storage().persistent().set(&key, &batch);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        let mut batches = Self::get_batches(env.clone(), merchant.clone());
+        batches.push_back(id);
+        let key = (symbol_short!("BATCHES"), merchant.clone());
+        env.storage().persistent().set(&key, &batches);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        env.events().publish(
+            (MERCHANT, symbol_short!("settled")),
+            (merchant, id, amount, count),
+        );
+        Ok(batch)
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_invoice(env: Env, invoice_hash: BytesN<32>) -> Option<Invoice> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("INVOICE"), invoice_hash))
+    }
+
+    /// Paid invoices in `token` not yet settled, oldest first
+    pub fn get_pending_invoices(env: Env, merchant: Address, token: Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PENDING"), merchant, token))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Held for the merchant in `token`, net of refunds
+    pub fn get_pending_balance(env: Env, merchant: Address, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PEND_BAL"), merchant, token))
+            .unwrap_or(0)
+    }
+
+    pub fn get_batch(env: Env, batch_id: u32) -> Option<SettlementBatch> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("BATCH"), batch_id))
+    }
+
+    /// IDs of the merchant's settlement batches, oldest first
+    pub fn get_batches(env: Env, merchant: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("BATCHES"), merchant))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn load_merchant(env: &Env, merchant: &Address) -> Result<Merchant, Error> {
+        Self::get_merchant(env.clone(), merchant.clone()).ok_or(Error::MerchantNotFound)
+    }
+
+    fn save_merchant(env: &Env, merchant: &Merchant) {
+        let key = (symbol_short!("MERCHANT"), merchant.address.clone());
+        env.storage().persistent().set(&key, merchant);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn load_invoice(env: &Env, invoice_hash: &BytesN<32>) -> Result<Invoice, Error> {
+        Self::get_invoice(env.clone(), invoice_hash.clone()).ok_or(Error::InvoiceNotFound)
+    }
+
+    fn save_invoice(env: &Env, invoice: &Invoice) {
+        let key = (symbol_short!("INVOICE"), invoice.hash.clone());
+        env.storage().persistent().set(&key, invoice);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn save_pending(env: &Env, merchant: &Address, token: &Address, pending: &Vec<BytesN<32>>) {
+        let key = (symbol_short!("PENDING"), merchant.clone(), token.clone());
+        env.storage().persistent().set(&key, pending);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn adjust_pending_balance(env: &Env, merchant: &Address, token: &Address, delta: i128) {
+        let key = (symbol_short!("PEND_BAL"), merchant.clone(), token.clone());
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + delta));
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
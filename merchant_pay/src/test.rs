@@ -0,0 +1,195 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, BytesN, Env, String,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: MerchantPayClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    merchant: Address,
+    payout: Address,
+    customer: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, MerchantPay);
+    let client = MerchantPayClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = TokenClient::new(&env, &asset.address());
+    let admin = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let payout = Address::generate(&env);
+    let customer = Address::generate(&env);
+    StellarAssetClient::new(&env, &asset.address()).mint(&customer, &1_000);
+    client.set_admin(&admin, &admin);
+    client.register_merchant(&merchant, &String::from_str(&env, "Corner shop"), &payout);
+
+    Setup {
+        env,
+        client,
+        token,
+        admin,
+        merchant,
+        payout,
+        customer,
+    }
+}
+
+fn invoice(s: &Setup, n: u8, amount: i128) -> BytesN<32> {
+    let hash = BytesN::from_array(&s.env, &[n; 32]);
+    s.client
+        .issue_invoice(&s.merchant, &hash, &s.token.address, &amount, &5_000);
+    hash
+}
+
+#[test]
+fn test_pay_and_settle_in_batches() {
+    let s = setup();
+    let first = invoice(&s, 1, 100);
+    let second = invoice(&s, 2, 250);
+    s.client.pay_invoice(&s.customer, &first);
+    s.client.pay_invoice(&s.customer, &second);
+
+    assert_eq!(s.token.balance(&s.client.address), 350);
+    assert_eq!(
+        s.client.get_pending_balance(&s.merchant, &s.token.address),
+        350
+    );
+    let paid = s.client.get_invoice(&first).unwrap();
+    assert_eq!(paid.status, InvoiceStatus::Paid);
+    assert_eq!(paid.customer, Some(s.customer.clone()));
+    assert_eq!(
+        s.client.try_pay_invoice(&s.customer, &first),
+        Err(Ok(Error::InvoiceNotOpen))
+    );
+
+    let batch = s.client.settle(&s.merchant, &s.token.address);
+    assert_eq!(batch.amount, 350);
+    assert_eq!(batch.invoices.len(), 2);
+    assert_eq!(s.token.balance(&s.payout), 350);
+    assert_eq!(
+        s.client.get_invoice(&second).unwrap().batch_id,
+        Some(batch.id)
+    );
+    assert_eq!(s.client.get_batches(&s.merchant).len(), 1);
+    assert_eq!(
+        s.client.get_pending_balance(&s.merchant, &s.token.address),
+        0
+    );
+    assert_eq!(
+        s.client.try_settle(&s.merchant, &s.token.address),
+        Err(Ok(Error::NothingToSettle))
+    );
+}
+
+#[test]
+fn test_batches_are_capped() {
+    let s = setup();
+    for n in 0..=MAX_BATCH_SIZE {
+        let hash = invoice(&s, n as u8, 10);
+        s.client.pay_invoice(&s.customer, &hash);
+    }
+
+    let batch = s.client.settle(&s.merchant, &s.token.address);
+    assert_eq!(batch.invoices.len(), MAX_BATCH_SIZE);
+    assert_eq!(batch.amount, 10 * MAX_BATCH_SIZE as i128);
+    let rest = s.client.settle(&s.merchant, &s.token.address);
+    assert_eq!(rest.invoices.len(), 1);
+    assert_eq!(
+        s.token.balance(&s.payout),
+        10 * (MAX_BATCH_SIZE as i128 + 1)
+    );
+}
+
+#[test]
+fn test_refunds_before_and_after_settlement() {
+    let s = setup();
+    let kept = invoice(&s, 1, 300);
+    let returned = invoice(&s, 2, 200);
+    s.client.pay_invoice(&s.customer, &kept);
+    s.client.pay_invoice(&s.customer, &returned);
+
+    // Unsettled: refunded from the held payment
+    assert_eq!(s.client.refund(&s.merchant, &kept, &50), 50);
+    assert_eq!(s.client.refund(&s.merchant, &returned, &200), 200);
+    assert_eq!(
+        s.client.try_refund(&s.merchant, &kept, &251),
+        Err(Ok(Error::RefundTooLarge))
+    );
+    assert_eq!(s.token.balance(&s.customer), 750);
+    assert_eq!(
+        s.client
+            .get_pending_invoices(&s.merchant, &s.token.address)
+            .len(),
+        1
+    );
+
+    let batch = s.client.settle(&s.merchant, &s.token.address);
+    assert_eq!(batch.amount, 250);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+
+    // Settled: the merchant pays the refund
+    StellarAssetClient::new(&s.env, &s.token.address).mint(&s.merchant, &100);
+    assert_eq!(s.client.refund(&s.merchant, &kept, &100), 150);
+    assert_eq!(s.token.balance(&s.merchant), 0);
+    assert_eq!(s.token.balance(&s.customer), 850);
+}
+
+#[test]
+fn test_invoice_rules_and_suspension() {
+    let s = setup();
+    let hash = invoice(&s, 1, 100);
+    assert_eq!(
+        s.client
+            .try_issue_invoice(&s.merchant, &hash, &s.token.address, &100, &5_000),
+        Err(Ok(Error::InvoiceExists))
+    );
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_issue_invoice(
+            &stranger,
+            &BytesN::from_array(&s.env, &[9; 32]),
+            &s.token.address,
+            &100,
+            &5_000
+        ),
+        Err(Ok(Error::MerchantNotFound))
+    );
+    assert_eq!(
+        s.client.try_refund(&s.merchant, &hash, &10),
+        Err(Ok(Error::InvoiceNotPaid))
+    );
+
+    s.client.set_merchant_active(&s.admin, &s.merchant, &false);
+    assert_eq!(
+        s.client.try_pay_invoice(&s.customer, &hash),
+        Err(Ok(Error::MerchantSuspended))
+    );
+    s.client.set_merchant_active(&s.admin, &s.merchant, &true);
+
+    s.env.ledger().with_mut(|l| l.timestamp = 5_000);
+    assert_eq!(
+        s.client.try_pay_invoice(&s.customer, &hash),
+        Err(Ok(Error::InvoiceExpired))
+    );
+    s.client.cancel_invoice(&s.merchant, &hash);
+    assert_eq!(
+        s.client.get_invoice(&hash).unwrap().status,
+        InvoiceStatus::Cancelled
+    );
+    assert_eq!(
+        s.client
+            .try_set_merchant_active(&stranger, &s.merchant, &false),
+        Err(Ok(Error::Unauthorized))
+    );
+}
@@ -16,12 +16,88 @@
 //!    - Pays Bills
 //!    - Pays Insurance Premiums
 //!
-//! ## Atomicity Guarantees
+//! ## Simulation
 //!
-//! All operations execute atomically via Soroban's panic/revert mechanism:
-//! - If any step fails, all prior state changes in the transaction are reverted
-//! - No partial state changes can occur
-//! - Events are also rolled back on failure
+//! `simulate_remittance_flow(caller, amount)` plans a profile flow without
+//! executing it: it runs the permission check and split calculation, assigns
+//! each target its share, and flags targets that would fail (missing, not the
+//! caller's, already-paid bills, inactive policies).
+//!
+//! ## Token Settlement
+//!
+//! By default the orchestrator only drives bookkeeping in the downstream
+//! contracts. A user who stores a `FlowSettlement` with `set_flow_settlement`
+//! also has their tokens moved by `execute_remittance_flow`: each category's
+//! delivered amount is transferred from the caller to the matching account in
+//! the same invocation, so one signature settles the whole remittance. A failed
+//! transfer returns `TokenTransferFailed` and reverts the flow in either mode.
+//!
+//! Token goals and token bills pull their tokens from the caller themselves.
+//! Whatever they pull in the settlement token is deducted from their
+//! category's transfer, so the caller is charged once. A goal or bill that
+//! pulls more than its share returns `ShareExceeded` and reverts the flow.
+//!
+//! `set_spending_route` decides what happens to the spending share: it can
+//! stay with the sender, go to a spending wallet, or go to the family wallet
+//! and be credited to a named envelope (`get_envelope_balance`). Each flow
+//! result records the route that was applied.
+//!
+//! ## Protocol Fees
+//!
+//! A hosted deployment can charge a fee on every remittance flow. The admin
+//! sets `FeeConfig` (basis points, treasury and token) with `set_fee_config`.
+//! The fee is taken off the flow total before the split, transferred from
+//! the caller to the treasury, and reported as `fee_amount` in the flow
+//! result and `total_fees_collected` in `get_execution_stats`.
+//!
+//! When the treasury is a Remitwise treasury contract, setting
+//! `record_revenue` makes each flow book its fee there as revenue from the
+//! orchestrator. The treasury must list the orchestrator as a source; if it
+//! refuses the fee the flow fails with `TreasuryRecordFailed`.
+//!
+//! ## Execution Modes
+//!
+//! Downstream calls are made with `try_` clients, so a failing contract yields
+//! an `OrchestratorError` instead of trapping. What happens next depends on
+//! the `ExecutionMode` passed to `execute_remittance_flow`:
+//! - `Atomic`: the error is returned, which reverts every state change and
+//!   event produced by the invocation, including earlier successful steps
+//! - `BestEffort`: the failure is recorded per target, the target's share is
+//!   added to the spending amount, and the flow emits `flow_part` instead of
+//!   `flow_ok`
+//!
+//! The single-operation and address-explicit entry points always run atomically.
+//!
+//! ## History and Monitoring
+//!
+//! Every completed operation updates `get_execution_stats` and appends to the
+//! audit log (`get_audit_log`). Remittance flow results are also kept per
+//! caller and can be paged with `get_flow_history`. Because an error return
+//! reverts storage, only best-effort flows with failed targets are counted as
+//! failed; atomic failures leave no record beyond the failed transaction.
+//!
+//! `get_step_metrics` breaks completed flows down by step (split, savings,
+//! bills, insurance): how many downstream calls each step made, how many
+//! failed, and rolling averages of calls and amount per flow.
+//!
+//! Downstream contracts cannot be rolled back once a best-effort flow moves
+//! past a failed step. Such a flow leaves an entry in `get_compensation_log`
+//! listing the effects that were applied and those that failed, so an admin
+//! can reverse or complete them and mark the entry with `resolve_compensation`.
+//!
+//! A flow run with an idempotency key is stored with that key. Retrying with
+//! the same key, e.g. after a client timeout, returns the stored result
+//! instead of paying twice.
+//!
+//! ## Step Events
+//!
+//! Besides the per-flow `flow_ok`/`flow_part` events, every flow step
+//! (permission check, split, each goal, bill and policy, each settlement
+//! transfer and the fee) emits a `FlowStepEvent` under `("flow_step", caller)`.
+//! The envelope carries `STEP_EVENT_VERSION`, the flow ID also returned in
+//! `RemittanceFlowResult`, the contract called, the amount and the outcome,
+//! so an indexer can follow a remittance across all downstream contracts
+//! from orchestrator events alone.
 //!
 //! ## Gas Estimation
 //!
@@ -31,14 +107,76 @@
 //! - Each downstream operation: ~4,000 gas
 //! - Complete remittance flow: ~22,000 gas
 //!
+//! ## Contract Registry
+//!
+//! The `execute_*` functions take every downstream contract address as a
+//! parameter. An admin can instead register the addresses once with
+//! `set_contracts`; `savings_deposit`, `bill_payment`, `insurance_payment` and
+//! `remittance_flow` then resolve them from storage.
+//!
+//! ## Access Control
+//!
+//! The admin (see `set_admin`) can pause the whole orchestrator with `pause`,
+//! or single operations with `pause_function` and the `pause_functions`
+//! symbols; paused calls fail with `Paused` or `FunctionPaused`. With
+//! `set_member_gate` enabled, only members of the registered family wallet
+//! may run operations; everyone else gets `NotFamilyMember`.
+//!
+//! Contract addresses passed to the `execute_*` functions, and the wallet and
+//! split contracts stored in a flow profile, are chosen by the caller. Once
+//! the admin lists trusted contracts with `add_trusted_contract`, any such
+//! address that is neither listed nor registered fails with
+//! `InvalidContractAddress`, so a caller cannot point the permission check at
+//! a wallet that approves everything.
+//!
+//! ## Version Handshake
+//!
+//! Downstream contracts can be upgraded independently. The admin records the
+//! versions each kind of contract is known to work with using
+//! `set_supported_versions`. Before calling a contract of such a kind, every
+//! operation reads its `get_version` and fails with
+//! `UnsupportedContractVersion` when it is out of range, rather than trapping
+//! halfway through a flow on a changed interface.
+//!
+//! ## Flow Profiles
+//!
+//! `set_flow_profile` stores a user's goal, bills, policies, family wallet and
+//! split contract so that `execute_remittance_flow(caller, amount)` needs
+//! nothing else. A profile may list several bills and policies; each is paid
+//! on every flow. `set_flow_targets` spreads the savings and bills shares over
+//! several goals and bills by weight, and `RemittanceFlowResult` reports the
+//! amount and outcome for every goal, bill and policy.
+//!
+//! ## Batch Flows
+//!
+//! `execute_batch_flows(operator, items)` lets an operator run up to
+//! `MAX_BATCH_FLOWS` users' profile flows in one transaction. Items run in
+//! best-effort mode; items rejected before any downstream call are skipped
+//! rather than failing the batch. The call returns a result per item and
+//! emits a `batch` event with the totals.
+//!
+//! ## Compliance Screening
+//!
+//! Once the admin sets a compliance registry (`set_compliance_registry`),
+//! every flow asks it whether the caller may move the flow's total and fails
+//! with `ComplianceRejected` before any downstream call if not.
+//!
+//! ## Family Flow Templates
+//!
+//! An Owner or Admin of a trusted family wallet can publish a flow template
+//! (`publish_flow_template`) with goals, bills, policies and an execution
+//! mode. Any member of that family then runs it with their own amount via
+//! `execute_flow_template`. Updating a template publishes a new version; a
+//! member must name the latest version to run it. `get_template_usage`
+//! reports how often, by how many members and for how much each template ran.
+//!
 //! ## Usage Example
 //!
 //! ```rust,ignore
 //! use orchestrator::{Orchestrator, OrchestratorClient};
 //!
 //! // Execute a complete remittance flow
-//! let result = orchestrator_client.execute_remittance_flow(
-//!     &env,
+//! let result = orchestrator_client.execute_remittance_flow_direct(
 //!     &user_address,
 //!     &1000_0000000, // 1000 tokens (7 decimals)
 //!     &family_wallet_addr,
@@ -50,11 +188,36 @@
 //!     &bill_id,
 //!     &policy_id,
 //! );
+//!
+//! // Or, once the admin has called set_contracts
+//! let result = orchestrator_client.remittance_flow(
+//!     &user_address,
+//!     &1000_0000000,
+//!     &goal_id,
+//!     &bill_id,
+//!     &policy_id,
+//! );
+//!
+//! // Or store a profile once and pass only the amount afterwards
+//! orchestrator_client.set_flow_profile(
+//!     &user_address,
+//!     &goal_id,
+//!     &bill_ids,
+//!     &policy_ids,
+//!     &family_wallet_addr,
+//!     &remittance_split_addr,
+//! );
+//! let result = orchestrator_client.execute_remittance_flow(
+//!     &user_address,
+//!     &1000_0000000,
+//!     &ExecutionMode::Atomic,
+//!     &Some(idempotency_key),
+//! );
 //! ```
 
 use soroban_sdk::{
-    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, Address,
-    Env, Map, Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short, token,
+    Address, BytesN, Env, Map, Symbol, TryFromVal, Val, Vec,
 };
 
 #[cfg(test)]
@@ -83,6 +246,42 @@ pub trait FamilyWalletTrait {
     /// # Gas Estimation
     /// ~2000 gas
     fn check_spending_limit(env: Env, caller: Address, amount: i128) -> bool;
+
+    /// Look up a family member, used by the member gate
+    fn get_family_member(env: Env, member: Address) -> Option<Map<Symbol, Val>>;
+}
+
+/// Compliance registry client interface
+///
+/// Screens flow callers by KYC tier caps and sanctions flags.
+#[contractclient(name = "ComplianceRegistryClient")]
+pub trait ComplianceRegistryTrait {
+    /// Whether `subject` may move `amount`
+    fn is_compliant(env: Env, subject: Address, amount: i128) -> bool;
+}
+
+/// Treasury contract client interface
+///
+/// Books collected protocol fees as revenue from the orchestrator.
+#[contractclient(name = "TreasuryClient")]
+pub trait TreasuryTrait {
+    /// Record `amount` of `token`, already transferred in, as revenue from `source`
+    fn record_revenue(
+        env: Env,
+        caller: Address,
+        source: Address,
+        token: Address,
+        amount: i128,
+    ) -> i128;
+}
+
+/// Version query shared by all Remitwise contracts
+///
+/// Used for the compatibility handshake before downstream calls.
+#[contractclient(name = "VersionedContractClient")]
+pub trait VersionedContractTrait {
+    /// Contract version, bumped by the contract's upgrade admin
+    fn get_version(env: Env) -> u32;
 }
 
 /// Remittance Split contract client interface
@@ -91,17 +290,21 @@ pub trait FamilyWalletTrait {
 /// Gas estimation: ~3000 gas per split calculation
 #[contractclient(name = "RemittanceSplitClient")]
 pub trait RemittanceSplitTrait {
-    /// Calculate split amounts from a total remittance amount
+    /// Calculate split amounts from a total remittance amount with `owner`'s configuration
     ///
     /// # Arguments
+    /// * `owner` - Address whose split applies (the deployment default if it has none)
     /// * `total_amount` - The total amount to split (must be positive)
     ///
     /// # Returns
-    /// Vec containing [spending, savings, bills, insurance] amounts
+    /// One amount per category, in the order of `get_split_categories(owner)`
     ///
     /// # Gas Estimation
     /// ~3000 gas
-    fn calculate_split(env: Env, total_amount: i128) -> Vec<i128>;
+    fn calculate_owner_split(env: Env, owner: Address, total_amount: i128) -> Vec<i128>;
+
+    /// Categories applied for `owner`; only each record's `name` is read
+    fn get_split_categories(env: Env, owner: Address) -> Vec<Map<Symbol, Val>>;
 }
 
 /// Savings Goals contract client interface
@@ -123,6 +326,9 @@ pub trait SavingsGoalsTrait {
     /// # Gas Estimation
     /// ~4000 gas
     fn add_to_goal(env: Env, caller: Address, goal_id: u32, amount: i128) -> i128;
+
+    /// Read a goal's stored fields, used by `simulate_remittance_flow`
+    fn get_goal(env: Env, goal_id: u32) -> Option<Map<Symbol, Val>>;
 }
 
 /// Bill Payments contract client interface
@@ -143,6 +349,9 @@ pub trait BillPaymentsTrait {
     /// # Gas Estimation
     /// ~4000 gas
     fn pay_bill(env: Env, caller: Address, bill_id: u32);
+
+    /// Read a bill's stored fields, used by `simulate_remittance_flow`
+    fn get_bill(env: Env, bill_id: u32) -> Option<Map<Symbol, Val>>;
 }
 
 /// Insurance contract client interface
@@ -163,6 +372,9 @@ pub trait InsuranceTrait {
     /// # Gas Estimation
     /// ~4000 gas
     fn pay_premium(env: Env, caller: Address, policy_id: u32) -> bool;
+
+    /// Read a policy's stored fields, used by `simulate_remittance_flow`
+    fn get_policy(env: Env, policy_id: u32) -> Option<Map<Symbol, Val>>;
 }
 
 /// Orchestrator-specific errors
@@ -188,15 +400,344 @@ pub enum OrchestratorError {
     InvalidContractAddress = 8,
     /// Generic cross-contract call failure
     CrossContractCallFailed = 9,
+    /// Caller is not the orchestrator admin
+    Unauthorized = 10,
+    /// Registry-backed call made before `set_contracts`
+    ContractsNotConfigured = 11,
+    /// Caller has no stored flow profile
+    ProfileNotFound = 12,
+    /// Profile lists more bills or policies than MAX_FLOW_TARGETS
+    TooManyTargets = 13,
+    /// Flow target weight must be positive
+    InvalidTargetWeight = 14,
+    /// Moving tokens to a settlement account failed (e.g. insufficient balance)
+    TokenTransferFailed = 15,
+    /// Idempotency key was already used for a flow with a different amount
+    IdempotencyKeyReused = 16,
+    /// The orchestrator is paused
+    Paused = 17,
+    /// This operation is paused
+    FunctionPaused = 18,
+    /// Member gate is on and the caller is not in the registered family wallet
+    NotFamilyMember = 19,
+    /// Spending route set before `set_flow_settlement`
+    SettlementNotFound = 20,
+    /// No flow template with this ID
+    TemplateNotFound = 21,
+    /// The template has a newer version than the one the caller expected
+    TemplateVersionMismatch = 22,
+    /// Batch has more items than MAX_BATCH_FLOWS
+    BatchTooLarge = 23,
+    /// No compensation log entry with this ID
+    CompensationNotFound = 24,
+    /// A downstream contract reports a version outside its supported range
+    UnsupportedContractVersion = 25,
+    /// Supported version range has min greater than max
+    InvalidVersionRange = 26,
+    /// Fee above MAX_FEE_BPS
+    InvalidFee = 27,
+    /// Caller failed the compliance registry check
+    ComplianceRejected = 28,
+    /// Treasury contract refused to record the protocol fee
+    TreasuryRecordFailed = 29,
+    /// A token goal or bill pulled more than its share of the allocation
+    ShareExceeded = 30,
+}
+
+/// Downstream contract addresses registered once per deployment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractRegistry {
+    /// Family Wallet contract (permission and spending-limit checks)
+    pub family_wallet: Address,
+    /// Remittance Split contract (allocation calculation)
+    pub remittance_split: Address,
+    /// Savings Goals contract
+    pub savings: Address,
+    /// Bill Payments contract
+    pub bills: Address,
+    /// Insurance contract
+    pub insurance: Address,
+}
+
+/// Kinds of downstream contract the orchestrator calls
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractKind {
+    /// Family wallet (permission checks and membership)
+    FamilyWallet,
+    /// Remittance split calculator
+    RemittanceSplit,
+    /// Savings goals
+    Savings,
+    /// Bill payments
+    Bills,
+    /// Insurance policies
+    Insurance,
+}
+
+/// Inclusive range of contract versions the orchestrator is known to work with
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// How a remittance flow treats downstream failures
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExecutionMode {
+    /// Any failed downstream call fails the flow and reverts all its effects
+    Atomic,
+    /// Failed calls are recorded and their shares routed to spending
+    BestEffort,
+}
+
+/// Destination of the spending share when a flow is settled in tokens
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SpendingRoute {
+    /// The spending share stays in the sender's account
+    Sender,
+    /// The spending share is transferred to this wallet
+    Wallet(Address),
+    /// The spending share is transferred to the family wallet and credited to
+    /// the named envelope
+    Envelope(Symbol),
+}
+
+/// A goal or bill receiving a weighted portion of its category's allocation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowTarget {
+    /// Goal or bill ID
+    pub id: u32,
+    /// Relative weight within the category (must be positive)
+    pub weight: u32,
+}
+
+/// Predicted result of calling a downstream target, see `simulate_remittance_flow`
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlannedOutcome {
+    /// No problem detected
+    Ready,
+    /// Goal, bill or policy does not exist
+    NotFound,
+    /// Target belongs to someone other than the caller
+    NotOwner,
+    /// Bill is already paid
+    AlreadyPaid,
+    /// Policy is not active
+    Inactive,
+    /// The downstream contract could not be read
+    Unknown,
+}
+
+/// Planned share and predicted outcome for one target
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlannedTarget {
+    /// Goal, bill or policy ID
+    pub id: u32,
+    /// Portion of the category allocation the flow would assign
+    pub amount: i128,
+    /// What the downstream call is expected to do
+    pub outcome: PlannedOutcome,
+}
+
+/// Dry-run result of a profile flow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemittanceFlowPlan {
+    /// Total remittance amount
+    pub total_amount: i128,
+    /// Protocol fee taken off the total before the split
+    pub fee_amount: i128,
+    /// Planned spending allocation
+    pub spending_amount: i128,
+    /// Planned savings allocation
+    pub savings_amount: i128,
+    /// Planned bills allocation
+    pub bills_amount: i128,
+    /// Planned insurance allocation
+    pub insurance_amount: i128,
+    /// Whether the family wallet and compliance registry would allow the
+    /// amount
+    pub permitted: bool,
+    /// Planned per-goal savings shares
+    pub savings_targets: Vec<PlannedTarget>,
+    /// Planned per-bill shares
+    pub bill_targets: Vec<PlannedTarget>,
+    /// Planned per-policy shares
+    pub policy_targets: Vec<PlannedTarget>,
+    /// Targets predicted to fail (excluding `Unknown`)
+    pub predicted_failures: u32,
+}
+
+/// Outcome for a single downstream target within a flow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TargetResult {
+    /// Goal, bill or policy ID
+    pub id: u32,
+    /// Portion of the category allocation assigned to this target
+    pub amount: i128,
+    /// Whether the downstream call succeeded
+    pub success: bool,
+}
+
+/// Protocol fee charged on every remittance flow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    /// Fee in basis points of the flow total (at most MAX_FEE_BPS)
+    pub fee_bps: u32,
+    /// Receives the fees
+    pub treasury: Address,
+    /// Token the fee is paid in
+    pub token: Address,
+    /// Treasury is a treasury contract; record each fee there as revenue
+    pub record_revenue: bool,
+}
+
+/// Token and destination accounts used to settle a profile flow in tokens
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowSettlement {
+    /// Token contract (e.g. USDC) moved from the caller
+    pub token: Address,
+    /// Receives the spending share, including rerouted shares, unless a
+    /// `SpendingRoute` is set
+    pub spending: Address,
+    /// Receives the delivered savings share
+    pub savings: Address,
+    /// Receives the delivered bills share
+    pub bills: Address,
+    /// Receives the delivered insurance share
+    pub insurance: Address,
+}
+
+/// Per-user flow configuration used by `execute_remittance_flow`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowProfile {
+    /// Savings goals sharing the savings allocation by weight
+    pub goals: Vec<FlowTarget>,
+    /// Bills paid on every flow, sharing the bills allocation by weight
+    pub bills: Vec<FlowTarget>,
+    /// Insurance policies whose premium is paid on every flow
+    pub policy_ids: Vec<u32>,
+    /// Family Wallet contract used for permission checks
+    pub family_wallet: Address,
+    /// Remittance Split contract used to calculate allocations
+    pub remittance_split: Address,
+}
+
+/// Allocation targets and mode of a family flow template
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowTemplateSpec {
+    /// Family wallet the template belongs to; its members may execute it
+    pub family_wallet: Address,
+    /// Remittance split contract
+    pub remittance_split: Address,
+    /// Savings goals sharing the savings allocation by weight
+    pub goals: Vec<FlowTarget>,
+    /// Bills sharing the bills allocation by weight
+    pub bills: Vec<FlowTarget>,
+    /// Policies whose premium is paid on every flow
+    pub policy_ids: Vec<u32>,
+    /// Execution mode used for every run of the template
+    pub mode: ExecutionMode,
+}
+
+/// One published version of a family flow template
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowTemplate {
+    /// Template ID, shared by all its versions
+    pub id: u32,
+    /// Version number, starting at 1
+    pub version: u32,
+    /// Owner or admin of the family wallet who published this version
+    pub publisher: Address,
+    /// Timestamp of publication
+    pub published_at: u64,
+    /// Targets and mode
+    pub spec: FlowTemplateSpec,
+}
+
+/// How often a template has been executed, across all its versions
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateUsage {
+    /// Completed flows run from the template
+    pub executions: u64,
+    /// Total amount of those flows
+    pub total_amount: i128,
+    /// Members who have run the template at least once
+    pub members: u32,
+    /// Timestamp of the last execution
+    pub last_executed: u64,
+}
+
+/// One flow in an operator batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchFlowItem {
+    /// User whose flow profile is executed (must authorize)
+    pub user: Address,
+    /// Total remittance amount for this user
+    pub amount: i128,
+}
+
+/// Outcome of one item of `execute_batch_flows`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchItemResult {
+    /// User whose flow was executed
+    pub user: Address,
+    /// Requested amount
+    pub amount: i128,
+    /// Whether the flow ran and was recorded in the user's history
+    pub executed: bool,
+    /// Why the item was skipped, or the first failed category of a partial flow
+    pub error_code: Option<u32>,
+    /// Index of the flow result in the user's `get_flow_history`
+    pub flow_index: Option<u32>,
+}
+
+/// Summary event emitted at the end of a batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchFlowSummary {
+    /// Operator that submitted the batch
+    pub operator: Address,
+    /// Number of items in the batch
+    pub items: u32,
+    /// Items whose flow ran with every target paid
+    pub succeeded: u32,
+    /// Items whose flow ran with some targets rerouted to spending
+    pub partial: u32,
+    /// Items skipped before any contract was called
+    pub skipped: u32,
+    /// Sum of the amounts of executed items
+    pub total_amount: i128,
+    /// Timestamp of execution
+    pub timestamp: u64,
 }
 
 /// Result of a complete remittance flow execution
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RemittanceFlowResult {
+    /// ID shared with the flow's `FlowStepEvent`s
+    pub flow_id: u64,
     /// Total remittance amount processed
     pub total_amount: i128,
-    /// Amount allocated to spending
+    /// Amount allocated to spending, including any rerouted shares
     pub spending_amount: i128,
     /// Amount allocated to savings
     pub savings_amount: i128,
@@ -210,10 +751,55 @@ pub struct RemittanceFlowResult {
     pub bills_success: bool,
     /// Whether insurance payment succeeded
     pub insurance_success: bool,
+    /// Per-goal split of the savings allocation
+    pub savings_targets: Vec<TargetResult>,
+    /// Per-bill split of the bills allocation
+    pub bill_targets: Vec<TargetResult>,
+    /// Per-policy split of the insurance allocation
+    pub policy_targets: Vec<TargetResult>,
+    /// Execution mode the flow ran in
+    pub mode: ExecutionMode,
+    /// Shares of failed targets added to `spending_amount` (best-effort only)
+    pub rerouted_to_spending: i128,
+    /// Whether tokens were transferred to the caller's settlement accounts
+    pub tokens_settled: bool,
+    /// Where the spending share went
+    pub spending_route: SpendingRoute,
+    /// Protocol fee taken off `total_amount` before the split
+    pub fee_amount: i128,
     /// Timestamp of execution
     pub timestamp: u64,
 }
 
+/// Version of the `FlowStepEvent` layout, bumped on any change to its fields
+pub const STEP_EVENT_VERSION: u32 = 1;
+
+/// Event emitted for every step of a remittance flow
+///
+/// Published under the topics `("flow_step", caller)`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowStepEvent {
+    /// Layout version (`STEP_EVENT_VERSION`)
+    pub version: u32,
+    /// Flow the step belongs to, shared by all its steps
+    pub flow_id: u64,
+    /// "perm", "split", "savings", "bills", "insuranc", "settle" or "fee"
+    pub step: Symbol,
+    /// Contract called by the step (the token for "settle" and "fee")
+    pub contract: Address,
+    /// Goal, bill or policy ID, 0 for steps without a target
+    pub target_id: u32,
+    /// Amount handled by the step
+    pub amount: i128,
+    /// Whether the step succeeded
+    pub success: bool,
+    /// OrchestratorError code of a failed step
+    pub error_code: Option<u32>,
+    /// Timestamp of the flow
+    pub timestamp: u64,
+}
+
 /// Event emitted on successful remittance flow completion
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -242,6 +828,24 @@ pub struct RemittanceFlowErrorEvent {
     pub timestamp: u64,
 }
 
+/// Counters and rolling averages for one step of the remittance flow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StepMetrics {
+    /// Step name: "split", "savings", "bills" or "insuranc"
+    pub step: Symbol,
+    /// Completed flows that reached this step
+    pub runs: u64,
+    /// Downstream contract calls made by this step
+    pub calls: u64,
+    /// Downstream calls that failed (best-effort flows only)
+    pub failures: u64,
+    /// Rolling average of downstream calls per run, in hundredths
+    pub avg_calls_x100: u64,
+    /// Rolling average of the amount handled per run
+    pub avg_amount: i128,
+}
+
 /// Execution statistics for monitoring orchestrator performance
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -252,8 +856,55 @@ pub struct ExecutionStats {
     pub total_flows_failed: u64,
     /// Total amount processed across all flows
     pub total_amount_processed: i128,
+    /// Protocol fees transferred to the treasury
+    pub total_fees_collected: i128,
     /// Timestamp of last execution
     pub last_execution: u64,
+    /// Remittance split calculation
+    pub split_metrics: StepMetrics,
+    /// Savings goal deposits
+    pub savings_metrics: StepMetrics,
+    /// Bill payments
+    pub bills_metrics: StepMetrics,
+    /// Insurance premium payments
+    pub insurance_metrics: StepMetrics,
+}
+
+/// A downstream state change made, or skipped, by a flow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DownstreamEffect {
+    /// Flow step: "savings", "bills" or "insuranc"
+    pub step: Symbol,
+    /// Contract the step called
+    pub contract: Address,
+    /// Goal, bill or policy ID
+    pub target_id: u32,
+    /// Share of the allocation for this target
+    pub amount: i128,
+}
+
+/// What a best-effort flow applied before one of its steps failed
+///
+/// `applied` lists the effects an operator would reverse to bring the
+/// downstream contracts back to the state before the flow.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompensationEntry {
+    /// Position in the compensation log
+    pub id: u32,
+    /// Address that ran the flow
+    pub caller: Address,
+    /// Index of the flow in the caller's `get_flow_history`
+    pub flow_index: u32,
+    /// Effects that took place
+    pub applied: Vec<DownstreamEffect>,
+    /// Effects that failed; their shares were rerouted to spending
+    pub failed: Vec<DownstreamEffect>,
+    /// Whether an admin marked the entry as handled
+    pub resolved: bool,
+    /// Timestamp of the flow
+    pub timestamp: u64,
 }
 
 /// Audit log entry for compliance and security tracking
@@ -274,13 +925,47 @@ pub struct OrchestratorAuditEntry {
     pub error_code: Option<u32>,
 }
 
+/// Symbols accepted by `pause_function`, one per operation
+pub mod pause_functions {
+    use soroban_sdk::symbol_short;
+    /// `execute_savings_deposit` and `savings_deposit`
+    pub const SAVINGS: soroban_sdk::Symbol = symbol_short!("exec_save");
+    /// `execute_bill_payment` and `bill_payment`
+    pub const BILL: soroban_sdk::Symbol = symbol_short!("exec_bill");
+    /// `execute_insurance_payment` and `insurance_payment`
+    pub const INSURANCE: soroban_sdk::Symbol = symbol_short!("exec_ins");
+    /// Every remittance flow entry point
+    pub const FLOW: soroban_sdk::Symbol = symbol_short!("exec_flow");
+    /// `execute_batch_flows`; its items also honour `FLOW`
+    pub const BATCH: soroban_sdk::Symbol = symbol_short!("exec_bat");
+}
+
 // Storage TTL constants matching other Remitwise contracts
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
 const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
 
+// Per-user entries (flow profiles and flow history) live in persistent storage
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400; // ~30 days
+
+// Maximum goals, bills or policies a single flow profile may target
+const MAX_FLOW_TARGETS: u32 = 10;
+
 // Maximum audit log entries to keep in storage
 const MAX_AUDIT_ENTRIES: u32 = 100;
 
+// Maximum flow results returned by one get_flow_history call
+const MAX_HISTORY_LIMIT: u32 = 50;
+
+// Highest protocol fee the admin can set (10%)
+const MAX_FEE_BPS: u32 = 1_000;
+
+// Maximum items in one execute_batch_flows call
+const MAX_BATCH_FLOWS: u32 = 20;
+
+// Number of recent flows the step metric averages roughly cover
+const METRICS_WINDOW: i128 = 10;
+
 /// Main orchestrator contract
 #[contract]
 pub struct Orchestrator;
@@ -378,11 +1063,13 @@ impl Orchestrator {
     /// Extract allocation amounts from the Remittance Split contract
     ///
     /// This function calls the Remittance Split contract to calculate how a total
-    /// remittance amount should be divided across spending, savings, bills, and insurance.
+    /// remittance amount should be divided across spending, savings, bills, and
+    /// insurance under the caller's own split configuration.
     ///
     /// # Arguments
     /// * `env` - The contract environment
     /// * `remittance_split_addr` - Address of the Remittance Split contract
+    /// * `caller` - Address whose split configuration applies
     /// * `total_amount` - Total remittance amount to split (must be positive)
     ///
     /// # Returns
@@ -395,11 +1082,13 @@ impl Orchestrator {
     /// # Cross-Contract Call Flow
     /// 1. Validate that total_amount is positive
     /// 2. Create RemittanceSplitClient instance
-    /// 3. Call calculate_split via cross-contract call
-    /// 4. Return the allocation vector
+    /// 3. Call calculate_owner_split and get_split_categories via cross-contract calls
+    /// 4. Map each amount to its category by name; shares of categories other
+    ///    than the four standard ones count as spending
     fn extract_allocations(
         env: &Env,
         remittance_split_addr: &Address,
+        caller: &Address,
         total_amount: i128,
     ) -> Result<Vec<i128>, OrchestratorError> {
         // Validate amount is positive
@@ -411,9 +1100,23 @@ impl Orchestrator {
         let split_client = RemittanceSplitClient::new(env, remittance_split_addr);
 
         // Gas estimation: ~3000 gas
-        // Call the remittance split contract to calculate allocations
-        // This returns Vec<i128> with [spending, savings, bills, insurance]
-        let allocations = split_client.calculate_split(&total_amount);
+        // Amounts come back in the order of the caller's categories
+        let amounts = split_client.calculate_owner_split(caller, &total_amount);
+        let categories = split_client.get_split_categories(caller);
+
+        let mut allocations = Vec::from_array(env, [0i128; 4]);
+        for (category, amount) in categories.iter().zip(amounts.iter()) {
+            let name = category
+                .get(Symbol::new(env, "name"))
+                .and_then(|v| Symbol::try_from_val(env, &v).ok());
+            let index = match name {
+                Some(n) if n == symbol_short!("SAVINGS") => 1,
+                Some(n) if n == symbol_short!("BILLS") => 2,
+                Some(n) if n == symbol_short!("INSURANCE") => 3,
+                _ => 0,
+            };
+            allocations.set(index, allocations.get_unchecked(index) + amount);
+        }
 
         Ok(allocations)
     }
@@ -444,7 +1147,7 @@ impl Orchestrator {
     /// # Cross-Contract Call Flow
     /// 1. Create SavingsGoalsClient instance
     /// 2. Call add_to_goal via cross-contract call
-    /// 3. If the call panics (goal not found, invalid amount), transaction reverts
+    /// 3. If the call fails (goal not found, invalid amount), return SavingsDepositFailed
     /// 4. Return success if call completes
     fn deposit_to_savings(
         env: &Env,
@@ -457,12 +1160,12 @@ impl Orchestrator {
         let savings_client = SavingsGoalsClient::new(env, savings_addr);
 
         // Gas estimation: ~4000 gas
-        // Call add_to_goal on the savings contract
-        // This will panic if the goal doesn't exist or amount is invalid
-        // The panic will cause the entire transaction to revert (atomicity)
-        savings_client.add_to_goal(owner, &goal_id, &amount);
-
-        Ok(())
+        // Call add_to_goal on the savings contract; a failed call has its own
+        // state rolled back and surfaces here as an error
+        match savings_client.try_add_to_goal(owner, &goal_id, &amount) {
+            Ok(Ok(_)) => Ok(()),
+            _ => Err(OrchestratorError::SavingsDepositFailed),
+        }
     }
 
     /// Execute bill payment via cross-contract call
@@ -486,7 +1189,7 @@ impl Orchestrator {
     /// # Cross-Contract Call Flow
     /// 1. Create BillPaymentsClient instance
     /// 2. Call pay_bill via cross-contract call
-    /// 3. If the call panics (bill not found, already paid), transaction reverts
+    /// 3. If the call fails (bill not found, already paid), return BillPaymentFailed
     /// 4. Return success if call completes
     fn execute_bill_payment_internal(
         env: &Env,
@@ -499,11 +1202,10 @@ impl Orchestrator {
 
         // Gas estimation: ~4000 gas
         // Call pay_bill on the bills contract
-        // This will panic if the bill doesn't exist or is already paid
-        // The panic will cause the entire transaction to revert (atomicity)
-        bills_client.pay_bill(caller, &bill_id);
-
-        Ok(())
+        match bills_client.try_pay_bill(caller, &bill_id) {
+            Ok(Ok(())) => Ok(()),
+            _ => Err(OrchestratorError::BillPaymentFailed),
+        }
     }
 
     /// Pay insurance premium via cross-contract call
@@ -527,7 +1229,8 @@ impl Orchestrator {
     /// # Cross-Contract Call Flow
     /// 1. Create InsuranceClient instance
     /// 2. Call pay_premium via cross-contract call
-    /// 3. If the call panics (policy not found, inactive), transaction reverts
+    /// 3. If the call fails or returns false (policy not found, inactive), return
+    ///    InsurancePaymentFailed
     /// 4. Return success if call completes
     fn pay_insurance_premium(
         env: &Env,
@@ -540,11 +1243,10 @@ impl Orchestrator {
 
         // Gas estimation: ~4000 gas
         // Call pay_premium on the insurance contract
-        // This will panic if the policy doesn't exist or is inactive
-        // The panic will cause the entire transaction to revert (atomicity)
-        insurance_client.pay_premium(caller, &policy_id);
-
-        Ok(())
+        match insurance_client.try_pay_premium(caller, &policy_id) {
+            Ok(Ok(true)) => Ok(()),
+            _ => Err(OrchestratorError::InsurancePaymentFailed),
+        }
     }
 
     // ============================================================================
@@ -579,6 +1281,58 @@ impl Orchestrator {
         env.events().publish((symbol_short!("flow_ok"),), event);
     }
 
+    /// Emit event for a best-effort flow where some targets failed
+    ///
+    /// Carries the same payload as the success event; `allocations[0]` includes
+    /// the shares of failed targets that were routed back to spending.
+    fn emit_partial_event(
+        env: &Env,
+        caller: &Address,
+        total_amount: i128,
+        allocations: &Vec<i128>,
+        timestamp: u64,
+    ) {
+        let event = RemittanceFlowEvent {
+            caller: caller.clone(),
+            total_amount,
+            allocations: allocations.clone(),
+            timestamp,
+        };
+
+        env.events().publish((symbol_short!("flow_part"),), event);
+    }
+
+    /// Emit the versioned envelope for one step of a remittance flow
+    ///
+    /// Indexers can rebuild a flow's timeline from these events alone by
+    /// grouping on `flow_id`. Failed steps only appear in best-effort flows,
+    /// because a failing atomic flow reverts its events along with its state.
+    #[allow(clippy::too_many_arguments)]
+    fn emit_step_event(
+        env: &Env,
+        caller: &Address,
+        flow_id: u64,
+        step: Symbol,
+        contract: &Address,
+        target_id: u32,
+        amount: i128,
+        error_code: Option<u32>,
+    ) {
+        let event = FlowStepEvent {
+            version: STEP_EVENT_VERSION,
+            flow_id,
+            step,
+            contract: contract.clone(),
+            target_id,
+            amount,
+            success: error_code.is_none(),
+            error_code,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.events()
+            .publish((symbol_short!("flow_step"), caller.clone()), event);
+    }
+
     /// Emit error event for a failed remittance flow
     ///
     /// This function creates and publishes a RemittanceFlowErrorEvent to the ledger,
@@ -608,102 +1362,1193 @@ impl Orchestrator {
     }
 
     // ============================================================================
-    // Public Functions - Individual Operations
+    // Helper Functions - Operation Bodies
     // ============================================================================
 
-    /// Execute a savings deposit with family wallet permission checks
-    ///
-    /// This function deposits funds to a savings goal after validating permissions
-    /// and spending limits via the Family Wallet contract.
-    ///
-    /// # Arguments
-    /// * `env` - The contract environment
-    /// * `caller` - Address initiating the operation (must authorize)
-    /// * `amount` - Amount to deposit
-    /// * `family_wallet_addr` - Address of the Family Wallet contract
-    /// * `savings_addr` - Address of the Savings Goals contract
-    /// * `goal_id` - Target savings goal ID
-    ///
-    /// # Returns
-    /// Ok(()) if successful, Err(OrchestratorError) if any step fails
-    ///
-    /// # Gas Estimation
-    /// - Base: ~3000 gas
-    /// - Family wallet check: ~2000 gas
-    /// - Savings deposit: ~4000 gas
-    /// - Total: ~9,000 gas
+    /// Run the permission gate shared by every entry point
     ///
-    /// # Execution Flow
-    /// 1. Require caller authorization
-    /// 2. Check family wallet permission
-    /// 3. Check spending limit
-    /// 4. Deposit to savings goal
-    /// 5. Emit success event
-    /// 6. On error, emit error event and return error
-    pub fn execute_savings_deposit(
+    /// Checks family wallet permission and the caller's spending limit, emitting
+    /// an error event tagged with the failing step before returning the error.
+    fn run_permission_checks(
+        env: &Env,
+        family_wallet_addr: &Address,
+        caller: &Address,
+        amount: i128,
+        timestamp: u64,
+    ) -> Result<(), OrchestratorError> {
+        Self::check_family_wallet_permission(env, family_wallet_addr, caller, amount).inspect_err(
+            |e| {
+                Self::emit_error_event(env, caller, symbol_short!("perm_chk"), *e as u32, timestamp)
+            },
+        )?;
+
+        Self::check_spending_limit(env, family_wallet_addr, caller, amount).inspect_err(|e| {
+            Self::emit_error_event(env, caller, symbol_short!("spend_lm"), *e as u32, timestamp)
+        })
+    }
+
+    fn run_savings_deposit(
+        env: &Env,
+        caller: &Address,
+        amount: i128,
+        family_wallet_addr: &Address,
+        savings_addr: &Address,
+        goal_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        let timestamp = env.ledger().timestamp();
+
+        Self::require_compatible(
+            env,
+            &[
+                (ContractKind::FamilyWallet, family_wallet_addr),
+                (ContractKind::Savings, savings_addr),
+            ],
+        )?;
+        Self::run_permission_checks(env, family_wallet_addr, caller, amount, timestamp)?;
+
+        Self::deposit_to_savings(env, savings_addr, caller, goal_id, amount).inspect_err(|e| {
+            Self::emit_error_event(env, caller, symbol_short!("savings"), *e as u32, timestamp)
+        })?;
+
+        let allocations = Vec::from_array(env, [0, amount, 0, 0]);
+        Self::emit_success_event(env, caller, amount, &allocations, timestamp);
+        Self::record_execution(env, caller, symbol_short!("exec_save"), amount, None);
+
+        Ok(())
+    }
+
+    fn run_bill_payment(
+        env: &Env,
+        caller: &Address,
+        amount: i128,
+        family_wallet_addr: &Address,
+        bills_addr: &Address,
+        bill_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        let timestamp = env.ledger().timestamp();
+
+        Self::require_compatible(
+            env,
+            &[
+                (ContractKind::FamilyWallet, family_wallet_addr),
+                (ContractKind::Bills, bills_addr),
+            ],
+        )?;
+        Self::run_permission_checks(env, family_wallet_addr, caller, amount, timestamp)?;
+
+        Self::execute_bill_payment_internal(env, bills_addr, caller, bill_id).inspect_err(|e| {
+            Self::emit_error_event(env, caller, symbol_short!("bills"), *e as u32, timestamp)
+        })?;
+
+        let allocations = Vec::from_array(env, [0, 0, amount, 0]);
+        Self::emit_success_event(env, caller, amount, &allocations, timestamp);
+        Self::record_execution(env, caller, symbol_short!("exec_bill"), amount, None);
+
+        Ok(())
+    }
+
+    fn run_insurance_payment(
+        env: &Env,
+        caller: &Address,
+        amount: i128,
+        family_wallet_addr: &Address,
+        insurance_addr: &Address,
+        policy_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        let timestamp = env.ledger().timestamp();
+
+        Self::require_compatible(
+            env,
+            &[
+                (ContractKind::FamilyWallet, family_wallet_addr),
+                (ContractKind::Insurance, insurance_addr),
+            ],
+        )?;
+        Self::run_permission_checks(env, family_wallet_addr, caller, amount, timestamp)?;
+
+        Self::pay_insurance_premium(env, insurance_addr, caller, policy_id).inspect_err(|e| {
+            Self::emit_error_event(env, caller, symbol_short!("insuranc"), *e as u32, timestamp)
+        })?;
+
+        let allocations = Vec::from_array(env, [0, 0, 0, amount]);
+        Self::emit_success_event(env, caller, amount, &allocations, timestamp);
+        Self::record_execution(env, caller, symbol_short!("exec_ins"), amount, None);
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn run_remittance_flow(
+        env: &Env,
+        caller: &Address,
+        total_amount: i128,
+        contracts: &ContractRegistry,
+        goals: &Vec<FlowTarget>,
+        bills: &Vec<FlowTarget>,
+        policies: &Vec<FlowTarget>,
+        mode: ExecutionMode,
+        settlement: Option<FlowSettlement>,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<RemittanceFlowResult, OrchestratorError> {
+        let timestamp = env.ledger().timestamp();
+
+        // Step 1: Validate amount
+        if total_amount <= 0 {
+            Self::emit_error_event(
+                env,
+                caller,
+                symbol_short!("validate"),
+                OrchestratorError::InvalidAmount as u32,
+                timestamp,
+            );
+            return Err(OrchestratorError::InvalidAmount);
+        }
+
+        // Handshake: every downstream contract must run a supported version
+        Self::require_compatible(
+            env,
+            &[
+                (ContractKind::FamilyWallet, &contracts.family_wallet),
+                (ContractKind::RemittanceSplit, &contracts.remittance_split),
+                (ContractKind::Savings, &contracts.savings),
+                (ContractKind::Bills, &contracts.bills),
+                (ContractKind::Insurance, &contracts.insurance),
+            ],
+        )?;
+
+        if let Err(e) = Self::require_compliant(env, caller, total_amount) {
+            Self::emit_error_event(env, caller, symbol_short!("comply"), e as u32, timestamp);
+            return Err(e);
+        }
+
+        let flow_id = Self::next_flow_id(env);
+
+        // Steps 2-3: Check family wallet permission and spending limit
+        Self::run_permission_checks(
+            env,
+            &contracts.family_wallet,
+            caller,
+            total_amount,
+            timestamp,
+        )?;
+        Self::emit_step_event(
+            env,
+            caller,
+            flow_id,
+            symbol_short!("perm"),
+            &contracts.family_wallet,
+            0,
+            total_amount,
+            None,
+        );
+
+        // Step 4: Extract allocations from remittance split, after the fee
+        let (fee_amount, fee_config) = Self::flow_fee(env, total_amount);
+        let allocations = Self::extract_allocations(
+            env,
+            &contracts.remittance_split,
+            caller,
+            total_amount - fee_amount,
+        )
+        .inspect_err(|e| {
+            Self::emit_error_event(env, caller, symbol_short!("split"), *e as u32, timestamp)
+        })?;
+        Self::emit_step_event(
+            env,
+            caller,
+            flow_id,
+            symbol_short!("split"),
+            &contracts.remittance_split,
+            0,
+            total_amount - fee_amount,
+            None,
+        );
+
+        // Extract individual amounts
+        let mut spending_amount = allocations.get(0).unwrap_or(0);
+        let savings_amount = allocations.get(1).unwrap_or(0);
+        let bills_amount = allocations.get(2).unwrap_or(0);
+        let insurance_amount = allocations.get(3).unwrap_or(0);
+
+        // Shares of failed targets in best-effort mode
+        let mut rerouted = 0i128;
+
+        // Token goals and token bills pull from the caller themselves. Step 8
+        // only settles what they left of their shares.
+        let settlement_token = settlement
+            .as_ref()
+            .map(|s| token::Client::new(env, &s.token));
+        let balance = || {
+            settlement_token
+                .as_ref()
+                .map(|t| t.balance(caller))
+                .unwrap_or(0)
+        };
+        let mut savings_pulled = 0i128;
+        let mut bills_pulled = 0i128;
+
+        // Step 5: Deposit to savings goals
+        let mut savings_targets = Vec::new(env);
+        let mut savings_success = true;
+        let goal_amounts = Self::weighted_shares(env, savings_amount, goals);
+        for (goal, amount) in goals.iter().zip(goal_amounts.iter()) {
+            let before = balance();
+            // A zero share has nothing to deposit
+            let outcome = if amount == 0 {
+                Ok(())
+            } else {
+                Self::deposit_to_savings(env, &contracts.savings, caller, goal.id, amount)
+                    .inspect_err(|e| {
+                        Self::emit_error_event(
+                            env,
+                            caller,
+                            symbol_short!("savings"),
+                            *e as u32,
+                            timestamp,
+                        )
+                    })
+            };
+            savings_pulled += Self::pulled_within_share(
+                env,
+                caller,
+                symbol_short!("savings"),
+                before - balance(),
+                amount,
+                timestamp,
+            )?;
+            let error_code = outcome.as_ref().err().map(|e| *e as u32);
+            let success = Self::settle_outcome(mode, outcome)?;
+            Self::emit_step_event(
+                env,
+                caller,
+                flow_id,
+                symbol_short!("savings"),
+                &contracts.savings,
+                goal.id,
+                amount,
+                error_code,
+            );
+            if !success {
+                rerouted += amount;
+            }
+            savings_success &= success;
+            savings_targets.push_back(TargetResult {
+                id: goal.id,
+                amount,
+                success,
+            });
+        }
+
+        // Step 6: Pay bills
+        let mut bill_targets = Vec::new(env);
+        let mut bills_success = true;
+        let bill_amounts = Self::weighted_shares(env, bills_amount, bills);
+        for (bill, amount) in bills.iter().zip(bill_amounts.iter()) {
+            let before = balance();
+            let outcome =
+                Self::execute_bill_payment_internal(env, &contracts.bills, caller, bill.id)
+                    .inspect_err(|e| {
+                        Self::emit_error_event(
+                            env,
+                            caller,
+                            symbol_short!("bills"),
+                            *e as u32,
+                            timestamp,
+                        )
+                    });
+            bills_pulled += Self::pulled_within_share(
+                env,
+                caller,
+                symbol_short!("bills"),
+                before - balance(),
+                amount,
+                timestamp,
+            )?;
+            let error_code = outcome.as_ref().err().map(|e| *e as u32);
+            let success = Self::settle_outcome(mode, outcome)?;
+            Self::emit_step_event(
+                env,
+                caller,
+                flow_id,
+                symbol_short!("bills"),
+                &contracts.bills,
+                bill.id,
+                amount,
+                error_code,
+            );
+            if !success {
+                rerouted += amount;
+            }
+            bills_success &= success;
+            bill_targets.push_back(TargetResult {
+                id: bill.id,
+                amount,
+                success,
+            });
+        }
+
+        // Step 7: Pay insurance premiums
+        let mut policy_targets = Vec::new(env);
+        let mut insurance_success = true;
+        let policy_amounts = Self::weighted_shares(env, insurance_amount, policies);
+        for (policy, amount) in policies.iter().zip(policy_amounts.iter()) {
+            let outcome = Self::pay_insurance_premium(env, &contracts.insurance, caller, policy.id)
+                .inspect_err(|e| {
+                    Self::emit_error_event(
+                        env,
+                        caller,
+                        symbol_short!("insuranc"),
+                        *e as u32,
+                        timestamp,
+                    )
+                });
+            let error_code = outcome.as_ref().err().map(|e| *e as u32);
+            let success = Self::settle_outcome(mode, outcome)?;
+            Self::emit_step_event(
+                env,
+                caller,
+                flow_id,
+                symbol_short!("insuranc"),
+                &contracts.insurance,
+                policy.id,
+                amount,
+                error_code,
+            );
+            if !success {
+                rerouted += amount;
+            }
+            insurance_success &= success;
+            policy_targets.push_back(TargetResult {
+                id: policy.id,
+                amount,
+                success,
+            });
+        }
+
+        // Failed shares stay with the sender as spending
+        spending_amount += rerouted;
+        let mut allocations = allocations;
+        allocations.set(0, spending_amount);
+
+        // Step 8: Move tokens for what was actually delivered
+        let spending_route = Self::resolve_spending_route(env, caller, settlement.as_ref());
+        let tokens_settled = settlement.is_some();
+        if let Some(settlement) = settlement {
+            let token_client = token::Client::new(env, &settlement.token);
+            let spending_to = match &spending_route {
+                SpendingRoute::Sender => caller.clone(),
+                SpendingRoute::Wallet(wallet) => wallet.clone(),
+                SpendingRoute::Envelope(_) => contracts.family_wallet.clone(),
+            };
+            let transfers = [
+                (spending_to, spending_amount),
+                (
+                    settlement.savings,
+                    Self::delivered(&savings_targets) - savings_pulled,
+                ),
+                (
+                    settlement.bills,
+                    Self::delivered(&bill_targets) - bills_pulled,
+                ),
+                (settlement.insurance, Self::delivered(&policy_targets)),
+            ];
+            for (to, amount) in transfers {
+                if amount <= 0 || to == *caller {
+                    continue;
+                }
+                if !matches!(token_client.try_transfer(caller, &to, &amount), Ok(Ok(()))) {
+                    Self::emit_error_event(
+                        env,
+                        caller,
+                        symbol_short!("settle"),
+                        OrchestratorError::TokenTransferFailed as u32,
+                        timestamp,
+                    );
+                    return Err(OrchestratorError::TokenTransferFailed);
+                }
+                Self::emit_step_event(
+                    env,
+                    caller,
+                    flow_id,
+                    symbol_short!("settle"),
+                    &settlement.token,
+                    0,
+                    amount,
+                    None,
+                );
+            }
+            if let SpendingRoute::Envelope(envelope) = &spending_route {
+                if spending_amount > 0 {
+                    Self::credit_envelope(env, &contracts.family_wallet, envelope, spending_amount);
+                }
+            }
+        }
+
+        // Step 9: Collect the protocol fee
+        if let Some(config) = fee_config.filter(|_| fee_amount > 0) {
+            let transfer = token::Client::new(env, &config.token).try_transfer(
+                caller,
+                &config.treasury,
+                &fee_amount,
+            );
+            if !matches!(transfer, Ok(Ok(()))) {
+                Self::emit_error_event(
+                    env,
+                    caller,
+                    symbol_short!("fee"),
+                    OrchestratorError::TokenTransferFailed as u32,
+                    timestamp,
+                );
+                return Err(OrchestratorError::TokenTransferFailed);
+            }
+            if config.record_revenue {
+                let this = env.current_contract_address();
+                let recorded = TreasuryClient::new(env, &config.treasury).try_record_revenue(
+                    &this,
+                    &this,
+                    &config.token,
+                    &fee_amount,
+                );
+                if !matches!(recorded, Ok(Ok(_))) {
+                    Self::emit_error_event(
+                        env,
+                        caller,
+                        symbol_short!("fee"),
+                        OrchestratorError::TreasuryRecordFailed as u32,
+                        timestamp,
+                    );
+                    return Err(OrchestratorError::TreasuryRecordFailed);
+                }
+            }
+            Self::emit_step_event(
+                env,
+                caller,
+                flow_id,
+                symbol_short!("fee"),
+                &config.token,
+                0,
+                fee_amount,
+                None,
+            );
+            env.events().publish(
+                (symbol_short!("fee"),),
+                (caller.clone(), config.treasury, fee_amount),
+            );
+        }
+
+        // Build result
+        let result = RemittanceFlowResult {
+            flow_id,
+            total_amount,
+            spending_amount,
+            savings_amount,
+            bills_amount,
+            insurance_amount,
+            savings_success,
+            bills_success,
+            insurance_success,
+            savings_targets,
+            bill_targets,
+            policy_targets,
+            mode,
+            rerouted_to_spending: rerouted,
+            tokens_settled,
+            spending_route,
+            fee_amount,
+            timestamp,
+        };
+
+        // A best-effort flow with failed targets is reported as partial
+        if !(savings_success && bills_success && insurance_success) {
+            Self::emit_partial_event(env, caller, total_amount, &allocations, timestamp);
+        } else {
+            Self::emit_success_event(env, caller, total_amount, &allocations, timestamp);
+        }
+
+        Self::record_execution(
+            env,
+            caller,
+            symbol_short!("exec_flow"),
+            total_amount,
+            Self::first_failure(&result),
+        );
+        Self::record_flow_stats(env, &result);
+        let index = Self::store_flow_result(env, caller, &result);
+        Self::record_compensation(env, caller, contracts, &result, index);
+        // Map the idempotency key to the stored result for retries
+        if let Some(key) = idempotency_key {
+            let idem_key = (symbol_short!("IDEM"), caller.clone(), key);
+            env.storage().persistent().set(&idem_key, &index);
+            env.storage().persistent().extend_ttl(
+                &idem_key,
+                PERSISTENT_LIFETIME_THRESHOLD,
+                PERSISTENT_BUMP_AMOUNT,
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Where this flow's spending share goes
+    ///
+    /// Flows without a settlement move no tokens, so the share stays with the
+    /// sender. A wallet route pointing back at the caller is the same thing.
+    fn resolve_spending_route(
+        env: &Env,
+        caller: &Address,
+        settlement: Option<&FlowSettlement>,
+    ) -> SpendingRoute {
+        let Some(settlement) = settlement else {
+            return SpendingRoute::Sender;
+        };
+        match Self::get_spending_route(env.clone(), caller.clone())
+            .unwrap_or_else(|| SpendingRoute::Wallet(settlement.spending.clone()))
+        {
+            SpendingRoute::Wallet(wallet) if wallet == *caller => SpendingRoute::Sender,
+            route => route,
+        }
+    }
+
+    /// Add a spending share to a family wallet envelope
+    fn credit_envelope(env: &Env, family_wallet: &Address, envelope: &Symbol, amount: i128) {
+        let key = (
+            symbol_short!("ENVELOPE"),
+            family_wallet.clone(),
+            envelope.clone(),
+        );
+        let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(balance + amount));
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        env.events().publish(
+            (symbol_short!("envelope"),),
+            (family_wallet.clone(), envelope.clone(), amount),
+        );
+    }
+
+    /// Apply the execution mode to a downstream outcome
+    ///
+    /// Atomic mode propagates the error so the whole invocation reverts;
+    /// best-effort mode records the failure and carries on.
+    fn settle_outcome(
+        mode: ExecutionMode,
+        outcome: Result<(), OrchestratorError>,
+    ) -> Result<bool, OrchestratorError> {
+        match outcome {
+            Ok(()) => Ok(true),
+            Err(e) if mode == ExecutionMode::Atomic => Err(e),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Check what a downstream call pulled from the caller against its share
+    ///
+    /// A token goal or bill that takes more than its share fails the flow in
+    /// either mode, since the transfer cannot be undone per target.
+    fn pulled_within_share(
+        env: &Env,
+        caller: &Address,
+        step: Symbol,
+        pulled: i128,
+        share: i128,
+        timestamp: u64,
+    ) -> Result<i128, OrchestratorError> {
+        if pulled > share {
+            Self::emit_error_event(
+                env,
+                caller,
+                step,
+                OrchestratorError::ShareExceeded as u32,
+                timestamp,
+            );
+            return Err(OrchestratorError::ShareExceeded);
+        }
+        Ok(pulled)
+    }
+
+    /// Sum of the shares whose downstream call succeeded
+    fn delivered(targets: &Vec<TargetResult>) -> i128 {
+        targets.iter().filter(|t| t.success).map(|t| t.amount).sum()
+    }
+
+    /// Split `amount` across `targets` in proportion to their weights
+    ///
+    /// Integer division leaves a remainder, which goes to the last target so the
+    /// shares always add up to `amount`.
+    fn weighted_shares(env: &Env, amount: i128, targets: &Vec<FlowTarget>) -> Vec<i128> {
+        let mut shares = Vec::new(env);
+        let total_weight: i128 = targets.iter().map(|t| t.weight as i128).sum();
+        if total_weight == 0 {
+            return shares;
+        }
+
+        let mut allocated = 0i128;
+        for (i, target) in targets.iter().enumerate() {
+            let share = if i as u32 + 1 == targets.len() {
+                amount - allocated
+            } else {
+                amount * target.weight as i128 / total_weight
+            };
+            allocated += share;
+            shares.push_back(share);
+        }
+        shares
+    }
+
+    /// Equal-weight targets for a list of IDs
+    fn targets_from_ids(env: &Env, ids: &Vec<u32>) -> Vec<FlowTarget> {
+        let mut targets = Vec::new(env);
+        for id in ids.iter() {
+            targets.push_back(FlowTarget { id, weight: 1 });
+        }
+        targets
+    }
+
+    fn single_target(env: &Env, id: u32) -> Vec<FlowTarget> {
+        Vec::from_array(env, [FlowTarget { id, weight: 1 }])
+    }
+
+    fn save_flow_profile(env: &Env, user: &Address, profile: &FlowProfile) {
+        let key = (symbol_short!("PROFILE"), user.clone());
+        env.storage().persistent().set(&key, profile);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    // ============================================================================
+    // Public Functions - Administration and Contract Registry
+    // ============================================================================
+
+    /// Set or transfer the orchestrator admin
+    ///
+    /// The first call bootstraps the admin and must be made by the new admin
+    /// itself; afterwards only the current admin can hand the role over.
+    ///
+    /// # Arguments
+    /// * `caller` - Current admin, or the bootstrapping address (must authorize)
+    /// * `new_admin` - Address that will manage the registry and pause controls
+    ///
+    /// # Returns
+    /// Ok(()) on success, Err(OrchestratorError::Unauthorized) otherwise
+    pub fn set_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+    ) -> Result<(), OrchestratorError> {
+        caller.require_auth();
+
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(OrchestratorError::Unauthorized),
+            Some(admin) if admin != caller => return Err(OrchestratorError::Unauthorized),
+            _ => {}
+        }
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+
+        Ok(())
+    }
+
+    /// Get the orchestrator admin, if one has been set
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Register the downstream contract addresses for this deployment
+    ///
+    /// Once registered, the registry-backed entry points (`savings_deposit`,
+    /// `bill_payment`, `insurance_payment` and `remittance_flow`) resolve every
+    /// contract address from storage instead of taking them as parameters.
+    /// Calling again replaces the whole registry.
+    ///
+    /// # Arguments
+    /// * `caller` - Admin address (must authorize)
+    /// * `contracts` - Addresses of the five downstream contracts
+    ///
+    /// # Returns
+    /// Ok(()) on success, Err(OrchestratorError::Unauthorized) if caller is not the admin
+    pub fn set_contracts(
+        env: Env,
+        caller: Address,
+        contracts: ContractRegistry,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CONTRACTS"), &contracts);
+
+        env.events()
+            .publish((symbol_short!("contracts"),), (caller, contracts));
+
+        Ok(())
+    }
+
+    /// Get the registered downstream contract addresses
+    pub fn get_contracts(env: Env) -> Option<ContractRegistry> {
+        env.storage().instance().get(&symbol_short!("CONTRACTS"))
+    }
+
+    /// Halt every operation until `unpause`
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    pub fn pause(env: Env, caller: Address) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &true);
+        env.events().publish((symbol_short!("paused"),), caller);
+        Ok(())
+    }
+
+    /// Resume operations halted by `pause`
+    ///
+    /// Functions paused individually with `pause_function` stay paused.
+    pub fn unpause(env: Env, caller: Address) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED"), &false);
+        env.events().publish((symbol_short!("unpaused"),), caller);
+        Ok(())
+    }
+
+    /// Halt one operation, identified by a `pause_functions` symbol
+    pub fn pause_function(
+        env: Env,
+        caller: Address,
+        func: Symbol,
+    ) -> Result<(), OrchestratorError> {
+        Self::set_function_paused(&env, &caller, func, true)
+    }
+
+    /// Resume an operation halted by `pause_function`
+    pub fn unpause_function(
+        env: Env,
+        caller: Address,
+        func: Symbol,
+    ) -> Result<(), OrchestratorError> {
+        Self::set_function_paused(&env, &caller, func, false)
+    }
+
+    /// Whether the whole orchestrator is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("PAUSED"))
+            .unwrap_or(false)
+    }
+
+    /// Whether one operation is paused on its own
+    pub fn is_function_paused(env: Env, func: Symbol) -> bool {
+        env.storage()
+            .instance()
+            .get::<_, Map<Symbol, bool>>(&symbol_short!("PAUSED_FN"))
+            .and_then(|paused| paused.get(func))
+            .unwrap_or(false)
+    }
+
+    /// Restrict callers to members of the registered family wallet
+    ///
+    /// While enabled, every operation first asks the registry's family wallet
+    /// for the caller's membership and rejects non-members with
+    /// `NotFamilyMember`.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    pub fn set_member_gate(
         env: Env,
         caller: Address,
-        amount: i128,
-        family_wallet_addr: Address,
-        savings_addr: Address,
-        goal_id: u32,
+        enabled: bool,
     ) -> Result<(), OrchestratorError> {
-        // Require caller authorization
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MBR_GATE"), &enabled);
+        env.events()
+            .publish((symbol_short!("mbr_gate"),), (caller, enabled));
+        Ok(())
+    }
+
+    /// Whether callers must be members of the registered family wallet
+    pub fn is_member_gate_enabled(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("MBR_GATE"))
+            .unwrap_or(false)
+    }
+
+    /// Add a downstream contract to the trusted allowlist
+    ///
+    /// Once the allowlist has an entry, every contract address an operation
+    /// receives as a parameter, or reads from a flow profile, must be on it or
+    /// in the registry; anything else fails with `InvalidContractAddress`.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    pub fn add_trusted_contract(
+        env: Env,
+        caller: Address,
+        contract: Address,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        let mut trusted = Self::load_trusted(&env);
+        trusted.set(contract.clone(), true);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TRUSTED"), &trusted);
+        env.events()
+            .publish((symbol_short!("trusted"),), (contract, true));
+        Ok(())
+    }
+
+    /// Remove a contract from the trusted allowlist
+    ///
+    /// Removing the last entry turns allowlist enforcement off again.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    pub fn remove_trusted_contract(
+        env: Env,
+        caller: Address,
+        contract: Address,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        let mut trusted = Self::load_trusted(&env);
+        trusted.remove(contract.clone());
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TRUSTED"), &trusted);
+        env.events()
+            .publish((symbol_short!("trusted"),), (contract, false));
+        Ok(())
+    }
+
+    /// Contracts on the trusted allowlist
+    pub fn get_trusted_contracts(env: Env) -> Vec<Address> {
+        Self::load_trusted(&env).keys()
+    }
+
+    /// Whether operations may call `contract`
+    ///
+    /// True for every address while the allowlist is empty, otherwise only
+    /// for listed and registered contracts.
+    pub fn is_trusted_contract(env: Env, contract: Address) -> bool {
+        let trusted = Self::load_trusted(&env);
+        if trusted.is_empty() || trusted.contains_key(contract.clone()) {
+            return true;
+        }
+        match Self::load_contracts(&env) {
+            Ok(registry) => {
+                contract == registry.family_wallet
+                    || contract == registry.remittance_split
+                    || contract == registry.savings
+                    || contract == registry.bills
+                    || contract == registry.insurance
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn load_trusted(env: &Env) -> Map<Address, bool> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("TRUSTED"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Reject any caller-supplied contract address that is not trusted
+    fn require_trusted(env: &Env, contracts: &[&Address]) -> Result<(), OrchestratorError> {
+        for contract in contracts {
+            if !Self::is_trusted_contract(env.clone(), (*contract).clone()) {
+                return Err(OrchestratorError::InvalidContractAddress);
+            }
+        }
+        Ok(())
+    }
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), OrchestratorError> {
         caller.require_auth();
+        if Self::get_admin(env.clone()) != Some(caller.clone()) {
+            return Err(OrchestratorError::Unauthorized);
+        }
+        Ok(())
+    }
 
-        let timestamp = env.ledger().timestamp();
+    fn set_function_paused(
+        env: &Env,
+        caller: &Address,
+        func: Symbol,
+        paused: bool,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(env, caller)?;
+        Self::extend_instance_ttl(env);
+        let mut functions: Map<Symbol, bool> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("PAUSED_FN"))
+            .unwrap_or_else(|| Map::new(env));
+        functions.set(func, paused);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("PAUSED_FN"), &functions);
+        Ok(())
+    }
 
-        // Step 1: Check family wallet permission
-        Self::check_family_wallet_permission(&env, &family_wallet_addr, &caller, amount).map_err(
-            |e| {
-                Self::emit_error_event(
-                    &env,
-                    &caller,
-                    symbol_short!("perm_chk"),
-                    e as u32,
-                    timestamp,
-                );
-                e
-            },
-        )?;
+    /// Pause and membership checks run by every operation before any other work
+    fn require_access(env: &Env, caller: &Address, func: Symbol) -> Result<(), OrchestratorError> {
+        if Self::is_paused(env.clone()) {
+            return Err(OrchestratorError::Paused);
+        }
+        if Self::is_function_paused(env.clone(), func) {
+            return Err(OrchestratorError::FunctionPaused);
+        }
+        if Self::is_member_gate_enabled(env.clone()) {
+            let contracts = Self::load_contracts(env)?;
+            let member = FamilyWalletClient::new(env, &contracts.family_wallet)
+                .try_get_family_member(caller);
+            if !matches!(member, Ok(Ok(Some(_)))) {
+                return Err(OrchestratorError::NotFamilyMember);
+            }
+        }
+        Ok(())
+    }
 
-        // Step 2: Check spending limit
-        Self::check_spending_limit(&env, &family_wallet_addr, &caller, amount).map_err(|e| {
-            Self::emit_error_event(
-                &env,
-                &caller,
-                symbol_short!("spend_lm"),
-                e as u32,
-                timestamp,
-            );
-            e
-        })?;
+    /// Charge a protocol fee on every remittance flow
+    ///
+    /// The fee is `fee_bps` basis points of the flow's total. It is taken off
+    /// the total before the split and transferred in `token` from the caller
+    /// to the treasury.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    /// Err(OrchestratorError::InvalidFee) if fee_bps exceeds MAX_FEE_BPS
+    pub fn set_fee_config(
+        env: Env,
+        caller: Address,
+        config: FeeConfig,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        if config.fee_bps > MAX_FEE_BPS {
+            return Err(OrchestratorError::InvalidFee);
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().set(&symbol_short!("FEE"), &config);
+        env.events().publish((symbol_short!("fee_cfg"),), config);
+        Ok(())
+    }
 
-        // Step 3: Deposit to savings
-        Self::deposit_to_savings(&env, &savings_addr, &caller, goal_id, amount).map_err(|e| {
-            Self::emit_error_event(&env, &caller, symbol_short!("savings"), e as u32, timestamp);
-            e
-        })?;
+    /// Stop charging protocol fees
+    pub fn clear_fee_config(env: Env, caller: Address) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().remove(&symbol_short!("FEE"));
+        Ok(())
+    }
 
-        // Emit success event
-        let allocations = Vec::from_array(&env, [0, amount, 0, 0]);
-        Self::emit_success_event(&env, &caller, amount, &allocations, timestamp);
+    /// Current protocol fee configuration, if fees are enabled
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&symbol_short!("FEE"))
+    }
+
+    /// Screen flow callers against a compliance registry
+    ///
+    /// Every flow checks `is_compliant(caller, total_amount)` before any
+    /// downstream call and fails with `ComplianceRejected` when it is false or
+    /// the registry cannot be reached.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    pub fn set_compliance_registry(
+        env: Env,
+        caller: Address,
+        registry: Address,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CMP_REG"), &registry);
+        env.events().publish((symbol_short!("cmp_reg"),), registry);
+        Ok(())
+    }
 
+    /// Stop screening flow callers
+    pub fn clear_compliance_registry(env: Env, caller: Address) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        env.storage().instance().remove(&symbol_short!("CMP_REG"));
         Ok(())
     }
 
-    /// Execute a bill payment with family wallet permission checks
+    pub fn get_compliance_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("CMP_REG"))
+    }
+
+    fn require_compliant(
+        env: &Env,
+        caller: &Address,
+        amount: i128,
+    ) -> Result<(), OrchestratorError> {
+        if let Some(registry) = Self::get_compliance_registry(env.clone()) {
+            let client = ComplianceRegistryClient::new(env, &registry);
+            if !matches!(client.try_is_compliant(caller, &amount), Ok(Ok(true))) {
+                return Err(OrchestratorError::ComplianceRejected);
+            }
+        }
+        Ok(())
+    }
+
+    /// Protocol fee owed on a flow of `total_amount`
+    fn flow_fee(env: &Env, total_amount: i128) -> (i128, Option<FeeConfig>) {
+        match Self::get_fee_config(env.clone()) {
+            Some(config) if total_amount > 0 => {
+                (total_amount * config.fee_bps as i128 / 10_000, Some(config))
+            }
+            config => (0, config),
+        }
+    }
+
+    /// Set the versions of a downstream contract kind the orchestrator accepts
     ///
-    /// This function pays a bill after validating permissions and spending limits
-    /// via the Family Wallet contract.
+    /// Operations read `get_version` from each contract they are about to
+    /// use whose kind has a range set, and fail with
+    /// `UnsupportedContractVersion` when it is outside the range or cannot be
+    /// read. Kinds without a range are not checked.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    /// Err(OrchestratorError::InvalidVersionRange) if min is greater than max
+    pub fn set_supported_versions(
+        env: Env,
+        caller: Address,
+        kind: ContractKind,
+        range: VersionRange,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        if range.min > range.max {
+            return Err(OrchestratorError::InvalidVersionRange);
+        }
+        Self::extend_instance_ttl(&env);
+        let mut ranges = Self::load_version_ranges(&env);
+        ranges.set(kind, range.clone());
+        env.storage()
+            .instance()
+            .set(&symbol_short!("VERSIONS"), &ranges);
+        env.events()
+            .publish((symbol_short!("versions"),), (kind, range));
+        Ok(())
+    }
+
+    /// Stop checking the version of a downstream contract kind
+    pub fn clear_supported_versions(
+        env: Env,
+        caller: Address,
+        kind: ContractKind,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+        Self::extend_instance_ttl(&env);
+        let mut ranges = Self::load_version_ranges(&env);
+        ranges.remove(kind);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("VERSIONS"), &ranges);
+        Ok(())
+    }
+
+    /// Supported version range of a downstream contract kind, if one is set
+    pub fn get_supported_versions(env: Env, kind: ContractKind) -> Option<VersionRange> {
+        Self::load_version_ranges(&env).get(kind)
+    }
+
+    /// Run the version handshake against every registered contract
+    ///
+    /// Lets an admin confirm after a downstream upgrade that operations will
+    /// still go through.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    /// Err(OrchestratorError::UnsupportedContractVersion) if a contract fails the check
+    pub fn check_contract_versions(env: Env) -> Result<(), OrchestratorError> {
+        let contracts = Self::load_contracts(&env)?;
+        Self::require_compatible(
+            &env,
+            &[
+                (ContractKind::FamilyWallet, &contracts.family_wallet),
+                (ContractKind::RemittanceSplit, &contracts.remittance_split),
+                (ContractKind::Savings, &contracts.savings),
+                (ContractKind::Bills, &contracts.bills),
+                (ContractKind::Insurance, &contracts.insurance),
+            ],
+        )
+    }
+
+    fn load_version_ranges(env: &Env) -> Map<ContractKind, VersionRange> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("VERSIONS"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Check each contract's `get_version` against its kind's supported range
+    fn require_compatible(
+        env: &Env,
+        contracts: &[(ContractKind, &Address)],
+    ) -> Result<(), OrchestratorError> {
+        let ranges = Self::load_version_ranges(env);
+        if ranges.is_empty() {
+            return Ok(());
+        }
+        for (kind, address) in contracts {
+            let Some(range) = ranges.get(*kind) else {
+                continue;
+            };
+            match VersionedContractClient::new(env, address).try_get_version() {
+                Ok(Ok(version)) if version >= range.min && version <= range.max => {}
+                _ => return Err(OrchestratorError::UnsupportedContractVersion),
+            }
+        }
+        Ok(())
+    }
+
+    fn load_contracts(env: &Env) -> Result<ContractRegistry, OrchestratorError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("CONTRACTS"))
+            .ok_or(OrchestratorError::ContractsNotConfigured)
+    }
+
+    // ============================================================================
+    // Public Functions - Individual Operations
+    // ============================================================================
+
+    /// Execute a savings deposit with family wallet permission checks
+    ///
+    /// This function deposits funds to a savings goal after validating permissions
+    /// and spending limits via the Family Wallet contract.
     ///
     /// # Arguments
     /// * `env` - The contract environment
     /// * `caller` - Address initiating the operation (must authorize)
-    /// * `amount` - Amount of the bill payment
+    /// * `amount` - Amount to deposit
     /// * `family_wallet_addr` - Address of the Family Wallet contract
-    /// * `bills_addr` - Address of the Bill Payments contract
-    /// * `bill_id` - Target bill ID
+    /// * `savings_addr` - Address of the Savings Goals contract
+    /// * `goal_id` - Target savings goal ID
     ///
     /// # Returns
     /// Ok(()) if successful, Err(OrchestratorError) if any step fails
@@ -711,66 +2556,89 @@ impl Orchestrator {
     /// # Gas Estimation
     /// - Base: ~3000 gas
     /// - Family wallet check: ~2000 gas
-    /// - Bill payment: ~4000 gas
+    /// - Savings deposit: ~4000 gas
     /// - Total: ~9,000 gas
     ///
     /// # Execution Flow
     /// 1. Require caller authorization
     /// 2. Check family wallet permission
     /// 3. Check spending limit
-    /// 4. Execute bill payment
+    /// 4. Deposit to savings goal
     /// 5. Emit success event
     /// 6. On error, emit error event and return error
-    pub fn execute_bill_payment(
+    pub fn execute_savings_deposit(
         env: Env,
         caller: Address,
         amount: i128,
         family_wallet_addr: Address,
-        bills_addr: Address,
-        bill_id: u32,
+        savings_addr: Address,
+        goal_id: u32,
     ) -> Result<(), OrchestratorError> {
         // Require caller authorization
         caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::SAVINGS)?;
+        Self::require_trusted(&env, &[&family_wallet_addr, &savings_addr])?;
 
-        let timestamp = env.ledger().timestamp();
-
-        // Step 1: Check family wallet permission
-        Self::check_family_wallet_permission(&env, &family_wallet_addr, &caller, amount).map_err(
-            |e| {
-                Self::emit_error_event(
-                    &env,
-                    &caller,
-                    symbol_short!("perm_chk"),
-                    e as u32,
-                    timestamp,
-                );
-                e
-            },
-        )?;
-
-        // Step 2: Check spending limit
-        Self::check_spending_limit(&env, &family_wallet_addr, &caller, amount).map_err(|e| {
-            Self::emit_error_event(
-                &env,
-                &caller,
-                symbol_short!("spend_lm"),
-                e as u32,
-                timestamp,
-            );
-            e
-        })?;
-
-        // Step 3: Execute bill payment
-        Self::execute_bill_payment_internal(&env, &bills_addr, &caller, bill_id).map_err(|e| {
-            Self::emit_error_event(&env, &caller, symbol_short!("bills"), e as u32, timestamp);
-            e
-        })?;
+        Self::run_savings_deposit(
+            &env,
+            &caller,
+            amount,
+            &family_wallet_addr,
+            &savings_addr,
+            goal_id,
+        )
+    }
 
-        // Emit success event
-        let allocations = Vec::from_array(&env, [0, 0, amount, 0]);
-        Self::emit_success_event(&env, &caller, amount, &allocations, timestamp);
+    /// Execute a bill payment with family wallet permission checks
+    ///
+    /// This function pays a bill after validating permissions and spending limits
+    /// via the Family Wallet contract.
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `caller` - Address initiating the operation (must authorize)
+    /// * `amount` - Amount of the bill payment
+    /// * `family_wallet_addr` - Address of the Family Wallet contract
+    /// * `bills_addr` - Address of the Bill Payments contract
+    /// * `bill_id` - Target bill ID
+    ///
+    /// # Returns
+    /// Ok(()) if successful, Err(OrchestratorError) if any step fails
+    ///
+    /// # Gas Estimation
+    /// - Base: ~3000 gas
+    /// - Family wallet check: ~2000 gas
+    /// - Bill payment: ~4000 gas
+    /// - Total: ~9,000 gas
+    ///
+    /// # Execution Flow
+    /// 1. Require caller authorization
+    /// 2. Check family wallet permission
+    /// 3. Check spending limit
+    /// 4. Execute bill payment
+    /// 5. Emit success event
+    /// 6. On error, emit error event and return error
+    pub fn execute_bill_payment(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        family_wallet_addr: Address,
+        bills_addr: Address,
+        bill_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        // Require caller authorization
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::BILL)?;
+        Self::require_trusted(&env, &[&family_wallet_addr, &bills_addr])?;
 
-        Ok(())
+        Self::run_bill_payment(
+            &env,
+            &caller,
+            amount,
+            &family_wallet_addr,
+            &bills_addr,
+            bill_id,
+        )
     }
 
     /// Execute an insurance premium payment with family wallet permission checks
@@ -812,63 +2680,700 @@ impl Orchestrator {
     ) -> Result<(), OrchestratorError> {
         // Require caller authorization
         caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::INSURANCE)?;
+        Self::require_trusted(&env, &[&family_wallet_addr, &insurance_addr])?;
 
-        let timestamp = env.ledger().timestamp();
+        Self::run_insurance_payment(
+            &env,
+            &caller,
+            amount,
+            &family_wallet_addr,
+            &insurance_addr,
+            policy_id,
+        )
+    }
 
-        // Step 1: Check family wallet permission
-        Self::check_family_wallet_permission(&env, &family_wallet_addr, &caller, amount).map_err(
-            |e| {
-                Self::emit_error_event(
-                    &env,
-                    &caller,
-                    symbol_short!("perm_chk"),
-                    e as u32,
-                    timestamp,
-                );
-                e
-            },
+    // ============================================================================
+    // Public Functions - Registry-Backed Operations
+    // ============================================================================
+
+    /// Savings deposit using the registered family wallet and savings contracts
+    ///
+    /// Same checks and events as `execute_savings_deposit`.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    pub fn savings_deposit(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        goal_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::SAVINGS)?;
+        let contracts = Self::load_contracts(&env)?;
+        Self::run_savings_deposit(
+            &env,
+            &caller,
+            amount,
+            &contracts.family_wallet,
+            &contracts.savings,
+            goal_id,
+        )
+    }
+
+    /// Bill payment using the registered family wallet and bill payments contracts
+    ///
+    /// Same checks and events as `execute_bill_payment`.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    pub fn bill_payment(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        bill_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::BILL)?;
+        let contracts = Self::load_contracts(&env)?;
+        Self::run_bill_payment(
+            &env,
+            &caller,
+            amount,
+            &contracts.family_wallet,
+            &contracts.bills,
+            bill_id,
+        )
+    }
+
+    /// Premium payment using the registered family wallet and insurance contracts
+    ///
+    /// Same checks and events as `execute_insurance_payment`.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    pub fn insurance_payment(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        policy_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::INSURANCE)?;
+        let contracts = Self::load_contracts(&env)?;
+        Self::run_insurance_payment(
+            &env,
+            &caller,
+            amount,
+            &contracts.family_wallet,
+            &contracts.insurance,
+            policy_id,
+        )
+    }
+
+    /// Complete remittance flow using the registered contract addresses
+    ///
+    /// Same steps, result and events as `execute_remittance_flow_direct`,
+    /// always in atomic mode.
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    pub fn remittance_flow(
+        env: Env,
+        caller: Address,
+        total_amount: i128,
+        goal_id: u32,
+        bill_id: u32,
+        policy_id: u32,
+    ) -> Result<RemittanceFlowResult, OrchestratorError> {
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::FLOW)?;
+        let contracts = Self::load_contracts(&env)?;
+        Self::run_remittance_flow(
+            &env,
+            &caller,
+            total_amount,
+            &contracts,
+            &Self::single_target(&env, goal_id),
+            &Self::single_target(&env, bill_id),
+            &Self::single_target(&env, policy_id),
+            ExecutionMode::Atomic,
+            None,
+            None,
+        )
+    }
+
+    // ============================================================================
+    // Public Functions - Complete Remittance Flow
+    // ============================================================================
+
+    /// Store the caller's flow profile
+    ///
+    /// A profile holds everything `execute_remittance_flow` needs besides the
+    /// amount: the savings goal, the bills and policies to pay on every flow,
+    /// and which family wallet and split contracts govern the caller. The
+    /// savings, bills and insurance contracts still come from the registry.
+    /// Setting a profile again replaces it.
+    ///
+    /// # Arguments
+    /// * `user` - Profile owner (must authorize)
+    /// * `goal_id` - Savings goal credited with the savings share
+    /// * `bill_ids` - Bills paid on each flow (at most MAX_FLOW_TARGETS)
+    /// * `policy_ids` - Policies whose premium is paid on each flow (at most MAX_FLOW_TARGETS)
+    /// * `wallet` - Family Wallet contract used for permission checks
+    /// * `split` - Remittance Split contract used to calculate allocations
+    ///
+    /// # Returns
+    /// Ok(()) on success, Err(OrchestratorError::TooManyTargets) if a list is too long
+    pub fn set_flow_profile(
+        env: Env,
+        user: Address,
+        goal_id: u32,
+        bill_ids: Vec<u32>,
+        policy_ids: Vec<u32>,
+        wallet: Address,
+        split: Address,
+    ) -> Result<(), OrchestratorError> {
+        user.require_auth();
+
+        if bill_ids.len() > MAX_FLOW_TARGETS || policy_ids.len() > MAX_FLOW_TARGETS {
+            return Err(OrchestratorError::TooManyTargets);
+        }
+
+        let profile = FlowProfile {
+            goals: Self::single_target(&env, goal_id),
+            bills: Self::targets_from_ids(&env, &bill_ids),
+            policy_ids,
+            family_wallet: wallet,
+            remittance_split: split,
+        };
+        Self::save_flow_profile(&env, &user, &profile);
+
+        env.events().publish((symbol_short!("profile"),), user);
+
+        Ok(())
+    }
+
+    /// Spread the savings and bills allocations over weighted targets
+    ///
+    /// Replaces the goals and bills of the caller's existing profile. Each
+    /// category's allocation is divided in proportion to the target weights,
+    /// with any rounding remainder going to the last target. An empty goal list
+    /// leaves the savings share undeposited.
+    ///
+    /// # Arguments
+    /// * `user` - Profile owner (must authorize)
+    /// * `goals` - Weighted savings goals (at most MAX_FLOW_TARGETS)
+    /// * `bills` - Weighted bills (at most MAX_FLOW_TARGETS)
+    ///
+    /// # Returns
+    /// Ok(()) on success
+    /// Err(OrchestratorError::ProfileNotFound) if the user has no profile
+    /// Err(OrchestratorError::TooManyTargets) if a list is too long
+    /// Err(OrchestratorError::InvalidTargetWeight) if any weight is zero
+    pub fn set_flow_targets(
+        env: Env,
+        user: Address,
+        goals: Vec<FlowTarget>,
+        bills: Vec<FlowTarget>,
+    ) -> Result<(), OrchestratorError> {
+        user.require_auth();
+
+        let mut profile = Self::get_flow_profile(env.clone(), user.clone())
+            .ok_or(OrchestratorError::ProfileNotFound)?;
+
+        if goals.len() > MAX_FLOW_TARGETS || bills.len() > MAX_FLOW_TARGETS {
+            return Err(OrchestratorError::TooManyTargets);
+        }
+        if goals.iter().chain(bills.iter()).any(|t| t.weight == 0) {
+            return Err(OrchestratorError::InvalidTargetWeight);
+        }
+
+        profile.goals = goals;
+        profile.bills = bills;
+        Self::save_flow_profile(&env, &user, &profile);
+
+        env.events().publish((symbol_short!("profile"),), user);
+
+        Ok(())
+    }
+
+    /// Get a user's flow profile, if one is stored
+    pub fn get_flow_profile(env: Env, user: Address) -> Option<FlowProfile> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PROFILE"), user))
+    }
+
+    /// Delete the caller's flow profile
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ProfileNotFound) if no profile is stored
+    pub fn remove_flow_profile(env: Env, user: Address) -> Result<(), OrchestratorError> {
+        user.require_auth();
+
+        let key = (symbol_short!("PROFILE"), user.clone());
+        if !env.storage().persistent().has(&key) {
+            return Err(OrchestratorError::ProfileNotFound);
+        }
+        env.storage().persistent().remove(&key);
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SETTLE"), user.clone()));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SPEND_RT"), user));
+
+        Ok(())
+    }
+
+    /// Settle the caller's profile flows in tokens
+    ///
+    /// With a settlement stored, `execute_remittance_flow` transfers the
+    /// caller's tokens to the configured accounts after the downstream calls:
+    /// each category's delivered share goes to its account, and the spending
+    /// share (plus anything rerouted in best-effort mode) to the spending
+    /// account. Accounts equal to the caller are skipped. The single signature
+    /// authorizing the flow also authorizes these transfers.
+    ///
+    /// # Arguments
+    /// * `user` - Profile owner (must authorize)
+    /// * `settlement` - Token and destination accounts
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::ProfileNotFound) if the user has no profile
+    pub fn set_flow_settlement(
+        env: Env,
+        user: Address,
+        settlement: FlowSettlement,
+    ) -> Result<(), OrchestratorError> {
+        user.require_auth();
+
+        if Self::get_flow_profile(env.clone(), user.clone()).is_none() {
+            return Err(OrchestratorError::ProfileNotFound);
+        }
+
+        let key = (symbol_short!("SETTLE"), user.clone());
+        env.storage().persistent().set(&key, &settlement);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        env.events().publish((symbol_short!("settle"),), user);
+
+        Ok(())
+    }
+
+    /// Stop moving tokens on the caller's profile flows
+    ///
+    /// Also removes the spending route, which has no effect without a token.
+    pub fn clear_flow_settlement(env: Env, user: Address) {
+        user.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SETTLE"), user.clone()));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SPEND_RT"), user));
+    }
+
+    /// Get a user's token settlement, if one is stored
+    pub fn get_flow_settlement(env: Env, user: Address) -> Option<FlowSettlement> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SETTLE"), user))
+    }
+
+    /// Choose where the spending share of the caller's profile flows goes
+    ///
+    /// Without a route the spending share is sent to the settlement's
+    /// `spending` account. The route is only applied while a settlement is
+    /// stored, since it needs the settlement token.
+    ///
+    /// # Arguments
+    /// * `user` - Profile owner (must authorize)
+    /// * `route` - Sender, a wallet address, or a family wallet envelope
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::SettlementNotFound) if the user has no settlement
+    pub fn set_spending_route(
+        env: Env,
+        user: Address,
+        route: SpendingRoute,
+    ) -> Result<(), OrchestratorError> {
+        user.require_auth();
+
+        if Self::get_flow_settlement(env.clone(), user.clone()).is_none() {
+            return Err(OrchestratorError::SettlementNotFound);
+        }
+
+        let key = (symbol_short!("SPEND_RT"), user.clone());
+        env.storage().persistent().set(&key, &route);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        env.events()
+            .publish((symbol_short!("spend_rt"),), (user, route));
+
+        Ok(())
+    }
+
+    /// Get a user's spending route, if one is stored
+    pub fn get_spending_route(env: Env, user: Address) -> Option<SpendingRoute> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SPEND_RT"), user))
+    }
+
+    /// Total spending credited to an envelope of a family wallet
+    pub fn get_envelope_balance(env: Env, family_wallet: Address, envelope: Symbol) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ENVELOPE"), family_wallet, envelope))
+            .unwrap_or(0)
+    }
+
+    /// Execute a complete remittance flow from the caller's stored profile
+    ///
+    /// Resolves the goal, bills, policies, family wallet and split contract from
+    /// the profile set with `set_flow_profile`, and the savings, bills and
+    /// insurance contracts from the registry. Every bill and policy in the
+    /// profile is paid. If the caller stored a `FlowSettlement`, tokens are
+    /// transferred to its accounts as the last step.
+    ///
+    /// # Arguments
+    /// * `caller` - Address initiating the operation (must authorize)
+    /// * `total_amount` - Total remittance amount to split
+    /// * `mode` - Atomic to revert on any downstream failure, BestEffort to
+    ///   record failures and route their shares to spending
+    /// * `idempotency_key` - Optional client-chosen key; calling again with the
+    ///   same key returns the first flow's result without executing anything
+    ///
+    /// # Returns
+    /// Ok(RemittanceFlowResult) with execution details if successful
+    /// Err(OrchestratorError::ProfileNotFound) if the caller has no profile
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    /// Err(OrchestratorError::InvalidContractAddress) if the profile's wallet or
+    /// split contract is not trusted
+    /// Err(OrchestratorError::IdempotencyKeyReused) if the key belongs to a flow
+    /// with a different amount
+    pub fn execute_remittance_flow(
+        env: Env,
+        caller: Address,
+        total_amount: i128,
+        mode: ExecutionMode,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<RemittanceFlowResult, OrchestratorError> {
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::FLOW)?;
+
+        // A retry of a flow that already completed gets the stored result
+        if let Some(key) = &idempotency_key {
+            let index: Option<u32> = env.storage().persistent().get(&(
+                symbol_short!("IDEM"),
+                caller.clone(),
+                key.clone(),
+            ));
+            if let Some(previous) = index.and_then(|i| {
+                env.storage().persistent().get::<_, RemittanceFlowResult>(&(
+                    symbol_short!("FLOW"),
+                    caller.clone(),
+                    i,
+                ))
+            }) {
+                if previous.total_amount != total_amount {
+                    return Err(OrchestratorError::IdempotencyKeyReused);
+                }
+                return Ok(previous);
+            }
+        }
+
+        Self::run_profile_flow(&env, &caller, total_amount, mode, idempotency_key)
+    }
+
+    /// Run many users' profile flows in one transaction
+    ///
+    /// Meant for operators paying out to many families at once. Every item
+    /// runs its user's profile in best-effort mode, so a failing goal, bill
+    /// or policy only reroutes that share. An item that fails before any
+    /// downstream contract is touched (invalid amount, missing profile,
+    /// permission, split, pause or allowlist checks) is skipped and reported
+    /// with its error code. A failed token settlement cannot be undone per
+    /// item and reverts the whole batch.
+    ///
+    /// # Arguments
+    /// * `operator` - Address submitting the batch (must authorize)
+    /// * `items` - Users and amounts, at most MAX_BATCH_FLOWS; each user must
+    ///   authorize as well
+    ///
+    /// # Returns
+    /// Ok(Vec<BatchItemResult>) with one entry per item, in order
+    /// Err(OrchestratorError::BatchTooLarge) if items exceeds MAX_BATCH_FLOWS
+    /// Err(OrchestratorError::TokenTransferFailed) if an item's settlement fails
+    pub fn execute_batch_flows(
+        env: Env,
+        operator: Address,
+        items: Vec<BatchFlowItem>,
+    ) -> Result<Vec<BatchItemResult>, OrchestratorError> {
+        operator.require_auth();
+        Self::require_access(&env, &operator, pause_functions::BATCH)?;
+        if items.len() > MAX_BATCH_FLOWS {
+            return Err(OrchestratorError::BatchTooLarge);
+        }
+
+        let mut results = Vec::new(&env);
+        let mut summary = BatchFlowSummary {
+            operator: operator.clone(),
+            items: items.len(),
+            succeeded: 0,
+            partial: 0,
+            skipped: 0,
+            total_amount: 0,
+            timestamp: env.ledger().timestamp(),
+        };
+
+        for item in items.iter() {
+            item.user.require_auth();
+            let outcome =
+                Self::require_access(&env, &item.user, pause_functions::FLOW).and_then(|_| {
+                    Self::run_profile_flow(
+                        &env,
+                        &item.user,
+                        item.amount,
+                        ExecutionMode::BestEffort,
+                        None,
+                    )
+                });
+
+            let result = match outcome {
+                Ok(flow) => {
+                    let error_code = Self::first_failure(&flow);
+                    if error_code.is_none() {
+                        summary.succeeded += 1;
+                    } else {
+                        summary.partial += 1;
+                    }
+                    summary.total_amount += item.amount;
+                    BatchItemResult {
+                        user: item.user.clone(),
+                        amount: item.amount,
+                        executed: true,
+                        error_code,
+                        flow_index: Some(Self::flow_count(&env, &item.user) - 1),
+                    }
+                }
+                Err(OrchestratorError::TokenTransferFailed) => {
+                    return Err(OrchestratorError::TokenTransferFailed)
+                }
+                Err(e) => {
+                    summary.skipped += 1;
+                    BatchItemResult {
+                        user: item.user.clone(),
+                        amount: item.amount,
+                        executed: false,
+                        error_code: Some(e as u32),
+                        flow_index: None,
+                    }
+                }
+            };
+            results.push_back(result);
+        }
+
+        env.events().publish((symbol_short!("batch"),), summary);
+
+        Ok(results)
+    }
+
+    /// Run a flow from the caller's stored profile and settlement
+    fn run_profile_flow(
+        env: &Env,
+        caller: &Address,
+        total_amount: i128,
+        mode: ExecutionMode,
+        idempotency_key: Option<BytesN<32>>,
+    ) -> Result<RemittanceFlowResult, OrchestratorError> {
+        let profile = Self::get_flow_profile(env.clone(), caller.clone())
+            .ok_or(OrchestratorError::ProfileNotFound)?;
+        Self::require_trusted(env, &[&profile.family_wallet, &profile.remittance_split])?;
+        let contracts = ContractRegistry {
+            family_wallet: profile.family_wallet,
+            remittance_split: profile.remittance_split,
+            ..Self::load_contracts(env)?
+        };
+
+        Self::run_remittance_flow(
+            env,
+            caller,
+            total_amount,
+            &contracts,
+            &profile.goals,
+            &profile.bills,
+            &Self::targets_from_ids(env, &profile.policy_ids),
+            mode,
+            Self::get_flow_settlement(env.clone(), caller.clone()),
+            idempotency_key,
+        )
+    }
+
+    /// Plan the caller's profile flow without executing it
+    ///
+    /// Runs the family wallet check and the split calculation, divides each
+    /// category over the profile's targets, and reads every goal, bill and
+    /// policy to predict failures such as an already-paid bill or an inactive
+    /// policy. Nothing is written and no events are emitted.
+    ///
+    /// # Arguments
+    /// * `caller` - Address whose profile to simulate
+    /// * `total_amount` - Total remittance amount to split
+    ///
+    /// # Returns
+    /// Ok(RemittanceFlowPlan) with planned amounts and predicted outcomes
+    /// Err(OrchestratorError::InvalidAmount) if total_amount is not positive
+    /// Err(OrchestratorError::ProfileNotFound) if the caller has no profile
+    /// Err(OrchestratorError::ContractsNotConfigured) if no registry is set
+    pub fn simulate_remittance_flow(
+        env: Env,
+        caller: Address,
+        total_amount: i128,
+    ) -> Result<RemittanceFlowPlan, OrchestratorError> {
+        let profile = Self::get_flow_profile(env.clone(), caller.clone())
+            .ok_or(OrchestratorError::ProfileNotFound)?;
+        let contracts = Self::load_contracts(&env)?;
+
+        let (fee_amount, _) = Self::flow_fee(&env, total_amount);
+        let allocations = Self::extract_allocations(
+            &env,
+            &profile.remittance_split,
+            &caller,
+            total_amount - fee_amount,
         )?;
+        let savings_amount = allocations.get(1).unwrap_or(0);
+        let bills_amount = allocations.get(2).unwrap_or(0);
+        let insurance_amount = allocations.get(3).unwrap_or(0);
 
-        // Step 2: Check spending limit
-        Self::check_spending_limit(&env, &family_wallet_addr, &caller, amount).map_err(|e| {
-            Self::emit_error_event(
-                &env,
-                &caller,
-                symbol_short!("spend_lm"),
-                e as u32,
-                timestamp,
-            );
-            e
-        })?;
+        let permitted = matches!(
+            FamilyWalletClient::new(&env, &profile.family_wallet)
+                .try_check_spending_limit(&caller, &total_amount),
+            Ok(Ok(true))
+        ) && Self::require_compliant(&env, &caller, total_amount).is_ok();
 
-        // Step 3: Pay insurance premium
-        Self::pay_insurance_premium(&env, &insurance_addr, &caller, policy_id).map_err(|e| {
-            Self::emit_error_event(
+        let savings_client = SavingsGoalsClient::new(&env, &contracts.savings);
+        let savings_targets =
+            Self::plan_targets(&env, &caller, savings_amount, &profile.goals, |id| {
+                match savings_client.try_get_goal(&id) {
+                    Ok(Ok(record)) => Some(record),
+                    _ => None,
+                }
+            });
+
+        let bills_client = BillPaymentsClient::new(&env, &contracts.bills);
+        let bill_targets =
+            Self::plan_targets(
                 &env,
                 &caller,
-                symbol_short!("insuranc"),
-                e as u32,
-                timestamp,
+                bills_amount,
+                &profile.bills,
+                |id| match bills_client.try_get_bill(&id) {
+                    Ok(Ok(record)) => Some(record),
+                    _ => None,
+                },
             );
-            e
-        })?;
 
-        // Emit success event
-        let allocations = Vec::from_array(&env, [0, 0, 0, amount]);
-        Self::emit_success_event(&env, &caller, amount, &allocations, timestamp);
+        let insurance_client = InsuranceClient::new(&env, &contracts.insurance);
+        let policy_targets = Self::plan_targets(
+            &env,
+            &caller,
+            insurance_amount,
+            &Self::targets_from_ids(&env, &profile.policy_ids),
+            |id| match insurance_client.try_get_policy(&id) {
+                Ok(Ok(record)) => Some(record),
+                _ => None,
+            },
+        );
+
+        let predicted_failures = savings_targets
+            .iter()
+            .chain(bill_targets.iter())
+            .chain(policy_targets.iter())
+            .filter(|t| !matches!(t.outcome, PlannedOutcome::Ready | PlannedOutcome::Unknown))
+            .count() as u32;
+
+        Ok(RemittanceFlowPlan {
+            total_amount,
+            fee_amount,
+            spending_amount: allocations.get(0).unwrap_or(0),
+            savings_amount,
+            bills_amount,
+            insurance_amount,
+            permitted,
+            savings_targets,
+            bill_targets,
+            policy_targets,
+            predicted_failures,
+        })
+    }
+
+    /// Weighted amounts and predicted outcomes for one category's targets
+    ///
+    /// `lookup` returns `None` when the downstream read itself failed, and
+    /// `Some(None)` when the target does not exist.
+    fn plan_targets(
+        env: &Env,
+        caller: &Address,
+        amount: i128,
+        targets: &Vec<FlowTarget>,
+        lookup: impl Fn(u32) -> Option<Option<Map<Symbol, Val>>>,
+    ) -> Vec<PlannedTarget> {
+        let mut planned = Vec::new(env);
+        let shares = Self::weighted_shares(env, amount, targets);
+        for (target, share) in targets.iter().zip(shares.iter()) {
+            let outcome = match lookup(target.id) {
+                None => PlannedOutcome::Unknown,
+                Some(None) => PlannedOutcome::NotFound,
+                Some(Some(record)) => Self::predict_outcome(env, caller, &record),
+            };
+            planned.push_back(PlannedTarget {
+                id: target.id,
+                amount: share,
+                outcome,
+            });
+        }
+        planned
+    }
+
+    /// Predict how a downstream call will go from the target's stored record
+    ///
+    /// Records are read as field maps rather than mirrored structs, so only
+    /// the fields checked here (`owner`, `paid`, `active`) need to match the
+    /// downstream contracts.
+    fn predict_outcome(env: &Env, caller: &Address, record: &Map<Symbol, Val>) -> PlannedOutcome {
+        let field = |name: &str| record.get(Symbol::new(env, name));
 
-        Ok(())
+        if let Some(owner) = field("owner").and_then(|v| Address::try_from_val(env, &v).ok()) {
+            if owner != *caller {
+                return PlannedOutcome::NotOwner;
+            }
+        }
+        if field("paid").and_then(|v| bool::try_from_val(env, &v).ok()) == Some(true) {
+            return PlannedOutcome::AlreadyPaid;
+        }
+        if field("active").and_then(|v| bool::try_from_val(env, &v).ok()) == Some(false) {
+            return PlannedOutcome::Inactive;
+        }
+        PlannedOutcome::Ready
     }
 
-    // ============================================================================
-    // Public Functions - Complete Remittance Flow
-    // ============================================================================
-
     /// Execute a complete remittance flow with automated allocation
     ///
-    /// This is the main orchestrator function that coordinates a full remittance
-    /// split across all downstream contracts (savings, bills, insurance) with
-    /// family wallet permission enforcement.
+    /// Coordinates a full remittance split across all downstream contracts
+    /// (savings, bills, insurance) with family wallet permission enforcement,
+    /// taking every contract address and target ID explicitly.
     ///
     /// # Arguments
     /// * `env` - The contract environment
@@ -897,8 +3402,8 @@ impl Orchestrator {
     /// - Total: ~22,000 gas for full flow
     ///
     /// # Atomicity Guarantee
-    /// All operations execute atomically via Soroban's panic/revert mechanism.
-    /// If any step fails, all prior state changes are automatically reverted.
+    /// Runs in atomic mode: a failing downstream call returns its error, and
+    /// returning an error reverts every state change made by the invocation.
     ///
     /// # Execution Flow
     /// 1. Require caller authorization
@@ -911,7 +3416,8 @@ impl Orchestrator {
     /// 8. Pay insurance premium
     /// 9. Build and return result
     /// 10. On error, emit error event and return error
-    pub fn execute_remittance_flow(
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_remittance_flow_direct(
         env: Env,
         caller: Address,
         total_amount: i128,
@@ -926,125 +3432,322 @@ impl Orchestrator {
     ) -> Result<RemittanceFlowResult, OrchestratorError> {
         // Require caller authorization
         caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::FLOW)?;
+        Self::require_trusted(
+            &env,
+            &[
+                &family_wallet_addr,
+                &remittance_split_addr,
+                &savings_addr,
+                &bills_addr,
+                &insurance_addr,
+            ],
+        )?;
 
-        let timestamp = env.ledger().timestamp();
+        let contracts = ContractRegistry {
+            family_wallet: family_wallet_addr,
+            remittance_split: remittance_split_addr,
+            savings: savings_addr,
+            bills: bills_addr,
+            insurance: insurance_addr,
+        };
 
-        // Step 1: Validate amount
-        if total_amount <= 0 {
-            Self::emit_error_event(
-                &env,
-                &caller,
-                symbol_short!("validate"),
-                OrchestratorError::InvalidAmount as u32,
-                timestamp,
-            );
-            return Err(OrchestratorError::InvalidAmount);
+        Self::run_remittance_flow(
+            &env,
+            &caller,
+            total_amount,
+            &contracts,
+            &Self::single_target(&env, goal_id),
+            &Self::single_target(&env, bill_id),
+            &Self::single_target(&env, policy_id),
+            ExecutionMode::Atomic,
+            None,
+            None,
+        )
+    }
+
+    // ============================================================================
+    // Public Functions - Family Flow Templates
+    // ============================================================================
+
+    /// Publish a flow template for the members of a family wallet
+    ///
+    /// The publisher must be an Owner or Admin of `spec.family_wallet`, and
+    /// the wallet and split contracts must be trusted (see
+    /// `add_trusted_contract`).
+    ///
+    /// # Arguments
+    /// * `publisher` - Owner or admin of the family wallet (must authorize)
+    /// * `spec` - Targets and execution mode
+    ///
+    /// # Returns
+    /// Ok(template_id) of the new template, at version 1
+    /// Err(OrchestratorError::Unauthorized) if the publisher is not an owner or admin
+    /// Err(OrchestratorError::TooManyTargets) if a list exceeds MAX_FLOW_TARGETS
+    /// Err(OrchestratorError::InvalidTargetWeight) if a target weight is zero
+    pub fn publish_flow_template(
+        env: Env,
+        publisher: Address,
+        spec: FlowTemplateSpec,
+    ) -> Result<u32, OrchestratorError> {
+        publisher.require_auth();
+        Self::validate_template(&env, &publisher, &spec)?;
+
+        Self::extend_instance_ttl(&env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TPL_NEXT"))
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TPL_NEXT"), &(id + 1));
+
+        Self::save_template_version(&env, id, 1, &publisher, spec);
+        Ok(id)
+    }
+
+    /// Publish a new version of a template
+    ///
+    /// Earlier versions stay readable with `get_flow_template_version`, but
+    /// only the latest can be executed.
+    ///
+    /// # Returns
+    /// Ok(version) of the new version
+    /// Err(OrchestratorError::TemplateNotFound) if the template does not exist
+    /// Err(OrchestratorError::Unauthorized) if the publisher is not an owner or
+    /// admin of the template's family wallet and of `spec.family_wallet`
+    pub fn update_flow_template(
+        env: Env,
+        publisher: Address,
+        template_id: u32,
+        spec: FlowTemplateSpec,
+    ) -> Result<u32, OrchestratorError> {
+        publisher.require_auth();
+        let current = Self::get_flow_template(env.clone(), template_id)
+            .ok_or(OrchestratorError::TemplateNotFound)?;
+        if current.spec.family_wallet != spec.family_wallet {
+            Self::require_family_role(&env, &current.spec.family_wallet, &publisher, true)?;
         }
+        Self::validate_template(&env, &publisher, &spec)?;
 
-        // Step 2: Check family wallet permission
-        Self::check_family_wallet_permission(&env, &family_wallet_addr, &caller, total_amount)
-            .map_err(|e| {
-                Self::emit_error_event(
-                    &env,
-                    &caller,
-                    symbol_short!("perm_chk"),
-                    e as u32,
-                    timestamp,
-                );
-                e
-            })?;
+        let version = current.version + 1;
+        Self::save_template_version(&env, template_id, version, &publisher, spec);
+        Ok(version)
+    }
 
-        // Step 3: Check spending limit
-        Self::check_spending_limit(&env, &family_wallet_addr, &caller, total_amount).map_err(
-            |e| {
-                Self::emit_error_event(
-                    &env,
-                    &caller,
-                    symbol_short!("spend_lm"),
-                    e as u32,
-                    timestamp,
-                );
-                e
-            },
-        )?;
+    /// Latest version of a template
+    pub fn get_flow_template(env: Env, template_id: u32) -> Option<FlowTemplate> {
+        let version: u32 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("TPL_VER"), template_id))?;
+        Self::get_flow_template_version(env, template_id, version)
+    }
 
-        // Step 4: Extract allocations from remittance split
-        let allocations = Self::extract_allocations(&env, &remittance_split_addr, total_amount)
-            .map_err(|e| {
-                Self::emit_error_event(&env, &caller, symbol_short!("split"), e as u32, timestamp);
-                e
-            })?;
+    /// A specific version of a template
+    pub fn get_flow_template_version(
+        env: Env,
+        template_id: u32,
+        version: u32,
+    ) -> Option<FlowTemplate> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("TPL"), template_id, version))
+    }
 
-        // Extract individual amounts
-        let spending_amount = allocations.get(0).unwrap_or(0);
-        let savings_amount = allocations.get(1).unwrap_or(0);
-        let bills_amount = allocations.get(2).unwrap_or(0);
-        let insurance_amount = allocations.get(3).unwrap_or(0);
+    /// Usage statistics of a template
+    pub fn get_template_usage(env: Env, template_id: u32) -> TemplateUsage {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("TPL_USE"), template_id))
+            .unwrap_or(TemplateUsage {
+                executions: 0,
+                total_amount: 0,
+                members: 0,
+                last_executed: 0,
+            })
+    }
 
-        // Step 5: Deposit to savings goal
-        let savings_success =
-            Self::deposit_to_savings(&env, &savings_addr, &caller, goal_id, savings_amount)
-                .map_err(|e| {
-                    Self::emit_error_event(
-                        &env,
-                        &caller,
-                        symbol_short!("savings"),
-                        e as u32,
-                        timestamp,
-                    );
-                    e
-                })
-                .is_ok();
-
-        // Step 6: Pay bill
-        let bills_success =
-            Self::execute_bill_payment_internal(&env, &bills_addr, &caller, bill_id)
-                .map_err(|e| {
-                    Self::emit_error_event(
-                        &env,
-                        &caller,
-                        symbol_short!("bills"),
-                        e as u32,
-                        timestamp,
-                    );
-                    e
-                })
-                .is_ok();
-
-        // Step 7: Pay insurance premium
-        let insurance_success =
-            Self::pay_insurance_premium(&env, &insurance_addr, &caller, policy_id)
-                .map_err(|e| {
-                    Self::emit_error_event(
-                        &env,
-                        &caller,
-                        symbol_short!("insuranc"),
-                        e as u32,
-                        timestamp,
-                    );
-                    e
-                })
-                .is_ok();
+    /// Run a family template with the caller's own amount
+    ///
+    /// The caller must be a member of the template's family wallet and name
+    /// the version they expect, so a template changed after the caller looked
+    /// at it is not executed unnoticed. The flow itself is the same as
+    /// `execute_remittance_flow` with the template's targets and mode; no
+    /// tokens are moved.
+    ///
+    /// # Arguments
+    /// * `caller` - Family member running the template (must authorize)
+    /// * `template_id` - Template to run
+    /// * `version` - Expected latest version of the template
+    /// * `total_amount` - Total remittance amount to split
+    ///
+    /// # Returns
+    /// Ok(RemittanceFlowResult) with execution details if successful
+    /// Err(OrchestratorError::TemplateNotFound) if the template does not exist
+    /// Err(OrchestratorError::TemplateVersionMismatch) if `version` is not the latest
+    /// Err(OrchestratorError::NotFamilyMember) if the caller is not in the family
+    pub fn execute_flow_template(
+        env: Env,
+        caller: Address,
+        template_id: u32,
+        version: u32,
+        total_amount: i128,
+    ) -> Result<RemittanceFlowResult, OrchestratorError> {
+        caller.require_auth();
+        Self::require_access(&env, &caller, pause_functions::FLOW)?;
 
-        // Build result
-        let result = RemittanceFlowResult {
-            total_amount,
-            spending_amount,
-            savings_amount,
-            bills_amount,
-            insurance_amount,
-            savings_success,
-            bills_success,
-            insurance_success,
-            timestamp,
+        let template = Self::get_flow_template(env.clone(), template_id)
+            .ok_or(OrchestratorError::TemplateNotFound)?;
+        if template.version != version {
+            return Err(OrchestratorError::TemplateVersionMismatch);
+        }
+        let spec = template.spec;
+        Self::require_family_role(&env, &spec.family_wallet, &caller, false)?;
+        Self::require_trusted(&env, &[&spec.family_wallet, &spec.remittance_split])?;
+        let contracts = ContractRegistry {
+            family_wallet: spec.family_wallet,
+            remittance_split: spec.remittance_split,
+            ..Self::load_contracts(&env)?
         };
 
-        // Emit success event
-        Self::emit_success_event(&env, &caller, total_amount, &allocations, timestamp);
+        let result = Self::run_remittance_flow(
+            &env,
+            &caller,
+            total_amount,
+            &contracts,
+            &spec.goals,
+            &spec.bills,
+            &Self::targets_from_ids(&env, &spec.policy_ids),
+            spec.mode,
+            None,
+            None,
+        )?;
+
+        Self::record_template_usage(&env, template_id, &caller, total_amount);
+        env.events().publish(
+            (symbol_short!("tpl_exec"),),
+            (template_id, version, caller, total_amount),
+        );
 
         Ok(result)
     }
 
+    fn validate_template(
+        env: &Env,
+        publisher: &Address,
+        spec: &FlowTemplateSpec,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_trusted(env, &[&spec.family_wallet, &spec.remittance_split])?;
+        Self::require_family_role(env, &spec.family_wallet, publisher, true)?;
+
+        if spec.goals.len() > MAX_FLOW_TARGETS
+            || spec.bills.len() > MAX_FLOW_TARGETS
+            || spec.policy_ids.len() > MAX_FLOW_TARGETS
+        {
+            return Err(OrchestratorError::TooManyTargets);
+        }
+        if spec
+            .goals
+            .iter()
+            .chain(spec.bills.iter())
+            .any(|t| t.weight == 0)
+        {
+            return Err(OrchestratorError::InvalidTargetWeight);
+        }
+        Ok(())
+    }
+
+    /// Check the address's membership in a family wallet
+    ///
+    /// The member record is read as a field map; `role` holds the family
+    /// wallet's `FamilyRole` discriminant (1 = Owner, 2 = Admin).
+    fn require_family_role(
+        env: &Env,
+        family_wallet: &Address,
+        member: &Address,
+        manager: bool,
+    ) -> Result<(), OrchestratorError> {
+        let record = match FamilyWalletClient::new(env, family_wallet).try_get_family_member(member)
+        {
+            Ok(Ok(Some(record))) => record,
+            _ if manager => return Err(OrchestratorError::Unauthorized),
+            _ => return Err(OrchestratorError::NotFamilyMember),
+        };
+        if manager {
+            let role = record
+                .get(symbol_short!("role"))
+                .and_then(|v| u32::try_from_val(env, &v).ok());
+            if !matches!(role, Some(1) | Some(2)) {
+                return Err(OrchestratorError::Unauthorized);
+            }
+        }
+        Ok(())
+    }
+
+    fn save_template_version(
+        env: &Env,
+        id: u32,
+        version: u32,
+        publisher: &Address,
+        spec: FlowTemplateSpec,
+    ) {
+        let template = FlowTemplate {
+            id,
+            version,
+            publisher: publisher.clone(),
+            published_at: env.ledger().timestamp(),
+            spec,
+        };
+        let key = (symbol_short!("TPL"), id, version);
+        env.storage().persistent().set(&key, &template);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        let ver_key = (symbol_short!("TPL_VER"), id);
+        env.storage().persistent().set(&ver_key, &version);
+        env.storage().persistent().extend_ttl(
+            &ver_key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        env.events().publish(
+            (symbol_short!("template"),),
+            (id, version, publisher.clone()),
+        );
+    }
+
+    fn record_template_usage(env: &Env, id: u32, caller: &Address, amount: i128) {
+        let mut usage = Self::get_template_usage(env.clone(), id);
+        usage.executions += 1;
+        usage.total_amount += amount;
+        usage.last_executed = env.ledger().timestamp();
+
+        // First run by this member
+        let member_key = (symbol_short!("TPL_MBR"), id, caller.clone());
+        if !env.storage().persistent().has(&member_key) {
+            usage.members += 1;
+        }
+        env.storage().persistent().set(&member_key, &true);
+        env.storage().persistent().extend_ttl(
+            &member_key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        let key = (symbol_short!("TPL_USE"), id);
+        env.storage().persistent().set(&key, &usage);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
     // ============================================================================
     // Helper Functions - Audit Logging and Statistics
     // ============================================================================
@@ -1061,16 +3764,7 @@ impl Orchestrator {
     fn update_execution_stats(env: &Env, success: bool, amount: i128) {
         Self::extend_instance_ttl(env);
 
-        let mut stats: ExecutionStats = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("STATS"))
-            .unwrap_or(ExecutionStats {
-                total_flows_executed: 0,
-                total_flows_failed: 0,
-                total_amount_processed: 0,
-                last_execution: 0,
-            });
+        let mut stats = Self::get_execution_stats(env.clone());
 
         if success {
             stats.total_flows_executed += 1;
@@ -1138,6 +3832,261 @@ impl Orchestrator {
         env.storage().instance().set(&symbol_short!("AUDIT"), &log);
     }
 
+    /// Record a completed operation in the execution stats and audit log
+    ///
+    /// Only operations that return Ok reach this point: an error return reverts
+    /// the invocation's storage writes, so a failed flow can only be recorded
+    /// here as a best-effort flow with failed targets (`error_code` is Some).
+    fn record_execution(
+        env: &Env,
+        caller: &Address,
+        operation: Symbol,
+        amount: i128,
+        error_code: Option<u32>,
+    ) {
+        let success = error_code.is_none();
+        Self::update_execution_stats(env, success, amount);
+        Self::append_audit_entry(env, caller, operation, amount, success, error_code);
+    }
+
+    /// Fold one completed flow into the per-step metrics and fee total
+    ///
+    /// A contract cannot read its own metered cost, so each step is measured
+    /// by the downstream calls it makes, which is what its cost scales with.
+    fn record_flow_stats(env: &Env, result: &RemittanceFlowResult) {
+        let mut stats = Self::get_execution_stats(env.clone());
+        stats.total_fees_collected += result.fee_amount;
+        Self::update_step(&mut stats.split_metrics, 1, 0, result.total_amount);
+        Self::update_step(
+            &mut stats.savings_metrics,
+            result
+                .savings_targets
+                .iter()
+                .filter(|t| t.amount != 0)
+                .count() as u64,
+            result.savings_targets.iter().filter(|t| !t.success).count() as u64,
+            result.savings_amount,
+        );
+        Self::update_step(
+            &mut stats.bills_metrics,
+            result.bill_targets.len() as u64,
+            result.bill_targets.iter().filter(|t| !t.success).count() as u64,
+            result.bills_amount,
+        );
+        Self::update_step(
+            &mut stats.insurance_metrics,
+            result.policy_targets.len() as u64,
+            result.policy_targets.iter().filter(|t| !t.success).count() as u64,
+            result.insurance_amount,
+        );
+        env.storage()
+            .instance()
+            .set(&symbol_short!("STATS"), &stats);
+    }
+
+    /// Exponential moving average over roughly the last METRICS_WINDOW runs
+    fn update_step(metrics: &mut StepMetrics, calls: u64, failures: u64, amount: i128) {
+        let calls_x100 = calls * 100;
+        if metrics.runs == 0 {
+            metrics.avg_calls_x100 = calls_x100;
+            metrics.avg_amount = amount;
+        } else {
+            let avg = metrics.avg_calls_x100 as i128;
+            metrics.avg_calls_x100 = (avg + (calls_x100 as i128 - avg) / METRICS_WINDOW) as u64;
+            metrics.avg_amount += (amount - metrics.avg_amount) / METRICS_WINDOW;
+        }
+        metrics.runs += 1;
+        metrics.calls += calls;
+        metrics.failures += failures;
+    }
+
+    fn empty_step_metrics(step: Symbol) -> StepMetrics {
+        StepMetrics {
+            step,
+            runs: 0,
+            calls: 0,
+            failures: 0,
+            avg_calls_x100: 0,
+            avg_amount: 0,
+        }
+    }
+
+    /// Log the downstream effects of a partial flow for later compensation
+    ///
+    /// Nothing is logged when no target succeeded, as there is nothing to
+    /// reverse.
+    fn record_compensation(
+        env: &Env,
+        caller: &Address,
+        contracts: &ContractRegistry,
+        result: &RemittanceFlowResult,
+        flow_index: u32,
+    ) {
+        let mut applied = Vec::new(env);
+        let mut failed = Vec::new(env);
+        let steps = [
+            (
+                symbol_short!("savings"),
+                &contracts.savings,
+                &result.savings_targets,
+            ),
+            (
+                symbol_short!("bills"),
+                &contracts.bills,
+                &result.bill_targets,
+            ),
+            (
+                symbol_short!("insuranc"),
+                &contracts.insurance,
+                &result.policy_targets,
+            ),
+        ];
+        for (step, contract, targets) in steps {
+            for target in targets.iter().filter(|t| t.amount != 0) {
+                let effect = DownstreamEffect {
+                    step: step.clone(),
+                    contract: contract.clone(),
+                    target_id: target.id,
+                    amount: target.amount,
+                };
+                if target.success {
+                    applied.push_back(effect);
+                } else {
+                    failed.push_back(effect);
+                }
+            }
+        }
+        if applied.is_empty() || failed.is_empty() {
+            return;
+        }
+
+        Self::extend_instance_ttl(env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("COMP_CNT"))
+            .unwrap_or(0);
+        let key = (symbol_short!("COMP"), id);
+        env.storage().persistent().set(
+            &key,
+            &CompensationEntry {
+                id,
+                caller: caller.clone(),
+                flow_index,
+                applied,
+                failed,
+                resolved: false,
+                timestamp: result.timestamp,
+            },
+        );
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+        env.storage()
+            .instance()
+            .set(&symbol_short!("COMP_CNT"), &(id + 1));
+
+        env.events()
+            .publish((symbol_short!("comp"),), (id, caller.clone()));
+    }
+
+    /// Error code of the first failed category in a best-effort flow
+    fn first_failure(result: &RemittanceFlowResult) -> Option<u32> {
+        if !result.savings_success {
+            Some(OrchestratorError::SavingsDepositFailed as u32)
+        } else if !result.bills_success {
+            Some(OrchestratorError::BillPaymentFailed as u32)
+        } else if !result.insurance_success {
+            Some(OrchestratorError::InsurancePaymentFailed as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Allocate the next flow ID
+    ///
+    /// IDs of flows that returned an error are reused, as their increment is
+    /// reverted along with the rest of the flow.
+    fn next_flow_id(env: &Env) -> u64 {
+        Self::extend_instance_ttl(env);
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("FLOW_SEQ"))
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&symbol_short!("FLOW_SEQ"), &id);
+        id
+    }
+
+    /// Number of flow results stored for a caller
+    fn flow_count(env: &Env, caller: &Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("FLOW_CNT"), caller.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Append a flow result to the caller's history, returning its index
+    fn store_flow_result(env: &Env, caller: &Address, result: &RemittanceFlowResult) -> u32 {
+        let count_key = (symbol_short!("FLOW_CNT"), caller.clone());
+        let count = Self::flow_count(env, caller);
+
+        let key = (symbol_short!("FLOW"), caller.clone(), count);
+        env.storage().persistent().set(&key, result);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        env.storage().persistent().set(&count_key, &(count + 1));
+        env.storage().persistent().extend_ttl(
+            &count_key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        count
+    }
+
+    /// Get a caller's remittance flow results, oldest first
+    ///
+    /// # Arguments
+    /// * `caller` - Address whose flows to return
+    /// * `offset` - Number of flows to skip
+    /// * `limit` - Maximum number of results (capped at MAX_HISTORY_LIMIT)
+    ///
+    /// # Returns
+    /// Vec of RemittanceFlowResult structs
+    pub fn get_flow_history(
+        env: Env,
+        caller: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<RemittanceFlowResult> {
+        let count = Self::flow_count(&env, &caller);
+        let mut out = Vec::new(&env);
+
+        let end = offset
+            .saturating_add(limit.min(MAX_HISTORY_LIMIT))
+            .min(count);
+        for i in offset..end {
+            if let Some(result) =
+                env.storage()
+                    .persistent()
+                    .get(&(symbol_short!("FLOW"), caller.clone(), i))
+            {
+                out.push_back(result);
+            }
+        }
+        out
+    }
+
     /// Get current execution statistics
     ///
     /// # Returns
@@ -1150,10 +4099,33 @@ impl Orchestrator {
                 total_flows_executed: 0,
                 total_flows_failed: 0,
                 total_amount_processed: 0,
+                total_fees_collected: 0,
                 last_execution: 0,
+                split_metrics: Self::empty_step_metrics(symbol_short!("split")),
+                savings_metrics: Self::empty_step_metrics(symbol_short!("savings")),
+                bills_metrics: Self::empty_step_metrics(symbol_short!("bills")),
+                insurance_metrics: Self::empty_step_metrics(symbol_short!("insuranc")),
             })
     }
 
+    /// Per-step metrics of completed remittance flows
+    ///
+    /// Returns the split, savings, bills and insurance steps in flow order.
+    /// Rising `avg_calls_x100` or `failures` on a step points at the
+    /// downstream contract to look at when tuning profile and batch sizes.
+    pub fn get_step_metrics(env: Env) -> Vec<StepMetrics> {
+        let stats = Self::get_execution_stats(env.clone());
+        Vec::from_array(
+            &env,
+            [
+                stats.split_metrics,
+                stats.savings_metrics,
+                stats.bills_metrics,
+                stats.insurance_metrics,
+            ],
+        )
+    }
+
     /// Get audit log entries
     ///
     /// # Arguments
@@ -1183,6 +4155,64 @@ impl Orchestrator {
         out
     }
 
+    /// Get compensation log entries, oldest first
+    ///
+    /// # Arguments
+    /// * `from_index` - Starting entry ID
+    /// * `limit` - Maximum number of entries to return (capped at MAX_AUDIT_ENTRIES)
+    ///
+    /// # Returns
+    /// Vec of CompensationEntry structs
+    pub fn get_compensation_log(env: Env, from_index: u32, limit: u32) -> Vec<CompensationEntry> {
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("COMP_CNT"))
+            .unwrap_or(0);
+        let mut out = Vec::new(&env);
+
+        let end = from_index
+            .saturating_add(MAX_AUDIT_ENTRIES.min(limit))
+            .min(count);
+        for i in from_index..end {
+            if let Some(entry) = env.storage().persistent().get(&(symbol_short!("COMP"), i)) {
+                out.push_back(entry);
+            }
+        }
+        out
+    }
+
+    /// Mark a compensation entry as handled
+    ///
+    /// # Returns
+    /// Err(OrchestratorError::Unauthorized) if caller is not the admin
+    /// Err(OrchestratorError::CompensationNotFound) if no entry has this ID
+    pub fn resolve_compensation(
+        env: Env,
+        caller: Address,
+        entry_id: u32,
+    ) -> Result<(), OrchestratorError> {
+        Self::require_admin(&env, &caller)?;
+
+        let key = (symbol_short!("COMP"), entry_id);
+        let mut entry: CompensationEntry = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(OrchestratorError::CompensationNotFound)?;
+        entry.resolved = true;
+        env.storage().persistent().set(&key, &entry);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        env.events()
+            .publish((symbol_short!("comp_done"),), (entry_id, caller));
+        Ok(())
+    }
+
     /// Extend the TTL of instance storage
     fn extend_instance_ttl(env: &Env) {
         env.storage()
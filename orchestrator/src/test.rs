@@ -1,12 +1,61 @@
 // Integration tests for the orchestrator contract
 
-use crate::{Orchestrator, OrchestratorClient, OrchestratorError};
-use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env, Vec};
+use crate::{
+    pause_functions, BatchFlowItem, ContractKind, ContractRegistry, DownstreamEffect,
+    ExecutionMode, FeeConfig, FlowSettlement, FlowStepEvent, FlowTarget, FlowTemplateSpec,
+    Orchestrator, OrchestratorClient, OrchestratorError, PlannedOutcome, SpendingRoute,
+    VersionRange, STEP_EVENT_VERSION,
+};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short,
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, Symbol, Vec,
+};
 
 // ============================================================================
 // Mock Contract Implementations
 // ============================================================================
 
+/// Family wallet member record returned to the member gate
+#[contracttype]
+#[derive(Clone)]
+pub struct MockMember {
+    pub address: Address,
+    pub role: u32,
+}
+
+/// Split category as reported by get_split_categories; only `name` is read
+#[contracttype]
+#[derive(Clone)]
+pub struct MockCategory {
+    pub name: Symbol,
+    pub percent: u32,
+}
+
+/// Subset of a savings goal's fields read by simulate_remittance_flow
+#[contracttype]
+#[derive(Clone)]
+pub struct MockGoal {
+    pub id: u32,
+    pub owner: Option<Address>,
+}
+
+/// Subset of a bill's fields read by simulate_remittance_flow
+#[contracttype]
+#[derive(Clone)]
+pub struct MockBill {
+    pub id: u32,
+    pub paid: bool,
+}
+
+/// Subset of a policy's fields read by simulate_remittance_flow
+#[contracttype]
+#[derive(Clone)]
+pub struct MockPolicy {
+    pub id: u32,
+    pub active: bool,
+}
+
 /// Mock Family Wallet contract for testing
 #[contract]
 pub struct MockFamilyWallet;
@@ -18,6 +67,26 @@ impl MockFamilyWallet {
     pub fn check_spending_limit(_env: Env, _caller: Address, amount: i128) -> bool {
         amount <= 10000
     }
+
+    /// Register a member for the orchestrator's member gate
+    pub fn add_member(env: Env, member: Address) {
+        Self::add_member_with_role(env, member, 3);
+    }
+
+    /// Register a member with a `FamilyRole` discriminant (1 = Owner, 2 = Admin)
+    pub fn add_member_with_role(env: Env, member: Address, role: u32) {
+        env.storage().instance().set(&member, &role);
+    }
+
+    pub fn get_family_member(env: Env, member: Address) -> Option<MockMember> {
+        env.storage()
+            .instance()
+            .get(&member)
+            .map(|role| MockMember {
+                address: member,
+                role,
+            })
+    }
 }
 
 /// Mock Remittance Split contract for testing
@@ -26,15 +95,34 @@ pub struct MockRemittanceSplit;
 
 #[contractimpl]
 impl MockRemittanceSplit {
-    /// Mock implementation of calculate_split
-    /// Returns [40%, 30%, 20%, 10%] split
-    pub fn calculate_split(env: Env, total_amount: i128) -> Vec<i128> {
-        let spending = (total_amount * 40) / 100;
-        let savings = (total_amount * 30) / 100;
-        let bills = (total_amount * 20) / 100;
-        let insurance = (total_amount * 10) / 100;
+    /// Mock implementation of calculate_owner_split
+    /// Defaults to a [40%, 30%, 20%, 10%] split over the standard categories
+    pub fn calculate_owner_split(env: Env, owner: Address, total_amount: i128) -> Vec<i128> {
+        let mut amounts = Vec::new(&env);
+        for category in Self::get_split_categories(env.clone(), owner).iter() {
+            amounts.push_back(total_amount * category.percent as i128 / 100);
+        }
+        amounts
+    }
+
+    pub fn get_split_categories(env: Env, owner: Address) -> Vec<MockCategory> {
+        env.storage().instance().get(&owner).unwrap_or_else(|| {
+            let mut categories = Vec::new(&env);
+            for (name, percent) in [
+                (symbol_short!("SPENDING"), 40),
+                (symbol_short!("SAVINGS"), 30),
+                (symbol_short!("BILLS"), 20),
+                (symbol_short!("INSURANCE"), 10),
+            ] {
+                categories.push_back(MockCategory { name, percent });
+            }
+            categories
+        })
+    }
 
-        Vec::from_array(&env, [spending, savings, bills, insurance])
+    /// Give `owner` their own categories, replacing the default split
+    pub fn set_owner_categories(env: Env, owner: Address, categories: Vec<MockCategory>) {
+        env.storage().instance().set(&owner, &categories);
     }
 }
 
@@ -46,11 +134,65 @@ pub struct MockSavingsGoals;
 impl MockSavingsGoals {
     /// Mock implementation of add_to_goal
     /// Panics if goal_id == 999 (simulating goal not found)
-    pub fn add_to_goal(_env: Env, _caller: Address, goal_id: u32, amount: i128) -> i128 {
+    pub fn add_to_goal(env: Env, caller: Address, goal_id: u32, amount: i128) -> i128 {
         if goal_id == 999 {
             panic!("Goal not found");
         }
-        amount
+        if let Some(token) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&symbol_short!("TOKEN"))
+        {
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                &env.current_contract_address(),
+                &amount,
+            );
+        }
+        let balance = Self::balance(env.clone(), goal_id) + amount;
+        env.storage().instance().set(&goal_id, &balance);
+        balance
+    }
+
+    pub fn get_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("VERSION"))
+            .unwrap_or(1)
+    }
+
+    /// Make every goal a token goal that pulls deposits from the caller
+    pub fn set_token(env: Env, token: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOKEN"), &token);
+    }
+
+    /// Simulate an upgrade of the savings contract
+    pub fn set_version(env: Env, version: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("VERSION"), &version);
+    }
+
+    /// Total deposited to a goal, used to observe rollbacks
+    pub fn balance(env: Env, goal_id: u32) -> i128 {
+        env.storage().instance().get(&goal_id).unwrap_or(0)
+    }
+
+    /// Goal 999 does not exist; goal 997 belongs to someone else
+    pub fn get_goal(env: Env, goal_id: u32) -> Option<MockGoal> {
+        match goal_id {
+            999 => None,
+            997 => Some(MockGoal {
+                id: goal_id,
+                owner: Some(env.current_contract_address()),
+            }),
+            _ => Some(MockGoal {
+                id: goal_id,
+                owner: None,
+            }),
+        }
     }
 }
 
@@ -62,10 +204,36 @@ pub struct MockBillPayments;
 impl MockBillPayments {
     /// Mock implementation of pay_bill
     /// Panics if bill_id == 999 (simulating bill not found or already paid)
-    pub fn pay_bill(_env: Env, _caller: Address, bill_id: u32) {
+    pub fn pay_bill(env: Env, caller: Address, bill_id: u32) {
         if bill_id == 999 {
             panic!("Bill not found or already paid");
         }
+        if let Some((token, amount)) = env
+            .storage()
+            .instance()
+            .get::<_, (Address, i128)>(&symbol_short!("TOKEN"))
+        {
+            token::Client::new(&env, &token).transfer(
+                &caller,
+                &env.current_contract_address(),
+                &amount,
+            );
+        }
+    }
+
+    /// Make every bill a token bill of `amount` that pulls from the caller
+    pub fn set_token_bill(env: Env, token: Address, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOKEN"), &(token, amount));
+    }
+
+    /// Bill 999 reads as already paid
+    pub fn get_bill(_env: Env, bill_id: u32) -> Option<MockBill> {
+        Some(MockBill {
+            id: bill_id,
+            paid: bill_id == 999,
+        })
     }
 }
 
@@ -80,6 +248,66 @@ impl MockInsurance {
     pub fn pay_premium(_env: Env, _caller: Address, policy_id: u32) -> bool {
         policy_id != 999
     }
+
+    /// Policy 999 reads as inactive
+    pub fn get_policy(_env: Env, policy_id: u32) -> Option<MockPolicy> {
+        Some(MockPolicy {
+            id: policy_id,
+            active: policy_id != 999,
+        })
+    }
+}
+
+/// Mock compliance registry that rejects addresses marked with `deny`
+#[contract]
+pub struct MockCompliance;
+
+#[contractimpl]
+impl MockCompliance {
+    pub fn deny(env: Env, subject: Address) {
+        env.storage().instance().set(&subject, &true);
+    }
+
+    pub fn is_compliant(env: Env, subject: Address, _amount: i128) -> bool {
+        !env.storage().instance().has(&subject)
+    }
+}
+
+/// Mock treasury that books revenue per source, refusing sources marked with `refuse`
+#[contract]
+pub struct MockTreasury;
+
+#[contractimpl]
+impl MockTreasury {
+    pub fn refuse(env: Env, source: Address) {
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("refused"), source), &true);
+    }
+
+    pub fn record_revenue(
+        env: Env,
+        caller: Address,
+        source: Address,
+        _token: Address,
+        amount: i128,
+    ) -> i128 {
+        caller.require_auth();
+        if env
+            .storage()
+            .instance()
+            .has(&(symbol_short!("refused"), source.clone()))
+        {
+            panic!("source refused");
+        }
+        let total = Self::revenue(env.clone(), source.clone()) + amount;
+        env.storage().instance().set(&source, &total);
+        amount
+    }
+
+    pub fn revenue(env: Env, source: Address) -> i128 {
+        env.storage().instance().get(&source).unwrap_or(0)
+    }
 }
 
 // ============================================================================
@@ -319,7 +547,7 @@ mod tests {
         let client = OrchestratorClient::new(&env, &orchestrator_id);
 
         // Execute complete remittance flow with amount within limit (10000)
-        let result = client.try_execute_remittance_flow(
+        let result = client.try_execute_remittance_flow_direct(
             &user,
             &10000,
             &family_wallet_id,
@@ -350,6 +578,81 @@ mod tests {
         assert!(flow_result.insurance_success);
     }
 
+    #[test]
+    fn test_flow_uses_callers_own_categories_by_name() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let split = MockRemittanceSplitClient::new(&env, &remittance_split_id);
+        // Another owner's split must not apply to `user`
+        split.set_owner_categories(
+            &Address::generate(&env),
+            &Vec::from_array(
+                &env,
+                [MockCategory {
+                    name: symbol_short!("SPENDING"),
+                    percent: 100,
+                }],
+            ),
+        );
+        // `user` lists categories out of the standard order, plus a custom one
+        split.set_owner_categories(
+            &user,
+            &Vec::from_array(
+                &env,
+                [
+                    MockCategory {
+                        name: symbol_short!("INSURANCE"),
+                        percent: 5,
+                    },
+                    MockCategory {
+                        name: symbol_short!("SAVINGS"),
+                        percent: 50,
+                    },
+                    MockCategory {
+                        name: symbol_short!("SCHOOL"),
+                        percent: 15,
+                    },
+                    MockCategory {
+                        name: symbol_short!("BILLS"),
+                        percent: 10,
+                    },
+                    MockCategory {
+                        name: symbol_short!("SPENDING"),
+                        percent: 20,
+                    },
+                ],
+            ),
+        );
+
+        let flow_result = client.execute_remittance_flow_direct(
+            &user,
+            &10000,
+            &family_wallet_id,
+            &remittance_split_id,
+            &savings_id,
+            &bills_id,
+            &insurance_id,
+            &1,
+            &1,
+            &1,
+        );
+        // The custom SCHOOL share counts as spending
+        assert_eq!(flow_result.spending_amount, 3500);
+        assert_eq!(flow_result.savings_amount, 5000);
+        assert_eq!(flow_result.bills_amount, 1000);
+        assert_eq!(flow_result.insurance_amount, 500);
+    }
+
     #[test]
     fn test_remittance_flow_bill_payment_failure_causes_rollback() {
         let (
@@ -367,7 +670,7 @@ mod tests {
 
         // Execute remittance flow with invalid bill_id (999)
         // The mock will panic, but the orchestrator catches it and returns an error
-        let result = client.try_execute_remittance_flow(
+        let result = client.try_execute_remittance_flow_direct(
             &user,
             &10000,
             &family_wallet_id,
@@ -401,7 +704,7 @@ mod tests {
 
         // Execute remittance flow with invalid goal_id (999)
         // The mock will panic, but the orchestrator catches it and returns an error
-        let result = client.try_execute_remittance_flow(
+        let result = client.try_execute_remittance_flow_direct(
             &user,
             &10000,
             &family_wallet_id,
@@ -434,7 +737,7 @@ mod tests {
         let client = OrchestratorClient::new(&env, &orchestrator_id);
 
         // Execute remittance flow with amount exceeding limit (15000 > 10000)
-        let result = client.try_execute_remittance_flow(
+        let result = client.try_execute_remittance_flow_direct(
             &user,
             &15000,
             &family_wallet_id,
@@ -473,7 +776,7 @@ mod tests {
         let client = OrchestratorClient::new(&env, &orchestrator_id);
 
         // Execute remittance flow with invalid amount (0)
-        let result = client.try_execute_remittance_flow(
+        let result = client.try_execute_remittance_flow_direct(
             &user,
             &0, // invalid amount
             &family_wallet_id,
@@ -520,4 +823,1578 @@ mod tests {
 
         assert_eq!(log.len(), 0);
     }
+
+    #[test]
+    fn test_registry_backed_entry_points() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+
+        // Nothing registered yet
+        assert_eq!(
+            client.try_remittance_flow(&user, &10000, &1, &1, &1),
+            Err(Ok(OrchestratorError::ContractsNotConfigured))
+        );
+
+        let contracts = ContractRegistry {
+            family_wallet: family_wallet_id,
+            remittance_split: remittance_split_id,
+            savings: savings_id,
+            bills: bills_id,
+            insurance: insurance_id,
+        };
+
+        // Only the admin may register contracts
+        client.set_admin(&admin, &admin);
+        assert_eq!(
+            client.try_set_contracts(&user, &contracts),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_set_admin(&user, &user),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+
+        client.set_contracts(&admin, &contracts);
+        assert_eq!(client.get_contracts(), Some(contracts));
+
+        let flow_result = client.remittance_flow(&user, &10000, &1, &1, &1);
+        assert_eq!(flow_result.savings_amount, 3000);
+        assert_eq!(flow_result.bills_amount, 2000);
+        assert_eq!(flow_result.insurance_amount, 1000);
+
+        client.savings_deposit(&user, &5000, &1);
+        client.bill_payment(&user, &3000, &1);
+        client.insurance_payment(&user, &2000, &1);
+        assert!(client.try_bill_payment(&user, &3000, &999).is_err());
+        assert_eq!(
+            client.try_savings_deposit(&user, &15000, &1),
+            Err(Ok(OrchestratorError::PermissionDenied))
+        );
+    }
+
+    #[test]
+    fn test_profile_flow_pays_every_bill_and_policy() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        assert_eq!(
+            client.try_execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None),
+            Err(Ok(OrchestratorError::ProfileNotFound))
+        );
+
+        let too_many = Vec::from_array(&env, [1u32; 11]);
+        assert_eq!(
+            client.try_set_flow_profile(
+                &user,
+                &1,
+                &too_many,
+                &Vec::new(&env),
+                &family_wallet_id,
+                &remittance_split_id,
+            ),
+            Err(Ok(OrchestratorError::TooManyTargets))
+        );
+
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 2, 3]),
+            &Vec::from_array(&env, [4u32, 5]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        let profile = client.get_flow_profile(&user).unwrap();
+        assert_eq!(profile.bills.len(), 3);
+        assert_eq!(profile.policy_ids.len(), 2);
+
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        assert_eq!(flow_result.total_amount, 10000);
+        assert_eq!(flow_result.savings_amount, 3000);
+        assert!(flow_result.bills_success);
+        assert!(flow_result.insurance_success);
+
+        // A bad bill anywhere in the profile fails the whole flow
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::new(&env),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        assert!(client
+            .try_execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None)
+            .is_err());
+
+        client.remove_flow_profile(&user);
+        assert_eq!(client.get_flow_profile(&user), None);
+        assert_eq!(
+            client.try_remove_flow_profile(&user),
+            Err(Ok(OrchestratorError::ProfileNotFound))
+        );
+    }
+
+    #[test]
+    fn test_weighted_targets_split_each_category() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        let goals = Vec::from_array(
+            &env,
+            [
+                FlowTarget { id: 1, weight: 1 },
+                FlowTarget { id: 2, weight: 2 },
+            ],
+        );
+        let bills = Vec::from_array(
+            &env,
+            [
+                FlowTarget { id: 1, weight: 1 },
+                FlowTarget { id: 2, weight: 1 },
+                FlowTarget { id: 3, weight: 1 },
+            ],
+        );
+        assert_eq!(
+            client.try_set_flow_targets(&user, &goals, &bills),
+            Err(Ok(OrchestratorError::ProfileNotFound))
+        );
+
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::new(&env),
+            &Vec::from_array(&env, [7u32, 8]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        let zero_weight = Vec::from_array(&env, [FlowTarget { id: 1, weight: 0 }]);
+        assert_eq!(
+            client.try_set_flow_targets(&user, &zero_weight, &bills),
+            Err(Ok(OrchestratorError::InvalidTargetWeight))
+        );
+        client.set_flow_targets(&user, &goals, &bills);
+
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+
+        // Savings 3000 split 1:2
+        assert_eq!(flow_result.savings_targets.len(), 2);
+        assert_eq!(flow_result.savings_targets.get(0).unwrap().amount, 1000);
+        assert_eq!(flow_result.savings_targets.get(1).unwrap().amount, 2000);
+
+        // Bills 2000 split evenly, remainder to the last bill
+        let mut amounts = Vec::new(&env);
+        for target in flow_result.bill_targets.iter() {
+            amounts.push_back(target.amount);
+        }
+        assert_eq!(amounts, Vec::from_array(&env, [666i128, 666, 668]));
+        assert!(flow_result.bill_targets.iter().all(|t| t.success));
+
+        // Insurance 1000 split evenly over the profile's policies
+        assert_eq!(flow_result.policy_targets.len(), 2);
+        assert_eq!(flow_result.policy_targets.get(0).unwrap().id, 7);
+        assert_eq!(flow_result.policy_targets.get(1).unwrap().amount, 500);
+    }
+
+    #[test]
+    fn test_execution_modes_atomic_reverts_best_effort_reroutes() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let savings = MockSavingsGoalsClient::new(&env, &savings_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::from_array(&env, [999u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+
+        // Atomic: the bad bill fails the flow and the savings deposit is undone
+        assert_eq!(
+            client.try_execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None),
+            Err(Ok(OrchestratorError::BillPaymentFailed))
+        );
+        assert_eq!(savings.balance(&1), 0);
+
+        // Best effort: failures are reported and their shares go to spending
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None);
+        assert_eq!(flow_result.mode, ExecutionMode::BestEffort);
+        assert!(flow_result.savings_success);
+        assert!(!flow_result.bills_success);
+        assert!(!flow_result.insurance_success);
+        assert!(flow_result.bill_targets.get(0).unwrap().success);
+        assert!(!flow_result.bill_targets.get(1).unwrap().success);
+        // Failed: half of the 2000 bills share and all 1000 insurance
+        assert_eq!(flow_result.rerouted_to_spending, 2000);
+        assert_eq!(flow_result.spending_amount, 6000);
+        assert_eq!(savings.balance(&1), 3000);
+    }
+
+    #[test]
+    fn test_flows_update_stats_audit_and_history() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+
+        client.execute_savings_deposit(&user, &500, &family_wallet_id, &savings_id, &1);
+        client.execute_remittance_flow_direct(
+            &user,
+            &10000,
+            &family_wallet_id,
+            &remittance_split_id,
+            &savings_id,
+            &bills_id,
+            &insurance_id,
+            &1,
+            &1,
+            &1,
+        );
+        // Atomic failure leaves no trace
+        assert!(client
+            .try_execute_bill_payment(&user, &100, &family_wallet_id, &bills_id, &999)
+            .is_err());
+
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [999u32]),
+            &Vec::new(&env),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        client.execute_remittance_flow(&user, &2000, &ExecutionMode::BestEffort, &None);
+
+        let stats = client.get_execution_stats();
+        assert_eq!(stats.total_flows_executed, 2);
+        assert_eq!(stats.total_flows_failed, 1);
+        assert_eq!(stats.total_amount_processed, 10500);
+
+        let log = client.get_audit_log(&0, &10);
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.get(0).unwrap().operation, symbol_short!("exec_save"));
+        assert_eq!(log.get(1).unwrap().operation, symbol_short!("exec_flow"));
+        let partial = log.get(2).unwrap();
+        assert!(!partial.success);
+        assert_eq!(
+            partial.error_code,
+            Some(OrchestratorError::BillPaymentFailed as u32)
+        );
+
+        let history = client.get_flow_history(&user, &0, &10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(0).unwrap().total_amount, 10000);
+        assert_eq!(history.get(1).unwrap().mode, ExecutionMode::BestEffort);
+        assert_eq!(client.get_flow_history(&user, &1, &10).len(), 1);
+        assert_eq!(client.get_flow_history(&user, &5, &10).len(), 0);
+        assert_eq!(
+            client
+                .get_flow_history(&Address::generate(&env), &0, &10)
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_settlement_moves_delivered_tokens() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&user, &15000);
+        let usdc = TokenClient::new(&env, &token.address());
+        let settlement = FlowSettlement {
+            token: token.address(),
+            spending: Address::generate(&env),
+            savings: Address::generate(&env),
+            bills: Address::generate(&env),
+            insurance: Address::generate(&env),
+        };
+
+        assert_eq!(
+            client.try_set_flow_settlement(&user, &settlement),
+            Err(Ok(OrchestratorError::ProfileNotFound))
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::from_array(&env, [1u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        client.set_flow_settlement(&user, &settlement);
+
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None);
+        assert!(flow_result.tokens_settled);
+        // The failed bill's 1000 goes to spending with the 4000 spending share
+        assert_eq!(usdc.balance(&settlement.spending), 5000);
+        assert_eq!(usdc.balance(&settlement.savings), 3000);
+        assert_eq!(usdc.balance(&settlement.bills), 1000);
+        assert_eq!(usdc.balance(&settlement.insurance), 1000);
+        assert_eq!(usdc.balance(&user), 5000);
+
+        // Not enough left for another 10000 flow
+        assert_eq!(
+            client.try_execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None),
+            Err(Ok(OrchestratorError::TokenTransferFailed))
+        );
+        assert_eq!(usdc.balance(&user), 5000);
+
+        client.clear_flow_settlement(&user);
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None);
+        assert!(!flow_result.tokens_settled);
+        assert_eq!(usdc.balance(&user), 5000);
+    }
+
+    #[test]
+    fn test_settlement_skips_shares_token_targets_pulled() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id.clone(),
+                bills: bills_id.clone(),
+                insurance: insurance_id,
+            },
+        );
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&user, &10000);
+        let usdc = TokenClient::new(&env, &token.address());
+        MockSavingsGoalsClient::new(&env, &savings_id).set_token(&token.address());
+        let bills = MockBillPaymentsClient::new(&env, &bills_id);
+        bills.set_token_bill(&token.address(), &1500);
+        let settlement = FlowSettlement {
+            token: token.address(),
+            spending: Address::generate(&env),
+            savings: Address::generate(&env),
+            bills: Address::generate(&env),
+            insurance: Address::generate(&env),
+        };
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32]),
+            &Vec::from_array(&env, [1u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        client.set_flow_settlement(&user, &settlement);
+
+        client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        // The 10000 leaves the user once: the goal takes its 3000 share and the
+        // bill 1500 of its 2000 share, so only the rest is settled
+        assert_eq!(usdc.balance(&user), 0);
+        assert_eq!(usdc.balance(&savings_id), 3000);
+        assert_eq!(usdc.balance(&settlement.savings), 0);
+        assert_eq!(usdc.balance(&bills_id), 1500);
+        assert_eq!(usdc.balance(&settlement.bills), 500);
+        assert_eq!(usdc.balance(&settlement.spending), 4000);
+        assert_eq!(usdc.balance(&settlement.insurance), 1000);
+
+        // A bill costing more than the bills share fails the flow
+        StellarAssetClient::new(&env, &token.address()).mint(&user, &20000);
+        bills.set_token_bill(&token.address(), &2500);
+        assert_eq!(
+            client.try_execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None),
+            Err(Ok(OrchestratorError::ShareExceeded))
+        );
+        assert_eq!(usdc.balance(&user), 20000);
+    }
+
+    #[test]
+    fn test_idempotency_key_replays_stored_result() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let savings = MockSavingsGoalsClient::new(&env, &savings_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::new(&env),
+            &Vec::new(&env),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+
+        let key = Some(BytesN::from_array(&env, &[7; 32]));
+        let first = client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &key);
+
+        env.ledger().with_mut(|l| l.timestamp += 60);
+        let retry = client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &key);
+        assert_eq!(retry, first);
+        assert_eq!(savings.balance(&1), 3000);
+        assert_eq!(client.get_flow_history(&user, &0, &10).len(), 1);
+
+        assert_eq!(
+            client.try_execute_remittance_flow(&user, &5000, &ExecutionMode::Atomic, &key),
+            Err(Ok(OrchestratorError::IdempotencyKeyReused))
+        );
+
+        // A new key, or no key, executes again
+        let other = Some(BytesN::from_array(&env, &[8; 32]));
+        client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &other);
+        client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        assert_eq!(savings.balance(&1), 9000);
+        assert_eq!(client.get_flow_history(&user, &0, &10).len(), 3);
+    }
+
+    #[test]
+    fn test_simulation_plans_without_executing() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let savings = MockSavingsGoalsClient::new(&env, &savings_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::from_array(&env, [1u32, 999]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        client.set_flow_targets(
+            &user,
+            &Vec::from_array(
+                &env,
+                [
+                    FlowTarget { id: 1, weight: 1 },
+                    FlowTarget { id: 997, weight: 1 },
+                    FlowTarget { id: 999, weight: 1 },
+                ],
+            ),
+            &Vec::from_array(
+                &env,
+                [
+                    FlowTarget { id: 1, weight: 3 },
+                    FlowTarget { id: 999, weight: 1 },
+                ],
+            ),
+        );
+
+        let plan = client.simulate_remittance_flow(&user, &9000);
+        assert!(plan.permitted);
+        assert_eq!(plan.savings_amount, 2700);
+        let goals = plan.savings_targets;
+        assert_eq!(goals.get(0).unwrap().outcome, PlannedOutcome::Ready);
+        assert_eq!(goals.get(1).unwrap().outcome, PlannedOutcome::NotOwner);
+        assert_eq!(goals.get(2).unwrap().outcome, PlannedOutcome::NotFound);
+        assert_eq!(plan.bill_targets.get(0).unwrap().amount, 1350);
+        assert_eq!(
+            plan.bill_targets.get(1).unwrap().outcome,
+            PlannedOutcome::AlreadyPaid
+        );
+        assert_eq!(
+            plan.policy_targets.get(1).unwrap().outcome,
+            PlannedOutcome::Inactive
+        );
+        assert_eq!(plan.predicted_failures, 4);
+
+        // Over the wallet limit is reported, not raised
+        assert!(!client.simulate_remittance_flow(&user, &20000).permitted);
+        assert_eq!(
+            client.try_simulate_remittance_flow(&user, &0),
+            Err(Ok(OrchestratorError::InvalidAmount))
+        );
+
+        // Nothing was executed or recorded
+        assert_eq!(savings.balance(&1), 0);
+        assert_eq!(client.get_flow_history(&user, &0, &10).len(), 0);
+        assert_eq!(client.get_execution_stats().total_flows_executed, 0);
+    }
+
+    #[test]
+    fn test_pause_and_member_gate() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id,
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        assert_eq!(
+            client.try_pause(&user),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        client.pause(&admin);
+        assert!(client.is_paused());
+        assert_eq!(
+            client.try_savings_deposit(&user, &100, &1),
+            Err(Ok(OrchestratorError::Paused))
+        );
+        client.unpause(&admin);
+
+        client.pause_function(&admin, &pause_functions::BILL);
+        assert!(client.is_function_paused(&pause_functions::BILL));
+        assert_eq!(
+            client.try_bill_payment(&user, &100, &1),
+            Err(Ok(OrchestratorError::FunctionPaused))
+        );
+        client.savings_deposit(&user, &100, &1);
+        client.unpause_function(&admin, &pause_functions::BILL);
+        client.bill_payment(&user, &100, &1);
+
+        // Member gate: only registered family members may run operations
+        assert_eq!(
+            client.try_set_member_gate(&user, &true),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        client.set_member_gate(&admin, &true);
+        assert_eq!(
+            client.try_remittance_flow(&user, &1000, &1, &1, &1),
+            Err(Ok(OrchestratorError::NotFamilyMember))
+        );
+        MockFamilyWalletClient::new(&env, &family_wallet_id).add_member(&user);
+        client.remittance_flow(&user, &1000, &1, &1, &1);
+
+        client.set_member_gate(&admin, &false);
+        assert!(!client.is_member_gate_enabled());
+    }
+
+    #[test]
+    fn test_trusted_contract_allowlist() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id.clone(),
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        let rogue_wallet = env.register_contract(None, MockFamilyWallet);
+
+        // Without an allowlist every address is accepted
+        assert!(client.is_trusted_contract(&rogue_wallet));
+        client.execute_savings_deposit(&user, &100, &rogue_wallet, &savings_id, &1);
+
+        assert_eq!(
+            client.try_add_trusted_contract(&user, &rogue_wallet),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        let other_split = env.register_contract(None, MockRemittanceSplit);
+        client.add_trusted_contract(&admin, &other_split);
+        assert_eq!(
+            client.get_trusted_contracts(),
+            Vec::from_array(&env, [other_split])
+        );
+
+        // Registered contracts stay trusted, unknown ones are rejected
+        assert!(client.is_trusted_contract(&family_wallet_id));
+        assert!(!client.is_trusted_contract(&rogue_wallet));
+        assert_eq!(
+            client.try_execute_savings_deposit(&user, &100, &rogue_wallet, &savings_id, &1),
+            Err(Ok(OrchestratorError::InvalidContractAddress))
+        );
+        client.execute_savings_deposit(&user, &100, &family_wallet_id, &savings_id, &1);
+
+        // The same applies to the wallet stored in a flow profile
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32]),
+            &Vec::new(&env),
+            &rogue_wallet,
+            &remittance_split_id,
+        );
+        assert_eq!(
+            client.try_execute_remittance_flow(&user, &1000, &ExecutionMode::Atomic, &None),
+            Err(Ok(OrchestratorError::InvalidContractAddress))
+        );
+        client.add_trusted_contract(&admin, &rogue_wallet);
+        client.execute_remittance_flow(&user, &1000, &ExecutionMode::Atomic, &None);
+
+        client.remove_trusted_contract(&admin, &rogue_wallet);
+        assert!(!client.is_trusted_contract(&rogue_wallet));
+    }
+
+    #[test]
+    fn test_spending_route() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&user, &30000);
+        let usdc = TokenClient::new(&env, &token.address());
+        let settlement = FlowSettlement {
+            token: token.address(),
+            spending: Address::generate(&env),
+            savings: Address::generate(&env),
+            bills: Address::generate(&env),
+            insurance: Address::generate(&env),
+        };
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32]),
+            &Vec::from_array(&env, [1u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+
+        assert_eq!(
+            client.try_set_spending_route(&user, &SpendingRoute::Sender),
+            Err(Ok(OrchestratorError::SettlementNotFound))
+        );
+        client.set_flow_settlement(&user, &settlement);
+
+        // Default: the settlement's spending account
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        assert_eq!(
+            flow_result.spending_route,
+            SpendingRoute::Wallet(settlement.spending.clone())
+        );
+        assert_eq!(usdc.balance(&settlement.spending), 4000);
+
+        // Sender: the 4000 spending share is not moved
+        client.set_spending_route(&user, &SpendingRoute::Sender);
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        assert_eq!(flow_result.spending_route, SpendingRoute::Sender);
+        assert_eq!(usdc.balance(&settlement.spending), 4000);
+        assert_eq!(usdc.balance(&user), 14000);
+
+        // Envelope: tokens go to the family wallet and the envelope is credited
+        let groceries = symbol_short!("groceries");
+        client.set_spending_route(&user, &SpendingRoute::Envelope(groceries.clone()));
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        assert_eq!(
+            flow_result.spending_route,
+            SpendingRoute::Envelope(groceries.clone())
+        );
+        assert_eq!(usdc.balance(&family_wallet_id), 4000);
+        assert_eq!(
+            client.get_envelope_balance(&family_wallet_id, &groceries),
+            4000
+        );
+        assert_eq!(usdc.balance(&user), 4000);
+
+        // Clearing the settlement drops the route as well
+        client.clear_flow_settlement(&user);
+        assert_eq!(client.get_spending_route(&user), None);
+        let flow_result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::Atomic, &None);
+        assert_eq!(flow_result.spending_route, SpendingRoute::Sender);
+    }
+
+    #[test]
+    fn test_step_metrics() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        assert_eq!(client.get_step_metrics().get(1).unwrap().runs, 0);
+
+        client.remittance_flow(&user, &10000, &1, &1, &1);
+        client.remittance_flow(&user, &10000, &1, &1, &1);
+        let metrics = client.get_step_metrics();
+        let split = metrics.get(0).unwrap();
+        assert_eq!(split.step, symbol_short!("split"));
+        assert_eq!((split.runs, split.calls), (2, 2));
+        assert_eq!(split.avg_amount, 10000);
+        let savings = metrics.get(1).unwrap();
+        assert_eq!((savings.runs, savings.calls, savings.failures), (2, 2, 0));
+        assert_eq!(savings.avg_amount, 3000);
+
+        // Two bills, one of them already paid
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::from_array(&env, [1u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+        client.execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None);
+        let bills = client.get_step_metrics().get(2).unwrap();
+        assert_eq!((bills.runs, bills.calls, bills.failures), (3, 4, 1));
+        assert_eq!(bills.avg_calls_x100, 110);
+
+        let stats = client.get_execution_stats();
+        assert_eq!(stats.bills_metrics, bills);
+    }
+
+    #[test]
+    fn test_family_flow_templates() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id.clone(),
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        let wallet = MockFamilyWalletClient::new(&env, &family_wallet_id);
+        let owner = Address::generate(&env);
+        wallet.add_member_with_role(&owner, &1);
+        wallet.add_member(&user);
+
+        let spec = FlowTemplateSpec {
+            family_wallet: family_wallet_id.clone(),
+            remittance_split: remittance_split_id,
+            goals: Vec::from_array(&env, [FlowTarget { id: 1, weight: 1 }]),
+            bills: Vec::from_array(&env, [FlowTarget { id: 1, weight: 1 }]),
+            policy_ids: Vec::from_array(&env, [1u32]),
+            mode: ExecutionMode::Atomic,
+        };
+
+        // Plain members cannot publish
+        assert_eq!(
+            client.try_publish_flow_template(&user, &spec),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        let id = client.publish_flow_template(&owner, &spec);
+        assert_eq!(client.get_flow_template(&id).unwrap().version, 1);
+
+        // Outsiders cannot run it; members run it with their own amount
+        let outsider = Address::generate(&env);
+        assert_eq!(
+            client.try_execute_flow_template(&outsider, &id, &1, &1000),
+            Err(Ok(OrchestratorError::NotFamilyMember))
+        );
+        let result = client.execute_flow_template(&user, &id, &1, &1000);
+        assert_eq!(result.savings_amount, 300);
+        client.execute_flow_template(&owner, &id, &1, &2000);
+
+        // A new version must be named explicitly
+        let mut spec_v2 = spec.clone();
+        spec_v2.goals = Vec::from_array(
+            &env,
+            [
+                FlowTarget { id: 1, weight: 1 },
+                FlowTarget { id: 2, weight: 1 },
+            ],
+        );
+        spec_v2.mode = ExecutionMode::BestEffort;
+        assert_eq!(client.update_flow_template(&owner, &id, &spec_v2), 2);
+        assert_eq!(
+            client.try_execute_flow_template(&user, &id, &1, &1000),
+            Err(Ok(OrchestratorError::TemplateVersionMismatch))
+        );
+        let result = client.execute_flow_template(&user, &id, &2, &1000);
+        assert_eq!(result.mode, ExecutionMode::BestEffort);
+        assert_eq!(result.savings_targets.len(), 2);
+        assert_eq!(
+            client.get_flow_template_version(&id, &1).unwrap().spec,
+            spec
+        );
+
+        let usage = client.get_template_usage(&id);
+        assert_eq!(usage.executions, 3);
+        assert_eq!(usage.total_amount, 4000);
+        assert_eq!(usage.members, 2);
+        assert_eq!(
+            client.try_execute_flow_template(&user, &99, &1, &1000),
+            Err(Ok(OrchestratorError::TemplateNotFound))
+        );
+    }
+
+    #[test]
+    fn test_batch_flows() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+        let operator = Address::generate(&env);
+        let paid_bill_user = Address::generate(&env);
+        let no_profile_user = Address::generate(&env);
+        for (member, bill) in [(&user, 1u32), (&paid_bill_user, 999)] {
+            client.set_flow_profile(
+                member,
+                &1,
+                &Vec::from_array(&env, [bill]),
+                &Vec::from_array(&env, [1u32]),
+                &family_wallet_id,
+                &remittance_split_id,
+            );
+        }
+
+        let items = Vec::from_array(
+            &env,
+            [
+                BatchFlowItem {
+                    user: user.clone(),
+                    amount: 1000,
+                },
+                BatchFlowItem {
+                    user: paid_bill_user.clone(),
+                    amount: 2000,
+                },
+                BatchFlowItem {
+                    user: no_profile_user.clone(),
+                    amount: 3000,
+                },
+            ],
+        );
+        let results = client.execute_batch_flows(&operator, &items);
+        assert_eq!(results.len(), 3);
+
+        let first = results.get(0).unwrap();
+        assert!(first.executed);
+        assert_eq!(first.error_code, None);
+        assert_eq!(first.flow_index, Some(0));
+
+        let second = results.get(1).unwrap();
+        assert!(second.executed);
+        assert_eq!(
+            second.error_code,
+            Some(OrchestratorError::BillPaymentFailed as u32)
+        );
+        assert_eq!(client.get_flow_history(&paid_bill_user, &0, &10).len(), 1);
+
+        let third = results.get(2).unwrap();
+        assert!(!third.executed);
+        assert_eq!(
+            third.error_code,
+            Some(OrchestratorError::ProfileNotFound as u32)
+        );
+        assert_eq!(third.flow_index, None);
+
+        let too_many = Vec::from_array(
+            &env,
+            core::array::from_fn::<_, 21, _>(|_| BatchFlowItem {
+                user: user.clone(),
+                amount: 100,
+            }),
+        );
+        assert_eq!(
+            client.try_execute_batch_flows(&operator, &too_many),
+            Err(Ok(OrchestratorError::BatchTooLarge))
+        );
+
+        client.pause_function(&admin, &pause_functions::BATCH);
+        assert_eq!(
+            client.try_execute_batch_flows(&operator, &items),
+            Err(Ok(OrchestratorError::FunctionPaused))
+        );
+    }
+
+    #[test]
+    fn test_compensation_log_for_partial_flows() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id.clone(),
+                bills: bills_id.clone(),
+                insurance: insurance_id,
+            },
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::from_array(&env, [1u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+
+        // A flow where everything succeeds needs no compensation
+        client.remittance_flow(&user, &10000, &1, &1, &1);
+        assert_eq!(client.get_compensation_log(&0, &10).len(), 0);
+
+        client.execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None);
+        let log = client.get_compensation_log(&0, &10);
+        assert_eq!(log.len(), 1);
+        let entry = log.get(0).unwrap();
+        assert_eq!(entry.flow_index, 1);
+        assert_eq!(entry.applied.len(), 3);
+        assert_eq!(
+            entry.applied.get(0).unwrap(),
+            DownstreamEffect {
+                step: symbol_short!("savings"),
+                contract: savings_id,
+                target_id: 1,
+                amount: 3000,
+            }
+        );
+        assert_eq!(
+            entry.failed,
+            Vec::from_array(
+                &env,
+                [DownstreamEffect {
+                    step: symbol_short!("bills"),
+                    contract: bills_id,
+                    target_id: 999,
+                    amount: 1000,
+                }]
+            )
+        );
+        assert!(!entry.resolved);
+
+        assert_eq!(
+            client.try_resolve_compensation(&user, &0),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_resolve_compensation(&admin, &5),
+            Err(Ok(OrchestratorError::CompensationNotFound))
+        );
+        client.resolve_compensation(&admin, &0);
+        assert!(
+            client
+                .get_compensation_log(&0, &10)
+                .get(0)
+                .unwrap()
+                .resolved
+        );
+    }
+
+    #[test]
+    fn test_version_handshake() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let savings = MockSavingsGoalsClient::new(&env, &savings_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id,
+                remittance_split: remittance_split_id,
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        assert_eq!(
+            client.try_set_supported_versions(
+                &admin,
+                &ContractKind::Savings,
+                &VersionRange { min: 2, max: 1 }
+            ),
+            Err(Ok(OrchestratorError::InvalidVersionRange))
+        );
+        client.set_supported_versions(
+            &admin,
+            &ContractKind::Savings,
+            &VersionRange { min: 1, max: 1 },
+        );
+        client.savings_deposit(&user, &100, &1);
+        client.check_contract_versions();
+
+        // An upgrade past the supported range fails before any call is made
+        savings.set_version(&2);
+        assert_eq!(
+            client.try_savings_deposit(&user, &100, &1),
+            Err(Ok(OrchestratorError::UnsupportedContractVersion))
+        );
+        assert_eq!(
+            client.try_remittance_flow(&user, &1000, &1, &1, &1),
+            Err(Ok(OrchestratorError::UnsupportedContractVersion))
+        );
+        assert_eq!(savings.balance(&1), 100);
+
+        client.set_supported_versions(
+            &admin,
+            &ContractKind::Savings,
+            &VersionRange { min: 1, max: 2 },
+        );
+        client.remittance_flow(&user, &1000, &1, &1, &1);
+
+        // A contract without get_version cannot pass the handshake
+        client.set_supported_versions(
+            &admin,
+            &ContractKind::Bills,
+            &VersionRange { min: 1, max: 1 },
+        );
+        assert_eq!(
+            client.try_check_contract_versions(),
+            Err(Ok(OrchestratorError::UnsupportedContractVersion))
+        );
+        client.clear_supported_versions(&admin, &ContractKind::Bills);
+        assert_eq!(client.get_supported_versions(&ContractKind::Bills), None);
+        client.bill_payment(&user, &100, &1);
+    }
+
+    #[test]
+    fn test_protocol_fee() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id,
+                remittance_split: remittance_split_id,
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&user, &150);
+        let usdc = TokenClient::new(&env, &token.address());
+        let treasury = Address::generate(&env);
+        let mut config = FeeConfig {
+            fee_bps: 1_001,
+            treasury: treasury.clone(),
+            token: token.address(),
+            record_revenue: false,
+        };
+        assert_eq!(
+            client.try_set_fee_config(&admin, &config),
+            Err(Ok(OrchestratorError::InvalidFee))
+        );
+        config.fee_bps = 100;
+        assert_eq!(
+            client.try_set_fee_config(&user, &config),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        client.set_fee_config(&admin, &config);
+
+        // 1% of 10000 is charged before the 40/30/20/10 split
+        let result = client.remittance_flow(&user, &10000, &1, &1, &1);
+        assert_eq!(result.fee_amount, 100);
+        assert_eq!(result.total_amount, 10000);
+        assert_eq!(result.spending_amount, 3960);
+        assert_eq!(result.savings_amount, 2970);
+        assert_eq!(usdc.balance(&treasury), 100);
+        assert_eq!(usdc.balance(&user), 50);
+        assert_eq!(client.get_execution_stats().total_fees_collected, 100);
+
+        // Not enough left to pay another 100 fee
+        assert_eq!(
+            client.try_remittance_flow(&user, &10000, &1, &1, &1),
+            Err(Ok(OrchestratorError::TokenTransferFailed))
+        );
+
+        client.clear_fee_config(&admin);
+        let result = client.remittance_flow(&user, &10000, &1, &1, &1);
+        assert_eq!(result.fee_amount, 0);
+        assert_eq!(result.spending_amount, 4000);
+    }
+
+    #[test]
+    fn test_flow_step_events() {
+        use soroban_sdk::{testutils::Events, Symbol, TryFromVal};
+
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id.clone(),
+                remittance_split: remittance_split_id.clone(),
+                savings: savings_id,
+                bills: bills_id.clone(),
+                insurance: insurance_id,
+            },
+        );
+        client.set_flow_profile(
+            &user,
+            &1,
+            &Vec::from_array(&env, [1u32, 999]),
+            &Vec::from_array(&env, [1u32]),
+            &family_wallet_id,
+            &remittance_split_id,
+        );
+
+        let first = client.remittance_flow(&user, &1000, &1, &1, &1);
+        let result =
+            client.execute_remittance_flow(&user, &10000, &ExecutionMode::BestEffort, &None);
+        assert_eq!(result.flow_id, first.flow_id + 1);
+
+        let mut steps = Vec::new(&env);
+        let mut names = Vec::new(&env);
+        for (_, topics, data) in env.events().all().iter() {
+            let topic = topics
+                .get(0)
+                .and_then(|t| Symbol::try_from_val(&env, &t).ok());
+            if topic == Some(symbol_short!("flow_step")) {
+                let event = FlowStepEvent::try_from_val(&env, &data).unwrap();
+                if event.flow_id == result.flow_id {
+                    names.push_back(event.step.clone());
+                    steps.push_back(event);
+                }
+            }
+        }
+        assert_eq!(
+            names,
+            Vec::from_array(
+                &env,
+                [
+                    symbol_short!("perm"),
+                    symbol_short!("split"),
+                    symbol_short!("savings"),
+                    symbol_short!("bills"),
+                    symbol_short!("bills"),
+                    symbol_short!("insuranc"),
+                ]
+            )
+        );
+        assert!(steps.iter().all(|e| e.version == STEP_EVENT_VERSION));
+
+        let failed = steps.get(4).unwrap();
+        assert_eq!(failed.contract, bills_id);
+        assert_eq!(failed.target_id, 999);
+        assert_eq!(failed.amount, 1000);
+        assert!(!failed.success);
+        assert_eq!(
+            failed.error_code,
+            Some(OrchestratorError::BillPaymentFailed as u32)
+        );
+    }
+
+    #[test]
+    fn test_compliance_registry_screens_callers() {
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id,
+                remittance_split: remittance_split_id,
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        let registry = env.register_contract(None, MockCompliance);
+        assert_eq!(
+            client.try_set_compliance_registry(&user, &registry),
+            Err(Ok(OrchestratorError::Unauthorized))
+        );
+        client.set_compliance_registry(&admin, &registry);
+        assert_eq!(client.get_compliance_registry(), Some(registry.clone()));
+
+        client.remittance_flow(&user, &10000, &1, &1, &1);
+
+        MockComplianceClient::new(&env, &registry).deny(&user);
+        assert_eq!(
+            client.try_remittance_flow(&user, &10000, &1, &1, &1),
+            Err(Ok(OrchestratorError::ComplianceRejected))
+        );
+
+        client.clear_compliance_registry(&admin);
+        client.remittance_flow(&user, &10000, &1, &1, &1);
+    }
+
+    #[test]
+    fn test_protocol_fee_recorded_in_treasury() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let (
+            env,
+            orchestrator_id,
+            family_wallet_id,
+            remittance_split_id,
+            savings_id,
+            bills_id,
+            insurance_id,
+            user,
+        ) = setup_test_env();
+
+        let client = OrchestratorClient::new(&env, &orchestrator_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin, &admin);
+        client.set_contracts(
+            &admin,
+            &ContractRegistry {
+                family_wallet: family_wallet_id,
+                remittance_split: remittance_split_id,
+                savings: savings_id,
+                bills: bills_id,
+                insurance: insurance_id,
+            },
+        );
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&user, &1_000);
+        let treasury = env.register_contract(None, MockTreasury);
+        let treasury_client = MockTreasuryClient::new(&env, &treasury);
+        client.set_fee_config(
+            &admin,
+            &FeeConfig {
+                fee_bps: 100,
+                treasury: treasury.clone(),
+                token: token.address(),
+                record_revenue: true,
+            },
+        );
+
+        client.remittance_flow(&user, &10000, &1, &1, &1);
+        assert_eq!(
+            TokenClient::new(&env, &token.address()).balance(&treasury),
+            100
+        );
+        assert_eq!(treasury_client.revenue(&orchestrator_id), 100);
+
+        // A treasury that refuses the fee reverts the whole flow
+        treasury_client.refuse(&orchestrator_id);
+        assert_eq!(
+            client.try_remittance_flow(&user, &10000, &1, &1, &1),
+            Err(Ok(OrchestratorError::TreasuryRecordFailed))
+        );
+        assert_eq!(
+            TokenClient::new(&env, &token.address()).balance(&treasury),
+            100
+        );
+    }
 }
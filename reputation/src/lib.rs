@@ -0,0 +1,355 @@
+#![no_std]
+
+//! # Reputation
+//!
+//! A payment track record per address, summarised as a score from 0 to
+//! 1000 that lenders and other contracts can read.
+//!
+//! Reporters appointed by the admin record three kinds of activity: bill
+//! payments and insurance premiums, each on time or late, and remittance
+//! schedule runs, each executed or missed. The bill payments, insurance and
+//! remittance split contracts report directly once a reputation contract is
+//! configured there; an off-chain indexer holding the reporter role can feed
+//! the same data from their events.
+//!
+//! ## Scoring
+//!
+//! Every report adds weight to the subject's positive or negative side: 3 for
+//! a bill or premium, 1 for a schedule run. Weights halve every
+//! `get_half_life` seconds (180 days by default), so recent behaviour counts
+//! most and an old late payment fades. The score is
+//!
+//! `1000 * (positive + PRIOR_WEIGHT / 2) / (positive + negative + PRIOR_WEIGHT)`
+//!
+//! An address without history scores 500, a long record of on-time payments
+//! approaches 1000 and a record of late ones approaches 0.
+//!
+//! ## Events
+//!
+//! `("rep", "recorded")` with the subject, activity, outcome and new score.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, Env, Map, Symbol,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const REP: Symbol = symbol_short!("rep");
+
+pub const MAX_SCORE: u32 = 1000;
+/// Weights are stored with this many units per point so decay keeps precision
+pub const WEIGHT_SCALE: i128 = 1_000;
+/// Neutral history every subject starts with, in points
+pub const PRIOR_WEIGHT: i128 = 6;
+pub const BILL_WEIGHT: i128 = 3;
+pub const PREMIUM_WEIGHT: i128 = 3;
+pub const SCHEDULE_WEIGHT: i128 = 1;
+pub const DEFAULT_HALF_LIFE: u64 = 180 * 24 * 60 * 60;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    InvalidHalfLife = 2,
+}
+
+/// Activity recorded for one address
+///
+/// `positive` and `negative` are decayed to `updated_at`; the counters are
+/// lifetime totals and never decay.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReputationProfile {
+    pub positive: i128,
+    pub negative: i128,
+    pub updated_at: u64,
+    pub bills_on_time: u32,
+    pub bills_late: u32,
+    pub premiums_on_time: u32,
+    pub premiums_late: u32,
+    pub schedules_executed: u32,
+    pub schedules_missed: u32,
+}
+
+#[contract]
+pub struct Reputation;
+
+#[contractimpl]
+impl Reputation {
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Set the admin who appoints reporters
+    ///
+    /// The first call must set the caller itself; afterwards only the current
+    /// admin can hand the role over.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
+        caller.require_auth();
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(Error::Unauthorized),
+            Some(admin) if admin != caller => return Err(Error::Unauthorized),
+            _ => {}
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Grant or remove the reporter role
+    pub fn set_reporter(
+        env: Env,
+        admin: Address,
+        reporter: Address,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        let mut reporters = Self::load_reporters(&env);
+        if enabled {
+            reporters.set(reporter.clone(), true);
+        } else {
+            reporters.remove(reporter.clone());
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("REPORTRS"), &reporters);
+
+        env.events()
+            .publish((REP, symbol_short!("reporter")), (reporter, enabled));
+        Ok(())
+    }
+
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        Self::load_reporters(&env).contains_key(reporter)
+    }
+
+    /// Set how many seconds it takes a report's weight to halve
+    ///
+    /// Weights already stored keep the decay applied so far.
+    pub fn set_half_life(env: Env, admin: Address, seconds: u64) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        if seconds == 0 {
+            return Err(Error::InvalidHalfLife);
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("HALFLIFE"), &seconds);
+        Ok(())
+    }
+
+    pub fn get_half_life(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("HALFLIFE"))
+            .unwrap_or(DEFAULT_HALF_LIFE)
+    }
+
+    // -----------------------------------------------------------------------
+    // Reporting
+    // -----------------------------------------------------------------------
+
+    /// Record a bill payment by `subject`
+    ///
+    /// # Returns
+    /// Ok(score) after the report
+    pub fn record_bill_payment(
+        env: Env,
+        reporter: Address,
+        subject: Address,
+        on_time: bool,
+    ) -> Result<u32, Error> {
+        Self::record(
+            &env,
+            &reporter,
+            subject,
+            symbol_short!("bill"),
+            BILL_WEIGHT,
+            on_time,
+            |p| {
+                if on_time {
+                    p.bills_on_time += 1
+                } else {
+                    p.bills_late += 1
+                }
+            },
+        )
+    }
+
+    /// Record an insurance premium payment by `subject`
+    pub fn record_premium_payment(
+        env: Env,
+        reporter: Address,
+        subject: Address,
+        on_time: bool,
+    ) -> Result<u32, Error> {
+        Self::record(
+            &env,
+            &reporter,
+            subject,
+            symbol_short!("premium"),
+            PREMIUM_WEIGHT,
+            on_time,
+            |p| {
+                if on_time {
+                    p.premiums_on_time += 1
+                } else {
+                    p.premiums_late += 1
+                }
+            },
+        )
+    }
+
+    /// Record whether one of `subject`'s scheduled remittances ran or was
+    /// missed for lack of funds or allowance
+    pub fn record_schedule_run(
+        env: Env,
+        reporter: Address,
+        subject: Address,
+        executed: bool,
+    ) -> Result<u32, Error> {
+        Self::record(
+            &env,
+            &reporter,
+            subject,
+            symbol_short!("schedule"),
+            SCHEDULE_WEIGHT,
+            executed,
+            |p| {
+                if executed {
+                    p.schedules_executed += 1
+                } else {
+                    p.schedules_missed += 1
+                }
+            },
+        )
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    /// Current score of `subject`, 500 if nothing has been reported
+    pub fn get_score(env: Env, subject: Address) -> u32 {
+        let profile = Self::get_profile(env.clone(), subject).unwrap_or_default();
+        let (positive, negative) = Self::decayed(&env, &profile);
+        Self::score(positive, negative)
+    }
+
+    /// Stored record for `subject`, with weights as of its last report
+    pub fn get_profile(env: Env, subject: Address) -> Option<ReputationProfile> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PROFILE"), subject))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    #[allow(clippy::too_many_arguments)]
+    fn record(
+        env: &Env,
+        reporter: &Address,
+        subject: Address,
+        activity: Symbol,
+        weight: i128,
+        good: bool,
+        count: impl FnOnce(&mut ReputationProfile),
+    ) -> Result<u32, Error> {
+        reporter.require_auth();
+        if !Self::load_reporters(env).contains_key(reporter.clone()) {
+            return Err(Error::Unauthorized);
+        }
+
+        let mut profile = Self::get_profile(env.clone(), subject.clone()).unwrap_or_default();
+        let (positive, negative) = Self::decayed(env, &profile);
+        profile.positive = positive;
+        profile.negative = negative;
+        if good {
+            profile.positive += weight * WEIGHT_SCALE;
+        } else {
+            profile.negative += weight * WEIGHT_SCALE;
+        }
+        profile.updated_at = env.ledger().timestamp();
+        count(&mut profile);
+
+        let key = (symbol_short!("PROFILE"), subject.clone());
+        env.storage().persistent().set(&key, &profile);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+
+        let score = Self::score(profile.positive, profile.negative);
+        env.events().publish(
+            (REP, symbol_short!("recorded")),
+            (subject, activity, good, score),
+        );
+        Ok(score)
+    }
+
+    /// Profile weights decayed from `updated_at` to now
+    fn decayed(env: &Env, profile: &ReputationProfile) -> (i128, i128) {
+        let elapsed = env.ledger().timestamp().saturating_sub(profile.updated_at);
+        let half_life = Self::get_half_life(env.clone());
+        (
+            Self::decay(profile.positive, elapsed, half_life),
+            Self::decay(profile.negative, elapsed, half_life),
+        )
+    }
+
+    /// Halve `weight` for every full half-life and interpolate linearly
+    /// within the last one
+    fn decay(weight: i128, elapsed: u64, half_life: u64) -> i128 {
+        let halvings = elapsed / half_life;
+        if halvings >= 64 {
+            return 0;
+        }
+        let weight = weight >> halvings;
+        let rest = (elapsed % half_life) as i128;
+        weight - weight * rest / (2 * half_life as i128)
+    }
+
+    fn score(positive: i128, negative: i128) -> u32 {
+        let prior = PRIOR_WEIGHT * WEIGHT_SCALE;
+        let score = MAX_SCORE as i128 * (positive + prior / 2) / (positive + negative + prior);
+        score as u32
+    }
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()).as_ref() != Some(admin) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn load_reporters(env: &Env) -> Map<Address, bool> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("REPORTRS"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
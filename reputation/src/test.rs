@@ -0,0 +1,127 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+struct Setup<'a> {
+    env: Env,
+    client: ReputationClient<'a>,
+    admin: Address,
+    reporter: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, Reputation);
+    let client = ReputationClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    let reporter = Address::generate(&env);
+    client.set_admin(&admin, &admin);
+    client.set_reporter(&admin, &reporter, &true);
+
+    Setup {
+        env,
+        client,
+        admin,
+        reporter,
+    }
+}
+
+#[test]
+fn test_score_follows_payment_history() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    assert_eq!(s.client.get_score(&user), 500);
+    assert_eq!(s.client.get_profile(&user), None);
+
+    assert_eq!(s.client.record_bill_payment(&s.reporter, &user, &true), 666);
+    assert_eq!(
+        s.client.record_premium_payment(&s.reporter, &user, &false),
+        500
+    );
+    s.client.record_schedule_run(&s.reporter, &user, &true);
+    s.client.record_schedule_run(&s.reporter, &user, &false);
+
+    let profile = s.client.get_profile(&user).unwrap();
+    assert_eq!(profile.bills_on_time, 1);
+    assert_eq!(profile.premiums_late, 1);
+    assert_eq!(profile.schedules_executed, 1);
+    assert_eq!(profile.schedules_missed, 1);
+    assert_eq!(profile.positive, 4 * WEIGHT_SCALE);
+    assert_eq!(profile.negative, 4 * WEIGHT_SCALE);
+    assert_eq!(s.client.get_score(&user), 500);
+
+    for _ in 0..20 {
+        s.client.record_bill_payment(&s.reporter, &user, &true);
+    }
+    assert!(s.client.get_score(&user) > 900);
+}
+
+#[test]
+fn test_weights_decay_over_time() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    assert_eq!(
+        s.client.record_bill_payment(&s.reporter, &user, &false),
+        333
+    );
+
+    // Half a half-life takes a quarter off, a full one halves the weight
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = 1_000 + DEFAULT_HALF_LIFE / 2);
+    assert_eq!(s.client.get_score(&user), 1000 * 3_000 / 8_250);
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = 1_000 + DEFAULT_HALF_LIFE);
+    assert_eq!(s.client.get_score(&user), 400);
+
+    // Long forgotten
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = 1_000 + 100 * DEFAULT_HALF_LIFE);
+    assert_eq!(s.client.get_score(&user), 500);
+
+    // A shorter half-life forgets faster
+    let other = Address::generate(&s.env);
+    s.client.set_half_life(&s.admin, &DAY);
+    s.client.record_bill_payment(&s.reporter, &other, &false);
+    s.env
+        .ledger()
+        .with_mut(|l| l.timestamp = 1_000 + 100 * DEFAULT_HALF_LIFE + DAY);
+    assert_eq!(s.client.get_score(&other), 400);
+}
+
+#[test]
+fn test_only_reporters_record() {
+    let s = setup();
+    let user = Address::generate(&s.env);
+    let outsider = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_record_bill_payment(&outsider, &user, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_set_reporter(&outsider, &outsider, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_set_half_life(&s.admin, &0),
+        Err(Ok(Error::InvalidHalfLife))
+    );
+
+    s.client.set_reporter(&s.admin, &s.reporter, &false);
+    assert!(!s.client.is_reporter(&s.reporter));
+    assert_eq!(
+        s.client.try_record_schedule_run(&s.reporter, &user, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+}
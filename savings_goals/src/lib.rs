@@ -1,6 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, Map, String, Symbol, Vec,
+    contract, contractclient, contracterror, contractimpl, contracttype, symbol_short,
+    token::TokenClient, xdr::ToXdr, Address, Bytes, Env, Map, String, Symbol, Vec,
 };
 
 // Event topics
@@ -38,11 +39,74 @@ pub struct GoalCompletedEvent {
 
 const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280;
 const INSTANCE_BUMP_AMOUNT: u32 = 518400;
+// Goals and owner indexes live in persistent storage, one entry each
+const GOAL_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const GOAL_BUMP_AMOUNT: u32 = 518400; // ~30 days
 
 /// Pagination constants
 pub const DEFAULT_PAGE_LIMIT: u32 = 20;
 pub const MAX_PAGE_LIMIT: u32 = 50;
 
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SavingsGoalError {
+    GoalNotFound = 1,
+    Unauthorized = 2,
+    InvalidAmount = 3,
+    TargetAmountMustBePositive = 4,
+    InsufficientBalance = 5,
+    GoalLocked = 6,
+    ContractPaused = 7,
+    FunctionPaused = 8,
+    Overflow = 9,
+    InvalidNonce = 10,
+    UnsupportedVersion = 11,
+    ChecksumMismatch = 12,
+    ScheduleNotFound = 13,
+    InvalidDueDate = 14,
+    BatchTooLarge = 15,
+    TimeLockActive = 16,
+    InvalidMilestones = 17,
+    InvalidMetadata = 18,
+    GoalNotEmpty = 19,
+    InvalidSweepTarget = 20,
+    InvalidPenalty = 21,
+    InvalidTransfer = 22,
+    NoPendingTransfer = 23,
+    InvalidBeneficiary = 24,
+    BeneficiaryNotFound = 25,
+    NotClaimable = 26,
+    InvalidMatch = 27,
+    MatchAlreadyExists = 28,
+    MatchNotFound = 29,
+    KeeperRewardNotSet = 30,
+    RewardPoolNotEmpty = 31,
+    InvalidRate = 32,
+    GuardianRequired = 33,
+    TargetBelowBalance = 34,
+    NotDormant = 35,
+    InvalidDormancyPolicy = 36,
+    VoucherNotUsable = 37,
+    /// Imported goals would owe a token more or less than is held for it
+    LiabilityMismatch = 38,
+}
+
+#[contractclient(name = "VouchersClient")]
+pub trait VouchersTrait {
+    #[allow(clippy::too_many_arguments)]
+    fn redeem(
+        env: Env,
+        redeemer: Address,
+        voucher_id: u32,
+        holder: Address,
+        token: Address,
+        amount: i128,
+        category: Symbol,
+        to: Address,
+    ) -> i128;
+}
+
 #[contract]
 pub struct SavingsGoalContract;
 
@@ -57,6 +121,217 @@ pub struct SavingsGoal {
     pub target_date: u64,
     pub locked: bool,
     pub unlock_date: Option<u64>,
+    /// Token held by the contract for this goal; `None` for a bookkeeping-only goal
+    pub token: Option<Address>,
+    /// Ascending milestone amounts; empty means 25%, 50% and 75% of the target
+    pub milestones: Vec<i128>,
+    /// How many milestones `current_amount` has reached so far
+    pub milestones_hit: u32,
+    /// Grouping such as `education`, `medical` or `housing`; `general` by default
+    pub category: Symbol,
+    pub tags: Vec<Symbol>,
+    /// Display emoji chosen by the app; empty when unset
+    pub emoji: String,
+}
+
+/// Final state of a goal removed with `close_goal`.
+#[contracttype]
+#[derive(Clone)]
+pub struct ArchivedGoal {
+    /// The goal as it was just before closing, including its balance
+    pub goal: SavingsGoal,
+    pub closed_at: u64,
+    pub swept_amount: i128,
+    pub swept_to: Option<Address>,
+    /// Goal that received the balance through a `CompletionAction::ToGoal`
+    pub swept_to_goal: Option<u32>,
+}
+
+/// What happens to a goal's balance once it reaches its target.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CompletionAction {
+    /// Send the balance to an address (family wallet, bills contract, ...)
+    Transfer(Address),
+    /// Move the balance into another goal of the same owner and token
+    ToGoal(u32),
+}
+
+/// Round-ups an owner recorded in one calendar month.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundupStats {
+    /// `YYYYMM`
+    pub month: u32,
+    pub count: u32,
+    pub purchases: i128,
+    pub saved: i128,
+}
+
+/// Running totals for an owner, kept up to date as they save.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SavingsStats {
+    /// Everything the owner put into goals, excluding matches and interest
+    pub total_saved: i128,
+    /// Gross amount taken out by withdrawals, closes and transfer sweeps
+    pub total_withdrawn: i128,
+    /// Goals that reached their target, each counted once
+    pub goals_completed: u32,
+    /// Scheduled deposits executed in a row without a miss or skip
+    pub deposit_streak: u32,
+    pub best_streak: u32,
+}
+
+/// Interest a goal has earned but not yet claimed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestState {
+    pub accrued: i128,
+    pub last_accrual: u64,
+}
+
+/// Tokens set aside to pay interest, and how much has been paid out.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestPool {
+    pub available: i128,
+    pub distributed: i128,
+}
+
+/// A pledge to match contributions to someone else's goal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchPledge {
+    pub matcher: Address,
+    pub ratio_bps: u32,
+    pub cap: i128,
+    pub matched: i128,
+}
+
+/// Reward paid to keepers for each schedule they execute.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperReward {
+    pub token: Address,
+    pub per_execution: i128,
+}
+
+/// How `allocate_across_goals` splits a deposit.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AllocationMode {
+    /// Fill the highest-priority goal to its target before the next
+    Priority,
+    /// Split by weight, then top up by priority with whatever is left
+    Proportional,
+}
+
+/// Per-goal allocation settings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllocationRule {
+    pub priority: u32,
+    pub weight: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalAllocation {
+    pub goal_id: u32,
+    pub amount: i128,
+}
+
+/// Where early-withdrawal penalties go.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PenaltySink {
+    /// Credited to another goal of the same owner and token
+    Goal(u32),
+    /// Paid to an address such as the family wallet
+    Address(Address),
+}
+
+/// Penalty charged on withdrawals before a goal's `target_date`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyConfig {
+    pub penalty_bps: u32,
+    pub sink: PenaltySink,
+}
+
+/// One penalty taken from an early withdrawal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PenaltyRecord {
+    pub goal_id: u32,
+    /// Gross amount withdrawn, including the penalty
+    pub amount: i128,
+    pub penalty: i128,
+    pub sink: PenaltySink,
+    pub timestamp: u64,
+}
+
+/// Who may take over a goal's funds if its owner goes quiet.
+///
+/// The beneficiary can claim once `claim_after` has passed and the owner has
+/// not acted on any goal for `inactivity_period` seconds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Beneficiary {
+    pub beneficiary: Address,
+    pub claim_after: u64,
+    pub inactivity_period: u64,
+}
+
+/// Wallet contract that must approve unlocking or draining a goal.
+///
+/// Withdrawals of at most `threshold` stay with the owner; anything larger has
+/// to be invoked by `guardian`, typically a family wallet after its multisig.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalGuardian {
+    pub guardian: Address,
+    pub threshold: i128,
+}
+
+/// Snapshot of how far a goal has come, for progress bars and reminders.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalProgress {
+    pub goal_id: u32,
+    /// Whole percent of the target saved, capped at 100
+    pub percentage: u32,
+    pub milestones: Vec<i128>,
+    pub milestones_hit: u32,
+    /// When active schedules would reach the target, if they ever do
+    pub projected_completion: Option<u64>,
+}
+
+/// A goal that will not reach its target by its `target_date`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AtRiskGoal {
+    pub goal_id: u32,
+    pub target_date: u64,
+    /// Current balance plus schedule payments due by `target_date`
+    pub projected_amount: i128,
+    pub shortfall: i128,
+    /// `target_date` has already passed
+    pub overdue: bool,
+}
+
+/// Contract-held balance of a token compared with what token-backed goals are owed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenReconciliation {
+    pub token: Address,
+    /// Actual balance of this contract
+    pub held: i128,
+    /// Sum of `current_amount` over goals backed by the token
+    pub owed: i128,
+    /// `held - owed`; negative means goals are under-collateralized
+    pub surplus: i128,
 }
 
 /// Paginated result for savings goal queries
@@ -85,6 +360,47 @@ pub struct SavingsSchedule {
     pub created_at: u64,
     pub last_executed: Option<u64>,
     pub missed_count: u32,
+    /// Account the keeper pulls tokens from for token-backed goals
+    pub funder: Address,
+    pub skipped_count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExecutionStatus {
+    Executed,
+    /// The funder's balance or allowance could not cover the amount
+    Skipped,
+}
+
+/// When untouched goals are flagged dormant and when they can be swept.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DormancyPolicy {
+    /// Seconds without any change before the keeper flags a goal
+    pub dormant_after: u64,
+    /// Seconds after flagging before the owner or beneficiary can sweep it
+    pub sweep_after: u64,
+}
+
+/// One change made with `update_goal`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoalAmendment {
+    pub old_target_amount: i128,
+    pub new_target_amount: i128,
+    pub old_target_date: u64,
+    pub new_target_date: u64,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleExecution {
+    pub schedule_id: u32,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub status: ExecutionStatus,
 }
 
 #[contracttype]
@@ -101,15 +417,52 @@ pub enum SavingsEvent {
     ScheduleMissed,
     ScheduleModified,
     ScheduleCancelled,
+    MilestoneReached,
+    MilestonesUpdated,
+    GoalCategorized,
+    GoalClosed,
+    CompletionActionSet,
+    GoalSwept,
+    PenaltyCharged,
+    TransferProposed,
+    GoalTransferred,
+    BeneficiarySet,
+    BeneficiaryClaimed,
+    FundsAllocated,
+    MatchPledged,
+    ContributionMatched,
+    MatchReleased,
+    KeeperRewarded,
+    ScheduleSkipped,
+    OverdueGoal,
+    ApyUpdated,
+    InterestClaimed,
+    RoundupRecorded,
+    GuardianSet,
+    GuardianRemoved,
+    GoalUpdated,
+    GoalDormant,
+    DormantGoalSwept,
+    VoucherRedeemed,
 }
 
+/// Contract state exported for migration.
+///
+/// `checksum` is the sha256 of the big-endian version followed by the XDR of
+/// every other field. Version 1 snapshots only carried goals and an additive
+/// digest, stored here as 8 big-endian bytes; importing one leaves schedules,
+/// nonces and the audit log as they are.
 #[contracttype]
 #[derive(Clone)]
 pub struct GoalsExportSnapshot {
     pub version: u32,
-    pub checksum: u64,
+    pub checksum: Bytes,
     pub next_id: u32,
     pub goals: Vec<SavingsGoal>,
+    pub next_schedule_id: u32,
+    pub schedules: Vec<SavingsSchedule>,
+    pub nonces: Map<Address, u64>,
+    pub audit_log: Vec<AuditEntry>,
 }
 
 #[contracttype]
@@ -121,10 +474,33 @@ pub struct AuditEntry {
     pub success: bool,
 }
 
-const SNAPSHOT_VERSION: u32 = 1;
+const SNAPSHOT_VERSION: u32 = 2;
+const LEGACY_SNAPSHOT_VERSION: u32 = 1;
 const MAX_AUDIT_ENTRIES: u32 = 100;
 const CONTRACT_VERSION: u32 = 1;
 const MAX_BATCH_SIZE: u32 = 50;
+/// Upper bound on schedules executed by a single keeper call
+const MAX_SCHEDULES_PER_RUN: u32 = 50;
+/// Executions kept per schedule by `get_schedule_executions`
+const MAX_SCHEDULE_HISTORY: u32 = 20;
+/// Amendments kept per goal by `get_goal_amendments`
+const MAX_GOAL_AMENDMENTS: u32 = 20;
+/// Goals checked for a missed target date by a single keeper call
+const MAX_OVERDUE_CHECKS_PER_RUN: u32 = 50;
+/// Goals checked for dormancy by a single keeper call
+const MAX_DORMANCY_CHECKS_PER_RUN: u32 = 50;
+const DEFAULT_MILESTONE_BPS: [i128; 3] = [2_500, 5_000, 7_500];
+const MAX_MILESTONES: u32 = 10;
+const MAX_PENALTY_BPS: u32 = 5_000;
+const MAX_APY_BPS: u32 = 5_000;
+const SECONDS_PER_YEAR: i128 = 365 * 86400;
+/// Matchers can pledge at most 10:1.
+const MAX_MATCH_RATIO_BPS: u32 = 100_000;
+const DEFAULT_CATEGORY: Symbol = symbol_short!("general");
+const MAX_TAGS: u32 = 5;
+const MAX_EMOJI_LEN: u32 = 16;
+/// Schedule payments simulated by `get_goal_progress` before giving up on a projection
+const MAX_PROJECTION_STEPS: u32 = 1_000;
 
 pub mod pause_functions {
     use soroban_sdk::{symbol_short, Symbol};
@@ -144,9 +520,6 @@ pub struct ContributionItem {
 
 #[contractimpl]
 impl SavingsGoalContract {
-    const STORAGE_NEXT_ID: Symbol = symbol_short!("NEXT_ID");
-    const STORAGE_GOALS: Symbol = symbol_short!("GOALS");
-
     // -----------------------------------------------------------------------
     // Internal helpers
     // -----------------------------------------------------------------------
@@ -178,72 +551,66 @@ impl SavingsGoalContract {
             .get(func)
             .unwrap_or(false)
     }
-    fn require_not_paused(env: &Env, func: Symbol) {
+    fn require_not_paused(env: &Env, func: Symbol) -> Result<(), SavingsGoalError> {
         if Self::get_global_paused(env) {
-            panic!("Contract is paused");
+            return Err(SavingsGoalError::ContractPaused);
         }
         if Self::is_function_paused(env, func) {
-            panic!("Function is paused");
+            return Err(SavingsGoalError::FunctionPaused);
         }
+        Ok(())
     }
 
     // -----------------------------------------------------------------------
     // Pause / upgrade
     // -----------------------------------------------------------------------
 
-    pub fn init(env: Env) {
-        let storage = env.storage().persistent();
-        if storage.get::<_, u32>(&Self::STORAGE_NEXT_ID).is_none() {
-            storage.set(&Self::STORAGE_NEXT_ID, &1u32);
-        }
-        if storage
-            .get::<_, Map<u32, SavingsGoal>>(&Self::STORAGE_GOALS)
-            .is_none()
-        {
-            storage.set(&Self::STORAGE_GOALS, &Map::<u32, SavingsGoal>::new(&env));
-        }
-    }
-
-    pub fn set_pause_admin(env: Env, caller: Address, new_admin: Address) {
+    pub fn set_pause_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+    ) -> Result<(), SavingsGoalError> {
         caller.require_auth();
         let current = Self::get_pause_admin(&env);
         match current {
             None => {
                 if caller != new_admin {
-                    panic!("Unauthorized");
+                    return Err(SavingsGoalError::Unauthorized);
                 }
             }
-            Some(admin) if admin != caller => panic!("Unauthorized"),
+            Some(admin) if admin != caller => return Err(SavingsGoalError::Unauthorized),
             _ => {}
         }
         env.storage()
             .instance()
             .set(&symbol_short!("PAUSE_ADM"), &new_admin);
+        Ok(())
     }
 
-    pub fn pause(env: Env, caller: Address) {
+    pub fn pause(env: Env, caller: Address) -> Result<(), SavingsGoalError> {
         caller.require_auth();
-        let admin = Self::get_pause_admin(&env).expect("No pause admin set");
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
         if admin != caller {
-            panic!("Unauthorized");
+            return Err(SavingsGoalError::Unauthorized);
         }
         env.storage()
             .instance()
             .set(&symbol_short!("PAUSED"), &true);
         env.events()
             .publish((symbol_short!("savings"), symbol_short!("paused")), ());
+        Ok(())
     }
 
-    pub fn unpause(env: Env, caller: Address) {
+    pub fn unpause(env: Env, caller: Address) -> Result<(), SavingsGoalError> {
         caller.require_auth();
-        let admin = Self::get_pause_admin(&env).expect("No pause admin set");
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
         if admin != caller {
-            panic!("Unauthorized");
+            return Err(SavingsGoalError::Unauthorized);
         }
         let unpause_at: Option<u64> = env.storage().instance().get(&symbol_short!("UNP_AT"));
         if let Some(at) = unpause_at {
             if env.ledger().timestamp() < at {
-                panic!("Time-locked unpause not yet reached");
+                return Err(SavingsGoalError::TimeLockActive);
             }
             env.storage().instance().remove(&symbol_short!("UNP_AT"));
         }
@@ -252,13 +619,14 @@ impl SavingsGoalContract {
             .set(&symbol_short!("PAUSED"), &false);
         env.events()
             .publish((symbol_short!("savings"), symbol_short!("unpaused")), ());
+        Ok(())
     }
 
-    pub fn pause_function(env: Env, caller: Address, func: Symbol) {
+    pub fn pause_function(env: Env, caller: Address, func: Symbol) -> Result<(), SavingsGoalError> {
         caller.require_auth();
-        let admin = Self::get_pause_admin(&env).expect("No pause admin set");
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
         if admin != caller {
-            panic!("Unauthorized");
+            return Err(SavingsGoalError::Unauthorized);
         }
         let mut m: Map<Symbol, bool> = env
             .storage()
@@ -269,13 +637,18 @@ impl SavingsGoalContract {
         env.storage()
             .instance()
             .set(&symbol_short!("PAUSED_FN"), &m);
+        Ok(())
     }
 
-    pub fn unpause_function(env: Env, caller: Address, func: Symbol) {
+    pub fn unpause_function(
+        env: Env,
+        caller: Address,
+        func: Symbol,
+    ) -> Result<(), SavingsGoalError> {
         caller.require_auth();
-        let admin = Self::get_pause_admin(&env).expect("No pause admin set");
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
         if admin != caller {
-            panic!("Unauthorized");
+            return Err(SavingsGoalError::Unauthorized);
         }
         let mut m: Map<Symbol, bool> = env
             .storage()
@@ -286,6 +659,7 @@ impl SavingsGoalContract {
         env.storage()
             .instance()
             .set(&symbol_short!("PAUSED_FN"), &m);
+        Ok(())
     }
 
     pub fn is_paused(env: Env) -> bool {
@@ -303,28 +677,37 @@ impl SavingsGoalContract {
         env.storage().instance().get(&symbol_short!("UPG_ADM"))
     }
 
-    pub fn set_upgrade_admin(env: Env, caller: Address, new_admin: Address) {
+    pub fn set_upgrade_admin(
+        env: Env,
+        caller: Address,
+        new_admin: Address,
+    ) -> Result<(), SavingsGoalError> {
         caller.require_auth();
         let current = Self::get_upgrade_admin(&env);
         match current {
             None => {
                 if caller != new_admin {
-                    panic!("Unauthorized");
+                    return Err(SavingsGoalError::Unauthorized);
                 }
             }
-            Some(adm) if adm != caller => panic!("Unauthorized"),
+            Some(adm) if adm != caller => return Err(SavingsGoalError::Unauthorized),
             _ => {}
         }
         env.storage()
             .instance()
             .set(&symbol_short!("UPG_ADM"), &new_admin);
+        Ok(())
     }
 
-    pub fn set_version(env: Env, caller: Address, new_version: u32) {
+    pub fn set_version(
+        env: Env,
+        caller: Address,
+        new_version: u32,
+    ) -> Result<(), SavingsGoalError> {
         caller.require_auth();
-        let admin = Self::get_upgrade_admin(&env).expect("No upgrade admin set");
+        let admin = Self::get_upgrade_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
         if admin != caller {
-            panic!("Unauthorized");
+            return Err(SavingsGoalError::Unauthorized);
         }
         let prev = Self::get_version(env.clone());
         env.storage()
@@ -334,6 +717,7 @@ impl SavingsGoalContract {
             (symbol_short!("savings"), symbol_short!("upgraded")),
             (prev, new_version),
         );
+        Ok(())
     }
 
     // -----------------------------------------------------------------------
@@ -346,23 +730,164 @@ impl SavingsGoalContract {
         name: String,
         target_amount: i128,
         target_date: u64,
-    ) -> u32 {
+    ) -> Result<u32, SavingsGoalError> {
+        Self::create_goal_internal(env, owner, name, target_amount, target_date, None)
+    }
+
+    /// Create a goal that holds real tokens.
+    ///
+    /// `add_to_goal` pulls `token` from the owner into this contract and
+    /// `withdraw_from_goal` sends it back, so `current_amount` is always backed.
+    pub fn create_token_goal(
+        env: Env,
+        owner: Address,
+        name: String,
+        target_amount: i128,
+        target_date: u64,
+        token: Address,
+    ) -> Result<u32, SavingsGoalError> {
+        Self::create_goal_internal(env, owner, name, target_amount, target_date, Some(token))
+    }
+
+    /// Create a goal that charges `penalty_bps` of any withdrawal made before
+    /// `target_date`, paid to `sink`.
+    ///
+    /// The penalty is fixed for the life of the goal so it cannot be dropped
+    /// right before raiding the savings. At most 50%.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_goal_with_penalty(
+        env: Env,
+        owner: Address,
+        name: String,
+        target_amount: i128,
+        target_date: u64,
+        token: Option<Address>,
+        penalty_bps: u32,
+        sink: PenaltySink,
+    ) -> Result<u32, SavingsGoalError> {
+        if penalty_bps == 0 || penalty_bps > MAX_PENALTY_BPS {
+            return Err(SavingsGoalError::InvalidPenalty);
+        }
+        if let PenaltySink::Goal(sink_id) = &sink {
+            let sink_goal =
+                Self::load_goal(&env, *sink_id).ok_or(SavingsGoalError::GoalNotFound)?;
+            if sink_goal.owner != owner || sink_goal.token != token {
+                return Err(SavingsGoalError::InvalidPenalty);
+            }
+        }
+
+        let goal_id = Self::create_goal_internal(
+            env.clone(),
+            owner,
+            name,
+            target_amount,
+            target_date,
+            token,
+        )?;
+        let key = (symbol_short!("PENALTY"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, &PenaltyConfig { penalty_bps, sink });
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Ok(goal_id)
+    }
+
+    pub fn get_penalty_config(env: Env, goal_id: u32) -> Option<PenaltyConfig> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PENALTY"), goal_id))
+    }
+
+    /// Penalties charged on a goal's early withdrawals, oldest first.
+    pub fn get_penalty_history(env: Env, goal_id: u32) -> Vec<PenaltyRecord> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PEN_HIST"), goal_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Take the early-withdrawal penalty on `amount` leaving `goal` and pay it
+    /// to the sink. Returns the penalty; the caller pays out the rest.
+    ///
+    /// No penalty applies on or after `target_date`, or if the sink goal has
+    /// since been closed.
+    fn charge_penalty(
+        env: &Env,
+        goal: &SavingsGoal,
+        amount: i128,
+    ) -> Result<i128, SavingsGoalError> {
+        if env.ledger().timestamp() >= goal.target_date {
+            return Ok(0);
+        }
+        let config = match Self::get_penalty_config(env.clone(), goal.id) {
+            Some(config) => config,
+            None => return Ok(0),
+        };
+        let bps = config.penalty_bps as i128;
+        let penalty = amount / 10_000 * bps + amount % 10_000 * bps / 10_000;
+        if penalty == 0 {
+            return Ok(0);
+        }
+
+        match &config.sink {
+            PenaltySink::Goal(sink_id) => {
+                let mut sink_goal = match Self::load_goal(env, *sink_id) {
+                    Some(sink_goal) => sink_goal,
+                    None => return Ok(0),
+                };
+                Self::accrue_interest(env, &sink_goal)?;
+                sink_goal.current_amount = sink_goal
+                    .current_amount
+                    .checked_add(penalty)
+                    .ok_or(SavingsGoalError::Overflow)?;
+                Self::record_milestones(env, &mut sink_goal);
+                Self::save_goal(env, &sink_goal);
+            }
+            PenaltySink::Address(to) => {
+                if let Some(token) = &goal.token {
+                    Self::release_tokens(env, token, to, penalty)?;
+                }
+            }
+        }
+
+        let record = PenaltyRecord {
+            goal_id: goal.id,
+            amount,
+            penalty,
+            sink: config.sink,
+            timestamp: env.ledger().timestamp(),
+        };
+        let key = (symbol_short!("PEN_HIST"), goal.id);
+        let storage = env.storage().persistent();
+        let mut history: Vec<PenaltyRecord> = storage.get(&key).unwrap_or_else(|| Vec::new(env));
+        history.push_back(record.clone());
+        storage.set(&key, &history);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::PenaltyCharged),
+            record,
+        );
+        Ok(penalty)
+    }
+
+    fn create_goal_internal(
+        env: Env,
+        owner: Address,
+        name: String,
+        target_amount: i128,
+        target_date: u64,
+        token: Option<Address>,
+    ) -> Result<u32, SavingsGoalError> {
         owner.require_auth();
-        Self::require_not_paused(&env, pause_functions::CREATE_GOAL);
+        Self::require_not_paused(&env, pause_functions::CREATE_GOAL)?;
 
         if target_amount <= 0 {
             Self::append_audit(&env, symbol_short!("create"), &owner, false);
-            panic!("Target amount must be positive");
+            return Err(SavingsGoalError::TargetAmountMustBePositive);
         }
 
         Self::extend_instance_ttl(&env);
 
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-
         let next_id = env
             .storage()
             .instance()
@@ -379,12 +904,19 @@ impl SavingsGoalContract {
             target_date,
             locked: true,
             unlock_date: None,
+            token,
+            milestones: Vec::new(&env),
+            milestones_hit: 0,
+            category: DEFAULT_CATEGORY,
+            tags: Vec::new(&env),
+            emoji: String::from_str(&env, ""),
         };
 
-        goals.set(next_id, goal.clone());
-        env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
+        Self::save_goal(&env, &goal);
+        let mut owned = Self::owner_goal_ids(&env, &owner);
+        owned.push_back(next_id);
+        Self::save_owner_goal_ids(&env, &owner, &owned);
+        Self::touch_owner(&env, &owner);
         env.storage()
             .instance()
             .set(&symbol_short!("NEXT_ID"), &next_id);
@@ -402,48 +934,160 @@ impl SavingsGoalContract {
             (next_id, owner),
         );
 
-        next_id
+        Ok(next_id)
+    }
+
+    /// Change a goal's target amount and date.
+    ///
+    /// The target cannot drop below what is already saved or below a custom
+    /// milestone, and the date must be in the future. Goals with an
+    /// early-withdrawal penalty cannot move their date earlier, which would
+    /// shorten the penalty window. Each change is kept in the goal's
+    /// amendment history.
+    pub fn update_goal(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        new_target_amount: i128,
+        new_target_date: u64,
+    ) -> Result<GoalAmendment, SavingsGoalError> {
+        caller.require_auth();
+        let mut goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != caller {
+            Self::append_audit(&env, symbol_short!("update"), &caller, false);
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if new_target_amount <= 0 {
+            return Err(SavingsGoalError::TargetAmountMustBePositive);
+        }
+        if new_target_amount < goal.current_amount {
+            return Err(SavingsGoalError::TargetBelowBalance);
+        }
+        // Lowering the target would bring forward the release of voucher funds
+        if new_target_amount < goal.target_amount
+            && Self::get_voucher_locked(env.clone(), goal_id) > 0
+        {
+            return Err(SavingsGoalError::GoalLocked);
+        }
+        if goal
+            .milestones
+            .last()
+            .is_some_and(|highest| highest > new_target_amount)
+        {
+            return Err(SavingsGoalError::InvalidMilestones);
+        }
+        let now = env.ledger().timestamp();
+        if new_target_date <= now {
+            return Err(SavingsGoalError::InvalidDueDate);
+        }
+        if new_target_date < goal.target_date
+            && Self::get_penalty_config(env.clone(), goal_id).is_some()
+        {
+            return Err(SavingsGoalError::InvalidDueDate);
+        }
+
+        Self::extend_instance_ttl(&env);
+        Self::accrue_interest(&env, &goal)?;
+        let amendment = GoalAmendment {
+            old_target_amount: goal.target_amount,
+            new_target_amount,
+            old_target_date: goal.target_date,
+            new_target_date,
+            timestamp: now,
+        };
+        let previously_completed = goal.current_amount >= goal.target_amount;
+        goal.target_amount = new_target_amount;
+        goal.target_date = new_target_date;
+        // Default milestones scale with the target; recount without events
+        let amounts = Self::milestone_amounts(&env, &goal);
+        goal.milestones_hit = amounts
+            .iter()
+            .filter(|amount| *amount <= goal.current_amount)
+            .count() as u32;
+        Self::save_goal(&env, &goal);
+
+        let storage = env.storage().persistent();
+        // A new deadline gets its own overdue warning
+        storage.remove(&(symbol_short!("OVERDUE"), goal_id));
+        let key = (symbol_short!("AMEND"), goal_id);
+        let mut history: Vec<GoalAmendment> = storage.get(&key).unwrap_or_else(|| Vec::new(&env));
+        if history.len() >= MAX_GOAL_AMENDMENTS {
+            history.remove(0);
+        }
+        history.push_back(amendment.clone());
+        storage.set(&key, &history);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+
+        Self::touch_owner(&env, &caller);
+        Self::append_audit(&env, symbol_short!("update"), &caller, true);
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GoalUpdated),
+            (goal_id, amendment.clone()),
+        );
+        if !previously_completed && goal.current_amount >= goal.target_amount {
+            Self::record_completion(&env, &goal);
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::GoalCompleted),
+                (goal_id, caller),
+            );
+            if Self::sweep_if_complete(&env, goal_id)? {
+                Self::cancel_goal_schedules(&env, goal_id);
+            }
+        }
+        Ok(amendment)
+    }
+
+    /// Changes made with `update_goal`, oldest first.
+    pub fn get_goal_amendments(env: Env, goal_id: u32) -> Vec<GoalAmendment> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("AMEND"), goal_id))
+            .unwrap_or_else(|| Vec::new(&env))
     }
 
-    pub fn add_to_goal(env: Env, caller: Address, goal_id: u32, amount: i128) -> i128 {
+    pub fn add_to_goal(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        amount: i128,
+    ) -> Result<i128, SavingsGoalError> {
         caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL);
+        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL)?;
 
         if amount <= 0 {
             Self::append_audit(&env, symbol_short!("add"), &caller, false);
-            panic!("Amount must be positive");
+            return Err(SavingsGoalError::InvalidAmount);
         }
 
         Self::extend_instance_ttl(&env);
 
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let mut goal = match goals.get(goal_id) {
+        let mut goal = match Self::load_goal(&env, goal_id) {
             Some(g) => g,
             None => {
                 Self::append_audit(&env, symbol_short!("add"), &caller, false);
-                panic!("Goal not found");
+                return Err(SavingsGoalError::GoalNotFound);
             }
         };
 
         if goal.owner != caller {
             Self::append_audit(&env, symbol_short!("add"), &caller, false);
-            panic!("Goal not found");
+            return Err(SavingsGoalError::Unauthorized);
         }
 
-        goal.current_amount = goal.current_amount.checked_add(amount).expect("overflow");
+        Self::accrue_interest(&env, &goal)?;
+        let previously_completed = goal.current_amount >= goal.target_amount;
+        goal.current_amount = goal
+            .current_amount
+            .checked_add(amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        if let Some(token) = &goal.token {
+            Self::deposit_tokens(&env, token, &caller, amount)?;
+        }
+        Self::apply_match(&env, &mut goal, amount)?;
         let new_total = goal.current_amount;
         let was_completed = new_total >= goal.target_amount;
-        let previously_completed = (new_total - amount) >= goal.target_amount;
-
-        goals.set(goal_id, goal.clone());
-        env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
+        Self::record_milestones(&env, &mut goal);
+        Self::save_goal(&env, &goal);
 
         let funds_event = FundsAddedEvent {
             goal_id,
@@ -463,6 +1107,8 @@ impl SavingsGoalContract {
             env.events().publish((GOAL_COMPLETED,), completed_event);
         }
 
+        Self::touch_owner(&env, &caller);
+        Self::update_stats(&env, &caller, |stats| stats.total_saved += amount);
         Self::append_audit(&env, symbol_short!("add"), &caller, true);
         env.events().publish(
             (symbol_short!("savings"), SavingsEvent::FundsAdded),
@@ -470,940 +1116,4352 @@ impl SavingsGoalContract {
         );
 
         if was_completed {
+            Self::record_completion(&env, &goal);
             env.events().publish(
                 (symbol_short!("savings"), SavingsEvent::GoalCompleted),
                 (goal_id, caller),
             );
+            if Self::sweep_if_complete(&env, goal_id)? {
+                Self::cancel_goal_schedules(&env, goal_id);
+            }
         }
 
-        new_total
+        Ok(new_total)
     }
 
     pub fn batch_add_to_goals(
         env: Env,
         caller: Address,
         contributions: Vec<ContributionItem>,
-    ) -> u32 {
+    ) -> Result<u32, SavingsGoalError> {
         caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL);
+        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL)?;
         if contributions.len() > MAX_BATCH_SIZE {
-            panic!("Batch too large");
+            return Err(SavingsGoalError::BatchTooLarge);
         }
-        let goals_map: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
         for item in contributions.iter() {
             if item.amount <= 0 {
-                panic!("Amount must be positive");
+                return Err(SavingsGoalError::InvalidAmount);
             }
-            let goal = goals_map.get(item.goal_id).expect("Goal not found");
+            let goal = Self::load_goal(&env, item.goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
             if goal.owner != caller {
-                panic!("Not owner of all goals");
+                return Err(SavingsGoalError::Unauthorized);
             }
         }
         Self::extend_instance_ttl(&env);
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
         let mut count = 0u32;
         for item in contributions.iter() {
-            let mut goal = goals.get(item.goal_id).expect("Goal not found");
-            if goal.owner != caller {
-                panic!("Batch validation failed");
-            }
-            goal.current_amount = goal
-                .current_amount
-                .checked_add(item.amount)
-                .expect("overflow");
-            let new_total = goal.current_amount;
-            let was_completed = new_total >= goal.target_amount;
-            let previously_completed = (new_total - item.amount) >= goal.target_amount;
-            goals.set(item.goal_id, goal.clone());
-            let funds_event = FundsAddedEvent {
-                goal_id: item.goal_id,
-                amount: item.amount,
-                new_total,
-                timestamp: env.ledger().timestamp(),
-            };
-            env.events().publish((FUNDS_ADDED,), funds_event);
-            if was_completed && !previously_completed {
-                let completed_event = GoalCompletedEvent {
-                    goal_id: item.goal_id,
-                    name: goal.name.clone(),
-                    final_amount: new_total,
-                    timestamp: env.ledger().timestamp(),
-                };
-                env.events().publish((GOAL_COMPLETED,), completed_event);
-            }
-            env.events().publish(
-                (symbol_short!("savings"), SavingsEvent::FundsAdded),
-                (item.goal_id, caller.clone(), item.amount),
-            );
-            if was_completed {
-                env.events().publish(
-                    (symbol_short!("savings"), SavingsEvent::GoalCompleted),
-                    (item.goal_id, caller.clone()),
-                );
-            }
+            Self::credit_goal(&env, &caller, item.goal_id, item.amount)?;
             count += 1;
         }
-        env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
+        Self::touch_owner(&env, &caller);
         env.events().publish(
             (symbol_short!("savings"), symbol_short!("batch_add")),
             (count, caller),
         );
-        count
+        Ok(count)
     }
 
-    pub fn withdraw_from_goal(env: Env, caller: Address, goal_id: u32, amount: i128) -> i128 {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::WITHDRAW);
+    /// Credit `amount` to one of `caller`'s goals, pulling tokens for
+    /// token-backed goals and running the completion action if it completes.
+    fn credit_goal(
+        env: &Env,
+        caller: &Address,
+        goal_id: u32,
+        amount: i128,
+    ) -> Result<i128, SavingsGoalError> {
+        Self::credit_goal_with(env, caller, goal_id, amount, true)
+    }
 
-        if amount <= 0 {
-            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
-            panic!("Amount must be positive");
+    /// `credit_goal` for tokens that may already be in the contract, as
+    /// when a voucher pays them in; `pull_tokens` is false then.
+    fn credit_goal_with(
+        env: &Env,
+        caller: &Address,
+        goal_id: u32,
+        amount: i128,
+        pull_tokens: bool,
+    ) -> Result<i128, SavingsGoalError> {
+        let mut goal = Self::load_goal(env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != *caller {
+            return Err(SavingsGoalError::Unauthorized);
         }
-
-        Self::extend_instance_ttl(&env);
-
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let mut goal = match goals.get(goal_id) {
-            Some(g) => g,
-            None => {
-                Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
-                panic!("Goal not found");
-            }
-        };
-
-        if goal.owner != caller {
-            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
-            panic!("Only the goal owner can withdraw funds");
+        Self::accrue_interest(env, &goal)?;
+        let previously_completed = goal.current_amount >= goal.target_amount;
+        goal.current_amount = goal
+            .current_amount
+            .checked_add(amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        match &goal.token {
+            Some(token) if pull_tokens => Self::deposit_tokens(env, token, caller, amount)?,
+            Some(token) => Self::adjust_liabilities(env, token, amount)?,
+            None => {}
         }
-
-        if goal.locked {
-            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
-            panic!("Cannot withdraw from a locked goal");
+        Self::apply_match(env, &mut goal, amount)?;
+        Self::record_milestones(env, &mut goal);
+        let new_total = goal.current_amount;
+        let was_completed = new_total >= goal.target_amount;
+        Self::save_goal(env, &goal);
+        let funds_event = FundsAddedEvent {
+            goal_id,
+            amount,
+            new_total,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.events().publish((FUNDS_ADDED,), funds_event);
+        if was_completed && !previously_completed {
+            let completed_event = GoalCompletedEvent {
+                goal_id,
+                name: goal.name.clone(),
+                final_amount: new_total,
+                timestamp: env.ledger().timestamp(),
+            };
+            env.events().publish((GOAL_COMPLETED,), completed_event);
         }
-
-        if let Some(unlock_date) = goal.unlock_date {
-            let current_time = env.ledger().timestamp();
-            if current_time < unlock_date {
-                Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
-                panic!("Goal is time-locked until unlock date");
+        Self::update_stats(env, caller, |stats| stats.total_saved += amount);
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::FundsAdded),
+            (goal_id, caller.clone(), amount),
+        );
+        if was_completed {
+            Self::record_completion(env, &goal);
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::GoalCompleted),
+                (goal_id, caller.clone()),
+            );
+            if Self::sweep_if_complete(env, goal_id)? {
+                Self::cancel_goal_schedules(env, goal_id);
             }
         }
+        Ok(new_total)
+    }
 
-        if amount > goal.current_amount {
-            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
-            panic!("Insufficient balance");
+    /// Fund a token goal from a gift voucher held by its owner.
+    ///
+    /// The vouchers contract sends `amount` into this contract if the
+    /// voucher is for savings and allows the goal's category. Voucher-funded
+    /// amounts cannot be withdrawn until the goal completes.
+    pub fn add_to_goal_with_voucher(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        voucher_id: u32,
+        amount: i128,
+    ) -> Result<i128, SavingsGoalError> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL)?;
+        if amount <= 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+        Self::extend_instance_ttl(&env);
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        let (vouchers, token) = match (Self::get_vouchers_contract(env.clone()), goal.token) {
+            (Some(vouchers), Some(token)) => (vouchers, token),
+            _ => return Err(SavingsGoalError::VoucherNotUsable),
+        };
+        match VouchersClient::new(&env, &vouchers).try_redeem(
+            &env.current_contract_address(),
+            &voucher_id,
+            &caller,
+            &token,
+            &amount,
+            &goal.category,
+            &env.current_contract_address(),
+        ) {
+            Ok(Ok(_)) => {}
+            _ => return Err(SavingsGoalError::VoucherNotUsable),
         }
 
-        goal.current_amount = goal.current_amount.checked_sub(amount).expect("underflow");
-        let new_amount = goal.current_amount;
-
-        goals.set(goal_id, goal);
+        // Lock before crediting, so a deposit that completes the goal frees it
+        let key = (symbol_short!("VCH_LOCK"), goal_id);
+        let locked = Self::get_voucher_locked(env.clone(), goal_id)
+            .checked_add(amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        env.storage().persistent().set(&key, &locked);
         env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
-
-        Self::append_audit(&env, symbol_short!("withdraw"), &caller, true);
+            .persistent()
+            .extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        let new_total = Self::credit_goal_with(&env, &caller, goal_id, amount, false)?;
+        Self::touch_owner(&env, &caller);
         env.events().publish(
-            (symbol_short!("savings"), SavingsEvent::FundsWithdrawn),
-            (goal_id, caller, amount),
+            (symbol_short!("savings"), SavingsEvent::VoucherRedeemed),
+            (goal_id, caller, voucher_id, amount),
         );
+        Ok(new_total)
+    }
 
-        new_amount
+    /// How much of a goal's balance came from vouchers and stays locked
+    /// until the goal completes.
+    pub fn get_voucher_locked(env: Env, goal_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("VCH_LOCK"), goal_id))
+            .unwrap_or(0)
     }
 
-    pub fn lock_goal(env: Env, caller: Address, goal_id: u32) -> bool {
+    /// Set the vouchers contract `add_to_goal_with_voucher` redeems from.
+    /// Pause admin only.
+    pub fn set_vouchers_contract(
+        env: Env,
+        caller: Address,
+        vouchers: Option<Address>,
+    ) -> Result<(), SavingsGoalError> {
         caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::LOCK);
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
+        if admin != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
         Self::extend_instance_ttl(&env);
+        match vouchers {
+            Some(vouchers) => env
+                .storage()
+                .instance()
+                .set(&symbol_short!("VCH_CTR"), &vouchers),
+            None => env.storage().instance().remove(&symbol_short!("VCH_CTR")),
+        }
+        Ok(())
+    }
 
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
+    pub fn get_vouchers_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("VCH_CTR"))
+    }
 
-        let mut goal = match goals.get(goal_id) {
-            Some(g) => g,
-            None => {
-                Self::append_audit(&env, symbol_short!("lock"), &caller, false);
-                panic!("Goal not found");
-            }
-        };
+    // -----------------------------------------------------------------------
+    // Interest
+    // -----------------------------------------------------------------------
 
-        if goal.owner != caller {
-            Self::append_audit(&env, symbol_short!("lock"), &caller, false);
-            panic!("Only the goal owner can lock this goal");
+    /// Set the yearly interest rate for goals in `category`. Pause admin only.
+    ///
+    /// Accrual is lazy, so a new rate also applies to the time since each
+    /// goal's balance last changed.
+    pub fn set_category_apy(
+        env: Env,
+        caller: Address,
+        category: Symbol,
+        apy_bps: u32,
+    ) -> Result<(), SavingsGoalError> {
+        caller.require_auth();
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
+        if admin != caller {
+            return Err(SavingsGoalError::Unauthorized);
         }
-
-        goal.locked = true;
-        goals.set(goal_id, goal);
-        env.storage()
+        if apy_bps > MAX_APY_BPS {
+            return Err(SavingsGoalError::InvalidRate);
+        }
+        let mut rates: Map<Symbol, u32> = env
+            .storage()
             .instance()
-            .set(&symbol_short!("GOALS"), &goals);
-
-        Self::append_audit(&env, symbol_short!("lock"), &caller, true);
+            .get(&symbol_short!("APY"))
+            .unwrap_or_else(|| Map::new(&env));
+        rates.set(category.clone(), apy_bps);
+        env.storage().instance().set(&symbol_short!("APY"), &rates);
         env.events().publish(
-            (symbol_short!("savings"), SavingsEvent::GoalLocked),
-            (goal_id, caller),
+            (symbol_short!("savings"), SavingsEvent::ApyUpdated),
+            (category, apy_bps),
         );
+        Ok(())
+    }
 
-        true
+    pub fn get_category_apy(env: Env, category: Symbol) -> u32 {
+        env.storage()
+            .instance()
+            .get::<_, Map<Symbol, u32>>(&symbol_short!("APY"))
+            .and_then(|rates| rates.get(category))
+            .unwrap_or(0)
     }
 
-    pub fn unlock_goal(env: Env, caller: Address, goal_id: u32) -> bool {
-        caller.require_auth();
-        Self::require_not_paused(&env, pause_functions::UNLOCK);
-        Self::extend_instance_ttl(&env);
+    /// Add tokens to the pool that pays interest on goals holding `token`.
+    pub fn fund_interest_pool(
+        env: Env,
+        funder: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<InterestPool, SavingsGoalError> {
+        funder.require_auth();
+        if amount <= 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+        Self::deposit_tokens(&env, &token, &funder, amount)?;
+        let mut pool = Self::get_interest_pool(env.clone(), token.clone());
+        pool.available = pool
+            .available
+            .checked_add(amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        Self::save_interest_pool(&env, &token, &pool);
+        Ok(pool)
+    }
 
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
+    pub fn get_interest_pool(env: Env, token: Address) -> InterestPool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("INT_POOL"), token))
+            .unwrap_or(InterestPool {
+                available: 0,
+                distributed: 0,
+            })
+    }
 
-        let mut goal = match goals.get(goal_id) {
-            Some(g) => g,
-            None => {
-                Self::append_audit(&env, symbol_short!("unlock"), &caller, false);
-                panic!("Goal not found");
-            }
-        };
+    /// Interest earned by a goal and not yet claimed, up to now.
+    pub fn get_accrued_interest(env: Env, goal_id: u32) -> i128 {
+        match Self::load_goal(&env, goal_id) {
+            Some(goal) => Self::pending_interest(&env, &goal)
+                .map(|state| state.accrued)
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
 
-        if goal.owner != caller {
-            Self::append_audit(&env, symbol_short!("unlock"), &caller, false);
-            panic!("Only the goal owner can unlock this goal");
+    /// Credit a goal's accrued interest from its token's interest pool.
+    ///
+    /// Anyone may call this since the interest only ever goes to the goal.
+    /// If the pool is short, the rest stays accrued for a later claim.
+    /// Returns the amount credited.
+    pub fn claim_interest(env: Env, goal_id: u32) -> Result<i128, SavingsGoalError> {
+        let mut goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        let mut state = Self::accrue_interest(&env, &goal)?;
+        let Some(token) = goal.token.clone() else {
+            return Ok(0);
+        };
+        let mut pool = Self::get_interest_pool(env.clone(), token.clone());
+        let paid = state.accrued.min(pool.available);
+        if paid <= 0 {
+            return Ok(0);
         }
 
-        goal.locked = false;
-        goals.set(goal_id, goal);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
+        pool.available -= paid;
+        pool.distributed = pool
+            .distributed
+            .checked_add(paid)
+            .ok_or(SavingsGoalError::Overflow)?;
+        Self::save_interest_pool(&env, &token, &pool);
+        state.accrued -= paid;
+        Self::save_interest_state(&env, goal_id, &state);
+
+        let previously_completed = goal.current_amount >= goal.target_amount;
+        goal.current_amount = goal
+            .current_amount
+            .checked_add(paid)
+            .ok_or(SavingsGoalError::Overflow)?;
+        Self::record_milestones(&env, &mut goal);
+        Self::save_goal(&env, &goal);
 
-        Self::append_audit(&env, symbol_short!("unlock"), &caller, true);
         env.events().publish(
-            (symbol_short!("savings"), SavingsEvent::GoalUnlocked),
-            (goal_id, caller),
+            (symbol_short!("savings"), SavingsEvent::InterestClaimed),
+            (goal_id, paid),
         );
-
-        true
+        if !previously_completed && goal.current_amount >= goal.target_amount {
+            Self::record_completion(&env, &goal);
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::GoalCompleted),
+                (goal_id, goal.owner),
+            );
+            if Self::sweep_if_complete(&env, goal_id)? {
+                Self::cancel_goal_schedules(&env, goal_id);
+            }
+        }
+        Ok(paid)
     }
 
-    pub fn get_goal(env: Env, goal_id: u32) -> Option<SavingsGoal> {
-        let goals: Map<u32, SavingsGoal> = env
+    /// Interest state of `goal` brought up to the current ledger time.
+    ///
+    /// Only token-backed goals earn interest, since the pool pays in tokens.
+    fn pending_interest(env: &Env, goal: &SavingsGoal) -> Result<InterestState, SavingsGoalError> {
+        let now = env.ledger().timestamp();
+        let mut state: InterestState = env
             .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-        goals.get(goal_id)
+            .persistent()
+            .get(&(symbol_short!("INTEREST"), goal.id))
+            .unwrap_or(InterestState {
+                accrued: 0,
+                last_accrual: now,
+            });
+        let elapsed = now.saturating_sub(state.last_accrual) as i128;
+        let apy_bps = Self::get_category_apy(env.clone(), goal.category.clone()) as i128;
+        if goal.token.is_some() && goal.current_amount > 0 && elapsed > 0 && apy_bps > 0 {
+            let earned = goal
+                .current_amount
+                .checked_mul(apy_bps)
+                .and_then(|v| v.checked_mul(elapsed))
+                .ok_or(SavingsGoalError::Overflow)?
+                / (10_000 * SECONDS_PER_YEAR);
+            state.accrued = state
+                .accrued
+                .checked_add(earned)
+                .ok_or(SavingsGoalError::Overflow)?;
+        }
+        state.last_accrual = now;
+        Ok(state)
+    }
+
+    /// Settle interest on the goal's current balance. Called before every
+    /// balance or category change.
+    fn accrue_interest(env: &Env, goal: &SavingsGoal) -> Result<InterestState, SavingsGoalError> {
+        let state = Self::pending_interest(env, goal)?;
+        Self::save_interest_state(env, goal.id, &state);
+        Ok(state)
+    }
+
+    fn save_interest_state(env: &Env, goal_id: u32, state: &InterestState) {
+        let key = (symbol_short!("INTEREST"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, state);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+    }
+
+    fn save_interest_pool(env: &Env, token: &Address, pool: &InterestPool) {
+        let key = (symbol_short!("INT_POOL"), token.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, pool);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
     }
 
     // -----------------------------------------------------------------------
-    // PAGINATED LIST QUERIES
+    // Matching contributions
     // -----------------------------------------------------------------------
 
-    /// Get a page of savings goals for `owner`.
+    /// Pledge to match contributions to a goal.
     ///
-    /// # Arguments
-    /// * `owner`  – whose goals to return
-    /// * `cursor` – start after this goal ID (pass 0 for the first page)
-    /// * `limit`  – max items per page (0 → DEFAULT_PAGE_LIMIT, capped at MAX_PAGE_LIMIT)
-    ///
-    /// # Returns
-    /// `GoalPage { items, next_cursor, count }`.
-    /// `next_cursor == 0` means no more pages.
-    pub fn get_goals(env: Env, owner: Address, cursor: u32, limit: u32) -> GoalPage {
-        let limit = Self::clamp_limit(limit);
-        let goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let mut result = Vec::new(&env);
-        let mut next_cursor: u32 = 0;
-        let mut collected: u32 = 0;
-
-        for (id, goal) in goals.iter() {
-            if id <= cursor {
-                continue;
-            }
-            if goal.owner != owner {
-                continue;
-            }
-            if collected < limit {
-                result.push_back(goal);
-                collected += 1;
-                next_cursor = id; // track last returned ID
-            } else {
-                break;
-            }
+    /// Every owner contribution is matched at `ratio_bps` (10_000 = 1:1) until
+    /// `cap` has been paid out. The full cap is escrowed up front; for
+    /// token-backed goals the matcher transfers it into the contract.
+    pub fn pledge_match(
+        env: Env,
+        matcher: Address,
+        goal_id: u32,
+        ratio_bps: u32,
+        cap: i128,
+    ) -> Result<MatchPledge, SavingsGoalError> {
+        matcher.require_auth();
+        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL)?;
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner == matcher {
+            return Err(SavingsGoalError::InvalidMatch);
+        }
+        if ratio_bps == 0 || ratio_bps > MAX_MATCH_RATIO_BPS {
+            return Err(SavingsGoalError::InvalidMatch);
+        }
+        if cap <= 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+        let key = (symbol_short!("MATCH"), goal_id);
+        if env.storage().persistent().has(&key) {
+            return Err(SavingsGoalError::MatchAlreadyExists);
         }
 
-        // If we didn't fill the page, there are no more items
-        if collected < limit {
-            next_cursor = 0;
+        if let Some(token) = &goal.token {
+            Self::deposit_tokens(&env, token, &matcher, cap)?;
         }
+        let pledge = MatchPledge {
+            matcher: matcher.clone(),
+            ratio_bps,
+            cap,
+            matched: 0,
+        };
+        let storage = env.storage().persistent();
+        storage.set(&key, &pledge);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
 
-        GoalPage {
-            items: result,
-            next_cursor,
-            count: collected,
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::MatchPledged),
+            (goal_id, matcher, ratio_bps, cap),
+        );
+        Ok(pledge)
+    }
+
+    /// Withdraw a pledge, returning the unused escrow to the matcher.
+    /// Amounts already matched stay in the goal.
+    pub fn cancel_match(
+        env: Env,
+        matcher: Address,
+        goal_id: u32,
+    ) -> Result<i128, SavingsGoalError> {
+        matcher.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        let pledge =
+            Self::get_match(env.clone(), goal_id).ok_or(SavingsGoalError::MatchNotFound)?;
+        if pledge.matcher != matcher {
+            return Err(SavingsGoalError::Unauthorized);
         }
+        Self::release_match(&env, &goal)
     }
 
-    /// Backward-compatible: returns ALL goals for owner in one Vec.
-    /// Prefer the paginated `get_goals` for production use.
-    pub fn get_all_goals(env: Env, owner: Address) -> Vec<SavingsGoal> {
-        let goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let mut result = Vec::new(&env);
-        for (_, goal) in goals.iter() {
-            if goal.owner == owner {
-                result.push_back(goal);
-            }
+    /// The goal's pledge; `matched` against `cap` is its utilization.
+    pub fn get_match(env: Env, goal_id: u32) -> Option<MatchPledge> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("MATCH"), goal_id))
+    }
+
+    /// Add the matcher's share of `contribution` to the goal.
+    fn apply_match(
+        env: &Env,
+        goal: &mut SavingsGoal,
+        contribution: i128,
+    ) -> Result<i128, SavingsGoalError> {
+        let key = (symbol_short!("MATCH"), goal.id);
+        let mut pledge: MatchPledge = match env.storage().persistent().get(&key) {
+            Some(p) => p,
+            None => return Ok(0),
+        };
+        let owed = contribution
+            .checked_mul(pledge.ratio_bps as i128)
+            .ok_or(SavingsGoalError::Overflow)?
+            / 10_000;
+        let matched = owed.min(pledge.cap - pledge.matched);
+        if matched <= 0 {
+            return Ok(0);
         }
-        result
+        // Escrowed tokens are already held, so only the goal balance moves
+        goal.current_amount = goal
+            .current_amount
+            .checked_add(matched)
+            .ok_or(SavingsGoalError::Overflow)?;
+        pledge.matched += matched;
+        env.storage().persistent().set(&key, &pledge);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::ContributionMatched),
+            (goal.id, pledge.matcher, matched),
+        );
+        Ok(matched)
     }
 
-    pub fn is_goal_completed(env: Env, goal_id: u32) -> bool {
-        let storage = env.storage().instance();
-        let goals: Map<u32, SavingsGoal> = storage
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or(Map::new(&env));
-        if let Some(goal) = goals.get(goal_id) {
-            goal.current_amount >= goal.target_amount
-        } else {
-            false
+    /// Drop the goal's pledge and refund whatever escrow was not used.
+    fn release_match(env: &Env, goal: &SavingsGoal) -> Result<i128, SavingsGoalError> {
+        let key = (symbol_short!("MATCH"), goal.id);
+        let pledge: MatchPledge = match env.storage().persistent().get(&key) {
+            Some(p) => p,
+            None => return Ok(0),
+        };
+        env.storage().persistent().remove(&key);
+        let unused = pledge.cap - pledge.matched;
+        if unused > 0 {
+            if let Some(token) = &goal.token {
+                Self::release_tokens(env, token, &pledge.matcher, unused)?;
+            }
         }
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::MatchReleased),
+            (goal.id, pledge.matcher, unused),
+        );
+        Ok(unused)
     }
 
     // -----------------------------------------------------------------------
-    // Snapshot, audit, schedule
+    // Statistics
     // -----------------------------------------------------------------------
 
-    pub fn get_nonce(env: Env, address: Address) -> u64 {
-        let nonces: Option<Map<Address, u64>> =
-            env.storage().instance().get(&symbol_short!("NONCES"));
-        nonces
-            .as_ref()
-            .and_then(|m: &Map<Address, u64>| m.get(address))
-            .unwrap_or(0)
+    /// Lifetime savings totals and the scheduled-deposit streak for `owner`.
+    pub fn get_savings_stats(env: Env, owner: Address) -> SavingsStats {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("STATS"), owner))
+            .unwrap_or_default()
     }
 
-    pub fn export_snapshot(env: Env, caller: Address) -> GoalsExportSnapshot {
-        caller.require_auth();
-        let goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-        let next_id = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("NEXT_ID"))
-            .unwrap_or(0u32);
-        let mut list = Vec::new(&env);
-        for i in 1..=next_id {
-            if let Some(g) = goals.get(i) {
-                list.push_back(g);
-            }
-        }
-        let checksum = Self::compute_goals_checksum(SNAPSHOT_VERSION, next_id, &list);
-        GoalsExportSnapshot {
-            version: SNAPSHOT_VERSION,
-            checksum,
-            next_id,
-            goals: list,
+    fn update_stats(env: &Env, owner: &Address, update: impl FnOnce(&mut SavingsStats)) {
+        let key = (symbol_short!("STATS"), owner.clone());
+        let storage = env.storage().persistent();
+        let mut stats: SavingsStats = storage.get(&key).unwrap_or_default();
+        update(&mut stats);
+        storage.set(&key, &stats);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+    }
+
+    /// Count `goal` towards its owner's completions the first time it hits
+    /// its target; refilling after a withdrawal does not count again.
+    /// Voucher funds are free to leave once the goal is reached.
+    fn record_completion(env: &Env, goal: &SavingsGoal) {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("VCH_LOCK"), goal.id));
+        let key = (symbol_short!("COMPLETED"), goal.id);
+        if env.storage().persistent().has(&key) {
+            return;
         }
+        env.storage().persistent().set(&key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::update_stats(env, &goal.owner, |stats| stats.goals_completed += 1);
     }
 
-    pub fn import_snapshot(
-        env: Env,
-        caller: Address,
-        nonce: u64,
-        snapshot: GoalsExportSnapshot,
-    ) -> bool {
-        caller.require_auth();
-        Self::require_nonce(&env, &caller, nonce);
+    // -----------------------------------------------------------------------
+    // Round-ups
+    // -----------------------------------------------------------------------
 
-        if snapshot.version != SNAPSHOT_VERSION {
-            Self::append_audit(&env, symbol_short!("import"), &caller, false);
-            panic!("Unsupported snapshot version");
+    /// Save the change from a purchase: add the difference between
+    /// `purchase_amount` and the next multiple of `round_to` to a goal.
+    ///
+    /// Token-backed goals pull the round-up from the owner like `add_to_goal`.
+    /// Returns the amount saved, which is 0 for an already round purchase.
+    pub fn record_roundup(
+        env: Env,
+        owner: Address,
+        goal_id: u32,
+        purchase_amount: i128,
+        round_to: i128,
+    ) -> Result<i128, SavingsGoalError> {
+        owner.require_auth();
+        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL)?;
+        if purchase_amount <= 0 || round_to <= 1 {
+            return Err(SavingsGoalError::InvalidAmount);
         }
-        let expected =
-            Self::compute_goals_checksum(snapshot.version, snapshot.next_id, &snapshot.goals);
-        if snapshot.checksum != expected {
-            Self::append_audit(&env, symbol_short!("import"), &caller, false);
-            panic!("Snapshot checksum mismatch");
+        let roundup = (round_to - purchase_amount % round_to) % round_to;
+        if roundup == 0 {
+            return Ok(0);
         }
-
         Self::extend_instance_ttl(&env);
-        let mut goals: Map<u32, SavingsGoal> = Map::new(&env);
-        for g in snapshot.goals.iter() {
-            goals.set(g.id, g);
-        }
-        env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("NEXT_ID"), &snapshot.next_id);
+        Self::credit_goal(&env, &owner, goal_id, roundup)?;
 
-        Self::increment_nonce(&env, &caller);
-        Self::append_audit(&env, symbol_short!("import"), &caller, true);
-        true
-    }
+        let month = Self::month_of(env.ledger().timestamp());
+        let key = (symbol_short!("ROUNDUP"), owner.clone(), month);
+        let storage = env.storage().persistent();
+        let mut stats: RoundupStats = storage.get(&key).unwrap_or(RoundupStats {
+            month,
+            count: 0,
+            purchases: 0,
+            saved: 0,
+        });
+        stats.count += 1;
+        stats.purchases = stats
+            .purchases
+            .checked_add(purchase_amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        stats.saved = stats
+            .saved
+            .checked_add(roundup)
+            .ok_or(SavingsGoalError::Overflow)?;
+        storage.set(&key, &stats);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::touch_owner(&env, &owner);
 
-    pub fn get_audit_log(env: Env, from_index: u32, limit: u32) -> Vec<AuditEntry> {
-        let log: Option<Vec<AuditEntry>> = env.storage().instance().get(&symbol_short!("AUDIT"));
-        let log = log.unwrap_or_else(|| Vec::new(&env));
-        let len = log.len();
-        let cap = MAX_AUDIT_ENTRIES.min(limit);
-        let mut out = Vec::new(&env);
-        if from_index >= len {
-            return out;
-        }
-        let end = (from_index + cap).min(len);
-        for i in from_index..end {
-            if let Some(entry) = log.get(i) {
-                out.push_back(entry);
-            }
-        }
-        out
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::RoundupRecorded),
+            (owner, goal_id, purchase_amount, roundup),
+        );
+        Ok(roundup)
     }
 
-    pub fn set_time_lock(env: Env, caller: Address, goal_id: u32, unlock_date: u64) -> bool {
-        caller.require_auth();
-        Self::extend_instance_ttl(&env);
+    /// Round-up totals for a calendar month given as `YYYYMM` (UTC).
+    pub fn get_roundup_stats(env: Env, owner: Address, month: u32) -> RoundupStats {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ROUNDUP"), owner, month))
+            .unwrap_or(RoundupStats {
+                month,
+                count: 0,
+                purchases: 0,
+                saved: 0,
+            })
+    }
 
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
+    /// Calendar month of a Unix timestamp as `YYYYMM`, in UTC.
+    fn month_of(timestamp: u64) -> u32 {
+        // Civil-from-days conversion on the proleptic Gregorian calendar
+        let days = (timestamp / 86400) as i64 + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year * 100 + month) as u32
+    }
 
-        let mut goal = match goals.get(goal_id) {
-            Some(g) => g,
-            None => {
-                Self::append_audit(&env, symbol_short!("timelock"), &caller, false);
-                panic!("Goal not found");
-            }
-        };
+    // -----------------------------------------------------------------------
+    // Auto-allocation
+    // -----------------------------------------------------------------------
 
+    /// Set how `allocate_across_goals` ranks and weights this goal.
+    pub fn set_goal_allocation(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        priority: u32,
+        weight: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
         if goal.owner != caller {
-            Self::append_audit(&env, symbol_short!("timelock"), &caller, false);
-            panic!("Only the goal owner can set time-lock");
-        }
-
-        let current_time = env.ledger().timestamp();
-        if unlock_date <= current_time {
-            Self::append_audit(&env, symbol_short!("timelock"), &caller, false);
-            panic!("Unlock date must be in the future");
+            return Err(SavingsGoalError::Unauthorized);
         }
+        let key = (symbol_short!("ALLOC"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, &AllocationRule { priority, weight });
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::touch_owner(&env, &caller);
+        Ok(true)
+    }
 
-        goal.unlock_date = Some(unlock_date);
-        goals.set(goal_id, goal);
+    /// Rule for a goal; goals without one have priority 0 and weight 1.
+    pub fn get_goal_allocation(env: Env, goal_id: u32) -> AllocationRule {
         env.storage()
-            .instance()
-            .set(&symbol_short!("GOALS"), &goals);
+            .persistent()
+            .get(&(symbol_short!("ALLOC"), goal_id))
+            .unwrap_or(AllocationRule {
+                priority: 0,
+                weight: 1,
+            })
+    }
 
-        Self::append_audit(&env, symbol_short!("timelock"), &caller, true);
+    pub fn set_allocation_mode(env: Env, owner: Address, mode: AllocationMode) -> bool {
+        owner.require_auth();
+        let key = (symbol_short!("ALLOC_MOD"), owner.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, &mode);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::touch_owner(&env, &owner);
         true
     }
 
-    pub fn create_savings_schedule(
+    pub fn get_allocation_mode(env: Env, owner: Address) -> AllocationMode {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ALLOC_MOD"), owner))
+            .unwrap_or(AllocationMode::Priority)
+    }
+
+    /// Spread one deposit across the owner's unfinished goals.
+    ///
+    /// No goal is filled past its target, so anything left once every goal is
+    /// full stays with the owner. Returns the amount credited to each goal.
+    pub fn allocate_across_goals(
         env: Env,
         owner: Address,
-        goal_id: u32,
         amount: i128,
-        next_due: u64,
-        interval: u64,
-    ) -> u32 {
+    ) -> Result<Vec<GoalAllocation>, SavingsGoalError> {
         owner.require_auth();
-
+        Self::require_not_paused(&env, pause_functions::ADD_TO_GOAL)?;
         if amount <= 0 {
-            panic!("Amount must be positive");
+            return Err(SavingsGoalError::InvalidAmount);
         }
+        Self::extend_instance_ttl(&env);
 
-        let goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let goal = goals.get(goal_id).expect("Goal not found");
-
-        if goal.owner != owner {
-            panic!("Only the goal owner can create schedules");
+        // Unfinished goals, highest priority first and oldest first on ties
+        let mut ids: Vec<u32> = Vec::new(&env);
+        let mut rules: Vec<AllocationRule> = Vec::new(&env);
+        let mut room: Vec<i128> = Vec::new(&env);
+        for goal_id in Self::owner_goal_ids(&env, &owner).iter() {
+            let goal = match Self::load_goal(&env, goal_id) {
+                Some(g) => g,
+                None => continue,
+            };
+            let remaining = goal.target_amount - goal.current_amount;
+            if remaining <= 0 {
+                continue;
+            }
+            let rule = Self::get_goal_allocation(env.clone(), goal_id);
+            let mut pos = ids.len();
+            for i in 0..ids.len() {
+                if rule.priority > rules.get(i).unwrap().priority {
+                    pos = i;
+                    break;
+                }
+            }
+            ids.insert(pos, goal_id);
+            rules.insert(pos, rule);
+            room.insert(pos, remaining);
         }
 
-        let current_time = env.ledger().timestamp();
-        if next_due <= current_time {
-            panic!("Next due date must be in the future");
+        let mut shares: Vec<i128> = Vec::new(&env);
+        for _ in 0..ids.len() {
+            shares.push_back(0);
+        }
+        let mut left = amount;
+
+        if Self::get_allocation_mode(env.clone(), owner.clone()) == AllocationMode::Proportional {
+            let total_weight: i128 = rules.iter().map(|r| r.weight as i128).sum();
+            if total_weight > 0 {
+                for i in 0..ids.len() {
+                    let weight = rules.get(i).unwrap().weight as i128;
+                    let share = (amount
+                        .checked_mul(weight)
+                        .ok_or(SavingsGoalError::Overflow)?
+                        / total_weight)
+                        .min(room.get(i).unwrap());
+                    shares.set(i, share);
+                    left -= share;
+                }
+            }
         }
 
-        Self::extend_instance_ttl(&env);
-
-        let mut schedules: Map<u32, SavingsSchedule> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("SAV_SCH"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let next_schedule_id = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("NEXT_SSCH"))
-            .unwrap_or(0u32)
-            + 1;
-
-        let schedule = SavingsSchedule {
-            id: next_schedule_id,
-            owner: owner.clone(),
-            goal_id,
-            amount,
-            next_due,
-            interval,
-            recurring: interval > 0,
-            active: true,
-            created_at: current_time,
-            last_executed: None,
-            missed_count: 0,
-        };
+        // Priority order takes the whole amount, or what rounding and
+        // capped goals left over from the proportional pass
+        for i in 0..ids.len() {
+            if left == 0 {
+                break;
+            }
+            let share = shares.get(i).unwrap();
+            let extra = left.min(room.get(i).unwrap() - share);
+            shares.set(i, share + extra);
+            left -= extra;
+        }
 
-        schedules.set(next_schedule_id, schedule);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("SAV_SCH"), &schedules);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("NEXT_SSCH"), &next_schedule_id);
+        let mut allocations = Vec::new(&env);
+        for i in 0..ids.len() {
+            let share = shares.get(i).unwrap();
+            if share > 0 {
+                let goal_id = ids.get(i).unwrap();
+                Self::credit_goal(&env, &owner, goal_id, share)?;
+                allocations.push_back(GoalAllocation {
+                    goal_id,
+                    amount: share,
+                });
+            }
+        }
 
+        Self::touch_owner(&env, &owner);
+        Self::append_audit(&env, symbol_short!("allocate"), &owner, true);
         env.events().publish(
-            (symbol_short!("savings"), SavingsEvent::ScheduleCreated),
-            (next_schedule_id, owner),
+            (symbol_short!("savings"), SavingsEvent::FundsAllocated),
+            (owner, amount - left, left),
         );
-
-        next_schedule_id
+        Ok(allocations)
     }
 
-    pub fn modify_savings_schedule(
+    pub fn withdraw_from_goal(
         env: Env,
         caller: Address,
-        schedule_id: u32,
+        goal_id: u32,
         amount: i128,
-        next_due: u64,
-        interval: u64,
-    ) -> bool {
+    ) -> Result<i128, SavingsGoalError> {
         caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::WITHDRAW)?;
 
         if amount <= 0 {
-            panic!("Amount must be positive");
-        }
-
-        let current_time = env.ledger().timestamp();
-        if next_due <= current_time {
-            panic!("Next due date must be in the future");
+            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+            return Err(SavingsGoalError::InvalidAmount);
         }
 
         Self::extend_instance_ttl(&env);
 
-        let mut schedules: Map<u32, SavingsSchedule> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("SAV_SCH"))
-            .unwrap_or_else(|| Map::new(&env));
+        let mut goal = match Self::load_goal(&env, goal_id) {
+            Some(g) => g,
+            None => {
+                Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+                return Err(SavingsGoalError::GoalNotFound);
+            }
+        };
 
-        let mut schedule = schedules.get(schedule_id).expect("Schedule not found");
+        let guardian = Self::get_goal_guardian(env.clone(), goal_id);
+        let by_guardian = guardian.as_ref().is_some_and(|g| g.guardian == caller);
+        if goal.owner != caller && !by_guardian {
+            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if guardian.is_some_and(|g| amount > g.threshold) && !by_guardian {
+            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+            return Err(SavingsGoalError::GuardianRequired);
+        }
 
-        if schedule.owner != caller {
-            panic!("Only the schedule owner can modify it");
+        if goal.locked {
+            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+            return Err(SavingsGoalError::GoalLocked);
         }
 
-        schedule.amount = amount;
-        schedule.next_due = next_due;
-        schedule.interval = interval;
-        schedule.recurring = interval > 0;
+        if let Some(unlock_date) = goal.unlock_date {
+            let current_time = env.ledger().timestamp();
+            if current_time < unlock_date {
+                Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+                return Err(SavingsGoalError::GoalLocked);
+            }
+        }
 
-        schedules.set(schedule_id, schedule);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("SAV_SCH"), &schedules);
+        if amount > goal.current_amount {
+            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+            return Err(SavingsGoalError::InsufficientBalance);
+        }
+        if goal.current_amount - amount < Self::get_voucher_locked(env.clone(), goal_id) {
+            Self::append_audit(&env, symbol_short!("withdraw"), &caller, false);
+            return Err(SavingsGoalError::GoalLocked);
+        }
+
+        Self::accrue_interest(&env, &goal)?;
+        goal.current_amount = goal
+            .current_amount
+            .checked_sub(amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        let new_amount = goal.current_amount;
+
+        let penalty = Self::charge_penalty(&env, &goal, amount)?;
+        if let Some(token) = &goal.token {
+            Self::release_tokens(&env, token, &goal.owner, amount - penalty)?;
+        }
+        Self::save_goal(&env, &goal);
 
+        Self::touch_owner(&env, &goal.owner);
+        Self::update_stats(&env, &goal.owner, |stats| stats.total_withdrawn += amount);
+        Self::append_audit(&env, symbol_short!("withdraw"), &caller, true);
         env.events().publish(
-            (symbol_short!("savings"), SavingsEvent::ScheduleModified),
-            (schedule_id, caller),
+            (symbol_short!("savings"), SavingsEvent::FundsWithdrawn),
+            (goal_id, caller, amount),
         );
 
-        true
+        Ok(new_amount)
     }
 
-    pub fn cancel_savings_schedule(env: Env, caller: Address, schedule_id: u32) -> bool {
+    pub fn lock_goal(env: Env, caller: Address, goal_id: u32) -> Result<bool, SavingsGoalError> {
         caller.require_auth();
-
+        Self::require_not_paused(&env, pause_functions::LOCK)?;
         Self::extend_instance_ttl(&env);
 
-        let mut schedules: Map<u32, SavingsSchedule> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("SAV_SCH"))
-            .unwrap_or_else(|| Map::new(&env));
-
-        let mut schedule = schedules.get(schedule_id).expect("Schedule not found");
+        let mut goal = match Self::load_goal(&env, goal_id) {
+            Some(g) => g,
+            None => {
+                Self::append_audit(&env, symbol_short!("lock"), &caller, false);
+                return Err(SavingsGoalError::GoalNotFound);
+            }
+        };
 
-        if schedule.owner != caller {
-            panic!("Only the schedule owner can cancel it");
+        if goal.owner != caller {
+            Self::append_audit(&env, symbol_short!("lock"), &caller, false);
+            return Err(SavingsGoalError::Unauthorized);
         }
 
-        schedule.active = false;
-
-        schedules.set(schedule_id, schedule);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("SAV_SCH"), &schedules);
+        goal.locked = true;
+        Self::save_goal(&env, &goal);
 
+        Self::touch_owner(&env, &caller);
+        Self::append_audit(&env, symbol_short!("lock"), &caller, true);
         env.events().publish(
-            (symbol_short!("savings"), SavingsEvent::ScheduleCancelled),
-            (schedule_id, caller),
+            (symbol_short!("savings"), SavingsEvent::GoalLocked),
+            (goal_id, caller),
         );
 
-        true
+        Ok(true)
     }
 
-    pub fn execute_due_savings_schedules(env: Env) -> Vec<u32> {
+    pub fn unlock_goal(env: Env, caller: Address, goal_id: u32) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::UNLOCK)?;
         Self::extend_instance_ttl(&env);
 
-        let current_time = env.ledger().timestamp();
-        let mut executed = Vec::new(&env);
+        let mut goal = match Self::load_goal(&env, goal_id) {
+            Some(g) => g,
+            None => {
+                Self::append_audit(&env, symbol_short!("unlock"), &caller, false);
+                return Err(SavingsGoalError::GoalNotFound);
+            }
+        };
 
-        let mut schedules: Map<u32, SavingsSchedule> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("SAV_SCH"))
-            .unwrap_or_else(|| Map::new(&env));
+        match Self::get_goal_guardian(env.clone(), goal_id) {
+            Some(g) if g.guardian != caller => {
+                Self::append_audit(&env, symbol_short!("unlock"), &caller, false);
+                return Err(SavingsGoalError::GuardianRequired);
+            }
+            None if goal.owner != caller => {
+                Self::append_audit(&env, symbol_short!("unlock"), &caller, false);
+                return Err(SavingsGoalError::Unauthorized);
+            }
+            _ => {}
+        }
 
-        let mut goals: Map<u32, SavingsGoal> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("GOALS"))
-            .unwrap_or_else(|| Map::new(&env));
+        goal.locked = false;
+        Self::save_goal(&env, &goal);
 
-        for (schedule_id, mut schedule) in schedules.iter() {
-            if !schedule.active || schedule.next_due > current_time {
-                continue;
-            }
+        Self::touch_owner(&env, &goal.owner);
+        Self::append_audit(&env, symbol_short!("unlock"), &caller, true);
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GoalUnlocked),
+            (goal_id, caller),
+        );
 
-            if let Some(mut goal) = goals.get(schedule.goal_id) {
-                goal.current_amount = goal
-                    .current_amount
-                    .checked_add(schedule.amount)
-                    .expect("overflow");
+        Ok(true)
+    }
 
-                let is_completed = goal.current_amount >= goal.target_amount;
-                goals.set(schedule.goal_id, goal.clone());
+    /// Close a goal and move it to the owner's archive.
+    ///
+    /// A goal with a remaining balance can only be closed by sweeping it to
+    /// `destination`; token-backed goals transfer the tokens there. Sweeping
+    /// follows the same lock rules as `withdraw_from_goal`. Schedules feeding
+    /// the goal are cancelled.
+    pub fn close_goal(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        destination: Option<Address>,
+    ) -> Result<ArchivedGoal, SavingsGoalError> {
+        caller.require_auth();
+        Self::require_not_paused(&env, pause_functions::WITHDRAW)?;
 
-                env.events().publish(
-                    (symbol_short!("savings"), SavingsEvent::FundsAdded),
-                    (schedule.goal_id, goal.owner.clone(), schedule.amount),
-                );
+        let goal = match Self::load_goal(&env, goal_id) {
+            Some(g) => g,
+            None => {
+                Self::append_audit(&env, symbol_short!("close"), &caller, false);
+                return Err(SavingsGoalError::GoalNotFound);
+            }
+        };
+        if goal.owner != caller {
+            Self::append_audit(&env, symbol_short!("close"), &caller, false);
+            return Err(SavingsGoalError::Unauthorized);
+        }
 
-                if is_completed {
-                    env.events().publish(
-                        (symbol_short!("savings"), SavingsEvent::GoalCompleted),
-                        (schedule.goal_id, goal.owner),
-                    );
+        let swept_amount = goal.current_amount;
+        if swept_amount > 0 {
+            let to = match &destination {
+                Some(to) => to,
+                None => {
+                    Self::append_audit(&env, symbol_short!("close"), &caller, false);
+                    return Err(SavingsGoalError::GoalNotEmpty);
                 }
+            };
+            let time_locked = goal
+                .unlock_date
+                .is_some_and(|at| env.ledger().timestamp() < at);
+            if goal.locked || time_locked || Self::get_voucher_locked(env.clone(), goal_id) > 0 {
+                Self::append_audit(&env, symbol_short!("close"), &caller, false);
+                return Err(SavingsGoalError::GoalLocked);
+            }
+            if Self::get_goal_guardian(env.clone(), goal_id)
+                .is_some_and(|g| swept_amount > g.threshold)
+            {
+                Self::append_audit(&env, symbol_short!("close"), &caller, false);
+                return Err(SavingsGoalError::GuardianRequired);
             }
+            let penalty = Self::charge_penalty(&env, &goal, swept_amount)?;
+            if let Some(token) = &goal.token {
+                Self::release_tokens(&env, token, to, swept_amount - penalty)?;
+            }
+        }
 
-            schedule.last_executed = Some(current_time);
+        Self::extend_instance_ttl(&env);
+        let swept_to = if swept_amount > 0 { destination } else { None };
+        let archived = Self::archive_goal(&env, goal, swept_amount.max(0), swept_to, None)?;
+        Self::cancel_goal_schedules(&env, goal_id);
+
+        Self::touch_owner(&env, &caller);
+        if archived.swept_amount > 0 {
+            Self::update_stats(&env, &caller, |stats| {
+                stats.total_withdrawn += archived.swept_amount
+            });
+        }
+        Self::append_audit(&env, symbol_short!("close"), &caller, true);
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GoalClosed),
+            (goal_id, caller, archived.swept_amount),
+        );
+        Ok(archived)
+    }
 
-            if schedule.recurring && schedule.interval > 0 {
-                let mut missed = 0u32;
-                let mut next = schedule.next_due + schedule.interval;
-                while next <= current_time {
-                    missed += 1;
-                    next += schedule.interval;
-                }
-                schedule.missed_count += missed;
-                schedule.next_due = next;
+    /// Remove `goal` and append it to the owner's archive.
+    ///
+    /// Callers cancel the goal's schedules; the keeper does so on its own copy.
+    fn archive_goal(
+        env: &Env,
+        goal: SavingsGoal,
+        swept_amount: i128,
+        swept_to: Option<Address>,
+        swept_to_goal: Option<u32>,
+    ) -> Result<ArchivedGoal, SavingsGoalError> {
+        let goal_id = goal.id;
+        let owner = goal.owner.clone();
+        Self::release_match(env, &goal)?;
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("GOAL"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("INTEREST"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SWEEP"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("GUARDIAN"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("COMPLETED"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("AMEND"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("GOAL_ACT"), goal_id));
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("DORMANT"), goal_id));
+        let mut owned = Self::owner_goal_ids(env, &owner);
+        if let Some(i) = owned.first_index_of(goal_id) {
+            owned.remove(i);
+        }
+        Self::save_owner_goal_ids(env, &owner, &owned);
+
+        let archived = ArchivedGoal {
+            goal,
+            closed_at: env.ledger().timestamp(),
+            swept_amount,
+            swept_to,
+            swept_to_goal,
+        };
+        let key = (symbol_short!("ARCHIVED"), owner);
+        let storage = env.storage().persistent();
+        let mut archive: Vec<ArchivedGoal> = storage.get(&key).unwrap_or_else(|| Vec::new(env));
+        archive.push_back(archived.clone());
+        storage.set(&key, &archive);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Ok(archived)
+    }
 
-                if missed > 0 {
-                    env.events().publish(
-                        (symbol_short!("savings"), SavingsEvent::ScheduleMissed),
-                        (schedule_id, missed),
-                    );
-                }
-            } else {
-                schedule.active = false;
+    /// Sweep a goal automatically once it reaches its target.
+    ///
+    /// The goal is closed and archived as soon as a contribution or schedule
+    /// completes it, bypassing locks since the owner chose the destination.
+    /// A `ToGoal` destination must belong to the same owner and hold the same
+    /// token; sweeps do not cascade from the receiving goal.
+    pub fn set_completion_action(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        action: CompletionAction,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if let CompletionAction::ToGoal(target_id) = &action {
+            let target = Self::load_goal(&env, *target_id).ok_or(SavingsGoalError::GoalNotFound)?;
+            if *target_id == goal_id || target.owner != caller || target.token != goal.token {
+                return Err(SavingsGoalError::InvalidSweepTarget);
             }
+        }
 
-            schedules.set(schedule_id, schedule);
-            executed.push_back(schedule_id);
+        let key = (symbol_short!("SWEEP"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, &action);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
 
-            env.events().publish(
-                (symbol_short!("savings"), SavingsEvent::ScheduleExecuted),
-                schedule_id,
-            );
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::CompletionActionSet),
+            (goal_id, action),
+        );
+        Ok(true)
+    }
+
+    pub fn clear_completion_action(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
         }
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("SWEEP"), goal_id));
+        Ok(true)
+    }
 
+    pub fn get_completion_action(env: Env, goal_id: u32) -> Option<CompletionAction> {
         env.storage()
+            .persistent()
+            .get(&(symbol_short!("SWEEP"), goal_id))
+    }
+
+    fn cancel_goal_schedules(env: &Env, goal_id: u32) {
+        let mut schedules: Map<u32, SavingsSchedule> = env
+            .storage()
             .instance()
-            .set(&symbol_short!("SAV_SCH"), &schedules);
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(env));
+        Self::deactivate_goal_schedules(&mut schedules, goal_id);
         env.storage()
             .instance()
-            .set(&symbol_short!("GOALS"), &goals);
+            .set(&symbol_short!("SAV_SCH"), &schedules);
+    }
+
+    fn deactivate_goal_schedules(schedules: &mut Map<u32, SavingsSchedule>, goal_id: u32) {
+        for (id, mut schedule) in schedules.iter() {
+            if schedule.goal_id == goal_id && schedule.active {
+                schedule.active = false;
+                schedules.set(id, schedule);
+            }
+        }
+    }
+
+    /// Run the goal's completion action if it has reached its target.
+    ///
+    /// Returns whether the goal was swept and archived.
+    fn sweep_if_complete(env: &Env, goal_id: u32) -> Result<bool, SavingsGoalError> {
+        let goal = match Self::load_goal(env, goal_id) {
+            Some(goal) if goal.current_amount >= goal.target_amount => goal,
+            _ => return Ok(false),
+        };
+        let action = match Self::get_completion_action(env.clone(), goal_id) {
+            Some(action) => action,
+            None => return Ok(false),
+        };
+
+        let amount = goal.current_amount;
+        let (swept_to, swept_to_goal) = match &action {
+            CompletionAction::Transfer(to) => {
+                if let Some(token) = &goal.token {
+                    Self::release_tokens(env, token, to, amount)?;
+                }
+                Self::update_stats(env, &goal.owner, |stats| stats.total_withdrawn += amount);
+                (Some(to.clone()), None)
+            }
+            CompletionAction::ToGoal(target_id) => {
+                // The target may have been closed since the action was set
+                let mut target = match Self::load_goal(env, *target_id) {
+                    Some(target) => target,
+                    None => return Ok(false),
+                };
+                Self::accrue_interest(env, &target)?;
+                target.current_amount = target
+                    .current_amount
+                    .checked_add(amount)
+                    .ok_or(SavingsGoalError::Overflow)?;
+                Self::record_milestones(env, &mut target);
+                Self::save_goal(env, &target);
+                (None, Some(*target_id))
+            }
+        };
+        Self::archive_goal(env, goal, amount, swept_to, swept_to_goal)?;
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GoalSwept),
+            (goal_id, amount, action),
+        );
+        Ok(true)
+    }
+
+    /// Goals `owner` has closed, oldest first.
+    pub fn get_archived_goals(env: Env, owner: Address) -> Vec<ArchivedGoal> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ARCHIVED"), owner))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // -----------------------------------------------------------------------
+    // Ownership transfer and beneficiaries
+    // -----------------------------------------------------------------------
+
+    /// Offer a goal to `new_owner`, who takes it over with
+    /// `accept_goal_transfer`. A new offer replaces the previous one.
+    pub fn transfer_goal(
+        env: Env,
+        owner: Address,
+        new_owner: Address,
+        goal_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        owner.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if new_owner == owner {
+            return Err(SavingsGoalError::InvalidTransfer);
+        }
+
+        let key = (symbol_short!("GOAL_XFER"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, &new_owner);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::touch_owner(&env, &owner);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::TransferProposed),
+            (goal_id, owner, new_owner),
+        );
+        Ok(true)
+    }
+
+    pub fn cancel_goal_transfer(
+        env: Env,
+        owner: Address,
+        goal_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        owner.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("GOAL_XFER"), goal_id));
+        Ok(true)
+    }
+
+    /// Take over a goal offered with `transfer_goal`.
+    ///
+    /// The previous owner's schedules, completion action and beneficiary for
+    /// the goal are dropped; its balance and penalty terms carry over.
+    pub fn accept_goal_transfer(
+        env: Env,
+        new_owner: Address,
+        goal_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        new_owner.require_auth();
+        let mut goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        let key = (symbol_short!("GOAL_XFER"), goal_id);
+        let pending: Option<Address> = env.storage().persistent().get(&key);
+        if pending != Some(new_owner.clone()) {
+            return Err(SavingsGoalError::NoPendingTransfer);
+        }
+
+        let previous = goal.owner.clone();
+        let mut old_ids = Self::owner_goal_ids(&env, &previous);
+        if let Some(i) = old_ids.first_index_of(goal_id) {
+            old_ids.remove(i);
+        }
+        Self::save_owner_goal_ids(&env, &previous, &old_ids);
+        let mut new_ids = Self::owner_goal_ids(&env, &new_owner);
+        new_ids.push_back(goal_id);
+        Self::save_owner_goal_ids(&env, &new_owner, &new_ids);
+
+        goal.owner = new_owner.clone();
+        Self::save_goal(&env, &goal);
+        let storage = env.storage().persistent();
+        storage.remove(&key);
+        storage.remove(&(symbol_short!("SWEEP"), goal_id));
+        storage.remove(&(symbol_short!("BENEFIC"), goal_id));
+        Self::cancel_goal_schedules(&env, goal_id);
+        Self::touch_owner(&env, &new_owner);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GoalTransferred),
+            (goal_id, previous, new_owner),
+        );
+        Ok(true)
+    }
+
+    pub fn get_pending_transfer(env: Env, goal_id: u32) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("GOAL_XFER"), goal_id))
+    }
+
+    /// Name a beneficiary who can claim the goal if the owner goes inactive.
+    pub fn set_beneficiary(
+        env: Env,
+        owner: Address,
+        goal_id: u32,
+        beneficiary: Address,
+        claim_after: u64,
+        inactivity_period: u64,
+    ) -> Result<bool, SavingsGoalError> {
+        owner.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if beneficiary == owner || inactivity_period == 0 {
+            return Err(SavingsGoalError::InvalidBeneficiary);
+        }
+
+        let config = Beneficiary {
+            beneficiary,
+            claim_after,
+            inactivity_period,
+        };
+        let key = (symbol_short!("BENEFIC"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, &config);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::touch_owner(&env, &owner);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::BeneficiarySet),
+            (goal_id, config),
+        );
+        Ok(true)
+    }
+
+    pub fn remove_beneficiary(
+        env: Env,
+        owner: Address,
+        goal_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        owner.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("BENEFIC"), goal_id));
+        Self::touch_owner(&env, &owner);
+        Ok(true)
+    }
+
+    pub fn get_beneficiary(env: Env, goal_id: u32) -> Option<Beneficiary> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("BENEFIC"), goal_id))
+    }
+
+    /// Put a goal under a guardian wallet.
+    ///
+    /// Both the owner and the guardian must authorize. From then on only the
+    /// guardian can unlock the goal or withdraw more than `threshold` at once;
+    /// the guardian stays in place if the goal changes owner.
+    pub fn set_goal_guardian(
+        env: Env,
+        owner: Address,
+        goal_id: u32,
+        guardian: Address,
+        threshold: i128,
+    ) -> Result<bool, SavingsGoalError> {
+        owner.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if threshold < 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+        if guardian != owner {
+            guardian.require_auth();
+        }
+        if let Some(current) = Self::get_goal_guardian(env.clone(), goal_id) {
+            if current.guardian != guardian && current.guardian != owner {
+                current.guardian.require_auth();
+            }
+        }
+
+        let config = GoalGuardian {
+            guardian,
+            threshold,
+        };
+        let key = (symbol_short!("GUARDIAN"), goal_id);
+        let storage = env.storage().persistent();
+        storage.set(&key, &config);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        Self::touch_owner(&env, &owner);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GuardianSet),
+            (goal_id, config),
+        );
+        Ok(true)
+    }
+
+    /// Release a goal from its guardian. Only the guardian can do this.
+    pub fn remove_goal_guardian(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        let config = Self::get_goal_guardian(env.clone(), goal_id)
+            .ok_or(SavingsGoalError::GuardianRequired)?;
+        if config.guardian != caller {
+            return Err(SavingsGoalError::GuardianRequired);
+        }
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("GUARDIAN"), goal_id));
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GuardianRemoved),
+            (goal_id, caller),
+        );
+        Ok(true)
+    }
+
+    pub fn get_goal_guardian(env: Env, goal_id: u32) -> Option<GoalGuardian> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("GUARDIAN"), goal_id))
+    }
+
+    /// Claim an inactive owner's goal as its beneficiary.
+    ///
+    /// The whole balance goes to the beneficiary regardless of locks and the
+    /// goal is archived. Returns the amount claimed.
+    pub fn claim_as_beneficiary(
+        env: Env,
+        beneficiary: Address,
+        goal_id: u32,
+    ) -> Result<i128, SavingsGoalError> {
+        beneficiary.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        let config = Self::get_beneficiary(env.clone(), goal_id)
+            .ok_or(SavingsGoalError::BeneficiaryNotFound)?;
+        if config.beneficiary != beneficiary {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+
+        let now = env.ledger().timestamp();
+        let last_active = Self::get_last_activity(env.clone(), goal.owner.clone());
+        if now < config.claim_after || now < last_active.saturating_add(config.inactivity_period) {
+            return Err(SavingsGoalError::NotClaimable);
+        }
+
+        let amount = goal.current_amount.max(0);
+        if amount > 0 {
+            if let Some(token) = &goal.token {
+                Self::release_tokens(&env, token, &beneficiary, amount)?;
+            }
+        }
+        let owner = goal.owner.clone();
+        Self::archive_goal(&env, goal, amount, Some(beneficiary.clone()), None)?;
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("BENEFIC"), goal_id));
+        Self::cancel_goal_schedules(&env, goal_id);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::BeneficiaryClaimed),
+            (goal_id, owner, beneficiary, amount),
+        );
+        Ok(amount)
+    }
+
+    /// Last time `owner` acted on any of their goals.
+    pub fn get_last_activity(env: Env, owner: Address) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ACTIVE"), owner))
+            .unwrap_or(0)
+    }
+
+    /// Record activity by `owner` and keep all of their goals live with it,
+    /// so a goal nobody writes to is not archived while its owner is active.
+    fn touch_owner(env: &Env, owner: &Address) {
+        let key = (symbol_short!("ACTIVE"), owner.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, &env.ledger().timestamp());
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+
+        let key = (symbol_short!("OWN_GOAL"), owner.clone());
+        if !storage.has(&key) {
+            return;
+        }
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        for id in Self::owner_goal_ids(env, owner).iter() {
+            if storage.has(&(symbol_short!("GOAL"), id)) {
+                Self::extend_goal_ttl(env, id);
+            }
+        }
+    }
+
+    pub fn get_goal(env: Env, goal_id: u32) -> Option<SavingsGoal> {
+        let goal = Self::load_goal(&env, goal_id)?;
+        Self::live_owner_goal_ids(&env, &goal.owner);
+        Self::extend_goal_ttl(&env, goal_id);
+        Some(goal)
+    }
+
+    // -----------------------------------------------------------------------
+    // Milestones and progress
+    // -----------------------------------------------------------------------
+
+    /// Replace a goal's milestones with custom amounts.
+    ///
+    /// Amounts must be positive, strictly ascending and at most the target;
+    /// an empty list restores the default 25/50/75% milestones. Milestones the
+    /// goal has already passed count as hit without emitting events.
+    pub fn set_goal_milestones(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        milestones: Vec<i128>,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+
+        let mut goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+
+        if milestones.len() > MAX_MILESTONES {
+            return Err(SavingsGoalError::InvalidMilestones);
+        }
+        let mut previous = 0i128;
+        for amount in milestones.iter() {
+            if amount <= previous || amount > goal.target_amount {
+                return Err(SavingsGoalError::InvalidMilestones);
+            }
+            previous = amount;
+        }
+
+        Self::extend_instance_ttl(&env);
+        goal.milestones = milestones.clone();
+        let amounts = Self::milestone_amounts(&env, &goal);
+        goal.milestones_hit = amounts
+            .iter()
+            .filter(|amount| *amount <= goal.current_amount)
+            .count() as u32;
+        Self::save_goal(&env, &goal);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::MilestonesUpdated),
+            (goal_id, milestones),
+        );
+        Ok(true)
+    }
+
+    /// Percentage saved, milestones reached and projected completion date.
+    ///
+    /// The projection plays the goal's active savings schedules forward from
+    /// their next due dates. It is `None` when they never reach the target.
+    pub fn get_goal_progress(env: Env, goal_id: u32) -> Option<GoalProgress> {
+        let goal = Self::get_goal(env.clone(), goal_id)?;
+        let percentage = if goal.current_amount >= goal.target_amount {
+            100
+        } else {
+            (goal.current_amount.max(0) * 100 / goal.target_amount) as u32
+        };
+        Some(GoalProgress {
+            goal_id,
+            percentage,
+            milestones: Self::milestone_amounts(&env, &goal),
+            milestones_hit: goal.milestones_hit,
+            projected_completion: Self::project_completion(&env, &goal),
+        })
+    }
+
+    fn milestone_amounts(env: &Env, goal: &SavingsGoal) -> Vec<i128> {
+        if !goal.milestones.is_empty() {
+            return goal.milestones.clone();
+        }
+        let mut amounts = Vec::new(env);
+        for bps in DEFAULT_MILESTONE_BPS {
+            amounts.push_back(
+                goal.target_amount / 10_000 * bps + goal.target_amount % 10_000 * bps / 10_000,
+            );
+        }
+        amounts
+    }
+
+    /// Emit `MilestoneReached` for every milestone `goal` has newly passed.
+    fn record_milestones(env: &Env, goal: &mut SavingsGoal) {
+        let amounts = Self::milestone_amounts(env, goal);
+        while let Some(amount) = amounts.get(goal.milestones_hit) {
+            if goal.current_amount < amount {
+                break;
+            }
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::MilestoneReached),
+                (goal.id, goal.milestones_hit, amount),
+            );
+            goal.milestones_hit += 1;
+        }
+    }
+
+    fn project_completion(env: &Env, goal: &SavingsGoal) -> Option<u64> {
+        let now = env.ledger().timestamp();
+        let mut remaining = goal.target_amount - goal.current_amount;
+        if remaining <= 0 {
+            return Some(now);
+        }
+
+        let schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut pending = Vec::new(env);
+        for (_, schedule) in schedules.iter() {
+            if schedule.active && schedule.goal_id == goal.id {
+                pending.push_back(schedule);
+            }
+        }
+
+        for _ in 0..MAX_PROJECTION_STEPS {
+            // Apply the earliest upcoming payment
+            let mut index = 0u32;
+            let mut schedule = pending.get(0)?;
+            for i in 1..pending.len() {
+                let candidate = pending.get(i)?;
+                if candidate.next_due < schedule.next_due {
+                    index = i;
+                    schedule = candidate;
+                }
+            }
+            remaining -= schedule.amount;
+            if remaining <= 0 {
+                return Some(schedule.next_due.max(now));
+            }
+            if schedule.recurring && schedule.interval > 0 {
+                schedule.next_due = schedule.next_due.checked_add(schedule.interval)?;
+                pending.set(index, schedule);
+            } else {
+                pending.remove(index);
+            }
+        }
+        None
+    }
+
+    /// Total of the goal's active schedule payments due on or before `deadline`.
+    fn scheduled_until(env: &Env, goal_id: u32, deadline: u64) -> i128 {
+        let schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(env));
+        let mut total = 0i128;
+        for (_, schedule) in schedules.iter() {
+            if !schedule.active || schedule.goal_id != goal_id {
+                continue;
+            }
+            let mut due = schedule.next_due;
+            let mut steps = 0u32;
+            while due <= deadline && steps < MAX_PROJECTION_STEPS {
+                total = total.saturating_add(schedule.amount);
+                if !schedule.recurring || schedule.interval == 0 {
+                    break;
+                }
+                due = due.saturating_add(schedule.interval);
+                steps += 1;
+            }
+        }
+        total
+    }
+
+    /// Goals of `owner` that their balance and active schedules will not
+    /// complete by `target_date`. Goals without a target date are skipped.
+    pub fn get_at_risk_goals(env: Env, owner: Address) -> Vec<AtRiskGoal> {
+        let now = env.ledger().timestamp();
+        let mut result = Vec::new(&env);
+        for goal_id in Self::owner_goal_ids(&env, &owner).iter() {
+            let goal = match Self::load_goal(&env, goal_id) {
+                Some(g) => g,
+                None => continue,
+            };
+            if goal.target_date == 0 || goal.current_amount >= goal.target_amount {
+                continue;
+            }
+            let overdue = now > goal.target_date;
+            let projected_amount = if overdue {
+                goal.current_amount
+            } else {
+                goal.current_amount.saturating_add(Self::scheduled_until(
+                    &env,
+                    goal_id,
+                    goal.target_date,
+                ))
+            };
+            if projected_amount < goal.target_amount {
+                result.push_back(AtRiskGoal {
+                    goal_id,
+                    target_date: goal.target_date,
+                    projected_amount,
+                    shortfall: goal.target_amount - projected_amount,
+                    overdue,
+                });
+            }
+        }
+        result
+    }
+
+    /// Emit `OverdueGoal` once for each goal whose target date passed unmet.
+    ///
+    /// Walks goal ids from a cursor, `MAX_OVERDUE_CHECKS_PER_RUN` per keeper
+    /// call, wrapping around at the newest goal.
+    fn flag_overdue_goals(env: &Env) {
+        let now = env.ledger().timestamp();
+        let next_id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(0);
+        let mut goal_id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("DDL_CUR"))
+            .unwrap_or(0);
+        for _ in 0..MAX_OVERDUE_CHECKS_PER_RUN.min(next_id) {
+            goal_id = if goal_id >= next_id { 1 } else { goal_id + 1 };
+            let Some(goal) = Self::load_goal(env, goal_id) else {
+                continue;
+            };
+            if goal.target_date == 0
+                || now <= goal.target_date
+                || goal.current_amount >= goal.target_amount
+            {
+                continue;
+            }
+            let key = (symbol_short!("OVERDUE"), goal_id);
+            if env.storage().persistent().has(&key) {
+                continue;
+            }
+            env.storage().persistent().set(&key, &now);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::OverdueGoal),
+                (
+                    goal_id,
+                    goal.owner,
+                    goal.target_amount - goal.current_amount,
+                ),
+            );
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("DDL_CUR"), &goal_id);
+    }
+
+    // -----------------------------------------------------------------------
+    // Dormancy
+    // -----------------------------------------------------------------------
+
+    /// Set the dormancy policy. Pause admin only.
+    pub fn set_dormancy_policy(
+        env: Env,
+        caller: Address,
+        dormant_after: u64,
+        sweep_after: u64,
+    ) -> Result<(), SavingsGoalError> {
+        caller.require_auth();
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
+        if admin != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if dormant_after == 0 || sweep_after == 0 {
+            return Err(SavingsGoalError::InvalidDormancyPolicy);
+        }
+        env.storage().instance().set(
+            &symbol_short!("DORMANCY"),
+            &DormancyPolicy {
+                dormant_after,
+                sweep_after,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn get_dormancy_policy(env: Env) -> Option<DormancyPolicy> {
+        env.storage().instance().get(&symbol_short!("DORMANCY"))
+    }
+
+    /// Last time the goal changed. Goals saved before this was tracked fall
+    /// back to the owner's last activity.
+    pub fn get_goal_last_touched(env: Env, goal_id: u32) -> u64 {
+        let touched: Option<u64> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("GOAL_ACT"), goal_id));
+        match (touched, Self::load_goal(&env, goal_id)) {
+            (Some(at), _) => at,
+            (None, Some(goal)) => Self::get_last_activity(env, goal.owner),
+            (None, None) => 0,
+        }
+    }
+
+    /// When the keeper flagged the goal dormant, if it has not changed since.
+    pub fn get_dormant_since(env: Env, goal_id: u32) -> Option<u64> {
+        let flagged: u64 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("DORMANT"), goal_id))?;
+        if Self::get_goal_last_touched(env, goal_id) >= flagged {
+            return None;
+        }
+        Some(flagged)
+    }
+
+    /// Sweep a dormant goal's balance to the caller and archive it.
+    ///
+    /// Only the owner or the goal's beneficiary can sweep, and only once
+    /// `sweep_after` has passed since the goal was flagged without it changing
+    /// in between. Locks and penalties do not apply. Returns the amount swept.
+    pub fn sweep_dormant_goal(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+    ) -> Result<i128, SavingsGoalError> {
+        caller.require_auth();
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        let is_beneficiary = Self::get_beneficiary(env.clone(), goal_id)
+            .is_some_and(|config| config.beneficiary == caller);
+        if goal.owner != caller && !is_beneficiary {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        let policy = Self::get_dormancy_policy(env.clone()).ok_or(SavingsGoalError::NotDormant)?;
+        let flagged =
+            Self::get_dormant_since(env.clone(), goal_id).ok_or(SavingsGoalError::NotDormant)?;
+        if env.ledger().timestamp() < flagged.saturating_add(policy.sweep_after) {
+            return Err(SavingsGoalError::NotDormant);
+        }
+
+        Self::accrue_interest(&env, &goal)?;
+        let amount = goal.current_amount.max(0);
+        if amount > 0 {
+            if let Some(token) = &goal.token {
+                Self::release_tokens(&env, token, &caller, amount)?;
+            }
+            if goal.owner == caller {
+                Self::update_stats(&env, &caller, |stats| stats.total_withdrawn += amount);
+            }
+        }
+        let owner = goal.owner.clone();
+        Self::archive_goal(&env, goal, amount, Some(caller.clone()), None)?;
+        Self::cancel_goal_schedules(&env, goal_id);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::DormantGoalSwept),
+            (goal_id, owner, caller, amount),
+        );
+        Ok(amount)
+    }
+
+    /// Emit `GoalDormant` for goals untouched for `dormant_after` seconds.
+    ///
+    /// Works like `flag_overdue_goals`: a bounded walk from a cursor. A goal
+    /// that changes after being flagged can be flagged again later.
+    fn flag_dormant_goals(env: &Env) {
+        let Some(policy) = Self::get_dormancy_policy(env.clone()) else {
+            return;
+        };
+        let now = env.ledger().timestamp();
+        let next_id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(0);
+        let mut goal_id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("DRM_CUR"))
+            .unwrap_or(0);
+        for _ in 0..MAX_DORMANCY_CHECKS_PER_RUN.min(next_id) {
+            goal_id = if goal_id >= next_id { 1 } else { goal_id + 1 };
+            let Some(goal) = Self::load_goal(env, goal_id) else {
+                continue;
+            };
+            let touched = Self::get_goal_last_touched(env.clone(), goal_id);
+            if now < touched.saturating_add(policy.dormant_after)
+                || Self::get_dormant_since(env.clone(), goal_id).is_some()
+            {
+                continue;
+            }
+            let key = (symbol_short!("DORMANT"), goal_id);
+            env.storage().persistent().set(&key, &now);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::GoalDormant),
+                (goal_id, goal.owner, touched),
+            );
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("DRM_CUR"), &goal_id);
+    }
+
+    // -----------------------------------------------------------------------
+    // Categories and metadata
+    // -----------------------------------------------------------------------
+
+    /// Set a goal's category, tags (up to 5) and display emoji (up to 16 bytes).
+    pub fn set_goal_category(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        category: Symbol,
+        tags: Vec<Symbol>,
+        emoji: String,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+
+        let mut goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+        if goal.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(SavingsGoalError::InvalidMetadata);
+        }
+        if emoji.len() > MAX_EMOJI_LEN {
+            return Err(SavingsGoalError::InvalidMetadata);
+        }
+
+        // Interest so far was earned at the old category's rate
+        Self::accrue_interest(&env, &goal)?;
+        goal.category = category.clone();
+        goal.tags = tags;
+        goal.emoji = emoji;
+        Self::save_goal(&env, &goal);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::GoalCategorized),
+            (goal_id, category),
+        );
+        Ok(true)
+    }
+
+    /// `owner`'s goals in `category`, in creation order.
+    pub fn get_goals_by_category(env: Env, owner: Address, category: Symbol) -> Vec<SavingsGoal> {
+        let mut result = Vec::new(&env);
+        for goal in Self::get_all_goals(env.clone(), owner).iter() {
+            if goal.category == category {
+                result.push_back(goal);
+            }
+        }
+        result
+    }
+
+    /// Sum of `current_amount` per category over `owner`'s goals.
+    pub fn get_total_saved_by_category(
+        env: Env,
+        owner: Address,
+    ) -> Result<Map<Symbol, i128>, SavingsGoalError> {
+        let mut totals: Map<Symbol, i128> = Map::new(&env);
+        for goal in Self::get_all_goals(env.clone(), owner).iter() {
+            let total = totals.get(goal.category.clone()).unwrap_or(0);
+            totals.set(
+                goal.category,
+                total
+                    .checked_add(goal.current_amount)
+                    .ok_or(SavingsGoalError::Overflow)?,
+            );
+        }
+        Ok(totals)
+    }
+
+    // -----------------------------------------------------------------------
+    // PAGINATED LIST QUERIES
+    // -----------------------------------------------------------------------
+
+    /// Get a page of savings goals for `owner`.
+    ///
+    /// # Arguments
+    /// * `owner`  – whose goals to return
+    /// * `cursor` – start after this goal ID (pass 0 for the first page)
+    /// * `limit`  – max items per page (0 → DEFAULT_PAGE_LIMIT, capped at MAX_PAGE_LIMIT)
+    ///
+    /// # Returns
+    /// `GoalPage { items, next_cursor, count }`.
+    /// `next_cursor == 0` means no more pages.
+    pub fn get_goals(env: Env, owner: Address, cursor: u32, limit: u32) -> GoalPage {
+        let limit = Self::clamp_limit(limit);
+
+        let mut result = Vec::new(&env);
+        let mut next_cursor: u32 = 0;
+        let mut collected: u32 = 0;
+
+        // The owner index is in creation order, so ids are ascending
+        for id in Self::live_owner_goal_ids(&env, &owner).iter() {
+            if id <= cursor {
+                continue;
+            }
+            let goal = match Self::load_goal(&env, id) {
+                Some(goal) => goal,
+                None => continue,
+            };
+            if collected < limit {
+                Self::extend_goal_ttl(&env, id);
+                result.push_back(goal);
+                collected += 1;
+                next_cursor = id; // track last returned ID
+            } else {
+                break;
+            }
+        }
+
+        // If we didn't fill the page, there are no more items
+        if collected < limit {
+            next_cursor = 0;
+        }
+
+        GoalPage {
+            items: result,
+            next_cursor,
+            count: collected,
+        }
+    }
+
+    /// Backward-compatible: returns ALL goals for owner in one Vec.
+    /// Prefer the paginated `get_goals` for production use.
+    pub fn get_all_goals(env: Env, owner: Address) -> Vec<SavingsGoal> {
+        let mut result = Vec::new(&env);
+        for id in Self::live_owner_goal_ids(&env, &owner).iter() {
+            if let Some(goal) = Self::load_goal(&env, id) {
+                Self::extend_goal_ttl(&env, id);
+                result.push_back(goal);
+            }
+        }
+        result
+    }
+
+    pub fn is_goal_completed(env: Env, goal_id: u32) -> bool {
+        if let Some(goal) = Self::load_goal(&env, goal_id) {
+            goal.current_amount >= goal.target_amount
+        } else {
+            false
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Token custody
+    // -----------------------------------------------------------------------
+
+    /// Total `current_amount` of all goals backed by `token`.
+    pub fn get_token_liabilities(env: Env, token: Address) -> i128 {
+        let owed: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TOK_OWED"))
+            .unwrap_or_else(|| Map::new(&env));
+        owed.get(token).unwrap_or(0)
+    }
+
+    /// Compare this contract's `token` balance with what goals are owed.
+    ///
+    /// Tokens sent to the contract directly show up as surplus; a negative
+    /// surplus means the contract cannot honour every withdrawal.
+    pub fn reconcile_token(env: Env, token: Address) -> TokenReconciliation {
+        let held = TokenClient::new(&env, &token).balance(&env.current_contract_address());
+        let owed = Self::get_token_liabilities(env.clone(), token.clone());
+        TokenReconciliation {
+            token,
+            held,
+            owed,
+            surplus: held - owed,
+        }
+    }
+
+    fn deposit_tokens(
+        env: &Env,
+        token: &Address,
+        from: &Address,
+        amount: i128,
+    ) -> Result<(), SavingsGoalError> {
+        TokenClient::new(env, token).transfer(from, &env.current_contract_address(), &amount);
+        Self::adjust_liabilities(env, token, amount)?;
+        Ok(())
+    }
+
+    fn release_tokens(
+        env: &Env,
+        token: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), SavingsGoalError> {
+        TokenClient::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+        Self::adjust_liabilities(env, token, -amount)?;
+        Ok(())
+    }
+
+    fn adjust_liabilities(env: &Env, token: &Address, delta: i128) -> Result<(), SavingsGoalError> {
+        let mut owed: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TOK_OWED"))
+            .unwrap_or_else(|| Map::new(env));
+        let total = owed
+            .get(token.clone())
+            .unwrap_or(0)
+            .checked_add(delta)
+            .ok_or(SavingsGoalError::Overflow)?;
+        owed.set(token.clone(), total);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOK_OWED"), &owed);
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Snapshot, audit, schedule
+    // -----------------------------------------------------------------------
+
+    pub fn get_nonce(env: Env, address: Address) -> u64 {
+        let nonces: Option<Map<Address, u64>> =
+            env.storage().instance().get(&symbol_short!("NONCES"));
+        nonces
+            .as_ref()
+            .and_then(|m: &Map<Address, u64>| m.get(address))
+            .unwrap_or(0)
+    }
+
+    pub fn export_snapshot(env: Env, caller: Address) -> GoalsExportSnapshot {
+        caller.require_auth();
+        let next_id = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(0u32);
+        let mut list = Vec::new(&env);
+        for i in 1..=next_id {
+            if let Some(g) = Self::load_goal(&env, i) {
+                list.push_back(g);
+            }
+        }
+        let schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut snapshot = GoalsExportSnapshot {
+            version: SNAPSHOT_VERSION,
+            checksum: Bytes::new(&env),
+            next_id,
+            goals: list,
+            next_schedule_id: env
+                .storage()
+                .instance()
+                .get(&symbol_short!("NEXT_SSCH"))
+                .unwrap_or(0u32),
+            schedules: schedules.values(),
+            nonces: env
+                .storage()
+                .instance()
+                .get(&symbol_short!("NONCES"))
+                .unwrap_or_else(|| Map::new(&env)),
+            audit_log: env
+                .storage()
+                .instance()
+                .get(&symbol_short!("AUDIT"))
+                .unwrap_or_else(|| Vec::new(&env)),
+        };
+        snapshot.checksum = Self::compute_snapshot_checksum(&env, &snapshot);
+        snapshot
+    }
+
+    pub fn import_snapshot(
+        env: Env,
+        caller: Address,
+        nonce: u64,
+        snapshot: GoalsExportSnapshot,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        if Self::get_pause_admin(&env) != Some(caller.clone()) {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        Self::require_nonce(&env, &caller, nonce)?;
+
+        let expected = match snapshot.version {
+            SNAPSHOT_VERSION => Self::compute_snapshot_checksum(&env, &snapshot),
+            LEGACY_SNAPSHOT_VERSION => Bytes::from_array(
+                &env,
+                &Self::legacy_checksum(snapshot.version, snapshot.next_id, &snapshot.goals)
+                    .to_be_bytes(),
+            ),
+            _ => {
+                Self::append_audit(&env, symbol_short!("import"), &caller, false);
+                return Err(SavingsGoalError::UnsupportedVersion);
+            }
+        };
+        if snapshot.checksum != expected {
+            Self::append_audit(&env, symbol_short!("import"), &caller, false);
+            return Err(SavingsGoalError::ChecksumMismatch);
+        }
+
+        // Token goals must stay backed: what the snapshot owes per token
+        // either stays as it is or equals the balance actually held
+        let mut owed: Map<Address, i128> = Map::new(&env);
+        for g in snapshot.goals.iter() {
+            if let Some(token) = g.token {
+                let total = owed.get(token.clone()).unwrap_or(0);
+                owed.set(
+                    token,
+                    total
+                        .checked_add(g.current_amount)
+                        .ok_or(SavingsGoalError::Overflow)?,
+                );
+            }
+        }
+        let current: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TOK_OWED"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut tokens = owed.keys();
+        for token in current.keys().iter() {
+            if !owed.contains_key(token.clone()) {
+                tokens.push_back(token);
+            }
+        }
+        for token in tokens.iter() {
+            let imported = owed.get(token.clone()).unwrap_or(0);
+            let reconciliation = Self::reconcile_token(env.clone(), token);
+            if imported != reconciliation.owed && imported != reconciliation.held {
+                Self::append_audit(&env, symbol_short!("import"), &caller, false);
+                return Err(SavingsGoalError::LiabilityMismatch);
+            }
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        // Drop the current goals and owner indexes before writing the snapshot's
+        let storage = env.storage().persistent();
+        let old_next_id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(0);
+        for id in 1..=old_next_id {
+            if let Some(goal) = Self::load_goal(&env, id) {
+                storage.remove(&(symbol_short!("GOAL"), id));
+                storage.remove(&(symbol_short!("OWN_GOAL"), goal.owner));
+            }
+        }
+
+        let mut indexes: Map<Address, Vec<u32>> = Map::new(&env);
+        for g in snapshot.goals.iter() {
+            let mut owned = indexes
+                .get(g.owner.clone())
+                .unwrap_or_else(|| Vec::new(&env));
+            owned.push_back(g.id);
+            indexes.set(g.owner.clone(), owned);
+            Self::save_goal(&env, &g);
+        }
+        for (owner, owned) in indexes.iter() {
+            Self::save_owner_goal_ids(&env, &owner, &owned);
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOK_OWED"), &owed);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_ID"), &snapshot.next_id);
+
+        if snapshot.version == SNAPSHOT_VERSION {
+            Self::import_schedules_and_audit(&env, &snapshot);
+        }
+
+        Self::increment_nonce(&env, &caller)?;
+        Self::append_audit(&env, symbol_short!("import"), &caller, true);
+        Ok(true)
+    }
+
+    /// Replace schedules and the audit log with the snapshot's.
+    ///
+    /// Nonces only move forward, so a snapshot cannot reopen a nonce that
+    /// was already spent here.
+    fn import_schedules_and_audit(env: &Env, snapshot: &GoalsExportSnapshot) {
+        let mut schedules: Map<u32, SavingsSchedule> = Map::new(env);
+        for schedule in snapshot.schedules.iter() {
+            schedules.set(schedule.id, schedule);
+        }
+        let instance = env.storage().instance();
+        instance.set(&symbol_short!("SAV_SCH"), &schedules);
+        instance.set(&symbol_short!("NEXT_SSCH"), &snapshot.next_schedule_id);
+        instance.set(&symbol_short!("SCH_CUR"), &0u32);
+
+        let mut nonces: Map<Address, u64> = instance
+            .get(&symbol_short!("NONCES"))
+            .unwrap_or_else(|| Map::new(env));
+        for (address, nonce) in snapshot.nonces.iter() {
+            if nonce > nonces.get(address.clone()).unwrap_or(0) {
+                nonces.set(address, nonce);
+            }
+        }
+        instance.set(&symbol_short!("NONCES"), &nonces);
+
+        let mut log = snapshot.audit_log.clone();
+        while log.len() > MAX_AUDIT_ENTRIES {
+            log.pop_front();
+        }
+        instance.set(&symbol_short!("AUDIT"), &log);
+    }
+
+    pub fn get_audit_log(env: Env, from_index: u32, limit: u32) -> Vec<AuditEntry> {
+        let log: Option<Vec<AuditEntry>> = env.storage().instance().get(&symbol_short!("AUDIT"));
+        let log = log.unwrap_or_else(|| Vec::new(&env));
+        let len = log.len();
+        let cap = MAX_AUDIT_ENTRIES.min(limit);
+        let mut out = Vec::new(&env);
+        if from_index >= len {
+            return out;
+        }
+        let end = (from_index + cap).min(len);
+        for i in from_index..end {
+            if let Some(entry) = log.get(i) {
+                out.push_back(entry);
+            }
+        }
+        out
+    }
+
+    pub fn set_time_lock(
+        env: Env,
+        caller: Address,
+        goal_id: u32,
+        unlock_date: u64,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+        Self::extend_instance_ttl(&env);
+
+        let mut goal = match Self::load_goal(&env, goal_id) {
+            Some(g) => g,
+            None => {
+                Self::append_audit(&env, symbol_short!("timelock"), &caller, false);
+                return Err(SavingsGoalError::GoalNotFound);
+            }
+        };
+
+        if goal.owner != caller {
+            Self::append_audit(&env, symbol_short!("timelock"), &caller, false);
+            return Err(SavingsGoalError::Unauthorized);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if unlock_date <= current_time {
+            Self::append_audit(&env, symbol_short!("timelock"), &caller, false);
+            return Err(SavingsGoalError::InvalidDueDate);
+        }
+
+        goal.unlock_date = Some(unlock_date);
+        Self::save_goal(&env, &goal);
+
+        Self::touch_owner(&env, &caller);
+        Self::append_audit(&env, symbol_short!("timelock"), &caller, true);
+        Ok(true)
+    }
+
+    pub fn create_savings_schedule(
+        env: Env,
+        owner: Address,
+        goal_id: u32,
+        amount: i128,
+        next_due: u64,
+        interval: u64,
+    ) -> Result<u32, SavingsGoalError> {
+        owner.require_auth();
+
+        if amount <= 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+
+        let goal = Self::load_goal(&env, goal_id).ok_or(SavingsGoalError::GoalNotFound)?;
+
+        if goal.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if next_due <= current_time {
+            return Err(SavingsGoalError::InvalidDueDate);
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let next_schedule_id = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_SSCH"))
+            .unwrap_or(0u32)
+            + 1;
+
+        let schedule = SavingsSchedule {
+            id: next_schedule_id,
+            owner: owner.clone(),
+            goal_id,
+            amount,
+            next_due,
+            interval,
+            recurring: interval > 0,
+            active: true,
+            created_at: current_time,
+            last_executed: None,
+            missed_count: 0,
+            funder: owner.clone(),
+            skipped_count: 0,
+        };
+
+        schedules.set(next_schedule_id, schedule);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SAV_SCH"), &schedules);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_SSCH"), &next_schedule_id);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::ScheduleCreated),
+            (next_schedule_id, owner),
+        );
+
+        Ok(next_schedule_id)
+    }
+
+    /// Pull a schedule's tokens from `funder` instead of the owner, e.g. a
+    /// family wallet. The funder must approve this contract as a spender.
+    pub fn set_schedule_funder(
+        env: Env,
+        owner: Address,
+        schedule_id: u32,
+        funder: Address,
+    ) -> Result<bool, SavingsGoalError> {
+        owner.require_auth();
+        if funder != owner {
+            funder.require_auth();
+        }
+        let mut schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+        let mut schedule = schedules
+            .get(schedule_id)
+            .ok_or(SavingsGoalError::ScheduleNotFound)?;
+        if schedule.owner != owner {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        schedule.funder = funder;
+        schedules.set(schedule_id, schedule);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SAV_SCH"), &schedules);
+        Ok(true)
+    }
+
+    /// Recent executions of a schedule, oldest first, including skipped ones.
+    pub fn get_schedule_executions(env: Env, schedule_id: u32) -> Vec<ScheduleExecution> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SCH_HIST"), schedule_id))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    fn record_execution(env: &Env, schedule: &SavingsSchedule, status: ExecutionStatus) {
+        let key = (symbol_short!("SCH_HIST"), schedule.id);
+        let storage = env.storage().persistent();
+        let mut history: Vec<ScheduleExecution> =
+            storage.get(&key).unwrap_or_else(|| Vec::new(env));
+        if history.len() >= MAX_SCHEDULE_HISTORY {
+            history.remove(0);
+        }
+        history.push_back(ScheduleExecution {
+            schedule_id: schedule.id,
+            amount: schedule.amount,
+            timestamp: env.ledger().timestamp(),
+            status,
+        });
+        storage.set(&key, &history);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+    }
+
+    /// Pull a schedule payment for a token-backed goal from its funder.
+    ///
+    /// Plain goals are always funded. A failed transfer (missing allowance or
+    /// balance) is reported as `false` rather than aborting the keeper run.
+    fn pull_schedule_funds(
+        env: &Env,
+        schedule: &SavingsSchedule,
+        goal: &SavingsGoal,
+    ) -> Result<bool, SavingsGoalError> {
+        let Some(token) = &goal.token else {
+            return Ok(true);
+        };
+        let this = env.current_contract_address();
+        let pulled = TokenClient::new(env, token).try_transfer_from(
+            &this,
+            &schedule.funder,
+            &this,
+            &schedule.amount,
+        );
+        if !matches!(pulled, Ok(Ok(()))) {
+            return Ok(false);
+        }
+        Self::adjust_liabilities(env, token, schedule.amount)?;
+        Ok(true)
+    }
+
+    pub fn modify_savings_schedule(
+        env: Env,
+        caller: Address,
+        schedule_id: u32,
+        amount: i128,
+        next_due: u64,
+        interval: u64,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+
+        if amount <= 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+
+        let current_time = env.ledger().timestamp();
+        if next_due <= current_time {
+            return Err(SavingsGoalError::InvalidDueDate);
+        }
+
+        Self::extend_instance_ttl(&env);
+
+        let mut schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut schedule = schedules
+            .get(schedule_id)
+            .ok_or(SavingsGoalError::ScheduleNotFound)?;
+
+        if schedule.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+
+        schedule.amount = amount;
+        schedule.next_due = next_due;
+        schedule.interval = interval;
+        schedule.recurring = interval > 0;
+
+        schedules.set(schedule_id, schedule);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SAV_SCH"), &schedules);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::ScheduleModified),
+            (schedule_id, caller),
+        );
+
+        Ok(true)
+    }
+
+    pub fn cancel_savings_schedule(
+        env: Env,
+        caller: Address,
+        schedule_id: u32,
+    ) -> Result<bool, SavingsGoalError> {
+        caller.require_auth();
+
+        Self::extend_instance_ttl(&env);
+
+        let mut schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut schedule = schedules
+            .get(schedule_id)
+            .ok_or(SavingsGoalError::ScheduleNotFound)?;
+
+        if schedule.owner != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+
+        schedule.active = false;
+
+        schedules.set(schedule_id, schedule);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SAV_SCH"), &schedules);
+
+        env.events().publish(
+            (symbol_short!("savings"), SavingsEvent::ScheduleCancelled),
+            (schedule_id, caller),
+        );
+
+        Ok(true)
+    }
+
+    /// Execute due savings schedules, at most `MAX_SCHEDULES_PER_RUN` per call.
+    ///
+    /// Each call resumes after the schedule id stored in the execution cursor
+    /// and resets it to 0 once the end of the schedule map is reached.
+    pub fn execute_due_savings_schedules(env: Env) -> Result<Vec<u32>, SavingsGoalError> {
+        Self::run_due_schedules(env)
+    }
+
+    /// Run the keeper and pay `keeper` the configured reward for each
+    /// executed schedule, as far as the reward pool allows.
+    pub fn execute_schedules_for_reward(
+        env: Env,
+        keeper: Address,
+    ) -> Result<Vec<u32>, SavingsGoalError> {
+        keeper.require_auth();
+        let executed = Self::run_due_schedules(env.clone())?;
+
+        let Some(reward) = Self::get_keeper_reward(env.clone()) else {
+            return Ok(executed);
+        };
+        let pool = Self::get_keeper_pool(env.clone());
+        let owed = reward
+            .per_execution
+            .checked_mul(executed.len() as i128)
+            .ok_or(SavingsGoalError::Overflow)?
+            .min(pool);
+        if owed > 0 {
+            Self::release_tokens(&env, &reward.token, &keeper, owed)?;
+            env.storage()
+                .instance()
+                .set(&symbol_short!("KPR_POOL"), &(pool - owed));
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::KeeperRewarded),
+                (keeper, executed.len(), owed),
+            );
+        }
+        Ok(executed)
+    }
+
+    /// Set the token and amount paid per executed schedule. Pause admin only.
+    ///
+    /// A reward of 0 turns rewards off. The token can only change while the
+    /// reward pool is empty.
+    pub fn set_keeper_reward(
+        env: Env,
+        caller: Address,
+        token: Address,
+        per_execution: i128,
+    ) -> Result<(), SavingsGoalError> {
+        caller.require_auth();
+        let admin = Self::get_pause_admin(&env).ok_or(SavingsGoalError::Unauthorized)?;
+        if admin != caller {
+            return Err(SavingsGoalError::Unauthorized);
+        }
+        if per_execution < 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+        if let Some(current) = Self::get_keeper_reward(env.clone()) {
+            if current.token != token && Self::get_keeper_pool(env.clone()) > 0 {
+                return Err(SavingsGoalError::RewardPoolNotEmpty);
+            }
+        }
+        env.storage().instance().set(
+            &symbol_short!("KPR_RWD"),
+            &KeeperReward {
+                token,
+                per_execution,
+            },
+        );
+        Ok(())
+    }
+
+    /// Add reward tokens to the keeper pool. Anyone can fund it.
+    pub fn fund_keeper_rewards(
+        env: Env,
+        funder: Address,
+        amount: i128,
+    ) -> Result<i128, SavingsGoalError> {
+        funder.require_auth();
+        if amount <= 0 {
+            return Err(SavingsGoalError::InvalidAmount);
+        }
+        let reward =
+            Self::get_keeper_reward(env.clone()).ok_or(SavingsGoalError::KeeperRewardNotSet)?;
+        Self::deposit_tokens(&env, &reward.token, &funder, amount)?;
+        let pool = Self::get_keeper_pool(env.clone())
+            .checked_add(amount)
+            .ok_or(SavingsGoalError::Overflow)?;
+        env.storage()
+            .instance()
+            .set(&symbol_short!("KPR_POOL"), &pool);
+        Ok(pool)
+    }
+
+    pub fn get_keeper_reward(env: Env) -> Option<KeeperReward> {
+        env.storage().instance().get(&symbol_short!("KPR_RWD"))
+    }
+
+    pub fn get_keeper_pool(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("KPR_POOL"))
+            .unwrap_or(0)
+    }
+
+    pub fn get_schedule_cursor(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("SCH_CUR"))
+            .unwrap_or(0)
+    }
+
+    fn run_due_schedules(env: Env) -> Result<Vec<u32>, SavingsGoalError> {
+        Self::extend_instance_ttl(&env);
+
+        let current_time = env.ledger().timestamp();
+        let mut executed = Vec::new(&env);
+        let cursor = Self::get_schedule_cursor(env.clone());
+
+        let mut schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut processed = 0u32;
+        let mut last_processed = cursor;
+        let mut next_cursor = 0u32;
+
+        for (schedule_id, mut schedule) in schedules.iter() {
+            if schedule_id <= cursor {
+                continue;
+            }
+            if !schedule.active || schedule.next_due > current_time {
+                continue;
+            }
+            if processed >= MAX_SCHEDULES_PER_RUN {
+                next_cursor = last_processed;
+                break;
+            }
+            processed += 1;
+            last_processed = schedule_id;
+
+            // The goal was closed or swept earlier in this run
+            let mut goal = match Self::load_goal(&env, schedule.goal_id) {
+                Some(goal) => goal,
+                None => {
+                    schedule.active = false;
+                    schedules.set(schedule_id, schedule);
+                    continue;
+                }
+            };
+            let owner = goal.owner.clone();
+            let funded = Self::pull_schedule_funds(&env, &schedule, &goal)?;
+            let mut swept = false;
+            if funded {
+                Self::accrue_interest(&env, &goal)?;
+                goal.current_amount = goal
+                    .current_amount
+                    .checked_add(schedule.amount)
+                    .ok_or(SavingsGoalError::Overflow)?;
+                Self::record_milestones(&env, &mut goal);
+                Self::save_goal(&env, &goal);
+
+                env.events().publish(
+                    (symbol_short!("savings"), SavingsEvent::FundsAdded),
+                    (schedule.goal_id, goal.owner.clone(), schedule.amount),
+                );
+                Self::record_execution(&env, &schedule, ExecutionStatus::Executed);
+            } else {
+                Self::update_stats(&env, &owner, |stats| stats.deposit_streak = 0);
+                schedule.skipped_count += 1;
+                Self::record_execution(&env, &schedule, ExecutionStatus::Skipped);
+                env.events().publish(
+                    (symbol_short!("savings"), SavingsEvent::ScheduleSkipped),
+                    (schedule_id, schedule.goal_id, schedule.funder.clone()),
+                );
+            }
+
+            if funded && goal.current_amount >= goal.target_amount {
+                Self::record_completion(&env, &goal);
+                env.events().publish(
+                    (symbol_short!("savings"), SavingsEvent::GoalCompleted),
+                    (schedule.goal_id, goal.owner),
+                );
+                swept = Self::sweep_if_complete(&env, schedule.goal_id)?;
+                if swept {
+                    Self::deactivate_goal_schedules(&mut schedules, schedule.goal_id);
+                }
+            }
+
+            if funded {
+                schedule.last_executed = Some(current_time);
+            }
+
+            if schedule.recurring && schedule.interval > 0 {
+                let mut missed = 0u32;
+                let mut next = schedule.next_due + schedule.interval;
+                while next <= current_time {
+                    missed += 1;
+                    next += schedule.interval;
+                }
+                schedule.missed_count += missed;
+                schedule.next_due = next;
+                if missed > 0 {
+                    Self::update_stats(&env, &owner, |stats| stats.deposit_streak = 0);
+                }
+
+                if missed > 0 {
+                    env.events().publish(
+                        (symbol_short!("savings"), SavingsEvent::ScheduleMissed),
+                        (schedule_id, missed),
+                    );
+                }
+            } else {
+                schedule.active = false;
+            }
+            if swept {
+                schedule.active = false;
+            }
+
+            if !funded {
+                schedules.set(schedule_id, schedule);
+                continue;
+            }
+            // A late run still counts, but only after the misses broke the streak
+            Self::update_stats(&env, &owner, |stats| {
+                stats.total_saved += schedule.amount;
+                stats.deposit_streak += 1;
+                stats.best_streak = stats.best_streak.max(stats.deposit_streak);
+            });
+            schedules.set(schedule_id, schedule);
+            executed.push_back(schedule_id);
+
+            env.events().publish(
+                (symbol_short!("savings"), SavingsEvent::ScheduleExecuted),
+                schedule_id,
+            );
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SAV_SCH"), &schedules);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SCH_CUR"), &next_cursor);
+        Self::flag_overdue_goals(&env);
+        Self::flag_dormant_goals(&env);
+
+        Ok(executed)
+    }
+
+    pub fn get_savings_schedules(env: Env, owner: Address) -> Vec<SavingsSchedule> {
+        let schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+
+        let mut result = Vec::new(&env);
+        for (_, schedule) in schedules.iter() {
+            if schedule.owner == owner {
+                result.push_back(schedule);
+            }
+        }
+        result
+    }
+
+    pub fn get_savings_schedule(env: Env, schedule_id: u32) -> Option<SavingsSchedule> {
+        let schedules: Map<u32, SavingsSchedule> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("SAV_SCH"))
+            .unwrap_or_else(|| Map::new(&env));
+        schedules.get(schedule_id)
+    }
+
+    // -----------------------------------------------------------------------
+    // Private helpers
+    // -----------------------------------------------------------------------
+
+    fn require_nonce(env: &Env, address: &Address, expected: u64) -> Result<(), SavingsGoalError> {
+        let current = Self::get_nonce(env.clone(), address.clone());
+        if expected != current {
+            return Err(SavingsGoalError::InvalidNonce);
+        }
+        Ok(())
+    }
+
+    fn increment_nonce(env: &Env, address: &Address) -> Result<(), SavingsGoalError> {
+        let current = Self::get_nonce(env.clone(), address.clone());
+        let next = current.checked_add(1).ok_or(SavingsGoalError::Overflow)?;
+        let mut nonces: Map<Address, u64> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NONCES"))
+            .unwrap_or_else(|| Map::new(env));
+        nonces.set(address.clone(), next);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NONCES"), &nonces);
+        Ok(())
+    }
+
+    /// sha256 over the big-endian version followed by the XDR of each field.
+    fn compute_snapshot_checksum(env: &Env, snapshot: &GoalsExportSnapshot) -> Bytes {
+        let mut data = Bytes::from_array(env, &snapshot.version.to_be_bytes());
+        data.append(&snapshot.next_id.to_xdr(env));
+        data.append(&snapshot.goals.clone().to_xdr(env));
+        data.append(&snapshot.next_schedule_id.to_xdr(env));
+        data.append(&snapshot.schedules.clone().to_xdr(env));
+        data.append(&snapshot.nonces.clone().to_xdr(env));
+        data.append(&snapshot.audit_log.clone().to_xdr(env));
+        env.crypto().sha256(&data).into()
+    }
+
+    /// Additive digest used by version 1 snapshots.
+    fn legacy_checksum(version: u32, next_id: u32, goals: &Vec<SavingsGoal>) -> u64 {
+        let mut c = version as u64 + next_id as u64;
+        for i in 0..goals.len() {
+            if let Some(g) = goals.get(i) {
+                c = c
+                    .wrapping_add(g.id as u64)
+                    .wrapping_add(g.target_amount as u64)
+                    .wrapping_add(g.current_amount as u64);
+            }
+        }
+        c.wrapping_mul(31)
+    }
+
+    fn append_audit(env: &Env, operation: Symbol, caller: &Address, success: bool) {
+        let timestamp = env.ledger().timestamp();
+        let mut log: Vec<AuditEntry> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("AUDIT"))
+            .unwrap_or_else(|| Vec::new(env));
+        if log.len() >= MAX_AUDIT_ENTRIES {
+            let mut new_log = Vec::new(env);
+            for i in 1..log.len() {
+                if let Some(entry) = log.get(i) {
+                    new_log.push_back(entry);
+                }
+            }
+            log = new_log;
+        }
+        log.push_back(AuditEntry {
+            operation,
+            caller: caller.clone(),
+            timestamp,
+            success,
+        });
+        env.storage().instance().set(&symbol_short!("AUDIT"), &log);
+    }
+
+    fn load_goal(env: &Env, goal_id: u32) -> Option<SavingsGoal> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("GOAL"), goal_id))
+    }
+
+    /// Write a goal to its own persistent entry and keep the entry alive.
+    ///
+    /// Also stamps the goal's last change for the dormancy policy.
+    fn save_goal(env: &Env, goal: &SavingsGoal) {
+        let key = (symbol_short!("GOAL"), goal.id);
+        let storage = env.storage().persistent();
+        storage.set(&key, goal);
+        let key = (symbol_short!("GOAL_ACT"), goal.id);
+        storage.set(&key, &env.ledger().timestamp());
+        Self::extend_goal_ttl(env, goal.id);
+    }
+
+    /// Extend a stored goal's entry and its activity stamp. Reads call this
+    /// too, so goals that are only looked at stay live.
+    fn extend_goal_ttl(env: &Env, goal_id: u32) {
+        let storage = env.storage().persistent();
+        storage.extend_ttl(
+            &(symbol_short!("GOAL"), goal_id),
+            GOAL_LIFETIME_THRESHOLD,
+            GOAL_BUMP_AMOUNT,
+        );
+        let key = (symbol_short!("GOAL_ACT"), goal_id);
+        if storage.has(&key) {
+            storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        }
+    }
+
+    /// Ids of `owner`'s goals in creation order.
+    fn owner_goal_ids(env: &Env, owner: &Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("OWN_GOAL"), owner.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// `owner_goal_ids` for reads that should keep the index and the
+    /// contract instance live.
+    fn live_owner_goal_ids(env: &Env, owner: &Address) -> Vec<u32> {
+        Self::extend_instance_ttl(env);
+        let key = (symbol_short!("OWN_GOAL"), owner.clone());
+        let storage = env.storage().persistent();
+        if !storage.has(&key) {
+            return Vec::new(env);
+        }
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+        storage.get(&key).unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn save_owner_goal_ids(env: &Env, owner: &Address, ids: &Vec<u32>) {
+        let key = (symbol_short!("OWN_GOAL"), owner.clone());
+        let storage = env.storage().persistent();
+        storage.set(&key, ids);
+        storage.extend_ttl(&key, GOAL_LIFETIME_THRESHOLD, GOAL_BUMP_AMOUNT);
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+// -----------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Env, String,
+    };
+
+    fn make_env() -> Env {
+        Env::default()
+    }
+
+    fn setup_goals(env: &Env, client: &SavingsGoalContractClient, owner: &Address, count: u32) {
+        for i in 0..count {
+            client.create_goal(
+                owner,
+                &String::from_str(env, "Goal"),
+                &(1000i128 * (i as i128 + 1)),
+                &(env.ledger().timestamp() + 86400 * (i as u64 + 1)),
+            );
+        }
+    }
+
+    // --- get_goals ---
+
+    #[test]
+    fn test_get_goals_empty() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        let page = client.get_goals(&owner, &0, &0);
+        assert_eq!(page.count, 0);
+        assert_eq!(page.next_cursor, 0);
+        assert_eq!(page.items.len(), 0);
+    }
+
+    #[test]
+    fn test_get_goals_single_page() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 5);
+
+        let page = client.get_goals(&owner, &0, &10);
+        assert_eq!(page.count, 5);
+        assert_eq!(page.next_cursor, 0);
+    }
+
+    #[test]
+    fn test_get_goals_multiple_pages() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 9);
+
+        // Page 1
+        let page1 = client.get_goals(&owner, &0, &4);
+        assert_eq!(page1.count, 4);
+        assert!(page1.next_cursor > 0);
+
+        // Page 2
+        let page2 = client.get_goals(&owner, &page1.next_cursor, &4);
+        assert_eq!(page2.count, 4);
+        assert!(page2.next_cursor > 0);
+
+        // Page 3 (last)
+        let page3 = client.get_goals(&owner, &page2.next_cursor, &4);
+        assert_eq!(page3.count, 1);
+        assert_eq!(page3.next_cursor, 0);
+    }
+
+    #[test]
+    fn test_get_goals_multi_owner_isolation() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner_a = Address::generate(&env);
+        let owner_b = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner_a, 3);
+        setup_goals(&env, &client, &owner_b, 4);
+
+        let page_a = client.get_goals(&owner_a, &0, &20);
+        assert_eq!(page_a.count, 3);
+        for g in page_a.items.iter() {
+            assert_eq!(g.owner, owner_a);
+        }
+
+        let page_b = client.get_goals(&owner_b, &0, &20);
+        assert_eq!(page_b.count, 4);
+    }
+
+    #[test]
+    fn test_get_goals_cursor_is_exclusive() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 4);
+
+        let first = client.get_goals(&owner, &0, &2);
+        assert_eq!(first.count, 2);
+        let last_id = first.items.get(1).unwrap().id;
+
+        // cursor should be exclusive — next page should NOT include `last_id`
+        let second = client.get_goals(&owner, &last_id, &2);
+        for g in second.items.iter() {
+            assert!(g.id > last_id, "cursor should be exclusive");
+        }
+    }
+
+    #[test]
+    fn test_limit_zero_uses_default() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 3);
+        let page = client.get_goals(&owner, &0, &0);
+        assert_eq!(page.count, 3); // 3 < DEFAULT_PAGE_LIMIT so all returned
+    }
+
+    #[test]
+    fn test_token_goal_holds_and_returns_tokens() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
+
+        let goal_id = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "School"),
+            &500,
+            &(env.ledger().timestamp() + 86400),
+            &token.address(),
+        );
+        client.add_to_goal(&owner, &goal_id, &400);
+        assert_eq!(balance.balance(&owner), 600);
+        assert_eq!(balance.balance(&id), 400);
+
+        client.unlock_goal(&owner, &goal_id);
+        client.withdraw_from_goal(&owner, &goal_id, &150);
+        assert_eq!(balance.balance(&owner), 750);
+
+        let report = client.reconcile_token(&token.address());
+        assert_eq!(report.held, 250);
+        assert_eq!(report.owed, 250);
+        assert_eq!(report.surplus, 0);
+
+        // A plain goal never touches tokens
+        let plain = client.create_goal(&owner, &String::from_str(&env, "Plan"), &100, &0);
+        client.add_to_goal(&owner, &plain, &50);
+        assert_eq!(client.get_token_liabilities(&token.address()), 250);
+    }
+
+    /// Holds one voucher's tokens and pays out only for `school` goals
+    #[contract]
+    pub struct MockVouchers;
+
+    #[contractimpl]
+    impl MockVouchers {
+        #[allow(clippy::too_many_arguments)]
+        pub fn redeem(
+            env: Env,
+            redeemer: Address,
+            _voucher_id: u32,
+            holder: Address,
+            token: Address,
+            amount: i128,
+            category: Symbol,
+            to: Address,
+        ) -> i128 {
+            redeemer.require_auth();
+            holder.require_auth();
+            assert_eq!(category, symbol_short!("school"));
+            let token = TokenClient::new(&env, &token);
+            token.transfer(&env.current_contract_address(), &to, &amount);
+            token.balance(&env.current_contract_address())
+        }
+    }
+
+    #[test]
+    fn test_add_to_goal_with_voucher() {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let vouchers_id = env.register_contract(None, MockVouchers);
+        StellarAssetClient::new(&env, &token.address()).mint(&vouchers_id, &500);
+        let goal_id = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "School"),
+            &1_000,
+            &(env.ledger().timestamp() + 86400),
+            &token.address(),
+        );
+        client.set_goal_category(
+            &owner,
+            &goal_id,
+            &symbol_short!("school"),
+            &Vec::new(&env),
+            &String::from_str(&env, ""),
+        );
+
+        assert_eq!(
+            client.try_add_to_goal_with_voucher(&owner, &goal_id, &1, &300),
+            Err(Ok(SavingsGoalError::VoucherNotUsable))
+        );
+        assert_eq!(
+            client.try_set_vouchers_contract(&owner, &Some(vouchers_id.clone())),
+            Err(Ok(SavingsGoalError::Unauthorized))
+        );
+        client.set_vouchers_contract(&admin, &Some(vouchers_id.clone()));
+        assert_eq!(client.get_vouchers_contract(), Some(vouchers_id.clone()));
+
+        assert_eq!(
+            client.add_to_goal_with_voucher(&owner, &goal_id, &1, &300),
+            300
+        );
+        let report = client.reconcile_token(&token.address());
+        assert_eq!(report.held, 300);
+        assert_eq!(report.owed, 300);
+
+        // Voucher funds stay in the goal until it completes
+        assert_eq!(client.get_voucher_locked(&goal_id), 300);
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &700);
+        client.add_to_goal(&owner, &goal_id, &100);
+        client.unlock_goal(&owner, &goal_id);
+        assert_eq!(
+            client.try_withdraw_from_goal(&owner, &goal_id, &101),
+            Err(Ok(SavingsGoalError::GoalLocked))
+        );
+        assert_eq!(client.withdraw_from_goal(&owner, &goal_id, &100), 300);
+        assert!(client
+            .try_close_goal(&owner, &goal_id, &Some(owner.clone()))
+            .is_err());
+        assert_eq!(
+            client.try_update_goal(&owner, &goal_id, &300, &(env.ledger().timestamp() + 86400)),
+            Err(Ok(SavingsGoalError::GoalLocked))
+        );
+        client.add_to_goal(&owner, &goal_id, &700);
+        assert_eq!(client.get_voucher_locked(&goal_id), 0);
+
+        // Goals outside the voucher's categories are refused
+        let other = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "Trip"),
+            &1_000,
+            &(env.ledger().timestamp() + 86400),
+            &token.address(),
+        );
+        assert_eq!(
+            client.try_add_to_goal_with_voucher(&owner, &other, &1, &100),
+            Err(Ok(SavingsGoalError::VoucherNotUsable))
+        );
+        assert_eq!(client.get_goal(&other).unwrap().current_amount, 0);
+    }
+
+    #[test]
+    fn test_milestones_and_progress() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        let goal_id =
+            client.create_goal(&owner, &String::from_str(&env, "Fees"), &1_000, &2_000_000);
+        client.add_to_goal(&owner, &goal_id, &600);
+        let progress = client.get_goal_progress(&goal_id).unwrap();
+        assert_eq!(progress.percentage, 60);
+        assert_eq!(progress.milestones, soroban_sdk::vec![&env, 250, 500, 750]);
+        assert_eq!(progress.milestones_hit, 2);
+        assert_eq!(progress.projected_completion, None);
+
+        // Weekly 100 from t=1000: 700, 800, 900, 1000 -> fourth payment completes
+        client.create_savings_schedule(&owner, &goal_id, &100, &1_000, &604_800);
+        let progress = client.get_goal_progress(&goal_id).unwrap();
+        assert_eq!(progress.projected_completion, Some(1_000 + 3 * 604_800));
+
+        client.set_goal_milestones(&owner, &goal_id, &soroban_sdk::vec![&env, 100, 900]);
+        assert_eq!(client.get_goal(&goal_id).unwrap().milestones_hit, 1);
+        client.add_to_goal(&owner, &goal_id, &300);
+        assert_eq!(client.get_goal(&goal_id).unwrap().milestones_hit, 2);
+        assert_eq!(client.get_goal_progress(&goal_id).unwrap().percentage, 90);
+    }
+
+    #[test]
+    fn test_goals_stored_in_persistent_entries() {
+        use soroban_sdk::testutils::storage::Persistent as _;
+
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let other = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 2);
+        setup_goals(&env, &client, &other, 1);
+
+        env.as_contract(&id, || {
+            let storage = env.storage().persistent();
+            let key = (symbol_short!("GOAL"), 3u32);
+            let goal: SavingsGoal = storage.get(&key).unwrap();
+            assert_eq!(goal.owner, other);
+            assert!(storage.get_ttl(&key) >= GOAL_BUMP_AMOUNT);
+
+            let index: Vec<u32> = storage
+                .get(&(symbol_short!("OWN_GOAL"), owner.clone()))
+                .unwrap();
+            assert_eq!(index, soroban_sdk::vec![&env, 1, 2]);
+            assert!(!env.storage().instance().has(&symbol_short!("GOALS")));
+        });
+        assert_eq!(client.get_all_goals(&other).len(), 1);
+    }
+
+    #[test]
+    fn test_totals_grouped_by_category() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 3);
+        let education = symbol_short!("education");
+        let tags = soroban_sdk::vec![&env, symbol_short!("kids")];
+        client.set_goal_category(&owner, &1, &education, &tags, &String::from_str(&env, "🎓"));
+        client.set_goal_category(
+            &owner,
+            &2,
+            &education,
+            &Vec::new(&env),
+            &String::from_str(&env, ""),
+        );
+        client.add_to_goal(&owner, &1, &100);
+        client.add_to_goal(&owner, &2, &250);
+        client.add_to_goal(&owner, &3, &40);
+
+        let totals = client.get_total_saved_by_category(&owner);
+        assert_eq!(totals.get(education.clone()), Some(350));
+        assert_eq!(totals.get(symbol_short!("general")), Some(40));
+        assert_eq!(client.get_goals_by_category(&owner, &education).len(), 2);
+        assert_eq!(client.get_goal(&1).unwrap().tags, tags);
+
+        let other = Address::generate(&env);
+        let result = client.try_set_goal_category(
+            &other,
+            &3,
+            &education,
+            &Vec::new(&env),
+            &String::from_str(&env, ""),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_close_goal_archives_and_sweeps() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 2);
+        client.create_savings_schedule(&owner, &2, &10, &1_000, &86_400);
+
+        let empty = client.close_goal(&owner, &1, &None);
+        assert_eq!(empty.swept_amount, 0);
+        assert!(client.get_goal(&1).is_none());
+
+        client.add_to_goal(&owner, &2, &300);
+        assert!(client.try_close_goal(&owner, &2, &None).is_err());
+        // New goals start locked
+        assert!(client
+            .try_close_goal(&owner, &2, &Some(wallet.clone()))
+            .is_err());
+        client.unlock_goal(&owner, &2);
+        let swept = client.close_goal(&owner, &2, &Some(wallet.clone()));
+        assert_eq!(swept.swept_amount, 300);
+        assert_eq!(swept.swept_to, Some(wallet));
+        assert!(!client.get_savings_schedule(&1).unwrap().active);
+
+        assert_eq!(client.get_all_goals(&owner).len(), 0);
+        let archive = client.get_archived_goals(&owner);
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.get(1).unwrap().goal.current_amount, 300);
+    }
+
+    #[test]
+    fn test_completion_action_sweeps_goal() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
+
+        // Goal 1 target 1000, goal 2 target 2000, goal 3 target 3000
+        setup_goals(&env, &client, &owner, 3);
+        client.set_completion_action(&owner, &1, &CompletionAction::ToGoal(2));
+        client.set_completion_action(&owner, &2, &CompletionAction::Transfer(wallet.clone()));
+        assert!(client
+            .try_set_completion_action(&owner, &3, &CompletionAction::ToGoal(3))
+            .is_err());
+
+        client.add_to_goal(&owner, &1, &1_000);
+        assert!(client.get_goal(&1).is_none());
+        assert_eq!(client.get_goal(&2).unwrap().current_amount, 1_000);
+
+        // A schedule completes goal 2, which sweeps to the wallet
+        client.create_savings_schedule(&owner, &2, &1_000, &500, &86_400);
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.execute_due_savings_schedules();
+        assert!(client.get_goal(&2).is_none());
+        assert!(!client.get_savings_schedule(&1).unwrap().active);
+
+        let archive = client.get_archived_goals(&owner);
+        assert_eq!(archive.get(0).unwrap().swept_to_goal, Some(2));
+        assert_eq!(archive.get(1).unwrap().swept_amount, 2_000);
+        assert_eq!(archive.get(1).unwrap().swept_to, Some(wallet));
+    }
+
+    #[test]
+    fn test_early_withdrawal_penalty_goes_to_sink() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        StellarAssetClient::new(&env, &token).mint(&owner, &10_000);
+        let balances = TokenClient::new(&env, &token);
+
+        let name = String::from_str(&env, "School");
+        let goal_id = client.create_goal_with_penalty(
+            &owner,
+            &name,
+            &5_000,
+            &1_000,
+            &Some(token.clone()),
+            &1_000,
+            &PenaltySink::Address(wallet.clone()),
+        );
+        client.add_to_goal(&owner, &goal_id, &2_000);
+        client.unlock_goal(&owner, &goal_id);
+
+        client.withdraw_from_goal(&owner, &goal_id, &500);
+        assert_eq!(balances.balance(&wallet), 50);
+        assert_eq!(balances.balance(&owner), 8_450);
+        let history = client.get_penalty_history(&goal_id);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.get(0).unwrap().penalty, 50);
+
+        // Free after the target date
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.withdraw_from_goal(&owner, &goal_id, &500);
+        assert_eq!(balances.balance(&owner), 8_950);
+        assert_eq!(client.get_penalty_history(&goal_id).len(), 1);
+
+        // Penalties can also feed another goal
+        let plain = client.create_goal(&owner, &name, &1_000, &0);
+        let raided = client.create_goal_with_penalty(
+            &owner,
+            &name,
+            &1_000,
+            &5_000,
+            &None,
+            &2_500,
+            &PenaltySink::Goal(plain),
+        );
+        client.add_to_goal(&owner, &raided, &400);
+        client.unlock_goal(&owner, &raided);
+        client.withdraw_from_goal(&owner, &raided, &400);
+        assert_eq!(client.get_goal(&plain).unwrap().current_amount, 100);
+    }
+
+    #[test]
+    fn test_goal_transfer_requires_acceptance() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let parent = Address::generate(&env);
+        let child = Address::generate(&env);
+        let stranger = Address::generate(&env);
+
+        setup_goals(&env, &client, &parent, 2);
+        client.add_to_goal(&parent, &1, &400);
+        client.transfer_goal(&parent, &child, &1);
+        assert_eq!(client.get_goal(&1).unwrap().owner, parent);
+        assert!(client.try_accept_goal_transfer(&stranger, &1).is_err());
+
+        client.accept_goal_transfer(&child, &1);
+        let goal = client.get_goal(&1).unwrap();
+        assert_eq!(goal.owner, child);
+        assert_eq!(goal.current_amount, 400);
+        assert_eq!(client.get_all_goals(&parent).len(), 1);
+        assert_eq!(client.get_all_goals(&child).len(), 1);
+        assert!(client.get_pending_transfer(&1).is_none());
+    }
+
+    #[test]
+    fn test_beneficiary_claims_after_owner_inactivity() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let relative = Address::generate(&env);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        setup_goals(&env, &client, &owner, 1);
+        client.add_to_goal(&owner, &1, &700);
+        client.set_beneficiary(&owner, &1, &relative, &5_000, &10_000);
+
+        env.ledger().with_mut(|li| li.timestamp = 9_000);
+        assert!(client.try_claim_as_beneficiary(&relative, &1).is_err());
+
+        // Owner activity restarts the inactivity clock
+        client.add_to_goal(&owner, &1, &100);
+        env.ledger().with_mut(|li| li.timestamp = 18_000);
+        assert!(client.try_claim_as_beneficiary(&relative, &1).is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 19_000);
+        assert_eq!(client.claim_as_beneficiary(&relative, &1), 800);
+        assert!(client.get_goal(&1).is_none());
+        let archived = client.get_archived_goals(&owner).get(0).unwrap();
+        assert_eq!(archived.swept_to, Some(relative));
+    }
+
+    #[test]
+    fn test_allocate_across_goals_by_priority_and_weight() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
 
-        executed
-    }
+        // Targets 1000, 2000, 3000
+        setup_goals(&env, &client, &owner, 3);
+        client.set_goal_allocation(&owner, &3, &10, &1);
+        client.set_goal_allocation(&owner, &2, &5, &3);
+
+        let result = client.allocate_across_goals(&owner, &3500);
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result.get(0).unwrap(),
+            GoalAllocation {
+                goal_id: 3,
+                amount: 3000
+            }
+        );
+        assert_eq!(
+            result.get(1).unwrap(),
+            GoalAllocation {
+                goal_id: 2,
+                amount: 500
+            }
+        );
 
-    pub fn get_savings_schedules(env: Env, owner: Address) -> Vec<SavingsSchedule> {
-        let schedules: Map<u32, SavingsSchedule> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("SAV_SCH"))
-            .unwrap_or_else(|| Map::new(&env));
+        client.set_allocation_mode(&owner, &AllocationMode::Proportional);
+        // Remaining room: goal 2 -> 1500 (weight 3), goal 1 -> 1000 (weight 1)
+        let result = client.allocate_across_goals(&owner, &2000);
+        assert_eq!(
+            result.get(0).unwrap(),
+            GoalAllocation {
+                goal_id: 2,
+                amount: 1500
+            }
+        );
+        assert_eq!(
+            result.get(1).unwrap(),
+            GoalAllocation {
+                goal_id: 1,
+                amount: 500
+            }
+        );
 
-        let mut result = Vec::new(&env);
-        for (_, schedule) in schedules.iter() {
-            if schedule.owner == owner {
-                result.push_back(schedule);
+        // Only 500 of room left, the rest is not taken
+        let result = client.allocate_across_goals(&owner, &900);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result.get(0).unwrap(),
+            GoalAllocation {
+                goal_id: 1,
+                amount: 500
             }
-        }
-        result
+        );
+        assert!(client.is_goal_completed(&1));
     }
 
-    pub fn get_savings_schedule(env: Env, schedule_id: u32) -> Option<SavingsSchedule> {
-        let schedules: Map<u32, SavingsSchedule> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("SAV_SCH"))
-            .unwrap_or_else(|| Map::new(&env));
-        schedules.get(schedule_id)
+    #[test]
+    fn test_matcher_escrow_matches_contributions_up_to_cap() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let parent = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        let minter = StellarAssetClient::new(&env, &token.address());
+        minter.mint(&owner, &1_000);
+        minter.mint(&parent, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
+
+        let goal_id = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "Laptop"),
+            &2_000,
+            &(env.ledger().timestamp() + 86400),
+            &token.address(),
+        );
+        // 1:2 match, at most 300
+        client.pledge_match(&parent, &goal_id, &5_000, &300);
+        assert_eq!(balance.balance(&parent), 700);
+
+        client.add_to_goal(&owner, &goal_id, &400);
+        assert_eq!(client.get_goal(&goal_id).unwrap().current_amount, 600);
+        client.add_to_goal(&owner, &goal_id, &400);
+        assert_eq!(client.get_goal(&goal_id).unwrap().current_amount, 1_100);
+        assert_eq!(client.get_match(&goal_id).unwrap().matched, 300);
+
+        // Fully used pledge refunds nothing; the held tokens match the goal
+        assert_eq!(client.cancel_match(&parent, &goal_id), 0);
+        let report = client.reconcile_token(&token.address());
+        assert_eq!(report.held, 1_100);
+        assert_eq!(report.surplus, 0);
+
+        // Unused escrow goes back when the goal is closed
+        client.pledge_match(&parent, &goal_id, &10_000, &500);
+        client.add_to_goal(&owner, &goal_id, &100);
+        client.unlock_goal(&owner, &goal_id);
+        client.close_goal(&owner, &goal_id, &Some(owner.clone()));
+        assert_eq!(balance.balance(&parent), 700 - 100);
+        assert_eq!(balance.balance(&owner), 1_000 + 300 + 100);
+        assert!(client.get_match(&goal_id).is_none());
     }
 
-    // -----------------------------------------------------------------------
-    // Private helpers
-    // -----------------------------------------------------------------------
+    #[test]
+    fn test_keeper_run_is_capped_and_rewarded() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
 
-    fn require_nonce(env: &Env, address: &Address, expected: u64) {
-        let current = Self::get_nonce(env.clone(), address.clone());
-        if expected != current {
-            panic!("Invalid nonce: expected {}, got {}", current, expected);
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let keeper = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        StellarAssetClient::new(&env, &token.address()).mint(&admin, &1_000);
+        client.set_pause_admin(&admin, &admin);
+        client.set_keeper_reward(&admin, &token.address(), &10);
+        client.fund_keeper_rewards(&admin, &520);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let goal_id = client.create_goal(&owner, &String::from_str(&env, "Big"), &1_000_000, &0);
+        for _ in 0..(MAX_SCHEDULES_PER_RUN + 5) {
+            client.create_savings_schedule(&owner, &goal_id, &1, &2_000, &0);
         }
-    }
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+
+        let first = client.execute_schedules_for_reward(&keeper);
+        assert_eq!(first.len(), MAX_SCHEDULES_PER_RUN);
+        assert_eq!(client.get_schedule_cursor(), MAX_SCHEDULES_PER_RUN);
+        assert_eq!(
+            TokenClient::new(&env, &token.address()).balance(&keeper),
+            500
+        );
 
-    fn increment_nonce(env: &Env, address: &Address) {
-        let current = Self::get_nonce(env.clone(), address.clone());
-        let next = current.checked_add(1).expect("nonce overflow");
-        let mut nonces: Map<Address, u64> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("NONCES"))
-            .unwrap_or_else(|| Map::new(env));
-        nonces.set(address.clone(), next);
-        env.storage()
-            .instance()
-            .set(&symbol_short!("NONCES"), &nonces);
+        let second = client.execute_schedules_for_reward(&keeper);
+        assert_eq!(second.len(), 5);
+        assert_eq!(client.get_schedule_cursor(), 0);
+        // The pool only covers part of the second reward
+        assert_eq!(
+            TokenClient::new(&env, &token.address()).balance(&keeper),
+            520
+        );
+        assert_eq!(client.get_keeper_pool(), 0);
+        assert_eq!(client.get_goal(&goal_id).unwrap().current_amount, 55);
     }
 
-    fn compute_goals_checksum(version: u32, next_id: u32, goals: &Vec<SavingsGoal>) -> u64 {
-        let mut c = version as u64 + next_id as u64;
-        for i in 0..goals.len() {
-            if let Some(g) = goals.get(i) {
-                c = c
-                    .wrapping_add(g.id as u64)
-                    .wrapping_add(g.target_amount as u64)
-                    .wrapping_add(g.current_amount as u64);
-            }
-        }
-        c.wrapping_mul(31)
-    }
+    #[test]
+    fn test_token_schedule_pulls_from_funder_or_skips() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
 
-    fn append_audit(env: &Env, operation: Symbol, caller: &Address, success: bool) {
-        let timestamp = env.ledger().timestamp();
-        let mut log: Vec<AuditEntry> = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("AUDIT"))
-            .unwrap_or_else(|| Vec::new(env));
-        if log.len() >= MAX_AUDIT_ENTRIES {
-            let mut new_log = Vec::new(env);
-            for i in 1..log.len() {
-                if let Some(entry) = log.get(i) {
-                    new_log.push_back(entry);
-                }
-            }
-            log = new_log;
-        }
-        log.push_back(AuditEntry {
-            operation,
-            caller: caller.clone(),
-            timestamp,
-            success,
-        });
-        env.storage().instance().set(&symbol_short!("AUDIT"), &log);
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        StellarAssetClient::new(&env, &token.address()).mint(&wallet, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let goal_id = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "Rent"),
+            &5_000,
+            &100_000,
+            &token.address(),
+        );
+        let schedule_id = client.create_savings_schedule(&owner, &goal_id, &300, &2_000, &1_000);
+        client.set_schedule_funder(&owner, &schedule_id, &wallet);
+
+        // No allowance yet
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        assert_eq!(client.execute_due_savings_schedules().len(), 0);
+        assert_eq!(client.get_goal(&goal_id).unwrap().current_amount, 0);
+
+        balance.approve(&wallet, &id, &600, &1_000);
+        env.ledger().with_mut(|li| li.timestamp = 3_000);
+        assert_eq!(client.execute_due_savings_schedules().len(), 1);
+        assert_eq!(balance.balance(&wallet), 700);
+        assert_eq!(client.get_goal(&goal_id).unwrap().current_amount, 300);
+
+        let history = client.get_schedule_executions(&schedule_id);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(0).unwrap().status, ExecutionStatus::Skipped);
+        assert_eq!(history.get(1).unwrap().status, ExecutionStatus::Executed);
+        assert_eq!(
+            client
+                .get_savings_schedule(&schedule_id)
+                .unwrap()
+                .skipped_count,
+            1
+        );
+        assert_eq!(client.reconcile_token(&token.address()).surplus, 0);
     }
 
-    fn extend_instance_ttl(env: &Env) {
-        env.storage()
-            .instance()
-            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-    }
-}
+    #[test]
+    fn test_at_risk_and_overdue_goals() {
+        use soroban_sdk::{testutils::Events, TryFromVal};
 
-// -----------------------------------------------------------------------
-// Tests
-// -----------------------------------------------------------------------
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::Address as _, Env, String};
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
 
-    fn make_env() -> Env {
-        Env::default()
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        // Goal 1: 1000 by 87_400, goal 2: 2000 by 173_800
+        setup_goals(&env, &client, &owner, 2);
+        client.create_savings_schedule(&owner, &1, &250, &10_000, &20_000);
+        client.create_savings_schedule(&owner, &2, &500, &10_000, &20_000);
+
+        // Goal 1 gets 250 at 10k, 30k, 50k, 70k; goal 2 keeps going
+        let at_risk = client.get_at_risk_goals(&owner);
+        assert_eq!(at_risk.len(), 0);
+
+        client.modify_savings_schedule(&owner, &1, &200, &10_000, &20_000);
+        let at_risk = client.get_at_risk_goals(&owner);
+        assert_eq!(at_risk.len(), 1);
+        let goal = at_risk.get(0).unwrap();
+        assert_eq!(goal.goal_id, 1);
+        assert_eq!(goal.projected_amount, 800);
+        assert_eq!(goal.shortfall, 200);
+        assert!(!goal.overdue);
+
+        client.cancel_savings_schedule(&owner, &1);
+        env.ledger().with_mut(|li| li.timestamp = 90_000);
+        client.execute_due_savings_schedules();
+        let overdue = env
+            .events()
+            .all()
+            .iter()
+            .filter(|e| {
+                e.1.get(1)
+                    .and_then(|topic| SavingsEvent::try_from_val(&env, &topic).ok())
+                    .is_some_and(|ev| matches!(ev, SavingsEvent::OverdueGoal))
+            })
+            .count();
+        assert_eq!(overdue, 1);
+        assert!(client.get_at_risk_goals(&owner).get(0).unwrap().overdue);
     }
 
-    fn setup_goals(env: &Env, client: &SavingsGoalContractClient, owner: &Address, count: u32) {
-        for i in 0..count {
-            client.create_goal(
-                owner,
-                &String::from_str(env, "Goal"),
-                &(1000i128 * (i as i128 + 1)),
-                &(env.ledger().timestamp() + 86400 * (i as u64 + 1)),
-            );
-        }
+    #[test]
+    fn test_ownership_failures_are_not_reported_as_missing_goals() {
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let other = Address::generate(&env);
+
+        setup_goals(&env, &client, &owner, 1);
+        assert_eq!(
+            client.try_add_to_goal(&other, &1, &100),
+            Err(Ok(SavingsGoalError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_add_to_goal(&owner, &7, &100),
+            Err(Ok(SavingsGoalError::GoalNotFound))
+        );
+        assert_eq!(
+            client.try_lock_goal(&other, &1),
+            Err(Ok(SavingsGoalError::Unauthorized))
+        );
     }
 
-    // --- get_goals ---
+    #[test]
+    fn test_interest_accrues_and_is_capped_by_pool() {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let env = make_env();
+        env.mock_all_auths();
+        let id = env.register_contract(None, SavingsGoalContract);
+        let client = SavingsGoalContractClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        let minter = StellarAssetClient::new(&env, &token.address());
+        minter.mint(&owner, &10_000);
+        minter.mint(&admin, &1_000);
+        client.set_pause_admin(&admin, &admin);
+        client.set_category_apy(&admin, &symbol_short!("general"), &1_000);
+        client.fund_interest_pool(&admin, &token.address(), &60);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let goal_id = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "Fees"),
+            &50_000,
+            &(1_000 + 2 * 365 * 86400),
+            &token.address(),
+        );
+        client.add_to_goal(&owner, &goal_id, &10_000);
+
+        // 10% a year on 10_000
+        env.ledger()
+            .with_mut(|li| li.timestamp = 1_000 + 365 * 86400);
+        assert_eq!(client.get_accrued_interest(&goal_id), 1_000);
+
+        // The pool only holds 60, so the rest stays accrued
+        assert_eq!(client.claim_interest(&goal_id), 60);
+        assert_eq!(client.get_goal(&goal_id).unwrap().current_amount, 10_060);
+        assert_eq!(client.get_accrued_interest(&goal_id), 940);
+        let pool = client.get_interest_pool(&token.address());
+        assert_eq!(pool.available, 0);
+        assert_eq!(pool.distributed, 60);
+        assert_eq!(client.claim_interest(&goal_id), 0);
+
+        client.fund_interest_pool(&admin, &token.address(), &940);
+        assert_eq!(client.claim_interest(&goal_id), 940);
+        assert_eq!(client.reconcile_token(&token.address()).surplus, 0);
+    }
 
     #[test]
-    fn test_get_goals_empty() {
+    fn test_roundups_feed_goal_and_monthly_stats() {
         let env = make_env();
         env.mock_all_auths();
         let id = env.register_contract(None, SavingsGoalContract);
         let client = SavingsGoalContractClient::new(&env, &id);
         let owner = Address::generate(&env);
 
-        let page = client.get_goals(&owner, &0, &0);
-        assert_eq!(page.count, 0);
-        assert_eq!(page.next_cursor, 0);
-        assert_eq!(page.items.len(), 0);
+        // 2024-03-15
+        env.ledger().with_mut(|li| li.timestamp = 1_710_460_800);
+        setup_goals(&env, &client, &owner, 1);
+        assert_eq!(client.record_roundup(&owner, &1, &1_234, &100), 66);
+        assert_eq!(client.record_roundup(&owner, &1, &500, &100), 0);
+        assert_eq!(client.record_roundup(&owner, &1, &95, &10), 5);
+
+        // 2024-04-01
+        env.ledger().with_mut(|li| li.timestamp = 1_711_929_600);
+        assert_eq!(client.record_roundup(&owner, &1, &1, &100), 99);
+
+        assert_eq!(client.get_goal(&1).unwrap().current_amount, 170);
+        let march = client.get_roundup_stats(&owner, &202403);
+        assert_eq!(march.count, 2);
+        assert_eq!(march.purchases, 1_329);
+        assert_eq!(march.saved, 71);
+        assert_eq!(client.get_roundup_stats(&owner, &202404).saved, 99);
+        assert_eq!(
+            client.try_record_roundup(&owner, &1, &100, &0),
+            Err(Ok(SavingsGoalError::InvalidAmount))
+        );
     }
 
     #[test]
-    fn test_get_goals_single_page() {
+    fn test_guardian_gates_unlock_and_large_withdrawals() {
+        use soroban_sdk::token::{StellarAssetClient, TokenClient};
+
         let env = make_env();
         env.mock_all_auths();
         let id = env.register_contract(None, SavingsGoalContract);
         let client = SavingsGoalContractClient::new(&env, &id);
         let owner = Address::generate(&env);
+        let wallet = Address::generate(&env);
+
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin);
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &1_000);
+        let balance = TokenClient::new(&env, &token.address());
+
+        let goal_id = client.create_token_goal(
+            &owner,
+            &String::from_str(&env, "House"),
+            &1_000,
+            &(env.ledger().timestamp() + 86400),
+            &token.address(),
+        );
+        client.add_to_goal(&owner, &goal_id, &600);
+        client.set_goal_guardian(&owner, &goal_id, &wallet, &100);
+        assert_eq!(
+            client.get_goal_guardian(&goal_id),
+            Some(GoalGuardian {
+                guardian: wallet.clone(),
+                threshold: 100,
+            })
+        );
 
-        setup_goals(&env, &client, &owner, 5);
+        assert_eq!(
+            client.try_unlock_goal(&owner, &goal_id),
+            Err(Ok(SavingsGoalError::GuardianRequired))
+        );
+        client.unlock_goal(&wallet, &goal_id);
 
-        let page = client.get_goals(&owner, &0, &10);
-        assert_eq!(page.count, 5);
-        assert_eq!(page.next_cursor, 0);
+        // Small withdrawals stay with the owner, large ones need the wallet
+        client.withdraw_from_goal(&owner, &goal_id, &100);
+        assert_eq!(
+            client.try_withdraw_from_goal(&owner, &goal_id, &101),
+            Err(Ok(SavingsGoalError::GuardianRequired))
+        );
+        client.withdraw_from_goal(&wallet, &goal_id, &300);
+        assert_eq!(balance.balance(&owner), 800);
+        assert_eq!(balance.balance(&wallet), 0);
+
+        // Closing with more than the threshold left is a large withdrawal too
+        assert_eq!(
+            client.try_remove_goal_guardian(&owner, &goal_id),
+            Err(Ok(SavingsGoalError::GuardianRequired))
+        );
+        client.remove_goal_guardian(&wallet, &goal_id);
+        assert_eq!(client.get_goal_guardian(&goal_id), None);
+        client.withdraw_from_goal(&owner, &goal_id, &200);
+        assert_eq!(balance.balance(&owner), 1_000);
     }
 
     #[test]
-    fn test_get_goals_multiple_pages() {
+    fn test_savings_stats_track_totals_and_streaks() {
         let env = make_env();
         env.mock_all_auths();
         let id = env.register_contract(None, SavingsGoalContract);
         let client = SavingsGoalContractClient::new(&env, &id);
         let owner = Address::generate(&env);
 
-        setup_goals(&env, &client, &owner, 9);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        setup_goals(&env, &client, &owner, 1);
+        client.create_savings_schedule(&owner, &1, &250, &10_000, &20_000);
+        for at in [10_000, 30_000] {
+            env.ledger().with_mut(|li| li.timestamp = at);
+            client.execute_due_savings_schedules();
+        }
+        let stats = client.get_savings_stats(&owner);
+        assert_eq!(stats.total_saved, 500);
+        assert_eq!(stats.deposit_streak, 2);
+
+        // Runs at 50k and 70k were missed, so the late one starts a new streak
+        env.ledger().with_mut(|li| li.timestamp = 90_000);
+        client.execute_due_savings_schedules();
+        let stats = client.get_savings_stats(&owner);
+        assert_eq!(stats.deposit_streak, 1);
+        assert_eq!(stats.best_streak, 2);
+
+        client.add_to_goal(&owner, &1, &250);
+        client.unlock_goal(&owner, &1);
+        client.withdraw_from_goal(&owner, &1, &100);
+        client.add_to_goal(&owner, &1, &100);
+        assert_eq!(
+            client.get_savings_stats(&owner),
+            SavingsStats {
+                total_saved: 1_100,
+                total_withdrawn: 100,
+                goals_completed: 1,
+                deposit_streak: 1,
+                best_streak: 2,
+            }
+        );
+    }
 
-        // Page 1
-        let page1 = client.get_goals(&owner, &0, &4);
-        assert_eq!(page1.count, 4);
-        assert!(page1.next_cursor > 0);
+    #[test]
+    fn test_snapshot_v2_carries_schedules_nonces_and_audit() {
+        let env = make_env();
+        env.mock_all_auths();
+        let old_id = env.register_contract(None, SavingsGoalContract);
+        let old = SavingsGoalContractClient::new(&env, &old_id);
+        let new_id = env.register_contract(None, SavingsGoalContract);
+        let new = SavingsGoalContractClient::new(&env, &new_id);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+
+        old.set_pause_admin(&admin, &admin);
+        new.set_pause_admin(&admin, &admin);
+
+        setup_goals(&env, &old, &owner, 2);
+        old.create_savings_schedule(&owner, &2, &100, &5_000, &86_400);
+        // Spend a nonce and leave an audit entry on the old contract
+        old.import_snapshot(&admin, &0, &old.export_snapshot(&owner));
+        let snapshot = old.export_snapshot(&owner);
+        assert_eq!(snapshot.version, 2);
+        assert_eq!(snapshot.checksum.len(), 32);
+        assert_eq!(snapshot.schedules.len(), 1);
+        assert_eq!(
+            new.try_import_snapshot(&owner, &0, &snapshot),
+            Err(Ok(SavingsGoalError::Unauthorized))
+        );
+        new.import_snapshot(&admin, &0, &snapshot);
+
+        let mut tampered = old.export_snapshot(&owner);
+        let mut schedule = tampered.schedules.get(0).unwrap();
+        schedule.amount = 1;
+        tampered.schedules.set(0, schedule);
+        assert_eq!(
+            new.try_import_snapshot(&admin, &new.get_nonce(&admin), &tampered),
+            Err(Ok(SavingsGoalError::ChecksumMismatch))
+        );
 
-        // Page 2
-        let page2 = client.get_goals(&owner, &page1.next_cursor, &4);
-        assert_eq!(page2.count, 4);
-        assert!(page2.next_cursor > 0);
+        assert_eq!(new.get_all_goals(&owner).len(), 2);
+        let schedules = new.get_savings_schedules(&owner);
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules.get(0).unwrap().amount, 100);
+        // The old nonce carries over, then this import spends another
+        assert_eq!(new.get_nonce(&admin), 2);
+        assert_eq!(
+            new.try_import_snapshot(&admin, &1, &snapshot),
+            Err(Ok(SavingsGoalError::InvalidNonce))
+        );
+        let log = new.get_audit_log(&0, &100);
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.get(0).unwrap().caller, admin);
+    }
 
-        // Page 3 (last)
-        let page3 = client.get_goals(&owner, &page2.next_cursor, &4);
-        assert_eq!(page3.count, 1);
-        assert_eq!(page3.next_cursor, 0);
+    #[test]
+    fn test_import_keeps_token_goals_backed() {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let env = make_env();
+        env.mock_all_auths();
+        let old_id = env.register_contract(None, SavingsGoalContract);
+        let old = SavingsGoalContractClient::new(&env, &old_id);
+        let new_id = env.register_contract(None, SavingsGoalContract);
+        let new = SavingsGoalContractClient::new(&env, &new_id);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        new.set_pause_admin(&admin, &admin);
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        StellarAssetClient::new(&env, &token.address()).mint(&owner, &400);
+        let goal_id = old.create_token_goal(
+            &owner,
+            &String::from_str(&env, "School"),
+            &1_000,
+            &(env.ledger().timestamp() + 86400),
+            &token.address(),
+        );
+        old.add_to_goal(&owner, &goal_id, &400);
+        let snapshot = old.export_snapshot(&owner);
+
+        // The new contract holds none of the tokens the goal claims
+        assert_eq!(
+            new.try_import_snapshot(&admin, &0, &snapshot),
+            Err(Ok(SavingsGoalError::LiabilityMismatch))
+        );
+        StellarAssetClient::new(&env, &token.address()).mint(&new_id, &400);
+        new.import_snapshot(&admin, &0, &snapshot);
+        let report = new.reconcile_token(&token.address());
+        assert_eq!(report.owed, 400);
+        assert_eq!(report.surplus, 0);
     }
 
     #[test]
-    fn test_get_goals_multi_owner_isolation() {
+    fn test_snapshot_v1_still_imports_goals() {
+        let env = make_env();
+        env.mock_all_auths();
+        let old_id = env.register_contract(None, SavingsGoalContract);
+        let old = SavingsGoalContractClient::new(&env, &old_id);
+        let new_id = env.register_contract(None, SavingsGoalContract);
+        let new = SavingsGoalContractClient::new(&env, &new_id);
+        let owner = Address::generate(&env);
+        let admin = Address::generate(&env);
+        new.set_pause_admin(&admin, &admin);
+
+        setup_goals(&env, &old, &owner, 2);
+        new.create_goal(&owner, &String::from_str(&env, "Local"), &100, &0);
+        new.create_savings_schedule(&owner, &1, &10, &5_000, &86_400);
+
+        let mut snapshot = old.export_snapshot(&owner);
+        snapshot.version = 1;
+        snapshot.schedules = Vec::new(&env);
+        snapshot.checksum = Bytes::from_array(
+            &env,
+            &SavingsGoalContract::legacy_checksum(1, snapshot.next_id, &snapshot.goals)
+                .to_be_bytes(),
+        );
+        new.import_snapshot(&admin, &0, &snapshot);
+
+        assert_eq!(new.get_all_goals(&owner).len(), 2);
+        // Version 1 carries no schedules, so the existing ones are kept
+        assert_eq!(new.get_savings_schedules(&owner).len(), 1);
+    }
+
+    #[test]
+    fn test_update_goal_validates_and_records_amendments() {
         let env = make_env();
         env.mock_all_auths();
         let id = env.register_contract(None, SavingsGoalContract);
         let client = SavingsGoalContractClient::new(&env, &id);
-        let owner_a = Address::generate(&env);
-        let owner_b = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let other = Address::generate(&env);
 
-        setup_goals(&env, &client, &owner_a, 3);
-        setup_goals(&env, &client, &owner_b, 4);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let goal_id = client.create_goal(&owner, &String::from_str(&env, "Car"), &1_000, &50_000);
+        client.add_to_goal(&owner, &goal_id, &400);
 
-        let page_a = client.get_goals(&owner_a, &0, &20);
-        assert_eq!(page_a.count, 3);
-        for g in page_a.items.iter() {
-            assert_eq!(g.owner, owner_a);
-        }
+        assert_eq!(
+            client.try_update_goal(&other, &goal_id, &800, &60_000),
+            Err(Ok(SavingsGoalError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_update_goal(&owner, &goal_id, &300, &60_000),
+            Err(Ok(SavingsGoalError::TargetBelowBalance))
+        );
+        assert_eq!(
+            client.try_update_goal(&owner, &goal_id, &800, &1_000),
+            Err(Ok(SavingsGoalError::InvalidDueDate))
+        );
 
-        let page_b = client.get_goals(&owner_b, &0, &20);
-        assert_eq!(page_b.count, 4);
+        let amendment = client.update_goal(&owner, &goal_id, &800, &60_000);
+        assert_eq!(amendment.old_target_amount, 1_000);
+        assert_eq!(amendment.old_target_date, 50_000);
+        let goal = client.get_goal(&goal_id).unwrap();
+        assert_eq!(goal.target_amount, 800);
+        assert_eq!(goal.target_date, 60_000);
+        // 25% and 50% of 800 are 200 and 400
+        assert_eq!(goal.milestones_hit, 2);
+
+        // Lowering the target to the balance completes the goal
+        client.update_goal(&owner, &goal_id, &400, &60_000);
+        assert_eq!(client.get_goal_amendments(&goal_id).len(), 2);
+        assert_eq!(client.get_savings_stats(&owner).goals_completed, 1);
     }
 
     #[test]
-    fn test_get_goals_cursor_is_exclusive() {
+    fn test_update_goal_cannot_shorten_penalty_window() {
         let env = make_env();
         env.mock_all_auths();
         let id = env.register_contract(None, SavingsGoalContract);
         let client = SavingsGoalContractClient::new(&env, &id);
         let owner = Address::generate(&env);
 
-        setup_goals(&env, &client, &owner, 4);
-
-        let first = client.get_goals(&owner, &0, &2);
-        assert_eq!(first.count, 2);
-        let last_id = first.items.get(1).unwrap().id;
-
-        // cursor should be exclusive — next page should NOT include `last_id`
-        let second = client.get_goals(&owner, &last_id, &2);
-        for g in second.items.iter() {
-            assert!(g.id > last_id, "cursor should be exclusive");
-        }
+        let goal_id = client.create_goal_with_penalty(
+            &owner,
+            &String::from_str(&env, "Locked in"),
+            &1_000,
+            &50_000,
+            &None,
+            &1_000,
+            &PenaltySink::Address(Address::generate(&env)),
+        );
+        assert_eq!(
+            client.try_update_goal(&owner, &goal_id, &1_000, &10_000),
+            Err(Ok(SavingsGoalError::InvalidDueDate))
+        );
+        client.update_goal(&owner, &goal_id, &1_000, &90_000);
     }
 
     #[test]
-    fn test_limit_zero_uses_default() {
+    fn test_dormant_goals_are_flagged_then_swept() {
+        use soroban_sdk::{testutils::Events, TryFromVal};
+
         let env = make_env();
         env.mock_all_auths();
         let id = env.register_contract(None, SavingsGoalContract);
         let client = SavingsGoalContractClient::new(&env, &id);
         let owner = Address::generate(&env);
+        let heir = Address::generate(&env);
+        let admin = Address::generate(&env);
+        client.set_pause_admin(&admin, &admin);
+        client.set_dormancy_policy(&admin, &1_000, &500);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let goal_id =
+            client.create_goal(&owner, &String::from_str(&env, "Rainy"), &1_000, &500_000);
+        client.add_to_goal(&owner, &goal_id, &300);
+        client.set_beneficiary(&owner, &goal_id, &heir, &0, &1_000_000);
+
+        let dormant_events = |env: &Env| {
+            env.events()
+                .all()
+                .iter()
+                .filter(|e| {
+                    e.1.get(1)
+                        .and_then(|topic| SavingsEvent::try_from_val(env, &topic).ok())
+                        .is_some_and(|ev| matches!(ev, SavingsEvent::GoalDormant))
+                })
+                .count()
+        };
 
-        setup_goals(&env, &client, &owner, 3);
-        let page = client.get_goals(&owner, &0, &0);
-        assert_eq!(page.count, 3); // 3 < DEFAULT_PAGE_LIMIT so all returned
+        env.ledger().with_mut(|li| li.timestamp = 2_001);
+        client.execute_due_savings_schedules();
+        assert_eq!(dormant_events(&env), 1);
+        assert_eq!(client.get_dormant_since(&goal_id), Some(2_001));
+        assert_eq!(
+            client.try_sweep_dormant_goal(&heir, &goal_id),
+            Err(Ok(SavingsGoalError::NotDormant))
+        );
+
+        // Touching the goal clears the flag until it goes quiet again
+        client.lock_goal(&owner, &goal_id);
+        assert_eq!(client.get_dormant_since(&goal_id), None);
+        env.ledger().with_mut(|li| li.timestamp = 3_100);
+        client.execute_due_savings_schedules();
+        assert_eq!(client.get_dormant_since(&goal_id), Some(3_100));
+
+        env.ledger().with_mut(|li| li.timestamp = 3_600);
+        assert_eq!(
+            client.try_sweep_dormant_goal(&Address::generate(&env), &goal_id),
+            Err(Ok(SavingsGoalError::Unauthorized))
+        );
+        assert_eq!(client.sweep_dormant_goal(&heir, &goal_id), 300);
+        assert!(client.get_goal(&goal_id).is_none());
+        let archived = client.get_archived_goals(&owner).get(0).unwrap();
+        assert_eq!(archived.swept_to, Some(heir));
     }
 
     #[test]
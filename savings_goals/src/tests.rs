@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::testutils::storage::Instance as _;
+use soroban_sdk::testutils::storage::{Instance as _, Persistent as _};
 use soroban_sdk::{
     testutils::{Address as AddressTrait, Events, Ledger, LedgerInfo},
     Address, Env, String, Symbol, TryFromVal,
@@ -29,8 +29,6 @@ fn test_create_goal_unique_ids() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
-
     let name1 = String::from_str(&env, "Goal 1");
     let name2 = String::from_str(&env, "Goal 2");
 
@@ -50,8 +48,6 @@ fn test_add_to_goal_increments() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
-
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "Save"), &1000, &2000000000);
 
@@ -66,7 +62,6 @@ fn test_add_to_non_existent_goal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let res = client.try_add_to_goal(&user, &99, &500);
     assert_eq!(res, Err(Ok(SavingsGoalError::GoalNotFound)));
@@ -79,7 +74,6 @@ fn test_get_goal_retrieval() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let name = String::from_str(&env, "Car");
     let id = client.create_goal(&user, &name, &5000, &2000000000);
@@ -95,7 +89,6 @@ fn test_get_all_goals() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     client.create_goal(&user, &String::from_str(&env, "A"), &100, &2000000000);
     client.create_goal(&user, &String::from_str(&env, "B"), &200, &2000000000);
@@ -111,7 +104,6 @@ fn test_is_goal_completed() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     // 1. Create a goal with a target of 1000
@@ -156,7 +148,6 @@ fn test_edge_cases_large_amounts() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -177,7 +168,6 @@ fn test_zero_amount_fails() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let res = client.try_create_goal(&user, &String::from_str(&env, "Fail"), &0, &2000000000);
     assert_eq!(res, Err(Ok(SavingsGoalError::TargetAmountMustBePositive)));
@@ -190,7 +180,6 @@ fn test_multiple_goals_management() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id1 = client.create_goal(&user, &String::from_str(&env, "G1"), &1000, &2000000000);
     let id2 = client.create_goal(&user, &String::from_str(&env, "G2"), &2000, &2000000000);
@@ -212,7 +201,6 @@ fn test_withdraw_from_goal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "W"), &1000, &2000000000);
 
@@ -235,7 +223,6 @@ fn test_withdraw_too_much() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "W"), &1000, &2000000000);
 
@@ -253,7 +240,6 @@ fn test_withdraw_locked() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "L"), &1000, &2000000000);
 
@@ -271,7 +257,6 @@ fn test_withdraw_unauthorized() {
     let user = Address::generate(&env);
     let other = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "Auth"), &1000, &2000000000);
 
@@ -289,7 +274,6 @@ fn test_lock_unlock_goal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "Lock"), &1000, &2000000000);
 
@@ -312,7 +296,6 @@ fn test_full_withdrawal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "W"), &1000, &2000000000);
 
@@ -335,7 +318,6 @@ fn test_exact_goal_completion() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(&user, &String::from_str(&env, "Exact"), &1000, &2000000000);
 
@@ -572,7 +554,6 @@ fn test_lock_goal_success() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -595,7 +576,6 @@ fn test_unlock_goal_success() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -618,7 +598,6 @@ fn test_lock_goal_unauthorized_panics() {
     let user = Address::generate(&env);
     let other = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -641,7 +620,6 @@ fn test_unlock_goal_unauthorized_panics() {
     let user = Address::generate(&env);
     let other = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -661,7 +639,6 @@ fn test_withdraw_after_lock_fails() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -685,7 +662,6 @@ fn test_withdraw_after_unlock_succeeds() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
     let id = client.create_goal(
         &user,
@@ -711,7 +687,6 @@ fn test_lock_nonexistent_goal_panics() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     let res = client.try_lock_goal(&user, &99);
@@ -725,7 +700,6 @@ fn test_create_goal_emits_event() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     // Create a goal
@@ -778,7 +752,6 @@ fn test_add_to_goal_emits_event() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     // Create a goal
@@ -832,7 +805,6 @@ fn test_goal_completed_emits_event() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     // Create a goal with small target
@@ -888,7 +860,6 @@ fn test_withdraw_from_goal_emits_event() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     let goal_id = client.create_goal(
@@ -929,7 +900,6 @@ fn test_lock_goal_emits_event() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     let goal_id = client.create_goal(
@@ -969,7 +939,6 @@ fn test_unlock_goal_emits_event() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     let goal_id = client.create_goal(
@@ -1008,7 +977,6 @@ fn test_multiple_goals_emit_separate_events() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
     env.mock_all_auths();
 
     // Create multiple goals
@@ -1059,8 +1027,6 @@ fn test_instance_ttl_extended_on_create_goal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
-
     // create_goal calls extend_instance_ttl
     let goal_id = client.create_goal(
         &user,
@@ -1103,8 +1069,6 @@ fn test_instance_ttl_refreshed_on_add_to_goal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
-
     let goal_id = client.create_goal(
         &user,
         &String::from_str(&env, "Vacation"),
@@ -1159,8 +1123,6 @@ fn test_savings_data_persists_across_ledger_advancements() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
-
     // Phase 1: Create goals at seq 100. live_until = 518,500
     let id1 = client.create_goal(
         &user,
@@ -1220,6 +1182,48 @@ fn test_savings_data_persists_across_ledger_advancements() {
     );
 }
 
+/// A goal that is only read, never written, must not be archived.
+#[test]
+fn test_goal_ttl_extended_on_read() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let at = |sequence_number: u32| LedgerInfo {
+        protocol_version: 20,
+        sequence_number,
+        timestamp: sequence_number as u64,
+        network_id: [0; 32],
+        base_reserve: 10,
+        min_temp_entry_ttl: 100,
+        min_persistent_entry_ttl: 100,
+        max_entry_ttl: 700_000,
+    };
+    env.ledger().set(at(100));
+
+    let contract_id = env.register_contract(None, SavingsGoalContract);
+    let client = SavingsGoalContractClient::new(&env, &contract_id);
+    let user = Address::generate(&env);
+    let id = client.create_goal(&user, &String::from_str(&env, "Land"), &10000, &2000000000);
+
+    // Seq 510,000: TTL = 8,500 < 17,280, so the read extends it
+    env.ledger().set(at(510_000));
+    assert!(client.get_goal(&id).is_some());
+    let ttl = env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .get_ttl(&(symbol_short!("GOAL"), id))
+    });
+    assert!(
+        ttl >= 518_400,
+        "Goal TTL ({}) must be refreshed on read",
+        ttl
+    );
+
+    // Past the original expiry of 518,500
+    env.ledger().set(at(1_020_000));
+    assert_eq!(client.get_goals(&user, &0, &10).count, 1);
+}
+
 /// Verify that lock_goal extends instance TTL.
 #[test]
 fn test_instance_ttl_extended_on_lock_goal() {
@@ -1241,8 +1245,6 @@ fn test_instance_ttl_extended_on_lock_goal() {
     let client = SavingsGoalContractClient::new(&env, &contract_id);
     let user = Address::generate(&env);
 
-    client.init();
-
     let goal_id = client.create_goal(
         &user,
         &String::from_str(&env, "Retirement"),
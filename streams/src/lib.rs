@@ -0,0 +1,355 @@
+#![no_std]
+
+//! # Token Streams
+//!
+//! Continuous allowances and payroll: a payer streams tokens to a recipient at
+//! a fixed rate per second instead of sending lump sums.
+//!
+//! A stream runs from `start` to `end`. Nothing can be withdrawn before the
+//! `cliff`; from then on the recipient can withdraw everything accrued since
+//! `start`. Streams do not have to be fully funded up front: accrual is capped
+//! by what the payer has deposited so far, and `top_up` adds more (up to the
+//! full schedule). This lets a family fund a year-long allowance month by
+//! month.
+//!
+//! Either party can cancel. Accrued funds go to the recipient and the
+//! unaccrued rest of the deposit goes back to the payer.
+//!
+//! ## Events
+//!
+//! `("stream", action)` with the stream ID for `created`, `withdraw`, `topup`
+//! and `canceled`.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
+    Env, Symbol, Vec,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const STREAM: Symbol = symbol_short!("stream");
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidAmount = 1,
+    InvalidRate = 2,
+    /// Start in the past, end not after start, cliff outside the stream, or
+    /// a total that overflows
+    InvalidSchedule = 3,
+    StreamNotFound = 4,
+    Unauthorized = 5,
+    /// Stream was canceled or fully paid out
+    NotActive = 6,
+    NothingToWithdraw = 7,
+    /// Deposit would exceed rate * duration
+    ExceedsSchedule = 8,
+    InvalidRecipient = 9,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StreamStatus {
+    Active,
+    Canceled,
+    /// Recipient withdrew the full schedule
+    Completed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub id: u32,
+    pub payer: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub rate_per_second: i128,
+    pub start: u64,
+    pub cliff: u64,
+    pub end: u64,
+    /// Funded by the payer so far
+    pub deposited: i128,
+    pub withdrawn: i128,
+    pub status: StreamStatus,
+}
+
+impl Stream {
+    fn total(&self) -> i128 {
+        self.rate_per_second * (self.end - self.start) as i128
+    }
+
+    /// Earned by `now`, capped by the deposit
+    fn accrued(&self, now: u64) -> i128 {
+        if now < self.cliff {
+            return 0;
+        }
+        let elapsed = now.min(self.end).saturating_sub(self.start);
+        (self.rate_per_second * elapsed as i128).min(self.deposited)
+    }
+}
+
+#[contract]
+pub struct Streams;
+
+#[contractimpl]
+impl Streams {
+    /// Open a stream and fund it with `deposit`
+    ///
+    /// # Returns
+    /// Ok(stream_id)
+    /// Err(Error::ExceedsSchedule) if deposit is more than the stream pays out
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream(
+        env: Env,
+        payer: Address,
+        recipient: Address,
+        token: Address,
+        rate_per_second: i128,
+        start: u64,
+        cliff: u64,
+        end: u64,
+        deposit: i128,
+    ) -> Result<u32, Error> {
+        payer.require_auth();
+        if payer == recipient {
+            return Err(Error::InvalidRecipient);
+        }
+        if rate_per_second <= 0 {
+            return Err(Error::InvalidRate);
+        }
+        if start < env.ledger().timestamp() || end <= start || cliff < start || cliff > end {
+            return Err(Error::InvalidSchedule);
+        }
+        let total = rate_per_second
+            .checked_mul((end - start) as i128)
+            .ok_or(Error::InvalidSchedule)?;
+        if deposit <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if deposit > total {
+            return Err(Error::ExceedsSchedule);
+        }
+
+        TokenClient::new(&env, &token).transfer(&payer, &env.current_contract_address(), &deposit);
+
+        Self::extend_instance_ttl(&env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_ID"), &(id + 1));
+
+        let stream = Stream {
+            id,
+            payer: payer.clone(),
+            recipient: recipient.clone(),
+            token,
+            rate_per_second,
+            start,
+            cliff,
+            end,
+            deposited: deposit,
+            withdrawn: 0,
+            status: StreamStatus::Active,
+        };
+        Self::save_stream(&env, &stream);
+        Self::index_stream(&env, symbol_short!("PAYER"), &payer, id);
+        Self::index_stream(&env, symbol_short!("RCPT"), &recipient, id);
+
+        env.events().publish(
+            (STREAM, symbol_short!("created")),
+            (id, payer, recipient, rate_per_second),
+        );
+        Ok(id)
+    }
+
+    /// Withdraw everything accrued and not yet withdrawn
+    ///
+    /// # Returns
+    /// Ok(amount) sent to the recipient
+    pub fn withdraw(env: Env, recipient: Address, stream_id: u32) -> Result<i128, Error> {
+        recipient.require_auth();
+        let mut stream = Self::load_stream(&env, stream_id)?;
+        if stream.recipient != recipient {
+            return Err(Error::Unauthorized);
+        }
+        if stream.status != StreamStatus::Active {
+            return Err(Error::NotActive);
+        }
+        let amount = stream.accrued(env.ledger().timestamp()) - stream.withdrawn;
+        if amount <= 0 {
+            return Err(Error::NothingToWithdraw);
+        }
+
+        TokenClient::new(&env, &stream.token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount,
+        );
+        stream.withdrawn += amount;
+        if stream.withdrawn == stream.total() {
+            stream.status = StreamStatus::Completed;
+        }
+        Self::save_stream(&env, &stream);
+
+        env.events()
+            .publish((STREAM, symbol_short!("withdraw")), (stream_id, amount));
+        Ok(amount)
+    }
+
+    /// Add funds to an active stream
+    ///
+    /// # Returns
+    /// Ok(deposited) total after the top-up
+    pub fn top_up(env: Env, payer: Address, stream_id: u32, amount: i128) -> Result<i128, Error> {
+        payer.require_auth();
+        let mut stream = Self::load_stream(&env, stream_id)?;
+        if stream.payer != payer {
+            return Err(Error::Unauthorized);
+        }
+        if stream.status != StreamStatus::Active {
+            return Err(Error::NotActive);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if stream.deposited + amount > stream.total() {
+            return Err(Error::ExceedsSchedule);
+        }
+
+        TokenClient::new(&env, &stream.token).transfer(
+            &payer,
+            &env.current_contract_address(),
+            &amount,
+        );
+        stream.deposited += amount;
+        Self::save_stream(&env, &stream);
+
+        env.events()
+            .publish((STREAM, symbol_short!("topup")), (stream_id, amount));
+        Ok(stream.deposited)
+    }
+
+    /// Stop a stream, settling both sides
+    ///
+    /// The payer or the recipient may cancel. The recipient receives what has
+    /// accrued and not been withdrawn; the payer gets the rest of the deposit.
+    ///
+    /// # Returns
+    /// Ok(refund) returned to the payer
+    pub fn cancel_stream(env: Env, caller: Address, stream_id: u32) -> Result<i128, Error> {
+        caller.require_auth();
+        let mut stream = Self::load_stream(&env, stream_id)?;
+        if caller != stream.payer && caller != stream.recipient {
+            return Err(Error::Unauthorized);
+        }
+        if stream.status != StreamStatus::Active {
+            return Err(Error::NotActive);
+        }
+
+        let accrued = stream.accrued(env.ledger().timestamp());
+        let owed = accrued - stream.withdrawn;
+        let refund = stream.deposited - accrued;
+        let token = TokenClient::new(&env, &stream.token);
+        if owed > 0 {
+            token.transfer(&env.current_contract_address(), &stream.recipient, &owed);
+        }
+        if refund > 0 {
+            token.transfer(&env.current_contract_address(), &stream.payer, &refund);
+        }
+        stream.withdrawn = accrued;
+        stream.deposited = accrued;
+        stream.status = StreamStatus::Canceled;
+        Self::save_stream(&env, &stream);
+
+        env.events().publish(
+            (STREAM, symbol_short!("canceled")),
+            (stream_id, caller, owed, refund),
+        );
+        Ok(refund)
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_stream(env: Env, stream_id: u32) -> Option<Stream> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("STREAM"), stream_id))
+    }
+
+    /// Amount the recipient could withdraw right now
+    pub fn withdrawable(env: Env, stream_id: u32) -> i128 {
+        match Self::get_stream(env.clone(), stream_id) {
+            Some(stream) if stream.status == StreamStatus::Active => {
+                stream.accrued(env.ledger().timestamp()) - stream.withdrawn
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn get_payer_streams(env: Env, payer: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PAYER"), payer))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_recipient_streams(env: Env, recipient: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("RCPT"), recipient))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn load_stream(env: &Env, stream_id: u32) -> Result<Stream, Error> {
+        Self::get_stream(env.clone(), stream_id).ok_or(Error::StreamNotFound)
+    }
+
+    fn save_stream(env: &Env, stream: &Stream) {
+        let key = (symbol_short!("STREAM"), stream.id);
+        env.storage().persistent().set(&key, stream);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn index_stream(env: &Env, kind: Symbol, owner: &Address, stream_id: u32) {
+        let key = (kind, owner.clone());
+        let mut ids: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        ids.push_back(stream_id);
+        env.storage().persistent().set(&key, &ids);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,207 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: StreamsClient<'a>,
+    token: TokenClient<'a>,
+    payer: Address,
+    recipient: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, Streams);
+    let client = StreamsClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &asset.address()).mint(&payer, &100_000);
+    let token = TokenClient::new(&env, &asset.address());
+    let recipient = Address::generate(&env);
+
+    Setup {
+        env,
+        client,
+        token,
+        payer,
+        recipient,
+    }
+}
+
+fn set_time(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|l| l.timestamp = timestamp);
+}
+
+/// 10 tokens per second from 1_000 to 2_000 with a cliff at 1_100
+fn open_stream(s: &Setup, deposit: i128) -> u32 {
+    s.client.create_stream(
+        &s.payer,
+        &s.recipient,
+        &s.token.address,
+        &10,
+        &1_000,
+        &1_100,
+        &2_000,
+        &deposit,
+    )
+}
+
+#[test]
+fn test_withdraw_accrued_after_cliff() {
+    let s = setup();
+    let id = open_stream(&s, 10_000);
+    assert_eq!(s.token.balance(&s.payer), 90_000);
+
+    set_time(&s.env, 1_050);
+    assert_eq!(s.client.withdrawable(&id), 0);
+    assert_eq!(
+        s.client.try_withdraw(&s.recipient, &id),
+        Err(Ok(Error::NothingToWithdraw))
+    );
+
+    set_time(&s.env, 1_200);
+    assert_eq!(s.client.withdrawable(&id), 2_000);
+    assert_eq!(
+        s.client.try_withdraw(&s.payer, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(s.client.withdraw(&s.recipient, &id), 2_000);
+
+    set_time(&s.env, 5_000);
+    assert_eq!(s.client.withdraw(&s.recipient, &id), 8_000);
+    assert_eq!(s.token.balance(&s.recipient), 10_000);
+    assert_eq!(
+        s.client.get_stream(&id).unwrap().status,
+        StreamStatus::Completed
+    );
+}
+
+#[test]
+fn test_accrual_capped_by_deposit_and_top_up() {
+    let s = setup();
+    let id = open_stream(&s, 3_000);
+
+    set_time(&s.env, 1_500);
+    assert_eq!(s.client.withdraw(&s.recipient, &id), 3_000);
+    assert_eq!(s.client.withdrawable(&id), 0);
+
+    assert_eq!(
+        s.client.try_top_up(&s.payer, &id, &7_001),
+        Err(Ok(Error::ExceedsSchedule))
+    );
+    assert_eq!(s.client.top_up(&s.payer, &id, &4_000), 7_000);
+    assert_eq!(s.client.withdraw(&s.recipient, &id), 2_000);
+
+    set_time(&s.env, 1_800);
+    assert_eq!(s.client.withdraw(&s.recipient, &id), 2_000);
+    assert_eq!(s.token.balance(&s.recipient), 7_000);
+}
+
+#[test]
+fn test_cancel_settles_both_sides() {
+    let s = setup();
+    let id = open_stream(&s, 10_000);
+
+    set_time(&s.env, 1_300);
+    s.client.withdraw(&s.recipient, &id);
+    set_time(&s.env, 1_400);
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_cancel_stream(&stranger, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(s.client.cancel_stream(&s.recipient, &id), 6_000);
+
+    assert_eq!(s.token.balance(&s.recipient), 4_000);
+    assert_eq!(s.token.balance(&s.payer), 96_000);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    assert_eq!(s.client.withdrawable(&id), 0);
+    assert_eq!(
+        s.client.try_top_up(&s.payer, &id, &100),
+        Err(Ok(Error::NotActive))
+    );
+}
+
+#[test]
+fn test_cancel_before_cliff_refunds_everything() {
+    let s = setup();
+    let id = open_stream(&s, 10_000);
+    set_time(&s.env, 1_050);
+    assert_eq!(s.client.cancel_stream(&s.payer, &id), 10_000);
+    assert_eq!(s.token.balance(&s.payer), 100_000);
+    assert_eq!(s.token.balance(&s.recipient), 0);
+}
+
+#[test]
+fn test_stream_indexes() {
+    let s = setup();
+    let first = open_stream(&s, 1_000);
+    let second = open_stream(&s, 2_000);
+    assert_eq!(s.client.get_payer_streams(&s.payer).len(), 2);
+    let received = s.client.get_recipient_streams(&s.recipient);
+    assert_eq!(received.get(0), Some(first));
+    assert_eq!(received.get(1), Some(second));
+}
+
+#[test]
+fn test_create_validation() {
+    let s = setup();
+    let create = |rate: i128, start: u64, cliff: u64, end: u64, deposit: i128| {
+        s.client.try_create_stream(
+            &s.payer,
+            &s.recipient,
+            &s.token.address,
+            &rate,
+            &start,
+            &cliff,
+            &end,
+            &deposit,
+        )
+    };
+    assert_eq!(
+        create(0, 1_000, 1_000, 2_000, 100),
+        Err(Ok(Error::InvalidRate))
+    );
+    assert_eq!(
+        create(10, 999, 1_000, 2_000, 100),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        create(10, 1_000, 2_001, 2_000, 100),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        create(10, 1_000, 1_000, 1_000, 100),
+        Err(Ok(Error::InvalidSchedule))
+    );
+    assert_eq!(
+        create(10, 1_000, 1_000, 2_000, 0),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        create(10, 1_000, 1_000, 2_000, 10_001),
+        Err(Ok(Error::ExceedsSchedule))
+    );
+    assert_eq!(
+        s.client.try_create_stream(
+            &s.payer,
+            &s.payer,
+            &s.token.address,
+            &10,
+            &1_000,
+            &1_000,
+            &2_000,
+            &100
+        ),
+        Err(Ok(Error::InvalidRecipient))
+    );
+}
@@ -0,0 +1,543 @@
+#![no_std]
+
+//! # Subscriptions
+//!
+//! Recurring token payments from users to services, charged by a keeper.
+//!
+//! A service (the provider) creates plans with a token, an amount and a
+//! billing interval. A user subscribes by first approving this contract as a
+//! spender of the plan token, then calling `subscribe`, which charges the
+//! first period right away. Later periods are pulled against the same
+//! allowance when anyone calls `charge_due_subscriptions`.
+//!
+//! A failed charge (missing balance or allowance) is retried a day later.
+//! After `MAX_FAILED_CHARGES` failures in a row the subscription lapses.
+//! Subscribers can pause, resume and cancel at any time. Paused time is not
+//! billed: resuming pushes the next charge back by the length of the pause.
+//!
+//! This is meant as the common schedule engine for premiums, bills and
+//! other recurring payments, which today each keep their own schedules.
+//!
+//! ## Events
+//!
+//! `("subs", action)` for `plan`, `plan_st`, `subscrib`, `charged`,
+//! `failed`, `lapsed`, `paused`, `resumed` and `cancel`.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
+    Env, IntoVal, String, Symbol, Val, Vec,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const SUBS: Symbol = symbol_short!("subs");
+
+/// Most entries one keeper call loads, counting index pages and
+/// subscriptions whether or not they turn out to be due
+pub const MAX_CHARGES_PER_RUN: u32 = 50;
+/// Subscription IDs per page of the persistent live index
+const LIVE_PAGE_SIZE: u32 = 25;
+/// Consecutive failed charges after which a subscription lapses
+pub const MAX_FAILED_CHARGES: u32 = 3;
+/// Wait before retrying a failed charge
+pub const RETRY_DELAY: u64 = 24 * 60 * 60;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    PlanNotFound = 1,
+    SubscriptionNotFound = 2,
+    Unauthorized = 3,
+    InvalidAmount = 4,
+    InvalidInterval = 5,
+    /// The plan takes no new subscribers
+    PlanInactive = 6,
+    /// This contract may not pull a period's amount from the subscriber
+    InsufficientAllowance = 7,
+    /// The subscriber already has a live subscription to the plan
+    AlreadySubscribed = 8,
+    /// The subscription is not in a state that allows the action
+    InvalidStatus = 9,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Plan {
+    pub id: u32,
+    pub provider: Address,
+    pub name: String,
+    pub token: Address,
+    /// Charged every `interval` seconds
+    pub amount: i128,
+    pub interval: u64,
+    pub active: bool,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubscriptionStatus {
+    Active,
+    Paused,
+    Cancelled,
+    /// Cancelled after `MAX_FAILED_CHARGES` failed charges in a row
+    Lapsed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    pub id: u32,
+    pub plan_id: u32,
+    pub subscriber: Address,
+    pub status: SubscriptionStatus,
+    pub started_at: u64,
+    /// When the keeper charges next; a retry time after a failed charge
+    pub next_charge: u64,
+    pub last_charged_at: Option<u64>,
+    /// Successful charges, the first one at `subscribe` included
+    pub charges: u32,
+    /// Failed charges since the last successful one
+    pub failed_charges: u32,
+    pub paused_at: Option<u64>,
+}
+
+#[contract]
+pub struct Subscriptions;
+
+#[contractimpl]
+impl Subscriptions {
+    // -----------------------------------------------------------------------
+    // Plans
+    // -----------------------------------------------------------------------
+
+    /// Offer a plan charging `amount` of `token` every `interval` seconds
+    ///
+    /// # Returns
+    /// Ok(plan_id)
+    pub fn create_plan(
+        env: Env,
+        provider: Address,
+        name: String,
+        token: Address,
+        amount: i128,
+        interval: u64,
+    ) -> Result<u32, Error> {
+        provider.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if interval == 0 {
+            return Err(Error::InvalidInterval);
+        }
+
+        Self::extend_instance_ttl(&env);
+        let id = Self::next_id(&env, symbol_short!("NEXT_PLN"));
+        let plan = Plan {
+            id,
+            provider: provider.clone(),
+            name,
+            token,
+            amount,
+            interval,
+            active: true,
+            created_at: env.ledger().timestamp(),
+        };
+        Self::save_plan(&env, &plan);
+        let mut plans = Self::get_plans(env.clone(), provider.clone());
+        plans.push_back(id);
+        Self::save_persistent(&env, &(symbol_short!("PLANS"), provider.clone()), &plans);
+
+        env.events()
+            .publish((SUBS, symbol_short!("plan")), (id, provider, amount));
+        Ok(id)
+    }
+
+    /// Open or close a plan
+    ///
+    /// A closed plan takes no new subscribers and its subscriptions are not
+    /// charged until it is opened again.
+    pub fn set_plan_active(
+        env: Env,
+        provider: Address,
+        plan_id: u32,
+        active: bool,
+    ) -> Result<(), Error> {
+        provider.require_auth();
+        let mut plan = Self::load_plan(&env, plan_id)?;
+        if plan.provider != provider {
+            return Err(Error::Unauthorized);
+        }
+        plan.active = active;
+        Self::save_plan(&env, &plan);
+
+        env.events()
+            .publish((SUBS, symbol_short!("plan_st")), (plan_id, active));
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Subscriptions
+    // -----------------------------------------------------------------------
+
+    /// Subscribe to a plan and pay the first period
+    ///
+    /// The subscriber must already have approved this contract as a spender
+    /// of the plan token. Every charge, the first one included, is drawn
+    /// from that allowance.
+    ///
+    /// # Returns
+    /// Ok(subscription_id)
+    pub fn subscribe(env: Env, subscriber: Address, plan_id: u32) -> Result<u32, Error> {
+        subscriber.require_auth();
+        let plan = Self::load_plan(&env, plan_id)?;
+        if !plan.active {
+            return Err(Error::PlanInactive);
+        }
+        let mut ids = Self::get_subscriptions(env.clone(), subscriber.clone());
+        for id in ids.iter() {
+            let existing = Self::load_subscription(&env, id)?;
+            if existing.plan_id == plan_id && Self::is_live(existing.status) {
+                return Err(Error::AlreadySubscribed);
+            }
+        }
+        let token = TokenClient::new(&env, &plan.token);
+        let this = env.current_contract_address();
+        if token.allowance(&subscriber, &this) < plan.amount {
+            return Err(Error::InsufficientAllowance);
+        }
+
+        token.transfer_from(&this, &subscriber, &plan.provider, &plan.amount);
+
+        Self::extend_instance_ttl(&env);
+        let id = Self::next_id(&env, symbol_short!("NEXT_SUB"));
+        let now = env.ledger().timestamp();
+        let subscription = Subscription {
+            id,
+            plan_id,
+            subscriber: subscriber.clone(),
+            status: SubscriptionStatus::Active,
+            started_at: now,
+            next_charge: now + plan.interval,
+            last_charged_at: Some(now),
+            charges: 1,
+            failed_charges: 0,
+            paused_at: None,
+        };
+        Self::save_subscription(&env, &subscription);
+        ids.push_back(id);
+        Self::save_persistent(&env, &(symbol_short!("SUBS"), subscriber.clone()), &ids);
+        Self::add_live(&env, id);
+
+        env.events().publish(
+            (SUBS, symbol_short!("subscrib")),
+            (id, plan_id, subscriber, plan.amount),
+        );
+        Ok(id)
+    }
+
+    pub fn pause(env: Env, subscriber: Address, subscription_id: u32) -> Result<(), Error> {
+        subscriber.require_auth();
+        let mut subscription = Self::load_subscription(&env, subscription_id)?;
+        if subscription.subscriber != subscriber {
+            return Err(Error::Unauthorized);
+        }
+        if subscription.status != SubscriptionStatus::Active {
+            return Err(Error::InvalidStatus);
+        }
+        subscription.status = SubscriptionStatus::Paused;
+        subscription.paused_at = Some(env.ledger().timestamp());
+        Self::save_subscription(&env, &subscription);
+
+        env.events()
+            .publish((SUBS, symbol_short!("paused")), subscription_id);
+        Ok(())
+    }
+
+    /// Resume a paused subscription, moving the next charge back by the
+    /// time it was paused
+    pub fn resume(env: Env, subscriber: Address, subscription_id: u32) -> Result<(), Error> {
+        subscriber.require_auth();
+        let mut subscription = Self::load_subscription(&env, subscription_id)?;
+        if subscription.subscriber != subscriber {
+            return Err(Error::Unauthorized);
+        }
+        if subscription.status != SubscriptionStatus::Paused {
+            return Err(Error::InvalidStatus);
+        }
+        let now = env.ledger().timestamp();
+        let paused_for = now.saturating_sub(subscription.paused_at.unwrap_or(now));
+        subscription.next_charge += paused_for;
+        subscription.status = SubscriptionStatus::Active;
+        subscription.paused_at = None;
+        Self::save_subscription(&env, &subscription);
+
+        env.events().publish(
+            (SUBS, symbol_short!("resumed")),
+            (subscription_id, subscription.next_charge),
+        );
+        Ok(())
+    }
+
+    /// End a subscription. The subscriber or the plan's provider can cancel.
+    pub fn cancel(env: Env, caller: Address, subscription_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        let mut subscription = Self::load_subscription(&env, subscription_id)?;
+        let plan = Self::load_plan(&env, subscription.plan_id)?;
+        if caller != subscription.subscriber && caller != plan.provider {
+            return Err(Error::Unauthorized);
+        }
+        if !Self::is_live(subscription.status) {
+            return Err(Error::InvalidStatus);
+        }
+        Self::end(&env, &mut subscription, SubscriptionStatus::Cancelled);
+
+        env.events()
+            .publish((SUBS, symbol_short!("cancel")), (subscription_id, caller));
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Keeper
+    // -----------------------------------------------------------------------
+
+    /// Charge active subscriptions that are due, loading at most
+    /// `MAX_CHARGES_PER_RUN` index pages and subscriptions per call
+    ///
+    /// Keeper entry point; anyone may call it. Each call continues after the
+    /// subscription the previous one stopped at (`get_charge_cursor`), so
+    /// subscriptions that are not due still use up the call's budget.
+    /// Subscriptions that fell behind are charged once and move to the first
+    /// due time after now.
+    ///
+    /// # Returns
+    /// IDs of the subscriptions charged
+    pub fn charge_due_subscriptions(env: Env) -> Vec<u32> {
+        Self::extend_instance_ttl(&env);
+        let now = env.ledger().timestamp();
+        let this = env.current_contract_address();
+        let cursor = Self::get_charge_cursor(env.clone());
+        let last_id = env
+            .storage()
+            .instance()
+            .get::<_, u32>(&symbol_short!("NEXT_SUB"))
+            .unwrap_or(1)
+            - 1;
+        let mut charged = Vec::new(&env);
+        let mut loaded = 0u32;
+        let mut next_cursor = 0u32;
+
+        let mut page = cursor / LIVE_PAGE_SIZE;
+        'pages: while last_id > 0 && page <= Self::live_page(last_id) {
+            if loaded >= MAX_CHARGES_PER_RUN {
+                next_cursor = page * LIVE_PAGE_SIZE;
+                break;
+            }
+            loaded += 1;
+            let ids = Self::load_live_page(&env, page);
+            page += 1;
+            for id in ids.iter() {
+                if id <= cursor {
+                    continue;
+                }
+                if loaded >= MAX_CHARGES_PER_RUN {
+                    next_cursor = id - 1;
+                    break 'pages;
+                }
+                loaded += 1;
+                Self::charge_if_due(&env, &this, now, id, &mut charged);
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CURSOR"), &next_cursor);
+        charged
+    }
+
+    /// Charge one subscription if it is active, due and on an active plan
+    fn charge_if_due(env: &Env, this: &Address, now: u64, id: u32, charged: &mut Vec<u32>) {
+        let Ok(mut subscription) = Self::load_subscription(env, id) else {
+            return;
+        };
+        if subscription.status != SubscriptionStatus::Active || subscription.next_charge > now {
+            return;
+        }
+        let Ok(plan) = Self::load_plan(env, subscription.plan_id) else {
+            return;
+        };
+        if !plan.active {
+            return;
+        }
+
+        let pulled = TokenClient::new(env, &plan.token).try_transfer_from(
+            this,
+            &subscription.subscriber,
+            &plan.provider,
+            &plan.amount,
+        );
+        if matches!(pulled, Ok(Ok(()))) {
+            subscription.charges += 1;
+            subscription.failed_charges = 0;
+            subscription.last_charged_at = Some(now);
+            while subscription.next_charge <= now {
+                subscription.next_charge += plan.interval;
+            }
+            Self::save_subscription(env, &subscription);
+            charged.push_back(id);
+            env.events()
+                .publish((SUBS, symbol_short!("charged")), (id, plan.amount));
+            return;
+        }
+
+        subscription.failed_charges += 1;
+        env.events().publish(
+            (SUBS, symbol_short!("failed")),
+            (id, subscription.failed_charges),
+        );
+        if subscription.failed_charges >= MAX_FAILED_CHARGES {
+            Self::end(env, &mut subscription, SubscriptionStatus::Lapsed);
+            env.events().publish((SUBS, symbol_short!("lapsed")), id);
+        } else {
+            subscription.next_charge = now + RETRY_DELAY;
+            Self::save_subscription(env, &subscription);
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_plan(env: Env, plan_id: u32) -> Option<Plan> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PLAN"), plan_id))
+    }
+
+    pub fn get_plans(env: Env, provider: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PLANS"), provider))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_subscription(env: Env, subscription_id: u32) -> Option<Subscription> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SUB"), subscription_id))
+    }
+
+    /// IDs of every subscription the address has taken out, oldest first
+    pub fn get_subscriptions(env: Env, subscriber: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SUBS"), subscriber))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Last subscription ID the previous keeper call looked at; 0 once a
+    /// call reached the end of the live index
+    pub fn get_charge_cursor(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("CURSOR"))
+            .unwrap_or(0)
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn is_live(status: SubscriptionStatus) -> bool {
+        matches!(
+            status,
+            SubscriptionStatus::Active | SubscriptionStatus::Paused
+        )
+    }
+
+    /// Close a subscription for good and drop it from the keeper's index
+    fn end(env: &Env, subscription: &mut Subscription, status: SubscriptionStatus) {
+        subscription.status = status;
+        Self::save_subscription(env, subscription);
+        let page = Self::live_page(subscription.id);
+        let mut live = Self::load_live_page(env, page);
+        if let Some(index) = live.first_index_of(subscription.id) {
+            live.remove(index);
+        }
+        if live.is_empty() {
+            env.storage()
+                .persistent()
+                .remove(&(symbol_short!("LIVE"), page));
+        } else {
+            Self::save_persistent(env, &(symbol_short!("LIVE"), page), &live);
+        }
+    }
+
+    fn next_id(env: &Env, key: Symbol) -> u32 {
+        let id: u32 = env.storage().instance().get(&key).unwrap_or(1);
+        env.storage().instance().set(&key, &(id + 1));
+        id
+    }
+
+    /// Index page holding `subscription_id`; IDs start at 1
+    fn live_page(subscription_id: u32) -> u32 {
+        (subscription_id - 1) / LIVE_PAGE_SIZE
+    }
+
+    /// Active or paused subscriptions on one index page, in ID order
+    fn load_live_page(env: &Env, page: u32) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("LIVE"), page))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn add_live(env: &Env, subscription_id: u32) {
+        let page = Self::live_page(subscription_id);
+        let mut live = Self::load_live_page(env, page);
+        live.push_back(subscription_id);
+        Self::save_persistent(env, &(symbol_short!("LIVE"), page), &live);
+    }
+
+    fn load_plan(env: &Env, plan_id: u32) -> Result<Plan, Error> {
+        Self::get_plan(env.clone(), plan_id).ok_or(Error::PlanNotFound)
+    }
+
+    fn save_plan(env: &Env, plan: &Plan) {
+        Self::save_persistent(env, &(symbol_short!("PLAN"), plan.id), plan);
+    }
+
+    fn load_subscription(env: &Env, subscription_id: u32) -> Result<Subscription, Error> {
+        Self::get_subscription(env.clone(), subscription_id).ok_or(Error::SubscriptionNotFound)
+    }
+
+    fn save_subscription(env: &Env, subscription: &Subscription) {
+        Self::save_persistent(env, &(symbol_short!("SUB"), subscription.id), subscription);
+    }
+
+    fn save_persistent<K, V>(env: &Env, key: &K, value: &V)
+    where
+        K: IntoVal<Env, Val>,
+        V: IntoVal<Env, Val>,
+    {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
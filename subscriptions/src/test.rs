@@ -0,0 +1,235 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String,
+};
+
+const MONTH: u64 = 30 * 24 * 60 * 60;
+
+struct Setup<'a> {
+    env: Env,
+    client: SubscriptionsClient<'a>,
+    token: TokenClient<'a>,
+    provider: Address,
+    subscriber: Address,
+    plan_id: u32,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, Subscriptions);
+    let client = SubscriptionsClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = TokenClient::new(&env, &asset.address());
+    let provider = Address::generate(&env);
+    let subscriber = Address::generate(&env);
+    StellarAssetClient::new(&env, &asset.address()).mint(&subscriber, &1_000);
+    let plan_id = client.create_plan(
+        &provider,
+        &String::from_str(&env, "Mobile data"),
+        &asset.address(),
+        &100,
+        &MONTH,
+    );
+
+    Setup {
+        env,
+        client,
+        token,
+        provider,
+        subscriber,
+        plan_id,
+    }
+}
+
+fn approve(s: &Setup, amount: i128) {
+    s.token
+        .approve(&s.subscriber, &s.client.address, &amount, &100_000);
+}
+
+fn set_time(s: &Setup, timestamp: u64) {
+    s.env.ledger().with_mut(|l| l.timestamp = timestamp);
+}
+
+#[test]
+fn test_subscribe_and_keeper_charges() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_subscribe(&s.subscriber, &s.plan_id),
+        Err(Ok(Error::InsufficientAllowance))
+    );
+    approve(&s, 300);
+    let id = s.client.subscribe(&s.subscriber, &s.plan_id);
+    assert_eq!(s.token.balance(&s.provider), 100);
+    assert_eq!(
+        s.client.try_subscribe(&s.subscriber, &s.plan_id),
+        Err(Ok(Error::AlreadySubscribed))
+    );
+
+    // Not due yet
+    assert_eq!(s.client.charge_due_subscriptions().len(), 0);
+
+    set_time(&s, 1_000 + MONTH);
+    assert_eq!(
+        s.client.charge_due_subscriptions(),
+        Vec::from_array(&s.env, [id])
+    );
+    assert_eq!(s.token.balance(&s.provider), 200);
+
+    // A keeper that falls behind charges once and moves past now
+    set_time(&s, 1_000 + 3 * MONTH + 5);
+    s.client.charge_due_subscriptions();
+    let subscription = s.client.get_subscription(&id).unwrap();
+    assert_eq!(subscription.charges, 3);
+    assert_eq!(subscription.next_charge, 1_000 + 4 * MONTH);
+    assert_eq!(s.token.balance(&s.provider), 300);
+}
+
+#[test]
+fn test_failed_charges_retry_then_lapse() {
+    let s = setup();
+    approve(&s, 100);
+    let id = s.client.subscribe(&s.subscriber, &s.plan_id);
+
+    // The allowance is used up, so every later charge fails
+    let mut now = 1_000 + MONTH;
+    for attempt in 1..MAX_FAILED_CHARGES {
+        set_time(&s, now);
+        assert_eq!(s.client.charge_due_subscriptions().len(), 0);
+        let subscription = s.client.get_subscription(&id).unwrap();
+        assert_eq!(subscription.failed_charges, attempt);
+        assert_eq!(subscription.next_charge, now + RETRY_DELAY);
+        now += RETRY_DELAY;
+    }
+
+    // Topping up the allowance before the last retry saves it
+    approve(&s, 100);
+    set_time(&s, now);
+    assert_eq!(s.client.charge_due_subscriptions().len(), 1);
+    assert_eq!(s.client.get_subscription(&id).unwrap().failed_charges, 0);
+
+    for _ in 0..MAX_FAILED_CHARGES {
+        now = s.client.get_subscription(&id).unwrap().next_charge;
+        set_time(&s, now);
+        s.client.charge_due_subscriptions();
+    }
+    assert_eq!(
+        s.client.get_subscription(&id).unwrap().status,
+        SubscriptionStatus::Lapsed
+    );
+    // A lapsed subscription no longer blocks a new one
+    approve(&s, 100);
+    s.client.subscribe(&s.subscriber, &s.plan_id);
+}
+
+#[test]
+fn test_pause_resume_and_cancel() {
+    let s = setup();
+    approve(&s, 1_000);
+    let id = s.client.subscribe(&s.subscriber, &s.plan_id);
+
+    set_time(&s, 1_000 + MONTH / 2);
+    s.client.pause(&s.subscriber, &id);
+    set_time(&s, 1_000 + 2 * MONTH);
+    assert_eq!(s.client.charge_due_subscriptions().len(), 0);
+
+    // Paused for 1.5 months, so the charge moves back by as much
+    s.client.resume(&s.subscriber, &id);
+    assert_eq!(
+        s.client.get_subscription(&id).unwrap().next_charge,
+        1_000 + MONTH + (3 * MONTH / 2)
+    );
+    assert_eq!(
+        s.client.try_resume(&s.subscriber, &id),
+        Err(Ok(Error::InvalidStatus))
+    );
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_cancel(&stranger, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+    // The provider can end it too
+    s.client.cancel(&s.provider, &id);
+    set_time(&s, 1_000 + 10 * MONTH);
+    assert_eq!(s.client.charge_due_subscriptions().len(), 0);
+    assert_eq!(
+        s.client.get_subscription(&id).unwrap().status,
+        SubscriptionStatus::Cancelled
+    );
+    assert_eq!(s.token.balance(&s.provider), 100);
+}
+
+#[test]
+fn test_closed_plans_and_keeper_cursor() {
+    let s = setup();
+    approve(&s, 1_000);
+    s.client.subscribe(&s.subscriber, &s.plan_id);
+    assert_eq!(
+        s.client
+            .try_set_plan_active(&s.subscriber, &s.plan_id, &false),
+        Err(Ok(Error::Unauthorized))
+    );
+    s.client.set_plan_active(&s.provider, &s.plan_id, &false);
+    set_time(&s, 1_000 + MONTH);
+    assert_eq!(s.client.charge_due_subscriptions().len(), 0);
+    let newcomer = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_subscribe(&newcomer, &s.plan_id),
+        Err(Ok(Error::PlanInactive))
+    );
+    s.client.set_plan_active(&s.provider, &s.plan_id, &true);
+
+    // Fill more than one keeper run
+    s.env.budget().reset_unlimited();
+    for _ in 0..MAX_CHARGES_PER_RUN {
+        let subscriber = Address::generate(&s.env);
+        StellarAssetClient::new(&s.env, &s.token.address).mint(&subscriber, &200);
+        s.token
+            .approve(&subscriber, &s.client.address, &200, &100_000);
+        s.client.subscribe(&subscriber, &s.plan_id);
+    }
+    set_time(&s, 1_000 + 2 * MONTH);
+    // Index pages count toward the budget along with subscriptions
+    let first = s.client.charge_due_subscriptions();
+    assert_eq!(first.len(), MAX_CHARGES_PER_RUN - 2);
+    assert_eq!(s.client.get_charge_cursor(), MAX_CHARGES_PER_RUN - 2);
+    let rest = s.client.charge_due_subscriptions();
+    assert_eq!(rest.len(), 3);
+    assert_eq!(s.client.get_charge_cursor(), 0);
+}
+
+#[test]
+fn test_keeper_budget_counts_subscriptions_not_due() {
+    let s = setup();
+    s.env.budget().reset_unlimited();
+    let mut ids = Vec::new(&s.env);
+    for n in 0..MAX_CHARGES_PER_RUN {
+        let subscriber = Address::generate(&s.env);
+        StellarAssetClient::new(&s.env, &s.token.address).mint(&subscriber, &200);
+        s.token
+            .approve(&subscriber, &s.client.address, &200, &100_000);
+        let id = s.client.subscribe(&subscriber, &s.plan_id);
+        // Only the last subscription is due when the keeper runs
+        if n + 1 < MAX_CHARGES_PER_RUN {
+            s.client.pause(&subscriber, &id);
+        }
+        ids.push_back(id);
+    }
+    set_time(&s, 1_000 + MONTH);
+
+    // Not-due entries fill the first call, the next one reaches the due one
+    assert_eq!(s.client.charge_due_subscriptions().len(), 0);
+    assert!(s.client.get_charge_cursor() > 0);
+    assert_eq!(
+        s.client.charge_due_subscriptions(),
+        Vec::from_array(&s.env, [ids.last().unwrap()])
+    );
+    assert_eq!(s.client.get_charge_cursor(), 0);
+}
@@ -0,0 +1,430 @@
+#![no_std]
+
+//! # Treasury
+//!
+//! Collects protocol fees from the Remitwise contracts, keeps the books per
+//! source contract and token, and shares revenue with partners.
+//!
+//! Sources pay fees by transferring tokens to the treasury and then calling
+//! `record_revenue` in the same invocation. Only sources enabled by the admin
+//! can record revenue, and a source can only record tokens that actually
+//! arrived: the amount must be covered by the treasury's unrecorded balance.
+//! The orchestrator records its flow fees when `FeeConfig::record_revenue` is
+//! set, and remittance_split records distribution fees paid to the treasury
+//! its owner configured.
+//!
+//! Each recorded fee is divided on arrival. Partners listed in the revenue
+//! shares earn their basis points of it and claim their balance whenever
+//! they like; the rest is retained by the protocol and can only leave through
+//! admin withdrawals. Changing the shares affects future revenue only.
+//!
+//! ## Events
+//!
+//! `("treasury", action)` for `source`, `shares`, `revenue`, `claim` and
+//! `withdraw`.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
+    Env, IntoVal, Map, Symbol, Val, Vec,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const TREASURY: Symbol = symbol_short!("treasury");
+
+pub const TOTAL_BPS: u32 = 10_000;
+pub const MAX_PARTNERS: u32 = 10;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Unauthorized = 1,
+    InvalidAmount = 2,
+    /// Caller is neither an enabled source nor the admin
+    UnknownSource = 3,
+    /// Amount is more than the treasury received and has not yet recorded
+    UnbackedRevenue = 4,
+    /// A share is zero, a partner is listed twice, or the shares exceed 100%
+    InvalidShares = 5,
+    TooManyPartners = 6,
+    NothingToClaim = 7,
+    /// Withdrawal is more than the protocol's retained revenue
+    InsufficientRetained = 8,
+}
+
+/// A partner's cut of all future revenue
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevenueShare {
+    pub partner: Address,
+    pub bps: u32,
+}
+
+/// Revenue one source contract has recorded in one token
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceRevenue {
+    pub total: i128,
+    /// Number of `record_revenue` calls
+    pub count: u32,
+    pub last_recorded_at: u64,
+}
+
+/// Running totals for one token across all sources
+///
+/// The protocol's retained revenue is `recorded - shared - withdrawn` and
+/// partners are owed `shared - claimed`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TokenLedger {
+    pub recorded: i128,
+    /// Allocated to partners
+    pub shared: i128,
+    /// Paid out to partners
+    pub claimed: i128,
+    /// Taken out by the admin
+    pub withdrawn: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PartnerBalance {
+    pub earned: i128,
+    pub claimed: i128,
+}
+
+#[contract]
+pub struct Treasury;
+
+#[contractimpl]
+impl Treasury {
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Set the admin who manages sources, shares and withdrawals
+    ///
+    /// The first call must set the caller itself; afterwards only the current
+    /// admin can hand the role over.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
+        caller.require_auth();
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(Error::Unauthorized),
+            Some(admin) if admin != caller => return Err(Error::Unauthorized),
+            _ => {}
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Allow or stop a contract recording revenue
+    pub fn set_source(
+        env: Env,
+        admin: Address,
+        source: Address,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        let mut sources = Self::load_sources(&env);
+        if enabled {
+            sources.set(source.clone(), true);
+        } else {
+            sources.remove(source.clone());
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SOURCES"), &sources);
+
+        env.events()
+            .publish((TREASURY, symbol_short!("source")), (source, enabled));
+        Ok(())
+    }
+
+    pub fn get_sources(env: Env) -> Vec<Address> {
+        Self::load_sources(&env).keys()
+    }
+
+    /// Replace the partner revenue shares
+    ///
+    /// Shares apply to revenue recorded from now on; balances partners have
+    /// already earned are unaffected. An empty list stops sharing.
+    ///
+    /// # Returns
+    /// Err(Error::InvalidShares) if a share is zero, a partner repeats or the
+    /// total is above 100%
+    pub fn set_revenue_shares(
+        env: Env,
+        admin: Address,
+        shares: Vec<RevenueShare>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        if shares.len() > MAX_PARTNERS {
+            return Err(Error::TooManyPartners);
+        }
+        let mut seen = Map::new(&env);
+        let mut total: u32 = 0;
+        for share in shares.iter() {
+            if share.bps == 0 || seen.contains_key(share.partner.clone()) {
+                return Err(Error::InvalidShares);
+            }
+            seen.set(share.partner.clone(), ());
+            total += share.bps;
+        }
+        if total > TOTAL_BPS {
+            return Err(Error::InvalidShares);
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SHARES"), &shares);
+
+        env.events()
+            .publish((TREASURY, symbol_short!("shares")), shares);
+        Ok(())
+    }
+
+    pub fn get_revenue_shares(env: Env) -> Vec<RevenueShare> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("SHARES"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // -----------------------------------------------------------------------
+    // Revenue
+    // -----------------------------------------------------------------------
+
+    /// Book `amount` of `token` already sent to the treasury as revenue
+    /// from `source`
+    ///
+    /// Called by an enabled source after transferring its fee, or by the
+    /// admin to attribute tokens that arrived some other way.
+    ///
+    /// # Returns
+    /// Ok(retained) the protocol's part after partner shares
+    /// Err(Error::UnbackedRevenue) if the treasury has not received the tokens
+    pub fn record_revenue(
+        env: Env,
+        caller: Address,
+        source: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<i128, Error> {
+        caller.require_auth();
+        let is_admin = Self::get_admin(env.clone()).as_ref() == Some(&caller);
+        if !is_admin && (caller != source || !Self::load_sources(&env).contains_key(source.clone()))
+        {
+            return Err(Error::UnknownSource);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > Self::get_unrecorded_balance(env.clone(), token.clone()) {
+            return Err(Error::UnbackedRevenue);
+        }
+
+        let mut shared = 0;
+        for share in Self::get_revenue_shares(env.clone()).iter() {
+            let cut = amount * share.bps as i128 / TOTAL_BPS as i128;
+            if cut > 0 {
+                let mut balance =
+                    Self::get_partner_balance(env.clone(), share.partner.clone(), token.clone());
+                balance.earned += cut;
+                Self::save_partner_balance(&env, &share.partner, &token, &balance);
+                shared += cut;
+            }
+        }
+
+        let mut ledger = Self::get_token_ledger(env.clone(), token.clone());
+        ledger.recorded += amount;
+        ledger.shared += shared;
+        Self::save_token_ledger(&env, &token, &ledger);
+
+        let mut revenue = Self::get_source_revenue(env.clone(), source.clone(), token.clone());
+        revenue.total += amount;
+        revenue.count += 1;
+        revenue.last_recorded_at = env.ledger().timestamp();
+        let key = (symbol_short!("SRC_REV"), source.clone(), token.clone());
+        Self::save_persistent(&env, &key, &revenue);
+
+        let mut tokens = Self::get_tokens(env.clone());
+        if !tokens.contains(&token) {
+            tokens.push_back(token.clone());
+            Self::extend_instance_ttl(&env);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("TOKENS"), &tokens);
+        }
+
+        env.events().publish(
+            (TREASURY, symbol_short!("revenue")),
+            (source, token, amount, shared),
+        );
+        Ok(amount - shared)
+    }
+
+    /// Pay a partner everything they have earned in `token`
+    ///
+    /// # Returns
+    /// Ok(amount) transferred to the partner
+    pub fn claim_share(env: Env, partner: Address, token: Address) -> Result<i128, Error> {
+        partner.require_auth();
+        let mut balance = Self::get_partner_balance(env.clone(), partner.clone(), token.clone());
+        let amount = balance.earned - balance.claimed;
+        if amount <= 0 {
+            return Err(Error::NothingToClaim);
+        }
+
+        TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &partner, &amount);
+        balance.claimed += amount;
+        Self::save_partner_balance(&env, &partner, &token, &balance);
+        let mut ledger = Self::get_token_ledger(env.clone(), token.clone());
+        ledger.claimed += amount;
+        Self::save_token_ledger(&env, &token, &ledger);
+
+        env.events()
+            .publish((TREASURY, symbol_short!("claim")), (partner, token, amount));
+        Ok(amount)
+    }
+
+    /// Move retained protocol revenue out of the treasury
+    ///
+    /// Partner balances and unrecorded tokens cannot be withdrawn.
+    pub fn withdraw(
+        env: Env,
+        admin: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env, &admin)?;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > Self::get_retained(env.clone(), token.clone()) {
+            return Err(Error::InsufficientRetained);
+        }
+
+        TokenClient::new(&env, &token).transfer(&env.current_contract_address(), &to, &amount);
+        let mut ledger = Self::get_token_ledger(env.clone(), token.clone());
+        ledger.withdrawn += amount;
+        Self::save_token_ledger(&env, &token, &ledger);
+
+        env.events()
+            .publish((TREASURY, symbol_short!("withdraw")), (token, to, amount));
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Accounting queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_source_revenue(env: Env, source: Address, token: Address) -> SourceRevenue {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SRC_REV"), source, token))
+            .unwrap_or_default()
+    }
+
+    pub fn get_token_ledger(env: Env, token: Address) -> TokenLedger {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("LEDGER"), token))
+            .unwrap_or_default()
+    }
+
+    pub fn get_partner_balance(env: Env, partner: Address, token: Address) -> PartnerBalance {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("PARTNER"), partner, token))
+            .unwrap_or_default()
+    }
+
+    /// Tokens revenue has been recorded in
+    pub fn get_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("TOKENS"))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Protocol revenue available to `withdraw`
+    pub fn get_retained(env: Env, token: Address) -> i128 {
+        let ledger = Self::get_token_ledger(env, token);
+        ledger.recorded - ledger.shared - ledger.withdrawn
+    }
+
+    /// Tokens held by the treasury that no source has recorded
+    pub fn get_unrecorded_balance(env: Env, token: Address) -> i128 {
+        let balance = TokenClient::new(&env, &token).balance(&env.current_contract_address());
+        let ledger = Self::get_token_ledger(env, token);
+        balance - (ledger.recorded - ledger.claimed - ledger.withdrawn)
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn require_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()).as_ref() != Some(admin) {
+            return Err(Error::Unauthorized);
+        }
+        Ok(())
+    }
+
+    fn load_sources(env: &Env) -> Map<Address, bool> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("SOURCES"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn save_token_ledger(env: &Env, token: &Address, ledger: &TokenLedger) {
+        Self::save_persistent(env, &(symbol_short!("LEDGER"), token.clone()), ledger);
+    }
+
+    fn save_partner_balance(
+        env: &Env,
+        partner: &Address,
+        token: &Address,
+        balance: &PartnerBalance,
+    ) {
+        let key = (symbol_short!("PARTNER"), partner.clone(), token.clone());
+        Self::save_persistent(env, &key, balance);
+    }
+
+    fn save_persistent<K, V>(env: &Env, key: &K, value: &V)
+    where
+        K: IntoVal<Env, Val>,
+        V: IntoVal<Env, Val>,
+    {
+        env.storage().persistent().set(key, value);
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,211 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: TreasuryClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    source: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, Treasury);
+    let client = TreasuryClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let token = TokenClient::new(&env, &asset.address());
+    let admin = Address::generate(&env);
+    let source = Address::generate(&env);
+    client.set_admin(&admin, &admin);
+    client.set_source(&admin, &source, &true);
+
+    Setup {
+        env,
+        client,
+        token,
+        admin,
+        source,
+    }
+}
+
+/// Send `amount` to the treasury as a source would before recording it
+fn deposit(s: &Setup, amount: i128) {
+    StellarAssetClient::new(&s.env, &s.token.address).mint(&s.client.address, &amount);
+}
+
+#[test]
+fn test_record_revenue_per_source() {
+    let s = setup();
+    deposit(&s, 300);
+    assert_eq!(s.client.get_unrecorded_balance(&s.token.address), 300);
+
+    assert_eq!(
+        s.client
+            .record_revenue(&s.source, &s.source, &s.token.address, &100),
+        100
+    );
+    s.env.ledger().with_mut(|l| l.timestamp = 2_000);
+    s.client
+        .record_revenue(&s.source, &s.source, &s.token.address, &150);
+
+    let revenue = s.client.get_source_revenue(&s.source, &s.token.address);
+    assert_eq!(revenue.total, 250);
+    assert_eq!(revenue.count, 2);
+    assert_eq!(revenue.last_recorded_at, 2_000);
+    assert_eq!(s.client.get_token_ledger(&s.token.address).recorded, 250);
+    assert_eq!(s.client.get_unrecorded_balance(&s.token.address), 50);
+    assert_eq!(s.client.get_tokens(), vec![&s.env, s.token.address.clone()]);
+
+    // Only tokens that actually arrived can be booked
+    assert_eq!(
+        s.client
+            .try_record_revenue(&s.source, &s.source, &s.token.address, &51),
+        Err(Ok(Error::UnbackedRevenue))
+    );
+}
+
+#[test]
+fn test_only_enabled_sources_record() {
+    let s = setup();
+    deposit(&s, 100);
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client
+            .try_record_revenue(&stranger, &stranger, &s.token.address, &10),
+        Err(Ok(Error::UnknownSource))
+    );
+    // A source cannot book revenue under another source's name
+    assert_eq!(
+        s.client
+            .try_record_revenue(&s.source, &stranger, &s.token.address, &10),
+        Err(Ok(Error::UnknownSource))
+    );
+    // The admin can attribute stray deposits to any source
+    s.client
+        .record_revenue(&s.admin, &stranger, &s.token.address, &10);
+    assert_eq!(
+        s.client
+            .get_source_revenue(&stranger, &s.token.address)
+            .total,
+        10
+    );
+
+    s.client.set_source(&s.admin, &s.source, &false);
+    assert_eq!(s.client.get_sources().len(), 0);
+    assert_eq!(
+        s.client
+            .try_record_revenue(&s.source, &s.source, &s.token.address, &10),
+        Err(Ok(Error::UnknownSource))
+    );
+}
+
+#[test]
+fn test_partner_shares_and_withdrawals() {
+    let s = setup();
+    let partner_a = Address::generate(&s.env);
+    let partner_b = Address::generate(&s.env);
+    s.client.set_revenue_shares(
+        &s.admin,
+        &vec![
+            &s.env,
+            RevenueShare {
+                partner: partner_a.clone(),
+                bps: 2_000,
+            },
+            RevenueShare {
+                partner: partner_b.clone(),
+                bps: 500,
+            },
+        ],
+    );
+
+    deposit(&s, 1_000);
+    assert_eq!(
+        s.client
+            .record_revenue(&s.source, &s.source, &s.token.address, &1_000),
+        750
+    );
+    assert_eq!(s.client.get_retained(&s.token.address), 750);
+
+    assert_eq!(s.client.claim_share(&partner_a, &s.token.address), 200);
+    assert_eq!(s.token.balance(&partner_a), 200);
+    assert_eq!(
+        s.client.try_claim_share(&partner_a, &s.token.address),
+        Err(Ok(Error::NothingToClaim))
+    );
+
+    let ops = Address::generate(&s.env);
+    assert_eq!(
+        s.client
+            .try_withdraw(&s.admin, &s.token.address, &ops, &751),
+        Err(Ok(Error::InsufficientRetained))
+    );
+    s.client.withdraw(&s.admin, &s.token.address, &ops, &700);
+    assert_eq!(s.token.balance(&ops), 700);
+
+    // Partner B's unclaimed 50 is still in the treasury and not withdrawable
+    let ledger = s.client.get_token_ledger(&s.token.address);
+    assert_eq!(ledger.shared, 250);
+    assert_eq!(ledger.claimed, 200);
+    assert_eq!(ledger.withdrawn, 700);
+    assert_eq!(s.token.balance(&s.client.address), 100);
+    assert_eq!(s.client.get_retained(&s.token.address), 50);
+    assert_eq!(s.client.get_unrecorded_balance(&s.token.address), 0);
+    assert_eq!(
+        s.client
+            .get_partner_balance(&partner_b, &s.token.address)
+            .earned,
+        50
+    );
+}
+
+#[test]
+fn test_share_validation_and_admin_checks() {
+    let s = setup();
+    let partner = Address::generate(&s.env);
+    let share = |bps| RevenueShare {
+        partner: partner.clone(),
+        bps,
+    };
+    assert_eq!(
+        s.client
+            .try_set_revenue_shares(&s.admin, &vec![&s.env, share(0)]),
+        Err(Ok(Error::InvalidShares))
+    );
+    assert_eq!(
+        s.client
+            .try_set_revenue_shares(&s.admin, &vec![&s.env, share(10), share(20)]),
+        Err(Ok(Error::InvalidShares))
+    );
+    assert_eq!(
+        s.client
+            .try_set_revenue_shares(&s.admin, &vec![&s.env, share(10_001)]),
+        Err(Ok(Error::InvalidShares))
+    );
+
+    let outsider = Address::generate(&s.env);
+    assert_eq!(
+        s.client
+            .try_set_revenue_shares(&outsider, &vec![&s.env, share(100)]),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client
+            .try_withdraw(&outsider, &s.token.address, &outsider, &1),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_set_admin(&outsider, &outsider),
+        Err(Ok(Error::Unauthorized))
+    );
+}
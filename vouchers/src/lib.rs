@@ -0,0 +1,379 @@
+#![no_std]
+
+//! # Gift Vouchers
+//!
+//! Token-backed vouchers that a sender gives instead of cash, restricted to
+//! what the money may be spent on.
+//!
+//! A voucher is for bills or for savings, optionally narrowed to a list of
+//! categories (for example bills in category `school`). The recipient cannot
+//! withdraw it. They spend it with `pay_bill_with_voucher` in bill_payments
+//! or `add_to_goal_with_voucher` in savings_goals. Those contracts are
+//! registered here as redeemers, so only they can release a voucher's
+//! tokens. Each redeemer passes the bill's or goal's category and sends the
+//! tokens straight to the payee or the goal.
+//!
+//! Vouchers can be spent in parts until their expiry. Afterwards the sender
+//! can reclaim what is left.
+//!
+//! ## Events
+//!
+//! `("voucher", action)` with the voucher ID for `created`, `redeemed` and
+//! `reclaimed`.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, token::TokenClient, Address,
+    Env, Map, Symbol, Vec,
+};
+
+const INSTANCE_LIFETIME_THRESHOLD: u32 = 17280; // ~1 day
+const INSTANCE_BUMP_AMOUNT: u32 = 518400; // ~30 days
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = 17280;
+const PERSISTENT_BUMP_AMOUNT: u32 = 518400;
+
+const VOUCHER: Symbol = symbol_short!("voucher");
+
+pub const MAX_CATEGORIES: u32 = 10;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidAmount = 1,
+    /// Expiry not in the future
+    InvalidExpiry = 2,
+    VoucherNotFound = 3,
+    Unauthorized = 4,
+    Expired = 5,
+    NotExpired = 6,
+    /// Redeemer is not the contract registered for the voucher's purpose
+    WrongRedeemer = 7,
+    CategoryNotAllowed = 8,
+    TokenMismatch = 9,
+    InsufficientBalance = 10,
+    NothingToReclaim = 11,
+    TooManyCategories = 12,
+    InvalidRecipient = 13,
+}
+
+/// What a voucher may be spent on
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Purpose {
+    /// Paying the recipient's bills in bill_payments
+    Bills,
+    /// Funding the recipient's goals in savings_goals
+    Savings,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Voucher {
+    pub id: u32,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Not yet redeemed or reclaimed
+    pub remaining: i128,
+    pub purpose: Purpose,
+    /// Bill or goal categories the voucher can pay for; empty allows any
+    pub categories: Vec<Symbol>,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+#[contract]
+pub struct Vouchers;
+
+#[contractimpl]
+impl Vouchers {
+    // -----------------------------------------------------------------------
+    // Admin
+    // -----------------------------------------------------------------------
+
+    /// Set the admin who registers redeemer contracts
+    ///
+    /// The first call must set the caller itself; afterwards only the current
+    /// admin can hand the role over.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
+        caller.require_auth();
+        match Self::get_admin(env.clone()) {
+            None if caller != new_admin => return Err(Error::Unauthorized),
+            Some(admin) if admin != caller => return Err(Error::Unauthorized),
+            _ => {}
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ADMIN"), &new_admin);
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("ADMIN"))
+    }
+
+    /// Register the contract that redeems vouchers for `purpose`, or None
+    /// to stop redemptions for it
+    pub fn set_redeemer(
+        env: Env,
+        admin: Address,
+        purpose: Purpose,
+        redeemer: Option<Address>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        if Self::get_admin(env.clone()) != Some(admin) {
+            return Err(Error::Unauthorized);
+        }
+        let mut redeemers = Self::load_redeemers(&env);
+        match redeemer {
+            Some(redeemer) => redeemers.set(purpose, redeemer),
+            None => {
+                redeemers.remove(purpose);
+            }
+        }
+        Self::extend_instance_ttl(&env);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("REDEEMRS"), &redeemers);
+        Ok(())
+    }
+
+    pub fn get_redeemer(env: Env, purpose: Purpose) -> Option<Address> {
+        Self::load_redeemers(&env).get(purpose)
+    }
+
+    // -----------------------------------------------------------------------
+    // Vouchers
+    // -----------------------------------------------------------------------
+
+    /// Lock `amount` of `token` in a voucher for `recipient`
+    ///
+    /// # Returns
+    /// Ok(voucher_id)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_voucher(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token: Address,
+        amount: i128,
+        purpose: Purpose,
+        categories: Vec<Symbol>,
+        expires_at: u64,
+    ) -> Result<u32, Error> {
+        sender.require_auth();
+        if sender == recipient {
+            return Err(Error::InvalidRecipient);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if categories.len() > MAX_CATEGORIES {
+            return Err(Error::TooManyCategories);
+        }
+        let now = env.ledger().timestamp();
+        if expires_at <= now {
+            return Err(Error::InvalidExpiry);
+        }
+
+        TokenClient::new(&env, &token).transfer(&sender, &env.current_contract_address(), &amount);
+
+        Self::extend_instance_ttl(&env);
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("NEXT_ID"))
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("NEXT_ID"), &(id + 1));
+
+        let voucher = Voucher {
+            id,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token,
+            amount,
+            remaining: amount,
+            purpose,
+            categories,
+            created_at: now,
+            expires_at,
+        };
+        Self::save_voucher(&env, &voucher);
+        Self::index_voucher(&env, symbol_short!("SENT"), &sender, id);
+        Self::index_voucher(&env, symbol_short!("RCVD"), &recipient, id);
+
+        env.events().publish(
+            (VOUCHER, symbol_short!("created")),
+            (id, sender, recipient, amount),
+        );
+        Ok(id)
+    }
+
+    /// Spend `amount` of a voucher, sending the tokens to `to`
+    ///
+    /// Only the redeemer registered for the voucher's purpose can call this,
+    /// on behalf of the voucher's recipient, for a bill or goal in one of the
+    /// voucher's categories.
+    ///
+    /// # Returns
+    /// Ok(remaining) balance of the voucher
+    #[allow(clippy::too_many_arguments)]
+    pub fn redeem(
+        env: Env,
+        redeemer: Address,
+        voucher_id: u32,
+        holder: Address,
+        token: Address,
+        amount: i128,
+        category: Symbol,
+        to: Address,
+    ) -> Result<i128, Error> {
+        redeemer.require_auth();
+        holder.require_auth();
+        let mut voucher = Self::load_voucher(&env, voucher_id)?;
+        if voucher.recipient != holder {
+            return Err(Error::Unauthorized);
+        }
+        if Self::get_redeemer(env.clone(), voucher.purpose) != Some(redeemer) {
+            return Err(Error::WrongRedeemer);
+        }
+        if env.ledger().timestamp() >= voucher.expires_at {
+            return Err(Error::Expired);
+        }
+        if !voucher.categories.is_empty() && !voucher.categories.contains(&category) {
+            return Err(Error::CategoryNotAllowed);
+        }
+        if voucher.token != token {
+            return Err(Error::TokenMismatch);
+        }
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > voucher.remaining {
+            return Err(Error::InsufficientBalance);
+        }
+
+        TokenClient::new(&env, &voucher.token).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+        voucher.remaining -= amount;
+        Self::save_voucher(&env, &voucher);
+
+        env.events().publish(
+            (VOUCHER, symbol_short!("redeemed")),
+            (voucher_id, category, to, amount),
+        );
+        Ok(voucher.remaining)
+    }
+
+    /// Return the unspent balance of an expired voucher to its sender
+    ///
+    /// # Returns
+    /// Ok(amount) returned
+    pub fn reclaim(env: Env, sender: Address, voucher_id: u32) -> Result<i128, Error> {
+        sender.require_auth();
+        let mut voucher = Self::load_voucher(&env, voucher_id)?;
+        if voucher.sender != sender {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().timestamp() < voucher.expires_at {
+            return Err(Error::NotExpired);
+        }
+        let amount = voucher.remaining;
+        if amount == 0 {
+            return Err(Error::NothingToReclaim);
+        }
+
+        TokenClient::new(&env, &voucher.token).transfer(
+            &env.current_contract_address(),
+            &sender,
+            &amount,
+        );
+        voucher.remaining = 0;
+        Self::save_voucher(&env, &voucher);
+
+        env.events()
+            .publish((VOUCHER, symbol_short!("reclaimed")), (voucher_id, amount));
+        Ok(amount)
+    }
+
+    // -----------------------------------------------------------------------
+    // Queries
+    // -----------------------------------------------------------------------
+
+    pub fn get_voucher(env: Env, voucher_id: u32) -> Option<Voucher> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("VOUCHER"), voucher_id))
+    }
+
+    pub fn get_sent_vouchers(env: Env, sender: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("SENT"), sender))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    pub fn get_received_vouchers(env: Env, recipient: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("RCVD"), recipient))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal helpers
+    // -----------------------------------------------------------------------
+
+    fn load_redeemers(env: &Env) -> Map<Purpose, Address> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("REDEEMRS"))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn load_voucher(env: &Env, voucher_id: u32) -> Result<Voucher, Error> {
+        Self::get_voucher(env.clone(), voucher_id).ok_or(Error::VoucherNotFound)
+    }
+
+    fn save_voucher(env: &Env, voucher: &Voucher) {
+        let key = (symbol_short!("VOUCHER"), voucher.id);
+        env.storage().persistent().set(&key, voucher);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn index_voucher(env: &Env, kind: Symbol, owner: &Address, voucher_id: u32) {
+        let key = (kind, owner.clone());
+        let mut ids: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(env));
+        ids.push_back(voucher_id);
+        env.storage().persistent().set(&key, &ids);
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+
+    fn extend_instance_ttl(env: &Env) {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,255 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env,
+};
+
+struct Setup<'a> {
+    env: Env,
+    client: VouchersClient<'a>,
+    token: TokenClient<'a>,
+    sender: Address,
+    recipient: Address,
+    /// Stands in for the bill payments contract
+    bills: Address,
+}
+
+fn setup() -> Setup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    let contract_id = env.register_contract(None, Vouchers);
+    let client = VouchersClient::new(&env, &contract_id);
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(&env));
+    let sender = Address::generate(&env);
+    StellarAssetClient::new(&env, &asset.address()).mint(&sender, &1_000);
+    let token = TokenClient::new(&env, &asset.address());
+    let admin = Address::generate(&env);
+    let bills = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    client.set_admin(&admin, &admin);
+    client.set_redeemer(&admin, &Purpose::Bills, &Some(bills.clone()));
+
+    Setup {
+        env,
+        client,
+        token,
+        sender,
+        recipient,
+        bills,
+    }
+}
+
+fn school_voucher(s: &Setup, amount: i128) -> u32 {
+    s.client.create_voucher(
+        &s.sender,
+        &s.recipient,
+        &s.token.address,
+        &amount,
+        &Purpose::Bills,
+        &vec![&s.env, symbol_short!("school")],
+        &5_000,
+    )
+}
+
+#[test]
+fn test_redeem_in_parts_to_payee() {
+    let s = setup();
+    let id = school_voucher(&s, 500);
+    assert_eq!(s.token.balance(&s.client.address), 500);
+    assert_eq!(
+        s.client.get_received_vouchers(&s.recipient),
+        vec![&s.env, id]
+    );
+    assert_eq!(s.client.get_sent_vouchers(&s.sender), vec![&s.env, id]);
+
+    let school = Address::generate(&s.env);
+    let category = symbol_short!("school");
+    assert_eq!(
+        s.client.redeem(
+            &s.bills,
+            &id,
+            &s.recipient,
+            &s.token.address,
+            &300,
+            &category,
+            &school
+        ),
+        200
+    );
+    assert_eq!(s.token.balance(&school), 300);
+    assert_eq!(
+        s.client.try_redeem(
+            &s.bills,
+            &id,
+            &s.recipient,
+            &s.token.address,
+            &201,
+            &category,
+            &school
+        ),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    s.client.redeem(
+        &s.bills,
+        &id,
+        &s.recipient,
+        &s.token.address,
+        &200,
+        &category,
+        &school,
+    );
+    assert_eq!(s.client.get_voucher(&id).unwrap().remaining, 0);
+}
+
+#[test]
+fn test_spend_restrictions() {
+    let s = setup();
+    let id = school_voucher(&s, 500);
+    let payee = Address::generate(&s.env);
+    let school = symbol_short!("school");
+
+    // Wrong category
+    assert_eq!(
+        s.client.try_redeem(
+            &s.bills,
+            &id,
+            &s.recipient,
+            &s.token.address,
+            &100,
+            &symbol_short!("utility"),
+            &payee
+        ),
+        Err(Ok(Error::CategoryNotAllowed))
+    );
+    // Only the registered redeemer can release funds
+    assert_eq!(
+        s.client.try_redeem(
+            &s.sender,
+            &id,
+            &s.recipient,
+            &s.token.address,
+            &100,
+            &school,
+            &s.recipient
+        ),
+        Err(Ok(Error::WrongRedeemer))
+    );
+    // ...and only for the voucher's recipient
+    assert_eq!(
+        s.client.try_redeem(
+            &s.bills,
+            &id,
+            &payee,
+            &s.token.address,
+            &100,
+            &school,
+            &payee
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    let other_token = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env));
+    assert_eq!(
+        s.client.try_redeem(
+            &s.bills,
+            &id,
+            &s.recipient,
+            &other_token.address(),
+            &100,
+            &school,
+            &payee
+        ),
+        Err(Ok(Error::TokenMismatch))
+    );
+
+    // A savings voucher cannot be spent by the bills redeemer
+    let savings = s.client.create_voucher(
+        &s.sender,
+        &s.recipient,
+        &s.token.address,
+        &100,
+        &Purpose::Savings,
+        &Vec::new(&s.env),
+        &5_000,
+    );
+    assert_eq!(
+        s.client.try_redeem(
+            &s.bills,
+            &savings,
+            &s.recipient,
+            &s.token.address,
+            &100,
+            &school,
+            &payee
+        ),
+        Err(Ok(Error::WrongRedeemer))
+    );
+}
+
+#[test]
+fn test_reclaim_after_expiry() {
+    let s = setup();
+    let id = school_voucher(&s, 500);
+    assert_eq!(
+        s.client.try_reclaim(&s.sender, &id),
+        Err(Ok(Error::NotExpired))
+    );
+
+    s.env.ledger().with_mut(|l| l.timestamp = 5_000);
+    assert_eq!(
+        s.client.try_redeem(
+            &s.bills,
+            &id,
+            &s.recipient,
+            &s.token.address,
+            &100,
+            &symbol_short!("school"),
+            &s.recipient
+        ),
+        Err(Ok(Error::Expired))
+    );
+    assert_eq!(
+        s.client.try_reclaim(&s.recipient, &id),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(s.client.reclaim(&s.sender, &id), 500);
+    assert_eq!(s.token.balance(&s.sender), 1_000);
+    assert_eq!(
+        s.client.try_reclaim(&s.sender, &id),
+        Err(Ok(Error::NothingToReclaim))
+    );
+}
+
+#[test]
+fn test_create_validation() {
+    let s = setup();
+    let create = |recipient: &Address, amount: i128, expires_at: u64| {
+        s.client.try_create_voucher(
+            &s.sender,
+            recipient,
+            &s.token.address,
+            &amount,
+            &Purpose::Bills,
+            &Vec::new(&s.env),
+            &expires_at,
+        )
+    };
+    assert_eq!(
+        create(&s.recipient, 0, 5_000),
+        Err(Ok(Error::InvalidAmount))
+    );
+    assert_eq!(
+        create(&s.recipient, 100, 1_000),
+        Err(Ok(Error::InvalidExpiry))
+    );
+    assert_eq!(
+        create(&s.sender, 100, 5_000),
+        Err(Ok(Error::InvalidRecipient))
+    );
+}